@@ -0,0 +1,87 @@
+//! Prints the cheapest way to win the wizard fight of day 22, turn by turn.
+//!
+//! ```sh
+//! cargo run --bin day22 -- --hard --boss 58,9 --player 50,500 --spells spells.txt
+//! ```
+//!
+//! All options are optional:
+//! - `--boss <hp>,<damage>` defaults to the boss of the puzzle input
+//! - `--player <hp>,<mana>` defaults to 50 hit points and 500 mana
+//! - `--spells <file>` has one spell per line, see the `rpg` module, and defaults to the puzzle's
+//! - `--hard` loses 1 hit point at the start of each player turn, `--drain <hp>` any other amount
+use advent_of_code_2015::rpg::{Boss, Combat, Difficulty, Player, Spellbook};
+
+const INPUT: &str = include_str!("../../input/day22.txt");
+
+fn pair(value: &str) -> (usize, usize) {
+    let (a, b) = value
+        .split_once(',')
+        .unwrap_or_else(|| exit_with(&format!("Expected two numbers, got '{}'", value)));
+    (number(a), number(b))
+}
+
+fn number(value: &str) -> usize {
+    value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| exit_with(&format!("Expected a number, got '{}'", value)))
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut player = Player::new(50, 500);
+    let mut boss = None;
+    let mut spellbook = Spellbook::wizard();
+    let mut difficulty = Difficulty::NORMAL;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| exit_with(&format!("Missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--boss" => {
+                let (hp, damage) = pair(&value());
+                boss = Some(Boss::new(hp, damage, 0));
+            }
+            "--player" => {
+                let (hp, mana) = pair(&value());
+                player = Player::new(hp, mana);
+            }
+            "--spells" => {
+                let path = value();
+                let spells = std::fs::read_to_string(&path)
+                    .unwrap_or_else(|e| exit_with(&format!("Can't read {}: {}", path, e)));
+                spellbook = Spellbook::try_from(spells.as_str())
+                    .unwrap_or_else(|e| exit_with(&format!("Invalid spells in {}: {}", path, e)));
+            }
+            "--hard" => difficulty = Difficulty::HARD,
+            "--drain" => {
+                difficulty = Difficulty {
+                    player_hp_drain: number(&value()),
+                }
+            }
+            _ => exit_with(&format!("Unknown option '{}'", arg)),
+        }
+    }
+
+    let boss = boss.unwrap_or_else(|| {
+        Boss::try_from(INPUT).unwrap_or_else(|e| exit_with(&format!("Invalid boss: {}", e)))
+    });
+    let combat = Combat::new(player, boss)
+        .with_spells(spellbook)
+        .with_difficulty(difficulty);
+    match combat.cheapest_win() {
+        Some(victory) => {
+            println!("{}", combat.narrate(victory.actions).unwrap());
+            println!();
+            println!("Spent {} mana in total", victory.mana_spent);
+        }
+        None => exit_with("The boss can't be beaten"),
+    }
+}
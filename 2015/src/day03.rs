@@ -1,4 +1,5 @@
 use crate::parse;
+use geometry::{Direction, Point2};
 use std::collections::HashSet;
 
 const INPUT: &str = include_str!("../input/day03.txt");
@@ -20,59 +21,22 @@ fn presents_delivered_by_santa_and_robo_santa(path: &str) -> usize {
 }
 
 fn presents_delivered(path: &str, courier_count: usize) -> usize {
-    let path: Vec<_> = path.chars().map(Dir::from).collect();
-    let mut pos = vec![Pos::default(); courier_count];
+    let path: Vec<_> = path
+        .chars()
+        .map(|c| Direction::try_from(c).unwrap())
+        .collect();
+    let mut pos = vec![Point2::<isize>::origin(); courier_count];
     let mut presents_delivered = HashSet::new();
     let mut courier_idx = 0;
     presents_delivered.insert(pos[courier_idx]);
     for dir in path {
-        pos[courier_idx].go(&dir);
+        pos[courier_idx] += dir.offset();
         presents_delivered.insert(pos[courier_idx]);
         courier_idx = (courier_idx + 1) % courier_count;
     }
     presents_delivered.len()
 }
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone)]
-struct Pos {
-    x: isize,
-    y: isize,
-}
-impl Default for Pos {
-    fn default() -> Self {
-        Pos { x: 0, y: 0 }
-    }
-}
-
-impl Pos {
-    fn go(&mut self, dir: &Dir) {
-        match dir {
-            Dir::N => self.y -= 1,
-            Dir::S => self.y += 1,
-            Dir::E => self.x += 1,
-            Dir::W => self.x -= 1,
-        }
-    }
-}
-enum Dir {
-    N,
-    S,
-    E,
-    W,
-}
-
-impl From<char> for Dir {
-    fn from(c: char) -> Self {
-        match c {
-            '^' => Dir::N,
-            'v' => Dir::S,
-            '>' => Dir::E,
-            '<' => Dir::W,
-            _ => unreachable!(),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
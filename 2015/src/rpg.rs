@@ -0,0 +1,1061 @@
+//! The turn based combat of days 21 and 22: the player, equipped from a shop or casting spells,
+//! against a boss. The player always goes first, and whoever drops to 0 hit points loses.
+//!
+//! Spells are written in a small language, one per line, such as
+//! `Poison costs 173 mana: 3 damage for 6 turns` or `Drain costs 73 mana: 2 damage, 2 healing`.
+//! Spells without a duration take effect instantly. Effects take effect at the start of every
+//! turn, and a spell can't be cast again while its effect is still active.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign};
+
+pub type HitPoints = usize;
+pub type Mana = usize;
+pub type Damage = usize;
+pub type Armor = usize;
+pub type Cost = usize;
+pub type Turns = usize;
+pub type SpellIdx = usize;
+
+const SHOP: &str = "\
+Weapons:    Cost  Damage  Armor
+Dagger        8     4       0
+Shortsword   10     5       0
+Warhammer    25     6       0
+Longsword    40     7       0
+Greataxe     74     8       0
+
+Armor:      Cost  Damage  Armor
+Leather      13     0       1
+Chainmail    31     0       2
+Splintmail   53     0       3
+Bandedmail   75     0       4
+Platemail   102     0       5
+
+Rings:      Cost  Damage  Armor
+Damage +1    25     1       0
+Damage +2    50     2       0
+Damage +3   100     3       0
+Defense +1   20     0       1
+Defense +2   40     0       2
+Defense +3   80     0       3";
+
+const SPELLS: &str = "\
+Magic Missile costs 53 mana: 4 damage
+Drain costs 73 mana: 2 damage, 2 healing
+Shield costs 113 mana: 7 armor for 6 turns
+Poison costs 173 mana: 3 damage for 6 turns
+Recharge costs 229 mana: 101 mana for 5 turns";
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Stats {
+    pub damage: Damage,
+    pub armor: Armor,
+}
+impl Stats {
+    pub fn new(damage: Damage, armor: Armor) -> Self {
+        Stats { damage, armor }
+    }
+}
+impl AddAssign for Stats {
+    fn add_assign(&mut self, rhs: Self) {
+        self.damage += rhs.damage;
+        self.armor += rhs.armor;
+    }
+}
+impl Add for Stats {
+    type Output = Stats;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Stats::new(self.damage + rhs.damage, self.armor + rhs.armor)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Item {
+    pub name: String,
+    pub cost: Cost,
+    pub stats: Stats,
+}
+impl TryFrom<&str> for Item {
+    type Error = ParseError;
+
+    /// A row of a shop table, such as `Damage +1    25     1       0`
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let parts: Vec<_> = line.split_ascii_whitespace().collect();
+        if parts.len() < 4 {
+            return Err(ParseError::Malformed(line.to_string()));
+        }
+        let (name, numbers) = parts.split_at(parts.len() - 3);
+        let numbers = numbers
+            .iter()
+            .map(|n| number(n))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Item {
+            name: name.join(" "),
+            cost: numbers[0],
+            stats: Stats::new(numbers[1], numbers[2]),
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Shop {
+    weapons: Vec<Item>,
+    armor: Vec<Item>,
+    rings: Vec<Item>,
+}
+impl Default for Shop {
+    fn default() -> Self {
+        Shop::try_from(SHOP).unwrap()
+    }
+}
+impl TryFrom<&str> for Shop {
+    type Error = ParseError;
+
+    /// The weapons, armor and rings tables, separated by blank lines
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let mut shop = Shop {
+            weapons: vec![],
+            armor: vec![],
+            rings: vec![],
+        };
+        for table in input.trim().split("\n\n") {
+            let mut lines = table.lines();
+            let header = lines.next().unwrap_or_default();
+            let items = match header
+                .split_once(':')
+                .map_or(header, |(category, _)| category)
+            {
+                "Weapons" => &mut shop.weapons,
+                "Armor" => &mut shop.armor,
+                "Rings" => &mut shop.rings,
+                category => return Err(ParseError::UnknownCategory(category.to_string())),
+            };
+            for line in lines {
+                items.push(Item::try_from(line)?);
+            }
+        }
+        Ok(shop)
+    }
+}
+impl Shop {
+    /// Every loadout of exactly one weapon, at most one armor and at most two different rings
+    pub fn loadouts(&self) -> Vec<Loadout<'_>> {
+        let armor_choices: Vec<Option<&Item>> = std::iter::once(None)
+            .chain(self.armor.iter().map(Some))
+            .collect();
+        let mut ring_choices: Vec<Vec<&Item>> = vec![vec![]];
+        for (i, ring1) in self.rings.iter().enumerate() {
+            ring_choices.push(vec![ring1]);
+            for ring2 in self.rings.iter().skip(i + 1) {
+                ring_choices.push(vec![ring1, ring2]);
+            }
+        }
+        let mut loadouts = vec![];
+        for weapon in &self.weapons {
+            for armor in &armor_choices {
+                for rings in &ring_choices {
+                    let mut items = vec![weapon];
+                    items.extend(armor);
+                    items.extend(rings);
+                    loadouts.push(Loadout { items });
+                }
+            }
+        }
+        loadouts
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Loadout<'a> {
+    pub items: Vec<&'a Item>,
+}
+impl Loadout<'_> {
+    pub fn cost(&self) -> Cost {
+        self.items.iter().map(|item| item.cost).sum()
+    }
+    pub fn stats(&self) -> Stats {
+        self.items
+            .iter()
+            .fold(Stats::default(), |stats, item| stats + item.stats)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Player {
+    pub hp: HitPoints,
+    pub mana: Mana,
+    pub stats: Stats,
+}
+impl Player {
+    pub fn new(hp: HitPoints, mana: Mana) -> Self {
+        Player {
+            hp,
+            mana,
+            stats: Stats::default(),
+        }
+    }
+    pub fn equipped_with(mut self, loadout: &Loadout) -> Self {
+        self.stats += loadout.stats();
+        self
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Boss {
+    pub hp: HitPoints,
+    pub stats: Stats,
+}
+impl Boss {
+    pub fn new(hp: HitPoints, damage: Damage, armor: Armor) -> Self {
+        Boss {
+            hp,
+            stats: Stats::new(damage, armor),
+        }
+    }
+}
+impl TryFrom<&str> for Boss {
+    type Error = ParseError;
+
+    /// The puzzle input, such as `Hit Points: 58` and `Damage: 9`, optionally with `Armor: 2`
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let mut boss = Boss::new(0, 0, 0);
+        for line in input.trim().lines() {
+            let (key, value) = line
+                .split_once(": ")
+                .ok_or_else(|| ParseError::Malformed(line.to_string()))?;
+            let value = number(value)?;
+            match key {
+                "Hit Points" => boss.hp = value,
+                "Damage" => boss.stats.damage = value,
+                "Armor" => boss.stats.armor = value,
+                _ => return Err(ParseError::UnknownStat(key.to_string())),
+            }
+        }
+        Ok(boss)
+    }
+}
+
+/// What a spell does, either once or at the start of every turn its effect is active.
+/// Armor only counts while an effect is active.
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
+pub struct Impact {
+    pub damage: Damage,
+    pub healing: HitPoints,
+    pub armor: Armor,
+    pub mana: Mana,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Spell {
+    pub name: String,
+    pub cost: Mana,
+    pub impact: Impact,
+    /// None for spells taking effect instantly
+    pub duration: Option<Turns>,
+}
+impl TryFrom<&str> for Spell {
+    type Error = ParseError;
+
+    fn try_from(line: &str) -> Result<Self, Self::Error> {
+        let malformed = || ParseError::Malformed(line.to_string());
+        let (name, rest) = line.split_once(" costs ").ok_or_else(malformed)?;
+        let (cost, rest) = rest.split_once(" mana: ").ok_or_else(malformed)?;
+        let (impacts, duration) = match rest.split_once(" for ") {
+            Some((impacts, duration)) => {
+                let turns = duration
+                    .trim_end_matches(" turns")
+                    .trim_end_matches(" turn");
+                (impacts, Some(number(turns)?))
+            }
+            None => (rest, None),
+        };
+        let mut impact = Impact::default();
+        for part in impacts.split(", ") {
+            let (amount, kind) = part.split_once(' ').ok_or_else(malformed)?;
+            let amount = number(amount)?;
+            match kind {
+                "damage" => impact.damage = amount,
+                "healing" => impact.healing = amount,
+                "armor" => impact.armor = amount,
+                "mana" => impact.mana = amount,
+                _ => {
+                    return Err(ParseError::UnknownImpact {
+                        spell: name.to_string(),
+                        impact: kind.to_string(),
+                    })
+                }
+            }
+        }
+        if impact.armor > 0 && duration.is_none() {
+            return Err(ParseError::ArmorWithoutDuration(name.to_string()));
+        }
+        Ok(Spell {
+            name: name.to_string(),
+            cost: number(cost)?,
+            impact,
+            duration,
+        })
+    }
+}
+/// Writes the spell the way it's parsed
+impl Display for Spell {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let impacts = [
+            (self.impact.damage, "damage"),
+            (self.impact.healing, "healing"),
+            (self.impact.armor, "armor"),
+            (self.impact.mana, "mana"),
+        ];
+        let impacts: Vec<_> = impacts
+            .iter()
+            .filter(|(amount, _)| *amount > 0)
+            .map(|(amount, kind)| format!("{} {}", amount, kind))
+            .collect();
+        write!(
+            f,
+            "{} costs {} mana: {}",
+            self.name,
+            self.cost,
+            impacts.join(", ")
+        )?;
+        match self.duration {
+            Some(1) => write!(f, " for 1 turn"),
+            Some(turns) => write!(f, " for {} turns", turns),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Spellbook {
+    spells: Vec<Spell>,
+}
+impl TryFrom<&str> for Spellbook {
+    type Error = ParseError;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let spells = input
+            .trim()
+            .lines()
+            .map(Spell::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Spellbook { spells })
+    }
+}
+impl Spellbook {
+    /// The spells of day 22
+    pub fn wizard() -> Self {
+        Spellbook::try_from(SPELLS).unwrap()
+    }
+    pub fn spells(&self) -> &[Spell] {
+        &self.spells
+    }
+    pub fn cast(&self, name: &str) -> Option<Action> {
+        self.spells
+            .iter()
+            .position(|spell| spell.name == name)
+            .map(Action::Cast)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Difficulty {
+    /// Hit points the player loses at the start of each of their turns
+    pub player_hp_drain: HitPoints,
+}
+impl Difficulty {
+    pub const NORMAL: Difficulty = Difficulty { player_hp_drain: 0 };
+    pub const HARD: Difficulty = Difficulty { player_hp_drain: 1 };
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Side {
+    Player,
+    Boss,
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Action {
+    /// Attack with the equipped weapon
+    Attack,
+    Cast(SpellIdx),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// A line that doesn't have the expected shape
+    Malformed(String),
+    NotANumber(String),
+    UnknownCategory(String),
+    UnknownStat(String),
+    UnknownImpact {
+        spell: String,
+        impact: String,
+    },
+    /// Armor only counts while an effect is active
+    ArmorWithoutDuration(String),
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Malformed(line) => write!(f, "Can't make sense of '{}'", line),
+            ParseError::NotANumber(value) => write!(f, "Expected a number, got '{}'", value),
+            ParseError::UnknownCategory(category) => {
+                write!(f, "Unknown item category '{}'", category)
+            }
+            ParseError::UnknownStat(stat) => write!(f, "Unknown boss stat '{}'", stat),
+            ParseError::UnknownImpact { spell, impact } => {
+                write!(f, "Unknown impact '{}' of spell {}", impact, spell)
+            }
+            ParseError::ArmorWithoutDuration(spell) => {
+                write!(f, "Armor of spell {} needs a duration", spell)
+            }
+        }
+    }
+}
+
+fn number(value: &str) -> Result<usize, ParseError> {
+    value
+        .parse()
+        .map_err(|_| ParseError::NotANumber(value.to_string()))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CombatError {
+    UnknownSpell(SpellIdx),
+    CannotAfford(String),
+    AlreadyActive(String),
+    /// The player has no weapon to attack with
+    Unarmed,
+    /// The actions ran out before anyone died
+    OutOfActions,
+}
+impl Display for CombatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CombatError::UnknownSpell(idx) => write!(f, "There's no spell #{}", idx),
+            CombatError::CannotAfford(name) => write!(f, "Not enough mana to cast {}", name),
+            CombatError::AlreadyActive(name) => write!(f, "{} is still active", name),
+            CombatError::Unarmed => write!(f, "Can't attack without a weapon"),
+            CombatError::OutOfActions => write!(f, "Nobody died yet"),
+        }
+    }
+}
+
+/// Everything that changes during a fight
+#[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
+pub struct State {
+    pub player_hp: HitPoints,
+    pub mana: Mana,
+    pub boss_hp: HitPoints,
+    /// The active effects by spell, with their remaining turns
+    effects: Vec<(SpellIdx, Turns)>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Victory {
+    pub mana_spent: Mana,
+    pub actions: Vec<Action>,
+}
+
+/// Collects what happens during a fight, if anybody's interested
+struct Log(Option<Vec<String>>);
+impl Log {
+    fn say(&mut self, line: impl FnOnce() -> String) {
+        if let Some(lines) = &mut self.0 {
+            lines.push(line());
+        }
+    }
+}
+
+/// Everything about a fight that doesn't change during it
+#[derive(Debug, Clone)]
+pub struct Combat {
+    player: Player,
+    boss: Boss,
+    spellbook: Spellbook,
+    difficulty: Difficulty,
+}
+impl Combat {
+    pub fn new(player: Player, boss: Boss) -> Self {
+        Combat {
+            player,
+            boss,
+            spellbook: Spellbook::default(),
+            difficulty: Difficulty::NORMAL,
+        }
+    }
+    pub fn with_spells(mut self, spellbook: Spellbook) -> Self {
+        self.spellbook = spellbook;
+        self
+    }
+    pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+    pub fn spellbook(&self) -> &Spellbook {
+        &self.spellbook
+    }
+    pub fn start(&self) -> State {
+        State {
+            player_hp: self.player.hp,
+            mana: self.player.mana,
+            boss_hp: self.boss.hp,
+            effects: vec![],
+        }
+    }
+
+    /// Plays the actions in order until someone dies
+    pub fn play(
+        &self,
+        actions: impl IntoIterator<Item = Action>,
+    ) -> Result<(Side, State), CombatError> {
+        self.play_logged(actions, &mut Log(None))
+    }
+
+    /// Describes the fight turn by turn, until someone dies or the actions run out
+    pub fn narrate(
+        &self,
+        actions: impl IntoIterator<Item = Action>,
+    ) -> Result<String, CombatError> {
+        let mut log = Log(Some(vec![]));
+        match self.play_logged(actions, &mut log) {
+            Ok(_) | Err(CombatError::OutOfActions) => {}
+            Err(e) => return Err(e),
+        }
+        Ok(log.0.unwrap().join("\n").trim_end().to_string())
+    }
+
+    /// The cheapest attacks and spells the player can use to win, found with Dijkstra's
+    /// algorithm over the fight states by the mana spent to reach them
+    pub fn cheapest_win(&self) -> Option<Victory> {
+        let start = self.start();
+        let mut mana_spent = HashMap::from([(start.clone(), 0)]);
+        // How each state was reached most cheaply. No action means the boss died
+        // at the start of the player's turn.
+        let mut previous: HashMap<State, (State, Option<Action>)> = HashMap::new();
+        let mut candidates = BinaryHeap::from([Reverse((0, start))]);
+        while let Some(Reverse((spent, state))) = candidates.pop() {
+            if spent > mana_spent[&state] {
+                continue; // Reached more cheaply already
+            }
+            if state.boss_hp == 0 {
+                let mut actions = vec![];
+                let mut curr = &state;
+                while let Some((prev, action)) = previous.get(curr) {
+                    actions.extend(action);
+                    curr = prev;
+                }
+                actions.reverse();
+                return Some(Victory {
+                    mana_spent: spent,
+                    actions,
+                });
+            }
+            let mut next_states = vec![];
+            let mut turn = state.clone();
+            match self.start_player_turn(&mut turn, &mut Log(None)) {
+                Some(Side::Player) => next_states.push((turn, None, 0)),
+                Some(Side::Boss) => {}
+                None => {
+                    let attack = self.can_attack().then_some((Action::Attack, 0));
+                    let casts = self
+                        .spellbook
+                        .spells
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, spell)| (Action::Cast(idx), spell.cost));
+                    for (action, cost) in attack.into_iter().chain(casts) {
+                        let mut next = turn.clone();
+                        match self.finish_round(&mut next, action, &mut Log(None)) {
+                            Ok(Some(Side::Boss)) | Err(_) => {}
+                            Ok(_) => next_states.push((next, Some(action), cost)),
+                        }
+                    }
+                }
+            }
+            for (next, action, cost) in next_states {
+                let next_spent = spent + cost;
+                if !matches!(mana_spent.get(&next), Some(&known) if known <= next_spent) {
+                    mana_spent.insert(next.clone(), next_spent);
+                    previous.insert(next.clone(), (state.clone(), action));
+                    candidates.push(Reverse((next_spent, next)));
+                }
+            }
+        }
+        None
+    }
+
+    fn play_logged(
+        &self,
+        actions: impl IntoIterator<Item = Action>,
+        log: &mut Log,
+    ) -> Result<(Side, State), CombatError> {
+        let mut state = self.start();
+        for action in actions {
+            let (next, winner) = self.round(&state, action, log)?;
+            state = next;
+            if let Some(winner) = winner {
+                return Ok((winner, state));
+            }
+        }
+        Err(CombatError::OutOfActions)
+    }
+
+    /// The player's turn followed by the boss's, unless someone dies first
+    fn round(
+        &self,
+        state: &State,
+        action: Action,
+        log: &mut Log,
+    ) -> Result<(State, Option<Side>), CombatError> {
+        let mut state = state.clone();
+        let winner = match self.start_player_turn(&mut state, log) {
+            Some(winner) => Some(winner),
+            None => self.finish_round(&mut state, action, log)?,
+        };
+        Ok((state, winner))
+    }
+
+    /// What happens at the start of the player's turn, before they get to act
+    fn start_player_turn(&self, state: &mut State, log: &mut Log) -> Option<Side> {
+        self.log_turn_start("Player", state, log);
+        let drain = self.difficulty.player_hp_drain;
+        if drain > 0 {
+            state.player_hp = state.player_hp.saturating_sub(drain);
+            log.say(|| format!("Player loses {}.", hit_points(drain)));
+            if let Some(winner) = Combat::winner(state, log) {
+                return Some(winner);
+            }
+        }
+        self.apply_effects(state, log);
+        Combat::winner(state, log)
+    }
+
+    /// The player's action, followed by the boss's turn
+    fn finish_round(
+        &self,
+        state: &mut State,
+        action: Action,
+        log: &mut Log,
+    ) -> Result<Option<Side>, CombatError> {
+        match action {
+            Action::Attack if !self.can_attack() => return Err(CombatError::Unarmed),
+            Action::Attack => {
+                let damage = self.attack_damage(self.player.stats.damage, self.boss.stats.armor);
+                state.boss_hp = state.boss_hp.saturating_sub(damage);
+                log.say(|| {
+                    let attack =
+                        self.describe_attack(self.player.stats.damage, self.boss.stats.armor);
+                    format!("Player attacks for {}.", attack)
+                });
+            }
+            Action::Cast(idx) => self.cast(state, idx, log)?,
+        }
+        if let Some(winner) = Combat::winner(state, log) {
+            return Ok(Some(winner));
+        }
+        Ok(self.boss_turn(state, log))
+    }
+
+    fn boss_turn(&self, state: &mut State, log: &mut Log) -> Option<Side> {
+        log.say(String::new);
+        self.log_turn_start("Boss", state, log);
+        self.apply_effects(state, log);
+        if let Some(winner) = Combat::winner(state, log) {
+            return Some(winner);
+        }
+        let armor = self.player_armor(state);
+        let damage = self.attack_damage(self.boss.stats.damage, armor);
+        state.player_hp = state.player_hp.saturating_sub(damage);
+        log.say(|| {
+            let attack = self.describe_attack(self.boss.stats.damage, armor);
+            format!("Boss attacks for {}.", attack)
+        });
+        let winner = Combat::winner(state, log);
+        log.say(String::new);
+        winner
+    }
+
+    fn cast(&self, state: &mut State, idx: SpellIdx, log: &mut Log) -> Result<(), CombatError> {
+        let spell = self
+            .spellbook
+            .spells
+            .get(idx)
+            .ok_or(CombatError::UnknownSpell(idx))?;
+        if state.mana < spell.cost {
+            return Err(CombatError::CannotAfford(spell.name.clone()));
+        }
+        if state.effects.iter().any(|(active, _)| *active == idx) {
+            return Err(CombatError::AlreadyActive(spell.name.clone()));
+        }
+        state.mana -= spell.cost;
+        let impact = &spell.impact;
+        match spell.duration {
+            None => {
+                state.boss_hp = state.boss_hp.saturating_sub(impact.damage);
+                state.player_hp += impact.healing;
+                state.mana += impact.mana;
+                log.say(|| {
+                    let mut parts = vec![];
+                    if impact.damage > 0 {
+                        parts.push(format!("dealing {} damage", impact.damage));
+                    }
+                    if impact.healing > 0 {
+                        parts.push(format!("healing {}", hit_points(impact.healing)));
+                    }
+                    if impact.mana > 0 {
+                        parts.push(format!("restoring {} mana", impact.mana));
+                    }
+                    format!("Player casts {}, {}.", spell.name, parts.join(", and "))
+                });
+            }
+            Some(turns) => {
+                state.effects.push((idx, turns));
+                state.effects.sort_unstable();
+                log.say(|| match impact.armor {
+                    0 => format!("Player casts {}.", spell.name),
+                    armor => format!(
+                        "Player casts {}, increasing armor by {}.",
+                        spell.name, armor
+                    ),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_effects(&self, state: &mut State, log: &mut Log) {
+        for (idx, turns_left) in state.effects.iter_mut() {
+            let spell = &self.spellbook.spells[*idx];
+            let impact = &spell.impact;
+            state.boss_hp = state.boss_hp.saturating_sub(impact.damage);
+            state.player_hp += impact.healing;
+            state.mana += impact.mana;
+            *turns_left -= 1;
+            log.say(|| {
+                let mut parts = vec![];
+                if impact.damage > 0 {
+                    parts.push(format!("deals {} damage", impact.damage));
+                }
+                if impact.healing > 0 {
+                    parts.push(format!("heals {}", hit_points(impact.healing)));
+                }
+                if impact.mana > 0 {
+                    parts.push(format!("provides {} mana", impact.mana));
+                }
+                if parts.is_empty() {
+                    format!("{}'s timer is now {}.", spell.name, turns_left)
+                } else {
+                    let parts = parts.join(" and ");
+                    format!("{} {}; its timer is now {}.", spell.name, parts, turns_left)
+                }
+            });
+            if *turns_left == 0 {
+                log.say(|| match impact.armor {
+                    0 => format!("{} wears off.", spell.name),
+                    armor => format!("{} wears off, decreasing armor by {}.", spell.name, armor),
+                });
+            }
+        }
+        state.effects.retain(|(_, turns_left)| *turns_left > 0);
+    }
+
+    fn player_armor(&self, state: &State) -> Armor {
+        let effects: Armor = state
+            .effects
+            .iter()
+            .map(|(idx, _)| self.spellbook.spells[*idx].impact.armor)
+            .sum();
+        self.player.stats.armor + effects
+    }
+
+    /// Only a player with a weapon can attack, a wizard has to rely on spells
+    fn can_attack(&self) -> bool {
+        self.player.stats.damage > 0
+    }
+
+    /// Attacks always deal at least 1 damage
+    fn attack_damage(&self, damage: Damage, armor: Armor) -> Damage {
+        usize::max(1, damage.saturating_sub(armor))
+    }
+
+    fn describe_attack(&self, damage: Damage, armor: Armor) -> String {
+        if armor == 0 {
+            format!("{} damage", damage)
+        } else {
+            let dealt = self.attack_damage(damage, armor);
+            format!("{} - {} = {} damage", damage, armor, dealt)
+        }
+    }
+
+    fn log_turn_start(&self, side: &str, state: &State, log: &mut Log) {
+        log.say(|| format!("-- {} turn --", side));
+        log.say(|| {
+            format!(
+                "- Player has {}, {} armor, {} mana",
+                hit_points(state.player_hp),
+                self.player_armor(state),
+                state.mana
+            )
+        });
+        log.say(|| format!("- Boss has {}", hit_points(state.boss_hp)));
+    }
+
+    fn winner(state: &State, log: &mut Log) -> Option<Side> {
+        if state.boss_hp == 0 {
+            log.say(|| "This kills the boss, and the player wins.".to_string());
+            Some(Side::Player)
+        } else if state.player_hp == 0 {
+            log.say(|| "This kills the player, and the boss wins.".to_string());
+            Some(Side::Boss)
+        } else {
+            None
+        }
+    }
+}
+
+fn hit_points(hp: HitPoints) -> String {
+    match hp {
+        1 => "1 hit point".to_string(),
+        hp => format!("{} hit points", hp),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::repeat;
+
+    #[test]
+    fn parse_boss() {
+        assert_eq!(
+            Ok(Boss::new(104, 8, 1)),
+            Boss::try_from("Hit Points: 104\nDamage: 8\nArmor: 1")
+        );
+        assert_eq!(
+            Ok(Boss::new(58, 9, 0)),
+            Boss::try_from("Hit Points: 58\nDamage: 9")
+        );
+        assert_eq!(
+            Err(ParseError::UnknownStat("Speed".to_string())),
+            Boss::try_from("Hit Points: 58\nSpeed: 9")
+        );
+        assert_eq!(
+            Err(ParseError::NotANumber("lots".to_string())),
+            Boss::try_from("Hit Points: lots")
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(ParseError::Malformed("Zap costs 5".to_string())),
+            Spell::try_from("Zap costs 5")
+        );
+        assert_eq!(
+            Err(ParseError::UnknownImpact {
+                spell: "Zap".to_string(),
+                impact: "shock".to_string()
+            }),
+            Spell::try_from("Zap costs 5 mana: 3 shock")
+        );
+        assert_eq!(
+            Err(ParseError::ArmorWithoutDuration("Zap".to_string())),
+            Spellbook::try_from("Magic Missile costs 53 mana: 4 damage\nZap costs 5 mana: 3 armor")
+        );
+        assert_eq!(
+            Err(ParseError::UnknownCategory("Potions".to_string())),
+            Shop::try_from("Potions:\nElixir 1 0 0")
+        );
+        assert_eq!(
+            Err(ParseError::Malformed("Stick 1 1".to_string())),
+            Shop::try_from("Weapons:\nStick 1 1")
+        );
+    }
+
+    #[test]
+    fn spells_round_trip() {
+        let spellbook = Spellbook::wizard();
+        let written: Vec<_> = spellbook.spells().iter().map(Spell::to_string).collect();
+        assert_eq!(SPELLS, written.join("\n"));
+        assert_eq!(
+            Spell {
+                name: "Drain".to_string(),
+                cost: 73,
+                impact: Impact {
+                    damage: 2,
+                    healing: 2,
+                    ..Impact::default()
+                },
+                duration: None,
+            },
+            spellbook.spells()[1]
+        );
+    }
+
+    #[test]
+    fn shop_loadouts() {
+        let shop = Shop::default();
+        // 5 weapons, 1 + 5 armor choices, 1 + 6 + 15 ring choices
+        assert_eq!(5 * 6 * 22, shop.loadouts().len());
+        let cheapest = shop
+            .loadouts()
+            .into_iter()
+            .min_by_key(Loadout::cost)
+            .unwrap();
+        assert_eq!(8, cheapest.cost());
+        assert_eq!("Dagger", cheapest.items[0].name);
+
+        let shop = Shop::try_from("Weapons:\nStick 1 1 0\n\nRings:\nA 2 0 1\nB 4 1 0").unwrap();
+        let stats: Vec<_> = shop.loadouts().iter().map(Loadout::stats).collect();
+        let expected = [(1, 0), (1, 1), (2, 1), (2, 0)].map(|(d, a)| Stats::new(d, a));
+        assert_eq!(expected.to_vec(), stats);
+    }
+
+    #[test]
+    fn narrate_example() {
+        let combat =
+            Combat::new(Player::new(10, 250), Boss::new(13, 8, 0)).with_spells(Spellbook::wizard());
+        let spells = ["Poison", "Magic Missile"].map(|name| combat.spellbook().cast(name).unwrap());
+        assert_eq!(
+            "\
+-- Player turn --
+- Player has 10 hit points, 0 armor, 250 mana
+- Boss has 13 hit points
+Player casts Poison.
+
+-- Boss turn --
+- Player has 10 hit points, 0 armor, 77 mana
+- Boss has 13 hit points
+Poison deals 3 damage; its timer is now 5.
+Boss attacks for 8 damage.
+
+-- Player turn --
+- Player has 2 hit points, 0 armor, 77 mana
+- Boss has 10 hit points
+Poison deals 3 damage; its timer is now 4.
+Player casts Magic Missile, dealing 4 damage.
+
+-- Boss turn --
+- Player has 2 hit points, 0 armor, 24 mana
+- Boss has 3 hit points
+Poison deals 3 damage; its timer is now 3.
+This kills the boss, and the player wins.",
+            combat.narrate(spells).unwrap()
+        );
+    }
+
+    #[test]
+    fn narrate_shield_and_hard_mode() {
+        let combat = Combat::new(Player::new(10, 250), Boss::new(14, 8, 0))
+            .with_spells(Spellbook::try_from("Shield costs 113 mana: 7 armor for 1 turn").unwrap())
+            .with_difficulty(Difficulty::HARD);
+        assert_eq!(
+            "\
+-- Player turn --
+- Player has 10 hit points, 0 armor, 250 mana
+- Boss has 14 hit points
+Player loses 1 hit point.
+Player casts Shield, increasing armor by 7.
+
+-- Boss turn --
+- Player has 9 hit points, 7 armor, 137 mana
+- Boss has 14 hit points
+Shield's timer is now 0.
+Shield wears off, decreasing armor by 7.
+Boss attacks for 8 damage.",
+            combat.narrate([Action::Cast(0)]).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_actions() {
+        let combat =
+            Combat::new(Player::new(10, 100), Boss::new(20, 8, 0)).with_spells(Spellbook::wizard());
+        let poison = combat.spellbook().cast("Poison").unwrap();
+        let shield = combat.spellbook().cast("Shield").unwrap();
+        assert_eq!(
+            Err(CombatError::CannotAfford("Poison".to_string())),
+            combat.play([poison])
+        );
+        let combat =
+            Combat::new(Player::new(50, 500), Boss::new(20, 8, 0)).with_spells(Spellbook::wizard());
+        assert_eq!(
+            Err(CombatError::AlreadyActive("Shield".to_string())),
+            combat.play([shield, shield])
+        );
+        assert_eq!(Err(CombatError::OutOfActions), combat.play([shield]));
+        assert_eq!(
+            Err(CombatError::UnknownSpell(9)),
+            combat.play([Action::Cast(9)])
+        );
+        assert_eq!(Err(CombatError::Unarmed), combat.play([Action::Attack]));
+    }
+
+    #[test]
+    fn cheapest_win_of_examples() {
+        let combat =
+            Combat::new(Player::new(10, 250), Boss::new(13, 8, 0)).with_spells(Spellbook::wizard());
+        let victory = combat.cheapest_win().unwrap();
+        assert_eq!(173 + 53, victory.mana_spent);
+
+        let combat =
+            Combat::new(Player::new(10, 250), Boss::new(14, 8, 0)).with_spells(Spellbook::wizard());
+        let victory = combat.cheapest_win().unwrap();
+        assert!(victory.mana_spent <= 229 + 113 + 73 + 173 + 53);
+        let spells = combat.spellbook().spells();
+        let mana_spent: Mana = victory
+            .actions
+            .iter()
+            .map(|action| match action {
+                Action::Cast(idx) => spells[*idx].cost,
+                Action::Attack => 0,
+            })
+            .sum();
+        assert_eq!(mana_spent, victory.mana_spent);
+        let (winner, _) = combat.play(victory.actions).unwrap();
+        assert_eq!(Side::Player, winner);
+    }
+
+    #[test]
+    fn cheapest_win_with_weapon_and_spells() {
+        let player = Player {
+            stats: Stats::new(8, 0),
+            ..Player::new(10, 250)
+        };
+        let combat = Combat::new(player, Boss::new(13, 8, 0)).with_spells(Spellbook::wizard());
+        let victory = combat.cheapest_win().unwrap();
+        assert_eq!(0, victory.mana_spent);
+        assert_eq!(vec![Action::Attack, Action::Attack], victory.actions);
+
+        // Attacking alone loses, but Shield keeps the player alive for long enough
+        let player = Player {
+            stats: Stats::new(5, 0),
+            ..Player::new(10, 250)
+        };
+        let combat = Combat::new(player, Boss::new(13, 8, 0)).with_spells(Spellbook::wizard());
+        assert_eq!(
+            Ok(Side::Boss),
+            combat
+                .play(repeat(Action::Attack))
+                .map(|(winner, _)| winner)
+        );
+        let shield = combat.spellbook().cast("Shield").unwrap();
+        let victory = combat.cheapest_win().unwrap();
+        assert_eq!(113, victory.mana_spent);
+        let attack = Action::Attack;
+        assert_eq!(vec![shield, attack, attack, attack], victory.actions);
+    }
+
+    #[test]
+    fn no_win_against_overwhelming_boss() {
+        let combat = Combat::new(Player::new(10, 250), Boss::new(100, 50, 0))
+            .with_spells(Spellbook::wizard());
+        assert_eq!(None, combat.cheapest_win());
+    }
+}
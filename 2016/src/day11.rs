@@ -1,11 +1,10 @@
 use crate::parse;
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashSet};
+use state_space::{Puzzle, Solver};
 
 const INPUT: &str = include_str!("../input/day11.txt");
 
 pub(crate) fn day11_part1() -> usize {
-    let mut facility = Facility::from(parse(INPUT));
+    let facility = Facility::from(parse(INPUT));
     facility.steps_to_bring_everything_to_floor_3()
 }
 
@@ -39,44 +38,52 @@ struct Generator {
     element: Element,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-struct State {
-    steps: Steps,
-    facility: Facility,
+/// Taking one or two items in the elevator to the next floor
+#[derive(Debug, Clone)]
+struct Trip {
+    generators: Vec<Element>,
+    microchips: Vec<Element>,
+    to: Floor,
 }
-impl State {
-    fn take_elevator(
-        &self,
-        gens: &[&Generator],
-        chips: &[&Microchip],
-        next_floor: Floor,
-    ) -> Option<State> {
-        let facility = self.facility.take_elevator(gens, chips, next_floor);
-        if facility.is_safe() {
-            Some(State {
-                steps: self.steps + 1,
-                facility,
-            })
-        } else {
-            None
-        }
+
+impl Puzzle for Facility {
+    type State = Facility;
+    type Move = Trip;
+
+    fn start(&self) -> Facility {
+        self.clone()
     }
-}
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    fn moves(&self, facility: &Facility) -> Vec<Trip> {
+        facility.safe_trips()
     }
-}
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Prefer fewer steps, or higher elevator
-        // (because the goal is to move everything to the top floor)
-        match self.steps.cmp(&other.steps) {
-            Ordering::Equal => self
-                .facility
-                .elevator_floor
-                .cmp(&other.facility.elevator_floor),
-            steps => steps.reverse(),
+    fn apply(&self, facility: &Facility, trip: &Trip) -> Facility {
+        facility.take_elevator(trip)
+    }
+    fn cost(&self, _facility: &Facility, _trip: &Trip) -> Steps {
+        1
+    }
+    fn is_goal(&self, facility: &Facility) -> bool {
+        facility.is_done()
+    }
+    fn heuristic(&self, facility: &Facility) -> Option<Steps> {
+        // Each step brings at most two items one floor closer to the top
+        let floors_to_go: usize = (facility.generators.iter())
+            .chain(facility.microchips.iter())
+            .map(|floor| 3 - floor)
+            .sum();
+        Some(floors_to_go.div_ceil(2))
+    }
+    fn canonical(&self, facility: &Facility) -> Facility {
+        // Elements are interchangeable, only the floors of each generator and microchip pair matter
+        let mut pairs: Vec<_> = (facility.generators.iter().copied())
+            .zip(facility.microchips.iter().copied())
+            .collect();
+        pairs.sort_unstable();
+        let (generators, microchips) = pairs.into_iter().unzip();
+        Facility {
+            generators,
+            microchips,
+            elevator_floor: facility.elevator_floor,
         }
     }
 }
@@ -137,14 +144,6 @@ impl From<Vec<&str>> for Facility {
     }
 }
 impl Facility {
-    fn state(&self) -> Vec<usize> {
-        let mut counts = vec![self.elevator_floor];
-        for floor in 0..4 {
-            counts.push(self.microchips_on(&floor).len());
-            counts.push(self.generators_on(&floor).len());
-        }
-        counts
-    }
     fn is_safe(&self) -> bool {
         for floor in 0..4 {
             let generators = self.generators_on(&floor);
@@ -158,19 +157,14 @@ impl Facility {
         }
         true
     }
-    fn take_elevator(
-        &self,
-        gens: &[&Generator],
-        chips: &[&Microchip],
-        next_floor: Floor,
-    ) -> Facility {
+    fn take_elevator(&self, trip: &Trip) -> Facility {
         let mut next = self.clone();
-        next.elevator_floor = next_floor;
-        for gen in gens {
-            next.generators[gen.element] = next_floor;
+        next.elevator_floor = trip.to;
+        for &element in &trip.generators {
+            next.generators[element] = trip.to;
         }
-        for chip in chips {
-            next.microchips[chip.element] = next_floor;
+        for &element in &trip.microchips {
+            next.microchips[element] = trip.to;
         }
         next
     }
@@ -182,67 +176,46 @@ impl Facility {
             && self.microchips_on_elevator_floor().len() == self.element_count()
             && self.generators_on_elevator_floor().len() == self.element_count()
     }
-    fn steps_to_bring_everything_to_floor_3(&mut self) -> usize {
-        let mut states: BinaryHeap<State> = BinaryHeap::new();
-        states.push(State {
-            facility: self.clone(),
-            steps: 0,
-        });
-        let mut seen = HashSet::new();
-        while let Some(curr) = states.pop() {
-            // println!("\nPopped state {:?}", curr);
-            let state = curr.facility.state();
-            if seen.contains(&state) {
-                continue;
-            } else {
-                seen.insert(state);
+    fn steps_to_bring_everything_to_floor_3(&self) -> usize {
+        Solver::new(self).minimize().unwrap().value
+    }
+    fn safe_trips(&self) -> Vec<Trip> {
+        let generators = self.generators_on_elevator_floor();
+        let microchips = self.microchips_on_elevator_floor();
+        let mut loads = vec![];
+        for (i, gen) in generators.iter().enumerate() {
+            // Bring a generator by itself
+            loads.push((vec![gen.element], vec![]));
+            // Bring a generator and a microchip
+            for chip in &microchips {
+                loads.push((vec![gen.element], vec![chip.element]));
             }
-            if curr.facility.is_done() {
-                return curr.steps;
+            // Bring two generators
+            for gen2 in &generators[i + 1..] {
+                loads.push((vec![gen.element, gen2.element], vec![]));
             }
-            // We can change one floor at a time
-            for next in curr.facility.next_floor_choices() {
-                // Bring a generator by itself
-                for gen in curr.facility.generators_on_elevator_floor() {
-                    if let Some(next) = curr.take_elevator(&[&gen], &[], next) {
-                        states.push(next)
-                    }
-
-                    // Bring a generator and a microchip
-                    for chip in curr.facility.microchips_on_elevator_floor() {
-                        if let Some(next) = curr.take_elevator(&[&gen], &[&chip], next) {
-                            states.push(next)
-                        }
-                    }
-
-                    // Bring two generators
-                    for gen2 in curr.facility.generators_on_elevator_floor() {
-                        if gen2 != gen {
-                            if let Some(next) = curr.take_elevator(&[&gen, &gen2], &[], next) {
-                                states.push(next)
-                            }
-                        }
-                    }
-                }
-
-                // Bring a microchip by itself
-                for chip in curr.facility.microchips_on_elevator_floor() {
-                    if let Some(next) = curr.take_elevator(&[], &[&chip], next) {
-                        states.push(next)
-                    }
-
-                    // Bring two microchips
-                    for chip2 in curr.facility.microchips_on_elevator_floor() {
-                        if chip2 != chip {
-                            if let Some(next) = curr.take_elevator(&[], &[&chip, &chip2], next) {
-                                states.push(next)
-                            }
-                        }
-                    }
-                }
+        }
+        for (i, chip) in microchips.iter().enumerate() {
+            // Bring a microchip by itself
+            loads.push((vec![], vec![chip.element]));
+            // Bring two microchips
+            for chip2 in &microchips[i + 1..] {
+                loads.push((vec![], vec![chip.element, chip2.element]));
             }
         }
-        unreachable!()
+
+        // We can change one floor at a time
+        self.next_floor_choices()
+            .into_iter()
+            .flat_map(|to| {
+                loads.iter().map(move |(generators, microchips)| Trip {
+                    generators: generators.clone(),
+                    microchips: microchips.clone(),
+                    to,
+                })
+            })
+            .filter(|trip| self.take_elevator(trip).is_safe())
+            .collect()
     }
     fn next_floor_choices(&self) -> Vec<Floor> {
         match self.elevator_floor {
@@ -290,10 +263,41 @@ The fourth floor contains nothing relevant.";
 
     #[test]
     fn part1_example() {
-        let mut facility = Facility::from(parse(EXAMPLE));
+        let facility = Facility::from(parse(EXAMPLE));
         assert_eq!(11, facility.steps_to_bring_everything_to_floor_3());
     }
 
+    #[test]
+    fn part1_example_trips() {
+        let facility = Facility::from(parse(EXAMPLE));
+        let solution = Solver::new(&facility).minimize().unwrap();
+        assert_eq!(11, solution.moves.len());
+        let end = (solution.moves.iter()).fold(facility, |facility, trip| {
+            assert!(facility.safe_trips().iter().any(|safe| safe.to == trip.to
+                && safe.generators == trip.generators
+                && safe.microchips == trip.microchips));
+            facility.take_elevator(trip)
+        });
+        assert!(end.is_done());
+    }
+
+    #[test]
+    fn canonical_facility_ignores_element_order() {
+        let facility = |generators, microchips| Facility {
+            generators,
+            microchips,
+            elevator_floor: 1,
+        };
+        let original = facility(vec![1, 2], vec![0, 3]);
+        let swapped = facility(vec![2, 1], vec![3, 0]);
+        let mismatched = facility(vec![2, 1], vec![0, 3]);
+        assert_eq!(original.canonical(&original), original.canonical(&swapped));
+        assert_ne!(
+            original.canonical(&original),
+            original.canonical(&mismatched)
+        );
+    }
+
     #[test]
     fn part1() {
         assert_eq!(37, day11_part1());
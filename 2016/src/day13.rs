@@ -1,54 +1,23 @@
+use geometry::Point2;
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashSet};
 const PUZZLE_INPUT: usize = 1350;
 
 pub(crate) fn day13_part1() -> usize {
-    shortest_path(Coord::default(), Coord { x: 31, y: 39 }, PUZZLE_INPUT)
+    shortest_path(START, Point2::new(31, 39), PUZZLE_INPUT)
 }
 
 pub(crate) fn day13_part2() -> usize {
-    reachable_with_steps(Coord::default(), 50, PUZZLE_INPUT)
+    reachable_with_steps(START, 50, PUZZLE_INPUT)
 }
 
 type MagicNumber = usize;
+type Coord = Point2;
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
-struct Coord {
-    x: usize,
-    y: usize,
-}
-impl Default for Coord {
-    fn default() -> Self {
-        Coord { x: 1, y: 1 }
-    }
-}
-impl Coord {
-    fn is_open(&self, fav: MagicNumber) -> bool {
-        is_open(self.x, self.y, fav)
-    }
-    fn offset_by(&self, x: isize, y: isize) -> Option<Coord> {
-        let x = self.x as isize + x;
-        let y = self.y as isize + y;
-        if x >= 0 && y >= 0 {
-            Some(Coord {
-                x: x as usize,
-                y: y as usize,
-            })
-        } else {
-            None
-        }
-    }
-    fn neighbors(&self) -> Vec<Coord> {
-        vec![
-            self.offset_by(1, 0),
-            self.offset_by(0, 1),
-            self.offset_by(-1, 0),
-            self.offset_by(0, -1),
-        ]
-        .into_iter()
-        .flatten()
-        .collect()
-    }
+const START: Coord = Point2::new(1, 1);
+
+fn is_open_at(pos: &Coord, fav: MagicNumber) -> bool {
+    pos.x() >= 0 && pos.y() >= 0 && is_open(pos.x() as usize, pos.y() as usize, fav)
 }
 
 fn is_open(x: usize, y: usize, fav: MagicNumber) -> bool {
@@ -92,8 +61,8 @@ fn shortest_path(from: Coord, to: Coord, fav: MagicNumber) -> usize {
         if !visited.insert(pos) {
             continue;
         }
-        for neighbor in pos.neighbors() {
-            if neighbor.is_open(fav) {
+        for neighbor in pos.orthogonal_neighbors() {
+            if is_open_at(&neighbor, fav) {
                 next.push(State::new(count + 1, neighbor));
             }
         }
@@ -112,8 +81,8 @@ fn reachable_with_steps(from: Coord, max_steps: usize, fav: MagicNumber) -> usiz
         if !visited.insert(pos) {
             continue;
         }
-        for neighbor in pos.neighbors() {
-            if neighbor.is_open(fav) && count < max_steps {
+        for neighbor in pos.orthogonal_neighbors() {
+            if is_open_at(&neighbor, fav) && count < max_steps {
                 next.push(State::new(count + 1, neighbor));
             }
         }
@@ -156,8 +125,8 @@ mod tests {
 
     #[test]
     fn part1_example_shortest_path() {
-        let from = Coord::default();
-        let to = Coord { x: 7, y: 4 };
+        let from = START;
+        let to = Point2::new(7, 4);
         let fav = 10;
         assert_eq!(11, shortest_path(from, to, fav));
     }
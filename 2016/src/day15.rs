@@ -1,4 +1,5 @@
 use crate::parse;
+use number_theory::crt;
 
 const INPUT: &str = include_str!("../input/day15.txt");
 
@@ -22,24 +23,10 @@ pub(crate) fn day15_part2() -> usize {
 }
 
 fn earliest_start_time_with_full_alignment(discs: Vec<Disc>) -> usize {
-    // The disc with the most positions takes the longest to re-align,
-    // it's pointless to try more often than this slowest rotation period
-    let (mut slowest, mut others) = split(discs);
-    while !slowest.is_aligned_with(&others) {
-        slowest.rotate_once();
-        for disc in others.iter_mut() {
-            while disc.time < slowest.time {
-                disc.rotate_once();
-            }
-        }
-    }
-    slowest.time
-}
-
-fn split(mut discs: Vec<Disc>) -> (Disc, Vec<Disc>) {
-    discs.sort_unstable_by_key(|disc| disc.period);
-    let slowest = discs.pop().unwrap();
-    (slowest, discs)
+    // A capsule passes through a disc every ${period} seconds after its first pass-through time
+    let congruences = discs.iter().map(|disc| (disc.time, disc.period));
+    let (time, _period) = crt(congruences).expect("discs that align eventually");
+    time
 }
 
 fn discs_from(input: Vec<&str>) -> Vec<Disc> {
@@ -78,12 +65,6 @@ impl Disc {
         // Subtract ${number} seconds to get the start time at the top
         t - number
     }
-    fn rotate_once(&mut self) {
-        self.time += self.period;
-    }
-    fn is_aligned_with(&self, others: &[Disc]) -> bool {
-        others.iter().all(|disc| disc.time == self.time)
-    }
 }
 
 #[cfg(test)]
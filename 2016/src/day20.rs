@@ -1,5 +1,5 @@
 use crate::parse;
-use std::collections::VecDeque;
+use geometry::IntervalSet;
 use std::ops::RangeInclusive;
 
 const INPUT: &str = include_str!("../input/day20.txt");
@@ -18,14 +18,7 @@ pub(crate) fn day20_part2() -> usize {
 
 type IpRange = RangeInclusive<usize>;
 fn lowest_valued_non_forbidden_ip(blacklist: Vec<IpRange>) -> usize {
-    let mut candidates: Vec<_> = blacklist.iter().map(|r| r.end() + 1).collect();
-    candidates.sort_unstable();
-    for candidate in candidates {
-        if blacklist.iter().all(|range| !range.contains(&candidate)) {
-            return candidate;
-        }
-    }
-    unreachable!()
+    allowed_ips(blacklist).min().unwrap()
 }
 
 fn parse_rules(input: Vec<&str>) -> Vec<IpRange> {
@@ -39,34 +32,11 @@ fn parse_rules(input: Vec<&str>) -> Vec<IpRange> {
 }
 
 fn number_of_allowed_ips(blacklist: Vec<IpRange>) -> usize {
-    MAX_IP + 1 - blocked_ip_count(blacklist)
+    allowed_ips(blacklist).len()
 }
 
-fn blocked_ip_count(blacklist: Vec<IpRange>) -> usize {
-    let non_overlapping_ranges = merge_overlapping_ranges(blacklist);
-    non_overlapping_ranges
-        .into_iter()
-        .map(|r| r.end() - r.start() + 1)
-        .sum()
-}
-
-fn merge_overlapping_ranges(mut blacklist: Vec<IpRange>) -> Vec<IpRange> {
-    blacklist.sort_unstable_by_key(|r| *r.start());
-    let mut blacklist: VecDeque<IpRange> = VecDeque::from(blacklist);
-    let mut merged = vec![];
-    while let Some(mut range) = blacklist.pop_front() {
-        while let Some(pos) = blacklist
-            .iter()
-            .position(|other| other.start() <= range.end())
-        {
-            let other = blacklist.remove(pos).unwrap();
-            if other.end() > range.end() {
-                range = *range.start()..=*other.end();
-            }
-        }
-        merged.push(range);
-    }
-    merged
+fn allowed_ips(blacklist: Vec<IpRange>) -> IntervalSet<usize> {
+    IntervalSet::from_iter(blacklist).complement(0..=MAX_IP)
 }
 
 #[cfg(test)]
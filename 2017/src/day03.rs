@@ -1,3 +1,4 @@
+use geometry::{Direction, Point2};
 use std::collections::HashMap;
 
 const DAY3_PART1_PUZZLE_INPUT: usize = 361527;
@@ -15,7 +16,7 @@ fn manhattan_distance_to_origin_of_nth_spiral_point(n: usize) -> usize {
     while spiral.total_steps_taken < n - 1 {
         spiral.do_step();
     }
-    spiral.current_position().distance_to_origin()
+    spiral.current_position().manhattan_length() as usize
 }
 
 fn nth_spiral_points_value_up_to_limit(n: usize) -> usize {
@@ -33,67 +34,7 @@ fn nth_spiral_points_value_up_to_limit(n: usize) -> usize {
     *grid.get(spiral.current_position()).unwrap()
 }
 
-#[derive(PartialEq, Eq, Hash, Copy, Clone)]
-struct Position {
-    x: isize,
-    y: isize,
-}
-
-impl Default for Position {
-    fn default() -> Self {
-        Position { x: 0, y: 0 }
-    }
-}
-
-impl Position {
-    fn move_in_direction(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Right => self.x += 1,
-            Direction::Up => self.y += 1,
-            Direction::Left => self.x -= 1,
-            Direction::Down => self.y -= 1,
-        }
-    }
-    fn distance_to_origin(&self) -> usize {
-        (self.x.abs() + self.y.abs()) as usize
-    }
-    fn neighbors(&self) -> [Position; 8] {
-        [
-            self.offset(1, 0),
-            self.offset(1, 1),
-            self.offset(0, 1),
-            self.offset(-1, 1),
-            self.offset(-1, 0),
-            self.offset(-1, -1),
-            self.offset(0, -1),
-            self.offset(1, -1),
-        ]
-    }
-    fn offset(&self, x: isize, y: isize) -> Self {
-        Position {
-            x: self.x + x,
-            y: self.y + y,
-        }
-    }
-}
-
-enum Direction {
-    Right,
-    Up,
-    Left,
-    Down,
-}
-
-impl Direction {
-    fn turn_left(&mut self) {
-        *self = match self {
-            Direction::Right => Direction::Up,
-            Direction::Up => Direction::Left,
-            Direction::Left => Direction::Down,
-            Direction::Down => Direction::Right,
-        };
-    }
-}
+type Position = Point2;
 
 struct Spiral {
     position: Position,
@@ -107,8 +48,8 @@ struct Spiral {
 impl Default for Spiral {
     fn default() -> Self {
         Spiral {
-            position: Position::default(),
-            direction: Direction::Right,
+            position: Position::origin(),
+            direction: Direction::East,
             total_steps_taken: 0,
             steps_in_same_direction: 0,
             side_length: 1,
@@ -127,7 +68,7 @@ impl Spiral {
     fn step_forward(&mut self) {
         self.total_steps_taken += 1;
         self.steps_in_same_direction += 1;
-        self.position.move_in_direction(&self.direction);
+        self.position += self.direction.offset();
     }
 
     fn reached_corner(&self) -> bool {
@@ -136,7 +77,7 @@ impl Spiral {
 
     fn turn_left(&mut self) {
         self.steps_in_same_direction = 0;
-        self.direction.turn_left();
+        self.direction = self.direction.turn_left();
         self.times_turned += 1;
         if self.times_turned % 2 == 0 {
             self.side_length += 1;
@@ -149,9 +90,8 @@ impl Spiral {
 
     fn neighbor_sum(&self, grid: &HashMap<Position, usize>) -> usize {
         self.current_position()
-            .neighbors()
-            .iter()
-            .filter_map(|pos| grid.get(pos))
+            .all_neighbors()
+            .filter_map(|pos| grid.get(&pos))
             .sum()
     }
 }
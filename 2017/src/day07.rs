@@ -1,4 +1,5 @@
 use crate::parse;
+use dot::{Graph, ToDot};
 use std::collections::{HashMap, HashSet};
 
 const INPUT: &str = include_str!("../input/day07.txt");
@@ -117,7 +118,10 @@ impl Weights {
 }
 
 fn fixed_weight_of_imbalancing_program(lines: Vec<&str>) -> Weight {
-    let (mut weights, mut connections) = parse_weights_and_parent_child_connections(lines);
+    let Tower {
+        mut weights,
+        children_by_parent: mut connections,
+    } = Tower::from(lines);
 
     while !connections.is_empty() {
         let mut leaf_nodes = find_leaves(&mut connections);
@@ -147,6 +151,57 @@ fn find_leaves(children_by_parent: &mut ChildrenNamesByParentName) -> Vec<Connec
         .collect::<Vec<_>>()
 }
 
+struct Tower {
+    weights: WeightByName,
+    children_by_parent: ChildrenNamesByParentName,
+}
+impl Tower {
+    fn total_weight(&self, name: &Name) -> Weight {
+        let children = self.children_by_parent.get(name).into_iter().flatten();
+        self.weights[name].parent
+            + children
+                .map(|child| self.total_weight(child))
+                .sum::<Weight>()
+    }
+}
+impl From<Vec<&str>> for Tower {
+    fn from(lines: Vec<&str>) -> Self {
+        let (weights, children_by_parent) = parse_weights_and_parent_child_connections(lines);
+        Tower {
+            weights,
+            children_by_parent,
+        }
+    }
+}
+
+/// Renders the tower top-down, labelling each program with its own and its sub-tower's weight.
+/// Programs holding sub-towers of differing weights are highlighted.
+impl ToDot for Tower {
+    fn to_dot(&self) -> String {
+        let mut graph = Graph::directed("tower");
+        let mut names: Vec<_> = self.weights.keys().collect();
+        names.sort_unstable();
+        for name in names {
+            let weight = self.weights[name].parent;
+            let Some(children) = self.children_by_parent.get(name) else {
+                graph.node(name, &[("label", &format!("{name}\n({weight})"))]);
+                continue;
+            };
+            let mut children: Vec<_> = children.iter().collect();
+            children.sort_unstable();
+            let totals: HashSet<_> = children.iter().map(|c| self.total_weight(c)).collect();
+            let color = if totals.len() > 1 { "red" } else { "black" };
+            let total = self.total_weight(name);
+            let label = format!("{name}\n({weight}, total {total})");
+            graph.node(name, &[("label", &label), ("color", &color)]);
+            for child in children {
+                graph.edge(name, child, &[]);
+            }
+        }
+        graph.to_dot()
+    }
+}
+
 fn parse_weights_and_parent_child_connections(
     lines: Vec<&str>,
 ) -> (WeightByName, ChildrenNamesByParentName) {
@@ -204,6 +259,15 @@ cntj (57)";
         );
     }
 
+    #[test]
+    fn example_to_dot() {
+        let dot = Tower::from(parse(EXAMPLE)).to_dot();
+        assert!(dot.contains("\"tknk\" [label=\"tknk\\n(41, total 778)\", color=\"red\"];"));
+        assert!(dot.contains("\"padx\" [label=\"padx\\n(45, total 243)\", color=\"black\"];"));
+        assert!(dot.contains("\"pbga\" [label=\"pbga\\n(66)\"];"));
+        assert!(dot.contains("\"tknk\" -> \"ugml\";"));
+    }
+
     #[test]
     fn part2() {
         assert_eq!(757, day7_part2());
@@ -1,4 +1,5 @@
 use crate::parse;
+use geometry::hex::{Axial, FlatTop};
 
 const INPUT: &str = include_str!("../input/day11.txt");
 
@@ -15,12 +16,12 @@ fn max_distance_to_origin(input: Vec<&str>) -> usize {
 }
 
 fn curr_and_max_distances_to_origin(input: Vec<&str>) -> (usize, usize) {
-    let steps: Vec<Dir> = input[0].split(',').map(Dir::from).collect();
+    let steps = FlatTop::parse_path(input[0]).unwrap();
     let mut max_dist = 0;
 
-    let mut pos = Hex::default();
+    let mut pos = Axial::ORIGIN;
     for dir in steps {
-        pos.move_in(dir);
+        pos = pos.step(dir);
         max_dist = max_dist.max(pos.distance_to_origin());
     }
 
@@ -31,79 +32,6 @@ pub(crate) fn day11_part2() -> usize {
     max_distance_to_origin(parse(INPUT))
 }
 
-enum Dir {
-    N,
-    NE,
-    SE,
-    S,
-    SW,
-    NW,
-}
-
-impl From<&str> for Dir {
-    fn from(dir: &str) -> Self {
-        match dir {
-            "n" => Dir::N,
-            "ne" => Dir::NE,
-            "se" => Dir::SE,
-            "s" => Dir::S,
-            "sw" => Dir::SW,
-            "nw" => Dir::NW,
-            _ => panic!("Illegal direction {}", dir),
-        }
-    }
-}
-
-struct Hex {
-    x: isize,
-    y: isize,
-    z: isize,
-}
-
-impl Default for Hex {
-    fn default() -> Self {
-        Hex { x: 0, y: 0, z: 0 }
-    }
-}
-
-impl Hex {
-    fn move_in(&mut self, dir: Dir) {
-        match dir {
-            Dir::N => {
-                self.y += 1;
-                self.z -= 1;
-            }
-            Dir::NE => {
-                self.x += 1;
-                self.z -= 1;
-            }
-            Dir::SE => {
-                self.x += 1;
-                self.y -= 1;
-            }
-            Dir::S => {
-                self.y -= 1;
-                self.z += 1;
-            }
-            Dir::SW => {
-                self.x -= 1;
-                self.z += 1;
-            }
-            Dir::NW => {
-                self.x -= 1;
-                self.y += 1;
-            }
-        }
-    }
-    fn distance_to_origin(&self) -> usize {
-        self.distance_to(&Hex::default())
-    }
-    fn distance_to(&self, other: &Hex) -> usize {
-        (((self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()) / 2)
-            as usize
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
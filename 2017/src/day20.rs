@@ -1,7 +1,6 @@
 use crate::parse;
-use reformation::Reformation;
+use geometry::Point3;
 use std::collections::HashMap;
-use std::ops::AddAssign;
 
 const INPUT: &str = include_str!("../input/day20.txt");
 
@@ -19,7 +18,7 @@ fn index_of_particle_staying_closest_to_origin(input: Vec<&str>) -> usize {
     particles
         .iter()
         .enumerate()
-        .min_by_key(|(_, p)| p.acc.abs())
+        .min_by_key(|(_, p)| p.acc.manhattan_length())
         .unwrap()
         .0
 }
@@ -58,44 +57,42 @@ fn parse_input(input: Vec<&str>) -> Vec<Particle> {
         .collect()
 }
 
-type Coord = isize;
+type Vec3 = Point3;
 
-#[derive(Reformation, PartialEq, Debug, Copy, Clone, Eq, Hash)]
-#[reformation(r"<{x},{y},{z}>")]
-struct Vec3 {
-    x: Coord,
-    y: Coord,
-    z: Coord,
-}
-impl AddAssign for Vec3 {
-    fn add_assign(&mut self, rhs: Self) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-        self.z += rhs.z;
-    }
-}
-impl Vec3 {
-    fn abs(&self) -> usize {
-        (self.x.abs() + self.y.abs() + self.z.abs()) as usize
-    }
+fn parse_vec3(s: &str) -> Option<Vec3> {
+    let coords: Vec<isize> = s
+        .strip_prefix('<')?
+        .strip_suffix('>')?
+        .split(',')
+        .map(|n| n.trim().parse().ok())
+        .collect::<Option<_>>()?;
+    <[isize; 3]>::try_from(coords).ok().map(Point3::from)
 }
 
-#[derive(Reformation, PartialEq, Debug)]
-#[reformation(r"p={pos}, v={vel}, a={acc}")]
+#[derive(PartialEq, Debug)]
 struct Particle {
     pos: Vec3,
     vel: Vec3,
     acc: Vec3,
 }
 impl Particle {
+    /// Parses lines such as `p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>`
+    fn parse(line: &str) -> Option<Self> {
+        let mut vectors = line
+            .split(", ")
+            .map(|part| part.split_once('=').and_then(|(_, v)| parse_vec3(v)));
+        Some(Particle {
+            pos: vectors.next()??,
+            vel: vectors.next()??,
+            acc: vectors.next()??,
+        })
+    }
     fn tick(&mut self) {
         self.vel += self.acc;
         self.pos += self.vel;
     }
     fn is_decelerating(&self) -> bool {
-        (self.vel.x.signum() != self.acc.x.signum() && self.acc.x != 0)
-            || (self.vel.y.signum() != self.acc.y.signum() && self.acc.y != 0)
-            || (self.vel.z.signum() != self.acc.z.signum() && self.acc.z != 0)
+        (0..3).any(|axis| self.vel[axis].signum() != self.acc[axis].signum() && self.acc[axis] != 0)
     }
 }
 
@@ -109,9 +106,9 @@ p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>
 p=<4,0,0>, v=<0,0,0>, a=<-2,0,0>";
 
     #[test]
-    fn parse_vec3() {
-        let vec3 = Vec3::parse("<3,0,0>").unwrap();
-        assert_eq!(Vec3 { x: 3, y: 0, z: 0 }, vec3);
+    fn parse_vector() {
+        let vec3 = parse_vec3("<3,0,0>").unwrap();
+        assert_eq!(Vec3::new(3, 0, 0), vec3);
     }
 
     #[test]
@@ -119,9 +116,9 @@ p=<4,0,0>, v=<0,0,0>, a=<-2,0,0>";
         let particle = Particle::parse("p=<3,0,0>, v=<2,0,0>, a=<-1,0,0>").unwrap();
         assert_eq!(
             Particle {
-                pos: Vec3 { x: 3, y: 0, z: 0 },
-                vel: Vec3 { x: 2, y: 0, z: 0 },
-                acc: Vec3 { x: -1, y: 0, z: 0 }
+                pos: Vec3::new(3, 0, 0),
+                vel: Vec3::new(2, 0, 0),
+                acc: Vec3::new(-1, 0, 0)
             },
             particle
         );
@@ -0,0 +1,115 @@
+//! Runs the mine cart simulation of day 13, printing the crashes and the carts left.
+//!
+//! ```sh
+//! cargo run --bin day13 -- --policy report-all --ticks 500 --turns LSR --snapshots tracks.txt
+//! ```
+//!
+//! All options are optional:
+//! - `--policy <policy>` is one of `first-crash` (part 1, the default), `remove-crashed` (part 2)
+//!   or `report-all`, which lets carts go through each other and so needs `--ticks`
+//! - `--turns <choices>` are the turns at intersections, each `L`, `S` or `R`, and default to `LSR`
+//! - `--ticks <count>` stops the simulation after this many ticks, as carts may never crash
+//! - `--snapshots` prints the tracks after every tick
+//! - the tracks are read from the given file, and default to the puzzle input
+use advent_of_code_2018::day13::{Choice, CollisionPolicy, Simulation};
+use advent_of_code_2018::parse;
+
+const INPUT: &str = include_str!("../../input/day13.txt");
+
+fn policy_named(name: &str) -> CollisionPolicy {
+    match name {
+        "first-crash" => CollisionPolicy::StopAtFirstCrash,
+        "remove-crashed" => CollisionPolicy::RemoveCrashedCarts,
+        "report-all" => CollisionPolicy::ReportAllCrashes,
+        _ => exit_with(&format!("Unknown collision policy '{}'", name)),
+    }
+}
+
+fn turns(value: &str) -> Vec<Choice> {
+    let turns: Vec<_> = value
+        .chars()
+        .map(|c| {
+            Choice::try_from(c)
+                .unwrap_or_else(|_| exit_with(&format!("Expected L, S or R, got '{}'", c)))
+        })
+        .collect();
+    if turns.is_empty() {
+        exit_with("Carts need at least one turn for intersections");
+    }
+    turns
+}
+
+fn number(value: &str) -> usize {
+    value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| exit_with(&format!("Expected a number, got '{}'", value)))
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut policy = CollisionPolicy::StopAtFirstCrash;
+    let mut choices = None;
+    let mut max_ticks = None;
+    let mut snapshots = false;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| exit_with(&format!("Missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--policy" => policy = policy_named(&value()),
+            "--turns" => choices = Some(turns(&value())),
+            "--ticks" => max_ticks = Some(number(&value())),
+            "--snapshots" => snapshots = true,
+            _ if arg.starts_with("--") => exit_with(&format!("Unknown option '{}'", arg)),
+            _ => path = Some(arg),
+        }
+    }
+    if policy == CollisionPolicy::ReportAllCrashes && max_ticks.is_none() {
+        exit_with("Reporting all crashes never ends, so it needs --ticks");
+    }
+    let max_ticks = max_ticks.unwrap_or(usize::MAX);
+
+    let tracks = match &path {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't read {}: {}", path, e))),
+        None => INPUT.to_string(),
+    };
+    let mut simulation = Simulation::new(&parse(&tracks), policy);
+    if let Some(choices) = choices {
+        simulation = simulation.with_turns(choices);
+    }
+
+    if snapshots {
+        println!("{}", simulation);
+        for (tick, snapshot) in simulation.snapshots().take(max_ticks).enumerate() {
+            println!("\nAfter tick {}:\n{}", tick + 1, snapshot);
+        }
+        println!();
+    } else {
+        simulation.run_for(max_ticks);
+    }
+
+    for crash in simulation.crashes() {
+        println!(
+            "Tick {}: crash at {},{}",
+            crash.tick, crash.loc.0, crash.loc.1
+        );
+    }
+    let carts = simulation.cart_locations();
+    let carts: Vec<_> = carts.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+    println!(
+        "{} carts left after {} ticks: {}",
+        carts.len(),
+        simulation.ticks(),
+        carts.join(" ")
+    );
+}
@@ -0,0 +1,89 @@
+//! Replays the combat of day 15, printing the grid after every round in the format of the
+//! puzzle's examples, or everything that happened during combat.
+//!
+//! ```sh
+//! cargo run --bin day15 -- --attack 15 --round 20 grid.txt
+//! ```
+//!
+//! All options are optional:
+//! - `--attack <power>` is the attack power of the elves, and defaults to 3 like the goblins'
+//! - `--round <count>` only prints the grid after this many rounds
+//! - `--events` prints the moves, attacks and deaths instead of the grids
+//! - the grid is read from the given file, and defaults to the puzzle input
+use advent_of_code_2018::day15::{Event, Grid};
+use advent_of_code_2018::parse;
+
+const INPUT: &str = include_str!("../../input/day15.txt");
+
+fn number(value: &str) -> usize {
+    value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| exit_with(&format!("Expected a number, got '{}'", value)))
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut attack_power = 3;
+    let mut round = None;
+    let mut events = false;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| exit_with(&format!("Missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--attack" => {
+                attack_power = u8::try_from(number(&value()))
+                    .unwrap_or_else(|_| exit_with("The attack power can be at most 255"))
+            }
+            "--round" => round = Some(number(&value())),
+            "--events" => events = true,
+            _ if arg.starts_with("--") => exit_with(&format!("Unknown option '{}'", arg)),
+            _ => path = Some(arg),
+        }
+    }
+
+    let input = match &path {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't read {}: {}", path, e))),
+        None => INPUT.to_string(),
+    };
+    let replay = Grid::from(&parse(&input)).record_combat(attack_power);
+
+    if events {
+        for event in replay.events() {
+            match event {
+                Event::RoundEnded { .. } => println!("{}\n", event),
+                _ => println!("{}", event),
+            }
+        }
+    } else if let Some(round) = round {
+        let grid = replay.grid_after(round).unwrap_or_else(|| {
+            exit_with(&format!(
+                "Combat ends after {} full rounds",
+                replay.rounds()
+            ))
+        });
+        println!("{}", grid.frame());
+    } else {
+        for round in 0..=replay.rounds() {
+            println!("After {} rounds:", round);
+            println!("{}\n", replay.grid_after(round).unwrap().frame());
+        }
+        println!("Once combat ends:");
+        println!("{}\n", replay.final_grid().frame());
+    }
+    println!(
+        "Combat ends after {} full rounds with an outcome of {}",
+        replay.rounds(),
+        replay.outcome()
+    );
+}
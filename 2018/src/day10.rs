@@ -1,32 +1,28 @@
 use crate::parse;
-use reformation::Reformation;
-use std::ops::RangeInclusive;
+use geometry::{BoundingBox, Point2};
 
 const INPUT: &str = include_str!("../input/day10.txt");
 
-type Coord = isize;
+type Coord2D = Point2;
 
-#[derive(Reformation, Debug, PartialEq)]
-#[reformation("<\\s?{x}, \\s?{y}>")]
-struct Coord2D {
-    x: Coord,
-    y: Coord,
-}
-impl Coord2D {
-    fn equals(&self, x: Coord, y: Coord) -> bool {
-        self.x == x && self.y == y
-    }
+/// Parses vectors such as `< 9,  1>` or `<-1, 1>`
+fn parse_coord2d(s: &str) -> Option<Coord2D> {
+    let (x, y) = s.strip_prefix('<')?.strip_suffix('>')?.split_once(',')?;
+    Some(Coord2D::new(x.trim().parse().ok()?, y.trim().parse().ok()?))
 }
 
-#[derive(Reformation, Debug, PartialEq)]
-#[reformation(r"position={position} velocity={velocity}")]
+#[derive(Debug, PartialEq)]
 struct Point {
     position: Coord2D,
     velocity: Coord2D,
 }
 impl Point {
-    fn is_at_pos(&self, x: Coord, y: Coord) -> bool {
-        self.position.equals(x, y)
+    fn parse(line: &str) -> Option<Self> {
+        let (position, velocity) = line.strip_prefix("position=")?.split_once(" velocity=")?;
+        Some(Point {
+            position: parse_coord2d(position)?,
+            velocity: parse_coord2d(velocity)?,
+        })
     }
 }
 
@@ -45,15 +41,12 @@ fn message(input: &[&str]) -> (String, usize) {
 }
 
 fn has_many_vertically_aligned_points(points: &[Point]) -> bool {
-    let min_x = points.iter().map(|p| p.position.x).min().unwrap();
-    points.iter().filter(|p| p.position.x == min_x).count() >= 8
+    let min_x = points.iter().map(|p| p.position.x()).min().unwrap();
+    points.iter().filter(|p| p.position.x() == min_x).count() >= 8
 }
 
 fn iterate(mut points: Vec<Point>) -> Vec<Point> {
-    points.iter_mut().for_each(|p| {
-        p.position.x += p.velocity.x;
-        p.position.y += p.velocity.y;
-    });
+    points.iter_mut().for_each(|p| p.position += p.velocity);
     points
 }
 
@@ -65,16 +58,12 @@ fn to_points(input: &[&str]) -> Vec<Point> {
 }
 
 fn to_string(points: &[Point]) -> String {
-    let x_range = get_x_range(&points);
-    let y_range = get_y_range(&points);
-    y_range
-        .into_iter()
+    let bbox = BoundingBox::from_points(points.iter().map(|p| &p.position)).unwrap();
+    (bbox.min.y()..=bbox.max.y())
         .map(|y| {
-            x_range
-                .clone()
-                .into_iter()
+            (bbox.min.x()..=bbox.max.x())
                 .map(|x| {
-                    if points.iter().any(|p| p.is_at_pos(x, y)) {
+                    if points.iter().any(|p| p.position == Coord2D::new(x, y)) {
                         '#'
                     } else {
                         '.'
@@ -86,16 +75,6 @@ fn to_string(points: &[Point]) -> String {
         .join("\n")
 }
 
-fn get_x_range(points: &[Point]) -> RangeInclusive<isize> {
-    points.iter().map(|p| p.position.x).min().unwrap()
-        ..=points.iter().map(|p| p.position.x).max().unwrap()
-}
-
-fn get_y_range(points: &[Point]) -> RangeInclusive<isize> {
-    points.iter().map(|p| p.position.y).min().unwrap()
-        ..=points.iter().map(|p| p.position.y).max().unwrap()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,15 +85,15 @@ mod tests {
         assert_eq!(
             Point::parse("position=< 9,  1> velocity=< 0,  2>").unwrap(),
             Point {
-                position: Coord2D { x: 9, y: 1 },
-                velocity: Coord2D { x: 0, y: 2 }
+                position: Coord2D::new(9, 1),
+                velocity: Coord2D::new(0, 2)
             }
         );
         assert_eq!(
             Point::parse("position=< 3, -2> velocity=<-1,  1>").unwrap(),
             Point {
-                position: Coord2D { x: 3, y: -2 },
-                velocity: Coord2D { x: -1, y: 1 }
+                position: Coord2D::new(3, -2),
+                velocity: Coord2D::new(-1, 1)
             }
         );
     }
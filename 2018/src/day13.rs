@@ -1,19 +1,20 @@
 use crate::parse;
 use std::cmp::Ordering;
 use std::convert::TryFrom;
+use std::fmt::{Display, Formatter};
 
 const INPUT: &str = include_str!("../input/day13.txt");
 
-pub(crate) fn day13_part1() -> Location {
+pub fn day13_part1() -> Location {
     location_of_first_crash(&parse(INPUT))
 }
 
-pub(crate) fn day13_part2() -> Location {
+pub fn day13_part2() -> Location {
     location_of_last_cart(&parse(INPUT))
 }
 
-type Coord = isize;
-type Location = (Coord, Coord);
+pub type Coord = isize;
+pub type Location = (Coord, Coord);
 
 enum Track {
     Intersection,
@@ -35,9 +36,38 @@ impl From<char> for Track {
     }
 }
 impl Track {
-    fn from_location(loc: &Location, lines: &[&str]) -> Track {
-        let track_char = lines[loc.1 as usize].chars().nth(loc.0 as usize).unwrap();
-        Track::from(track_char)
+    fn to_char(&self) -> char {
+        match self {
+            Track::Intersection => '+',
+            Track::Slash => '/',
+            Track::Backslash => '\\',
+            Track::Vertical => '|',
+            Track::Horizontal => '-',
+        }
+    }
+}
+
+/// Indexed by [y][x], without the carts
+struct Tracks(Vec<Vec<Option<Track>>>);
+impl From<&[&str]> for Tracks {
+    fn from(lines: &[&str]) -> Self {
+        let rows = lines
+            .iter()
+            .map(|line| {
+                line.chars()
+                    .map(|ch| (ch != ' ').then(|| Track::from(ch)))
+                    .collect()
+            })
+            .collect();
+        Tracks(rows)
+    }
+}
+impl Tracks {
+    fn at(&self, loc: &Location) -> &Track {
+        let track = (self.0.get(loc.1 as usize))
+            .and_then(|row| row.get(loc.0 as usize))
+            .and_then(Option::as_ref);
+        track.unwrap_or_else(|| panic!("Cart derailed at {:?}", loc))
     }
 }
 
@@ -45,21 +75,20 @@ impl Track {
 struct Cart {
     loc: Location,
     dir: Dir,
-    next: Choice,
+    /// How many intersections the cart passed, to know which way it turns at the next one
+    intersections_passed: usize,
 }
 impl PartialOrd<Self> for Cart {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        // Only the location matters: y first, then x.
-        let y_ord = self.loc.1.partial_cmp(&other.loc.1).unwrap();
-        match y_ord {
-            Ordering::Less | Ordering::Greater => Some(y_ord),
-            Ordering::Equal => self.loc.0.partial_cmp(&other.loc.0),
-        }
+        Some(self.cmp(other))
     }
 }
 impl Ord for Cart {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(&other).unwrap()
+        // Only the location matters, in reading order: y first, then x.
+        let (x, y) = self.loc;
+        let (other_x, other_y) = other.loc;
+        y.cmp(&other_y).then(x.cmp(&other_x))
     }
 }
 impl Cart {
@@ -68,24 +97,26 @@ impl Cart {
         self.loc.0 += offset.0;
         self.loc.1 += offset.1;
     }
-    fn turn(&mut self, track: &Track) {
+    fn turn(&mut self, track: &Track, turns: &[Choice]) {
         let dir = self.dir.clone();
         self.dir = match (&dir, track) {
             (Dir::Right, Track::Slash) | (Dir::Left, Track::Backslash) => Dir::Up,
             (Dir::Right, Track::Backslash) | (Dir::Left, Track::Slash) => Dir::Down,
             (Dir::Up, Track::Slash) | (Dir::Down, Track::Backslash) => Dir::Right,
             (Dir::Up, Track::Backslash) | (Dir::Down, Track::Slash) => Dir::Left,
-            (_dir, Track::Intersection) => match (&dir, &self.next) {
-                (Dir::Right, Choice::Right) | (Dir::Left, Choice::Left) => Dir::Down,
-                (Dir::Right, Choice::Left) | (Dir::Left, Choice::Right) => Dir::Up,
-                (Dir::Up, Choice::Right) | (Dir::Down, Choice::Left) => Dir::Right,
-                (Dir::Up, Choice::Left) | (Dir::Down, Choice::Right) => Dir::Left,
-                (_, Choice::Straight) => dir,
-            },
+            (_dir, Track::Intersection) => {
+                match (&dir, &turns[self.intersections_passed % turns.len()]) {
+                    (Dir::Right, Choice::Right) | (Dir::Left, Choice::Left) => Dir::Down,
+                    (Dir::Right, Choice::Left) | (Dir::Left, Choice::Right) => Dir::Up,
+                    (Dir::Up, Choice::Right) | (Dir::Down, Choice::Left) => Dir::Right,
+                    (Dir::Up, Choice::Left) | (Dir::Down, Choice::Right) => Dir::Left,
+                    (_, Choice::Straight) => dir,
+                }
+            }
             (_, Track::Vertical | Track::Horizontal) => dir,
         };
         if matches!(track, Track::Intersection) {
-            self.next.advance();
+            self.intersections_passed += 1;
         }
     }
 }
@@ -118,72 +149,189 @@ impl Dir {
             Dir::Down => (0, 1),
         }
     }
+    fn to_char(&self) -> char {
+        match self {
+            Dir::Right => '>',
+            Dir::Left => '<',
+            Dir::Up => '^',
+            Dir::Down => 'v',
+        }
+    }
 }
 
-#[derive(Debug, Eq, PartialEq)]
-enum Choice {
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Choice {
     Right,
     Left,
     Straight,
 }
-impl Choice {
-    fn advance(&mut self) {
-        *self = match self {
-            Choice::Left => Choice::Straight,
-            Choice::Straight => Choice::Right,
-            Choice::Right => Choice::Left,
-        };
+impl TryFrom<char> for Choice {
+    type Error = ();
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'R' => Ok(Choice::Right),
+            'L' => Ok(Choice::Left),
+            'S' => Ok(Choice::Straight),
+            _ => Err(()),
+        }
     }
 }
+/// The way carts turn at intersections, over and over again
+const DEFAULT_TURNS: [Choice; 3] = [Choice::Left, Choice::Straight, Choice::Right];
 
-pub(crate) fn location_of_first_crash(lines: &[&str]) -> Location {
-    location_of_cart(lines, true)
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum CollisionPolicy {
+    /// The simulation is over as soon as two carts crash, or if they never can
+    StopAtFirstCrash,
+    /// Both carts of each crash are removed, until there's at most one cart left
+    RemoveCrashedCarts,
+    /// Crashes are only reported, and the carts go on through each other. This never ends.
+    ReportAllCrashes,
 }
 
-pub(crate) fn location_of_last_cart(lines: &[&str]) -> Location {
-    location_of_cart(lines, false)
+#[derive(Debug, Eq, PartialEq)]
+pub struct Crash {
+    /// The tick during which the carts crashed, starting at 1
+    pub tick: usize,
+    pub loc: Location,
 }
 
-pub(crate) fn location_of_cart(lines: &[&str], return_on_first_collision: bool) -> Location {
-    let mut carts = initial_cart_locations(lines);
-    loop {
-        let mut handled_carts: Vec<Cart> = Vec::new();
-        carts.sort();
-        // println!("Carts: {:?}", carts);
-        while !carts.is_empty() {
-            let mut cart = carts.remove(0);
+pub struct Simulation {
+    tracks: Tracks,
+    carts: Vec<Cart>,
+    turns: Vec<Choice>,
+    policy: CollisionPolicy,
+    ticks: usize,
+    crashes: Vec<Crash>,
+}
 
-            // Move to new location
+impl Simulation {
+    pub fn new(lines: &[&str], policy: CollisionPolicy) -> Self {
+        Simulation {
+            tracks: Tracks::from(lines),
+            carts: initial_cart_locations(lines),
+            turns: DEFAULT_TURNS.to_vec(),
+            policy,
+            ticks: 0,
+            crashes: vec![],
+        }
+    }
+    /// Lets the carts turn at intersections in the given order instead of the default one
+    pub fn with_turns(mut self, turns: Vec<Choice>) -> Self {
+        assert!(!turns.is_empty(), "Carts need to know where to turn");
+        self.turns = turns;
+        self
+    }
+    pub fn is_over(&self) -> bool {
+        match self.policy {
+            CollisionPolicy::StopAtFirstCrash => !self.crashes.is_empty() || self.carts.len() <= 1,
+            CollisionPolicy::RemoveCrashedCarts => self.carts.len() <= 1,
+            CollisionPolicy::ReportAllCrashes => false,
+        }
+    }
+    /// Moves each cart once, in reading order of their locations at the start of the tick
+    fn tick(&mut self) {
+        self.ticks += 1;
+        self.carts.sort();
+        let mut crashed = vec![false; self.carts.len()];
+        for i in 0..self.carts.len() {
+            if crashed[i] {
+                continue;
+            }
+            let cart = &mut self.carts[i];
             cart.move_1();
+            cart.turn(self.tracks.at(&cart.loc), &self.turns);
 
-            // Turn into new direction as needed
-            let track = Track::from_location(&cart.loc, &lines);
-            cart.turn(&track);
-
-            // check for collisions
-            if carts.iter().any(|other| other.loc == cart.loc)
-                || handled_carts.iter().any(|other| other.loc == cart.loc)
-            {
-                if return_on_first_collision {
-                    return cart.loc;
-                } else if let Some(pos) = carts.iter().position(|other| other.loc == cart.loc) {
-                    carts.remove(pos);
-                } else if let Some(pos) =
-                    handled_carts.iter().position(|other| other.loc == cart.loc)
-                {
-                    handled_carts.remove(pos);
+            let loc = self.carts[i].loc;
+            let Some(other) =
+                (0..self.carts.len()).find(|&j| j != i && !crashed[j] && self.carts[j].loc == loc)
+            else {
+                continue;
+            };
+            self.crashes.push(Crash {
+                tick: self.ticks,
+                loc,
+            });
+            match self.policy {
+                CollisionPolicy::StopAtFirstCrash => return,
+                CollisionPolicy::RemoveCrashedCarts => {
+                    crashed[i] = true;
+                    crashed[other] = true;
                 }
-            } else {
-                handled_carts.push(cart);
+                CollisionPolicy::ReportAllCrashes => {}
             }
         }
-        carts = handled_carts;
-        if carts.len() == 1 {
-            return carts[0].loc;
+        let mut crashed = crashed.into_iter();
+        self.carts.retain(|_| !crashed.next().unwrap());
+    }
+    /// Ticks until the simulation is over, or the given number of ticks passed
+    pub fn run_for(&mut self, max_ticks: usize) {
+        for _ in 0..max_ticks {
+            if self.is_over() {
+                break;
+            }
+            self.tick();
+        }
+    }
+    pub fn run(&mut self) {
+        self.run_for(usize::MAX);
+    }
+    /// The tracks after each tick, until the simulation is over
+    pub fn snapshots(&mut self) -> impl Iterator<Item = String> + '_ {
+        std::iter::from_fn(move || {
+            (!self.is_over()).then(|| {
+                self.tick();
+                self.to_string()
+            })
+        })
+    }
+    pub fn ticks(&self) -> usize {
+        self.ticks
+    }
+    pub fn crashes(&self) -> &[Crash] {
+        &self.crashes
+    }
+    /// The locations of the carts still on the tracks, in reading order
+    pub fn cart_locations(&self) -> Vec<Location> {
+        let mut locations: Vec<_> = self.carts.iter().map(|cart| cart.loc).collect();
+        locations.sort_by_key(|&(x, y)| (y, x));
+        locations
+    }
+}
+
+impl Display for Simulation {
+    /// The tracks with the carts on them, and an X where carts crashed during the last tick
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut rows: Vec<Vec<char>> = (self.tracks.0.iter())
+            .map(|row| {
+                row.iter()
+                    .map(|track| track.as_ref().map_or(' ', Track::to_char))
+                    .collect()
+            })
+            .collect();
+        for cart in &self.carts {
+            rows[cart.loc.1 as usize][cart.loc.0 as usize] = cart.dir.to_char();
+        }
+        for crash in self.crashes.iter().filter(|crash| crash.tick == self.ticks) {
+            rows[crash.loc.1 as usize][crash.loc.0 as usize] = 'X';
         }
+        let rows: Vec<String> = rows.into_iter().map(String::from_iter).collect();
+        write!(f, "{}", rows.join("\n"))
     }
 }
 
+fn location_of_first_crash(lines: &[&str]) -> Location {
+    let mut simulation = Simulation::new(lines, CollisionPolicy::StopAtFirstCrash);
+    simulation.run();
+    simulation.crashes.first().expect("No crash").loc
+}
+
+fn location_of_last_cart(lines: &[&str]) -> Location {
+    let mut simulation = Simulation::new(lines, CollisionPolicy::RemoveCrashedCarts);
+    simulation.run();
+    simulation.carts.first().expect("No cart left").loc
+}
+
 fn initial_cart_locations(lines: &[&str]) -> Vec<Cart> {
     lines
         .iter()
@@ -194,7 +342,7 @@ fn initial_cart_locations(lines: &[&str]) -> Vec<Cart> {
                     .map(|dir| Cart {
                         loc: (x as isize, y as isize),
                         dir,
-                        next: Choice::Left,
+                        intersections_passed: 0,
                     })
                     .ok()
             })
@@ -277,4 +425,154 @@ v
     fn part2() {
         assert_eq!((146, 87), day13_part2());
     }
+
+    fn cart_at(loc: Location) -> Cart {
+        Cart {
+            loc,
+            dir: Dir::Up,
+            intersections_passed: 0,
+        }
+    }
+
+    #[test]
+    fn carts_sort_in_reading_order() {
+        let mut carts: Vec<_> = [(3, 0), (0, 2), (1, 0), (5, 1), (0, 1)]
+            .into_iter()
+            .map(cart_at)
+            .collect();
+        carts.sort();
+        let locations: Vec<_> = carts.iter().map(|cart| cart.loc).collect();
+        assert_eq!(vec![(1, 0), (3, 0), (0, 1), (5, 1), (0, 2)], locations);
+    }
+
+    #[test]
+    fn carts_move_in_reading_order() {
+        // The left cart moves first, into the right one before it moved away
+        let mut simulation = Simulation::new(&parse("->>--"), CollisionPolicy::StopAtFirstCrash);
+        simulation.run();
+        assert_eq!(
+            vec![Crash {
+                tick: 1,
+                loc: (2, 0)
+            }],
+            simulation.crashes
+        );
+
+        // Same for the upper cart
+        let track = "|\nv\nv\n|";
+        let mut simulation = Simulation::new(&parse(track), CollisionPolicy::StopAtFirstCrash);
+        simulation.run();
+        assert_eq!(
+            vec![Crash {
+                tick: 1,
+                loc: (0, 2)
+            }],
+            simulation.crashes
+        );
+
+        // The other way around, the carts move away before the others move in
+        let mut simulation = Simulation::new(&parse("-<<---"), CollisionPolicy::StopAtFirstCrash);
+        simulation.run_for(1);
+        assert!(simulation.crashes.is_empty());
+        assert_eq!("<<----", simulation.to_string());
+    }
+
+    #[test]
+    fn example_snapshots() {
+        let mut simulation = Simulation::new(&parse(EXAMPLE_1), CollisionPolicy::StopAtFirstCrash);
+        assert_eq!(EXAMPLE_1, simulation.to_string());
+        let snapshots: Vec<_> = simulation.snapshots().collect();
+        assert_eq!(14, snapshots.len());
+        assert_eq!(
+            "\
+/-->\\        \n\
+|   |  /----\\
+| /-+--+-\\  |
+| | |  | |  |
+\\-+-/  \\->--/
+  \\------/   ",
+            snapshots[0]
+        );
+        assert_eq!(
+            "\
+/---\\        \n\
+|   |  /----\\
+| /-+--+-\\  |
+| | |  X |  |
+\\-+-/  \\-+--/
+  \\------/   ",
+            snapshots[13]
+        );
+    }
+
+    #[test]
+    fn removed_crashes_with_ticks() {
+        let mut simulation =
+            Simulation::new(&parse(EXAMPLE_2), CollisionPolicy::RemoveCrashedCarts);
+        simulation.run();
+        let crashes: Vec<_> = (simulation.crashes.iter())
+            .map(|Crash { tick, loc }| (*tick, *loc))
+            .collect();
+        assert_eq!(
+            vec![(1, (2, 0)), (1, (2, 4)), (1, (6, 4)), (3, (2, 4))],
+            crashes
+        );
+        assert_eq!(3, simulation.ticks);
+        assert_eq!(1, simulation.carts.len());
+        assert_eq!((6, 4), simulation.carts[0].loc);
+    }
+
+    #[test]
+    fn reported_crashes_keep_carts_going() {
+        let mut simulation = Simulation::new(&parse(EXAMPLE_1), CollisionPolicy::ReportAllCrashes);
+        simulation.run_for(100);
+        assert_eq!(100, simulation.ticks);
+        assert_eq!(2, simulation.carts.len());
+        assert_eq!(
+            Crash {
+                tick: 14,
+                loc: (7, 3)
+            },
+            simulation.crashes[0]
+        );
+        assert!(simulation.crashes.windows(2).all(|w| w[0].tick < w[1].tick));
+    }
+
+    #[test]
+    fn other_turn_sequences() {
+        // The intersection connects a loop at the top left with one at the bottom right
+        const LOOPS: &str = "\
+/>\\
+| |
+\\-+-\\
+  | |
+  \\-/";
+        for (turns, loc) in [
+            (DEFAULT_TURNS.to_vec(), (3, 2)),
+            (vec![Choice::Left], (3, 2)),
+            (vec![Choice::Straight], (2, 3)),
+            (vec![Choice::Right, Choice::Left], (1, 2)),
+        ] {
+            let mut simulation =
+                Simulation::new(&parse(LOOPS), CollisionPolicy::ReportAllCrashes).with_turns(turns);
+            simulation.run_for(4);
+            assert_eq!(loc, simulation.carts[0].loc);
+        }
+
+        let mut simulation = Simulation::new(&parse(LOOPS), CollisionPolicy::ReportAllCrashes)
+            .with_turns(vec![Choice::Right]);
+        simulation.run_for(4);
+        assert_eq!(
+            "\
+/-\\
+| |
+\\<+-\\
+  | |
+  \\-/",
+            simulation.to_string()
+        );
+        // Always turning right, it goes around both loops, and back to the start after 16 ticks
+        simulation.run_for(12);
+        assert_eq!(LOOPS, simulation.to_string());
+    }
 }
@@ -1,20 +1,21 @@
 use crate::parse;
+use crate::search::{Outcome, SearchError, SmallestWinningParameter, Winner};
 use std::fmt::{Debug, Display, Formatter};
 
 const INPUT: &str = include_str!("../input/day15.txt");
 
-pub(crate) fn day15_part1() -> usize {
+pub fn day15_part1() -> usize {
     Grid::from(&parse(INPUT)).play_until_no_enemies_remain()
 }
 
-pub(crate) fn day15_part2() -> usize {
+pub fn day15_part2() -> usize {
     Grid::from(&parse(INPUT))
         .play_with_increasing_elf_attack_power_until_elves_win_without_a_single_loss()
 }
 
 type TileRows = Vec<Vec<Tile>>;
-type RoundCount = usize;
-type HitPoints = u8;
+pub type RoundCount = usize;
+pub type HitPoints = u8;
 type Coord = usize;
 type StepCount = usize;
 type StepCountRows = Vec<Vec<StepCount>>;
@@ -23,14 +24,35 @@ type SumOfRemainingHitPoints = usize;
 const INITIAL_HP: HitPoints = 200;
 const ATTACK_POWER: HitPoints = 3;
 
-#[derive(Debug, PartialEq)]
-pub(crate) struct Grid {
+#[derive(Debug, PartialEq, Clone)]
+pub struct Grid {
     rows: TileRows,
     rounds: RoundCount,
 }
 
+/// Everything that happens during combat, in order. Units are identified by their location
+/// at the time of the event, which is enough to replay the combat from the initial grid.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    UnitMoved {
+        from: Loc,
+        to: Loc,
+    },
+    UnitAttacked {
+        attacker: Loc,
+        target: Loc,
+        hp_left: HitPoints,
+    },
+    UnitDied {
+        at: Loc,
+    },
+    RoundEnded {
+        round: RoundCount,
+    },
+}
+
 impl Grid {
-    pub(crate) fn play_until_no_enemies_remain(&mut self) -> usize {
+    pub fn play_until_no_enemies_remain(&mut self) -> usize {
         loop {
             if let Some(sum_of_remaining_hit_points) = self.play_round() {
                 return sum_of_remaining_hit_points;
@@ -38,6 +60,15 @@ impl Grid {
         }
     }
     fn play_round(&mut self) -> Option<SumOfRemainingHitPoints> {
+        self.play_logged_round(ATTACK_POWER, &mut vec![])
+    }
+    /// Plays a round, appending what happens to the given events. Returns the outcome
+    /// if combat ended during this round, in which case the round doesn't count.
+    fn play_logged_round(
+        &mut self,
+        elf_attack_power: HitPoints,
+        events: &mut Vec<Event>,
+    ) -> Option<SumOfRemainingHitPoints> {
         let mut units = self.unit_locations();
         while !units.is_empty() {
             let mut unit = units.remove(0);
@@ -48,96 +79,117 @@ impl Grid {
             }
             // 1. Optionally move 1 step toward the closest enemy
             if !unit.is_in_range_of_any(&enemies) {
+                let from = unit.clone();
                 let did_move = self.try_to_move(&mut unit, &enemies);
                 if !did_move {
                     // If the unit cannot reach (find an open path to) any of the squares
                     // that are in range, it ends its turn.
                     continue;
                 }
+                let to = unit.clone();
+                events.push(Event::UnitMoved { from, to });
             }
             // 2. Attack if there's an enemy in range
             if unit.is_in_range_of_any(&enemies) {
                 let enemy = self.lowest_hp_adjacent_enemy_of(&mut unit);
-                let enemy_killed = self.attack(&enemy, ATTACK_POWER);
+                let attack_power = if self.is_elf_at(&enemy) {
+                    ATTACK_POWER
+                } else {
+                    elf_attack_power
+                };
+                let enemy_killed = self.attack(&enemy, attack_power);
+                let hp_left = if enemy_killed {
+                    0
+                } else {
+                    self.hp_of_unit_at(&enemy)
+                };
+                events.push(Event::UnitAttacked {
+                    attacker: unit.clone(),
+                    target: enemy.clone(),
+                    hp_left,
+                });
                 if enemy_killed {
                     // Remove the killed unit from the units to be handled if it's still there
                     if let Some(pos) = units.iter().position(|loc| loc == &enemy) {
                         units.remove(pos);
                     }
+                    events.push(Event::UnitDied { at: enemy });
                 }
             }
         }
         self.rounds += 1;
+        events.push(Event::RoundEnded { round: self.rounds });
         None
     }
-    pub(crate) fn play_with_increasing_elf_attack_power_until_elves_win_without_a_single_loss(
-        &mut self,
-    ) -> usize {
-        let rows_backup = self.rows.clone();
-        let mut elf_attack_power: HitPoints = 3;
-        loop {
-            elf_attack_power += 1;
-            if let Some(sum_of_remaining_hit_points) =
-                self.play_until_all_an_elf_dies_or_all_goblins_are_dead(elf_attack_power)
-            {
-                // println!(
-                //     "{} elves won after {} rounds with {} attack power",
-                //     self.elves().len(),
-                //     self.rounds,
-                //     elf_attack_power,
-                // );
-                return sum_of_remaining_hit_points;
+    /// Plays a copy of this grid until combat ends, recording everything that happens
+    pub fn record_combat(&self, elf_attack_power: HitPoints) -> Replay {
+        let mut grid = self.clone();
+        let mut events = vec![];
+        while grid
+            .play_logged_round(elf_attack_power, &mut events)
+            .is_none()
+        {}
+        Replay {
+            initial: self.clone(),
+            events,
+        }
+    }
+    fn apply(&mut self, event: &Event) {
+        match event {
+            Event::UnitMoved { from, to } => self.move_unit(from, to),
+            Event::UnitAttacked {
+                target, hp_left, ..
+            } => {
+                if let Tile::Elf(hp) | Tile::Goblin(hp) = &mut self.rows[target.y][target.x] {
+                    *hp = *hp_left;
+                }
             }
-            // reset
-            self.rows = rows_backup.clone();
-            self.rounds = 0;
+            Event::UnitDied { at } => self.rows[at.y][at.x] = Tile::Open,
+            Event::RoundEnded { round } => self.rounds = *round,
         }
     }
-    fn play_until_all_an_elf_dies_or_all_goblins_are_dead(
+    pub fn play_with_increasing_elf_attack_power_until_elves_win_without_a_single_loss(
+        &self,
+    ) -> usize {
+        // A larger attack power can change who attacks whom, and let an elf die after all,
+        // so the first win is only found by trying every attack power. Bisecting and then
+        // verifying monotonicity would try all smaller attack powers just the same.
+        let search = SmallestWinningParameter::starting_at(ATTACK_POWER as usize + 1)
+            .trying_every_parameter();
+        self.smallest_elf_attack_power_without_a_single_loss(search)
+            .expect("an attack power with which the elves win")
+            .value
+    }
+    /// The search is limited to the initial hit points: if the elves can't win by killing
+    /// with every hit, they never will.
+    fn smallest_elf_attack_power_without_a_single_loss(
+        &self,
+        search: SmallestWinningParameter,
+    ) -> Result<Winner<SumOfRemainingHitPoints>, SearchError> {
+        search.up_to(INITIAL_HP as usize).find(|elf_attack_power| {
+            self.clone()
+                .play_until_an_elf_dies_or_all_goblins_are_dead(elf_attack_power as HitPoints)
+        })
+    }
+    fn play_until_an_elf_dies_or_all_goblins_are_dead(
         &mut self,
         elf_attack_power: HitPoints,
-    ) -> Option<SumOfRemainingHitPoints> {
+    ) -> Outcome<SumOfRemainingHitPoints> {
+        let elf_count = self.elves().len();
+        let mut events = vec![];
         loop {
-            let mut units = self.unit_locations();
-            while !units.is_empty() {
-                let mut unit = units.remove(0);
-                let enemies = self.enemies_of(&unit);
-                if enemies.is_empty() {
-                    // If no more enemies remain, combat ends
-                    return Some(self.rounds * self.sum_of_remaining_hit_points());
-                }
-                // 1. Optionally move 1 step toward the closest enemy
-                if !unit.is_in_range_of_any(&enemies) {
-                    let did_move = self.try_to_move(&mut unit, &enemies);
-                    if !did_move {
-                        // If the unit cannot reach (find an open path to) any of the squares
-                        // that are in range, it ends its turn.
-                        continue;
-                    }
-                }
-                // 2. Attack if there's an enemy in range
-                if unit.is_in_range_of_any(&enemies) {
-                    let enemy = self.lowest_hp_adjacent_enemy_of(&mut unit);
-                    let enemy_is_elf = self.is_elf_at(&enemy);
-                    let attack_power = if enemy_is_elf {
-                        ATTACK_POWER
-                    } else {
-                        elf_attack_power
-                    };
-                    let enemy_killed = self.attack(&enemy, attack_power);
-                    if enemy_killed {
-                        if enemy_is_elf {
-                            // If an elf died, start over
-                            return None;
-                        }
-                        // Remove the killed unit from the units to be handled if it's still there
-                        if let Some(pos) = units.iter().position(|loc| loc == &enemy) {
-                            units.remove(pos);
-                        }
-                    }
-                }
+            events.clear();
+            let outcome = self.play_logged_round(elf_attack_power, &mut events);
+            if self.elves().len() < elf_count {
+                return Outcome::Loss;
+            }
+            if let Some(sum_of_remaining_hit_points) = outcome {
+                return Outcome::Win(sum_of_remaining_hit_points);
+            }
+            if events == [Event::RoundEnded { round: self.rounds }] {
+                // Nobody moved or attacked, so nothing will ever change
+                return Outcome::Stalemate;
             }
-            self.rounds += 1;
         }
     }
     fn unit_locations(&self) -> Vec<Loc> {
@@ -273,17 +325,28 @@ impl Grid {
     fn height(&self) -> usize {
         self.rows.len()
     }
-    #[allow(dead_code)]
-    fn print_unit_health(&self) {
-        println!(
-            "Round {}: {:?}",
-            self.rounds,
-            self.unit_locations()
-                .iter()
-                .map(|loc| format!("{:?}: {}", loc, self.hp_of_unit_at(loc)))
-                .collect::<Vec<_>>()
-                .join(", ")
-        );
+    /// The grid in the format of the puzzle's examples, with each row followed by
+    /// the hit points of the units in that row
+    pub fn frame(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| {
+                let tiles: String = row.iter().map(Tile::to_string).collect();
+                let units: Vec<_> = row
+                    .iter()
+                    .filter_map(|tile| match tile {
+                        Tile::Elf(hp) | Tile::Goblin(hp) => {
+                            Some(format!("{}({})", tile.to_string(), hp))
+                        }
+                        Tile::Wall | Tile::Open => None,
+                    })
+                    .collect();
+                format!("{}   {}", tiles, units.join(", "))
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
     fn hp_of_unit_at(&self, loc: &Loc) -> HitPoints {
         match &self.rows[loc.y][loc.x] {
@@ -351,8 +414,69 @@ impl From<&Vec<&str>> for Grid {
         }
     }
 }
+/// A recorded combat, from which the grid can be reconstructed as it was after any round
+#[derive(Debug)]
+pub struct Replay {
+    initial: Grid,
+    events: Vec<Event>,
+}
+
+impl Replay {
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+    /// The number of full rounds, not counting the one during which combat ended
+    pub fn rounds(&self) -> RoundCount {
+        self.events
+            .iter()
+            .filter(|event| matches!(event, Event::RoundEnded { .. }))
+            .count()
+    }
+    pub fn grid_after(&self, round: RoundCount) -> Option<Grid> {
+        if round > self.rounds() {
+            return None;
+        }
+        let mut grid = self.initial.clone();
+        for event in self.events.iter() {
+            if grid.rounds == round {
+                break;
+            }
+            grid.apply(event);
+        }
+        Some(grid)
+    }
+    /// The grid at the end of combat, including the last, partial round
+    pub fn final_grid(&self) -> Grid {
+        let mut grid = self.initial.clone();
+        self.events.iter().for_each(|event| grid.apply(event));
+        grid
+    }
+    pub fn outcome(&self) -> usize {
+        self.rounds() * self.final_grid().sum_of_remaining_hit_points()
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Event::UnitMoved { from, to } => write!(f, "{:?} moves to {:?}", from, to),
+            Event::UnitAttacked {
+                attacker,
+                target,
+                hp_left,
+            } => write!(
+                f,
+                "{:?} attacks {:?}, leaving it with {} HP",
+                attacker, target, hp_left
+            ),
+            Event::UnitDied { at } => write!(f, "{:?} dies", at),
+            Event::RoundEnded { round } => write!(f, "Round {} ends", round),
+        }
+    }
+}
+
 #[derive(PartialEq, Clone)]
-struct Loc {
+pub struct Loc {
     x: Coord,
     y: Coord,
 }
@@ -774,6 +898,183 @@ mod tests {
         assert_eq!(27_730, sum_of_remaining_hit_points);
     }
 
+    // The frames shown in the puzzle description for COMBAT_EXAMPLE
+    const COMBAT_EXAMPLE_FRAMES: [(RoundCount, &str); 10] = [
+        (
+            0,
+            "\
+#######
+#.G...#   G(200)
+#...EG#   E(200), G(200)
+#.#.#G#   G(200)
+#..G#E#   G(200), E(200)
+#.....#
+#######",
+        ),
+        (
+            1,
+            "\
+#######
+#..G..#   G(200)
+#...EG#   E(197), G(197)
+#.#G#G#   G(200), G(197)
+#...#E#   E(197)
+#.....#
+#######",
+        ),
+        (
+            2,
+            "\
+#######
+#...G.#   G(200)
+#..GEG#   G(200), E(188), G(194)
+#.#.#G#   G(194)
+#...#E#   E(194)
+#.....#
+#######",
+        ),
+        (
+            23,
+            "\
+#######
+#...G.#   G(200)
+#..G.G#   G(200), G(131)
+#.#.#G#   G(131)
+#...#E#   E(131)
+#.....#
+#######",
+        ),
+        (
+            24,
+            "\
+#######
+#..G..#   G(200)
+#...G.#   G(131)
+#.#G#G#   G(200), G(128)
+#...#E#   E(128)
+#.....#
+#######",
+        ),
+        (
+            25,
+            "\
+#######
+#.G...#   G(200)
+#..G..#   G(131)
+#.#.#G#   G(125)
+#..G#E#   G(200), E(125)
+#.....#
+#######",
+        ),
+        (
+            26,
+            "\
+#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(122)
+#...#E#   E(122)
+#..G..#   G(200)
+#######",
+        ),
+        (
+            27,
+            "\
+#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(119)
+#...#E#   E(119)
+#...G.#   G(200)
+#######",
+        ),
+        (
+            28,
+            "\
+#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(116)
+#...#E#   E(113)
+#....G#   G(200)
+#######",
+        ),
+        (
+            47,
+            "\
+#######
+#G....#   G(200)
+#.G...#   G(131)
+#.#.#G#   G(59)
+#...#.#
+#....G#   G(200)
+#######",
+        ),
+    ];
+
+    #[test]
+    fn combat_example_replay_matches_published_frames() {
+        let replay = Grid::from(&parse(COMBAT_EXAMPLE)).record_combat(ATTACK_POWER);
+        assert_eq!(47, replay.rounds());
+        for (round, frame) in COMBAT_EXAMPLE_FRAMES {
+            let grid = replay.grid_after(round).unwrap();
+            assert_eq!(round, grid.rounds);
+            assert_eq!(frame, grid.frame(), "after {} rounds", round);
+        }
+        assert_eq!(None, replay.grid_after(48));
+    }
+
+    #[test]
+    fn combat_example_replay_matches_simulation() {
+        let mut grid = Grid::from(&parse(COMBAT_EXAMPLE));
+        let replay = grid.record_combat(ATTACK_POWER);
+        assert_eq!(grid.play_until_no_enemies_remain(), replay.outcome());
+        assert_eq!(grid, replay.final_grid());
+        for round in 0..=replay.rounds() {
+            let mut expected = Grid::from(&parse(COMBAT_EXAMPLE));
+            (0..round).for_each(|_| assert_eq!(None, expected.play_round()));
+            assert_eq!(Some(expected), replay.grid_after(round));
+        }
+    }
+
+    #[test]
+    fn combat_example_first_round_events() {
+        let replay = Grid::from(&parse(COMBAT_EXAMPLE)).record_combat(ATTACK_POWER);
+        let log: Vec<_> = replay
+            .events()
+            .iter()
+            .take_while(|event| event != &&Event::RoundEnded { round: 1 })
+            .map(Event::to_string)
+            .collect();
+        assert_eq!(
+            vec![
+                "(2,1) moves to (3,1)",
+                "(4,2) attacks (5,2), leaving it with 197 HP",
+                "(5,2) attacks (4,2), leaving it with 197 HP",
+                "(5,3) attacks (5,4), leaving it with 197 HP",
+                "(3,4) moves to (3,3)",
+                "(5,4) attacks (5,3), leaving it with 197 HP",
+            ],
+            log
+        );
+    }
+
+    #[test]
+    fn elf_deaths_are_logged() {
+        let replay = Grid::from(&parse(COMBAT_EXAMPLE)).record_combat(ATTACK_POWER);
+        let deaths: Vec<_> = replay
+            .events()
+            .iter()
+            .filter(|event| matches!(event, Event::UnitDied { .. }))
+            .cloned()
+            .collect();
+        let elf_deaths = vec![
+            Event::UnitDied { at: Loc::new(4, 2) },
+            Event::UnitDied { at: Loc::new(5, 4) },
+        ];
+        assert_eq!(elf_deaths, deaths);
+    }
+
     #[test]
     fn combat_example_2() {
         assert_eq!(
@@ -963,6 +1264,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn part2_smallest_elf_attack_power_for_combat_example() {
+        let grid = Grid::from(&parse(COMBAT_EXAMPLE));
+        let search = SmallestWinningParameter::starting_at(4).trying_every_parameter();
+        let winner = grid
+            .smallest_elf_attack_power_without_a_single_loss(search)
+            .unwrap();
+        assert_eq!(15, winner.parameter);
+        assert_eq!(4_988, winner.value);
+        // Bisecting happens to find the same attack power for this example
+        let search = SmallestWinningParameter::starting_at(4);
+        let bisected = grid
+            .smallest_elf_attack_power_without_a_single_loss(search)
+            .unwrap();
+        assert_eq!(15, bisected.parameter);
+    }
+
+    #[test]
+    fn part2_stalemate_when_elves_cannot_reach_goblins() {
+        let mut grid = Grid::from(&parse(
+            "\
+#######
+#E.#.G#
+#######",
+        ));
+        assert_eq!(
+            Outcome::Stalemate,
+            grid.play_until_an_elf_dies_or_all_goblins_are_dead(ATTACK_POWER)
+        );
+        let search = SmallestWinningParameter::starting_at(4);
+        assert_eq!(
+            Err(SearchError::NoWinUpTo(INITIAL_HP as usize)),
+            grid.smallest_elf_attack_power_without_a_single_loss(search)
+        );
+    }
+
     #[test] // pretty slow at 2min 44s
     fn part2() {
         assert_eq!(49_120, day15_part2());
@@ -1,6 +1,7 @@
 use std::cmp::Ordering;
 
 use crate::parse;
+use advent_of_code_2018::search::{Outcome, SearchError, SmallestWinningParameter, Winner};
 
 const INPUT: &str = include_str!("../input/day24.txt");
 
@@ -163,7 +164,6 @@ impl Army {
             .filter(|(_, g)| g.count > 0)
             .for_each(|(i, g)| println!("Group {} contains {} units", i + 1, g.count));
     }
-    #[allow(unused)]
     fn apply_damage_boost(&mut self, damage_boost: usize) {
         self.groups.iter_mut().for_each(|g| {
             g.attack_damage += damage_boost;
@@ -235,21 +235,42 @@ impl GroupId {
 
 fn fight_until_one_army_left(lines: Vec<&str>) -> usize {
     let (army1, army2) = parse_input(lines);
-    fight_armies_until_only_one_left(army1, army2).unit_count()
+    fight_armies_until_only_one_left(army1, army2)
+        .expect("a winner without any boost")
+        .unit_count()
 }
 
-#[allow(unused)]
 fn fight_until_army1_wins_with_smallest_possible_boost(lines: Vec<&str>) -> usize {
-    let (orig_army1, orig_army2) = parse_input(lines);
-    let mut damage_boost = 1;
-    loop {
-        let (mut army1, army2) = (orig_army1.clone(), orig_army2.clone());
-        army1.apply_damage_boost(damage_boost);
-        let army = fight_armies_until_only_one_left(army1, army2);
-        if army.name.eq(&orig_army1.name) {
-            return army.unit_count();
-        }
-        damage_boost += 1;
+    let (army1, army2) = parse_input(lines);
+    // Boosts causing a stalemate may sit between losing and winning ones,
+    // so the first win is only found by trying every boost. Bisecting and then verifying
+    // monotonicity would try all smaller boosts just the same.
+    let search = SmallestWinningParameter::starting_at(1).trying_every_parameter();
+    smallest_winning_boost(&army1, &army2, search)
+        .expect("a boost with which army1 wins")
+        .value
+}
+
+fn smallest_winning_boost(
+    army1: &Army,
+    army2: &Army,
+    search: SmallestWinningParameter,
+) -> Result<Winner<usize>, SearchError> {
+    // With a boost large enough to kill a whole group with one attack, army1 either wins,
+    // or the other army is immune to it
+    let largest_useful_boost = army2.groups.iter().map(|g| g.count * g.hp).sum();
+    search
+        .up_to(largest_useful_boost)
+        .find(|damage_boost| fight_with_boost(army1, army2, damage_boost))
+}
+
+fn fight_with_boost(army1: &Army, army2: &Army, damage_boost: usize) -> Outcome<usize> {
+    let mut boosted = army1.clone();
+    boosted.apply_damage_boost(damage_boost);
+    match fight_armies_until_only_one_left(boosted, army2.clone()) {
+        Some(winner) if winner.name == army1.name => Outcome::Win(winner.unit_count()),
+        Some(_) => Outcome::Loss,
+        None => Outcome::Stalemate,
     }
 }
 
@@ -257,7 +278,9 @@ fn fight_until_army1_wins_with_smallest_possible_boost(lines: Vec<&str>) -> usiz
 fn fight_until_one_army_left_with_boost(lines: Vec<&str>, army1_damage_boost: usize) -> usize {
     let (mut army1, army2) = parse_input(lines);
     army1.apply_damage_boost(army1_damage_boost);
-    fight_armies_until_only_one_left(army1, army2).unit_count()
+    fight_armies_until_only_one_left(army1, army2)
+        .expect("a winner with this boost")
+        .unit_count()
 }
 
 fn parse_input(lines: Vec<&str>) -> (Army, Army) {
@@ -266,7 +289,8 @@ fn parse_input(lines: Vec<&str>) -> (Army, Army) {
     (army1, army2)
 }
 
-fn fight_armies_until_only_one_left(mut army1: Army, mut army2: Army) -> Army {
+/// The army left standing, or None if neither can kill any more units of the other
+fn fight_armies_until_only_one_left(mut army1: Army, mut army2: Army) -> Option<Army> {
     let mut counts = (army1.unit_count(), army2.unit_count());
     while army1.unit_count() > 0 && army2.unit_count() > 0 {
         // println!("\n--------------------------\n");
@@ -290,18 +314,18 @@ fn fight_armies_until_only_one_left(mut army1: Army, mut army2: Army) -> Army {
             // print_fight_details(attacker, defender, defending_group, killed);
         }
         if counts == (army1.unit_count(), army2.unit_count()) {
-            // Infinite loop, bail!
-            return army2;
+            // Stalemate, this would go on forever
+            return None;
         } else {
             counts = (army1.unit_count(), army2.unit_count());
         }
     }
     if army1.unit_count() > 0 {
         // army1.print_summary();
-        army1
+        Some(army1)
     } else {
         // army2.print_summary();
-        army2
+        Some(army2)
     }
 }
 
@@ -472,6 +496,22 @@ Infection:
         assert_eq!(51, unit_count_of_winning_army);
     }
 
+    #[test]
+    fn part2_example_smallest_boost() {
+        let (army1, army2) = parse_input(parse(EXAMPLE));
+        let search = SmallestWinningParameter::starting_at(1).trying_every_parameter();
+        let winner = smallest_winning_boost(&army1, &army2, search).unwrap();
+        assert_eq!(1570, winner.parameter);
+        assert_eq!(51, winner.value);
+    }
+
+    #[test]
+    fn part2_example_boost_outcomes() {
+        let (army1, army2) = parse_input(parse(EXAMPLE));
+        assert_eq!(Outcome::Loss, fight_with_boost(&army1, &army2, 1569));
+        assert_eq!(Outcome::Win(51), fight_with_boost(&army1, &army2, 1570));
+    }
+
     #[test]
     fn part2() {
         assert_eq!(935 + 857 + 2535 /* 4327 */, day24_part2());
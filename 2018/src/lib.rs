@@ -0,0 +1,7 @@
+pub mod day13;
+pub mod day15;
+pub mod search;
+
+pub fn parse(input: &str) -> Vec<&str> {
+    input.lines().collect()
+}
@@ -1,9 +1,7 @@
 mod device;
 mod opcode;
 
-fn parse(input: &str) -> Vec<&str> {
-    input.lines().collect()
-}
+use advent_of_code_2018::parse;
 
 use crate::day01::{day1_part1, day1_part2};
 mod day01;
@@ -88,8 +86,7 @@ fn day12() {
     assert_eq!(1_600_000_000_328, day12_part2());
 }
 
-use crate::day13::{day13_part1, day13_part2};
-mod day13;
+use advent_of_code_2018::day13::{day13_part1, day13_part2};
 fn day13() {
     assert_eq!((102, 114), day13_part1());
     assert_eq!((146, 87), day13_part2());
@@ -102,8 +99,7 @@ fn day14() {
     assert_eq!(20_177_474, day14_part2());
 }
 
-use crate::day15::{day15_part1, day15_part2};
-mod day15;
+use advent_of_code_2018::day15::{day15_part1, day15_part2};
 fn day15() {
     assert_eq!(207_059, day15_part1());
     assert_eq!(49_120, day15_part2());
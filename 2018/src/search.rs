@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+/// How a simulation played with a given parameter ended, from the point of view
+/// of the side whose parameter is searched
+#[derive(Debug, PartialEq, Clone)]
+pub enum Outcome<T> {
+    Win(T),
+    Loss,
+    /// Neither side can make progress anymore, so it would go on forever
+    Stalemate,
+}
+
+impl<T> Outcome<T> {
+    fn is_win(&self) -> bool {
+        matches!(self, Outcome::Win(_))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Winner<T> {
+    pub parameter: usize,
+    pub value: T,
+    /// All parameters tried along the way that ended in a stalemate
+    pub stalemates: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SearchError {
+    NoWinUpTo(usize),
+    /// Winning with a parameter doesn't mean also winning with any larger one
+    NotMonotonic {
+        win: usize,
+        no_win: usize,
+    },
+}
+
+/// Searches the smallest parameter, such as an attack power or a boost, for which a simulation
+/// is won. The steps are doubled until the first win, and the gap to the last non-win is then
+/// bisected. This assumes that increasing the parameter never turns a win into a loss,
+/// which can optionally be verified by also trying all smaller parameters.
+/// Where it doesn't hold, every parameter can be tried in order instead.
+#[derive(Debug)]
+pub struct SmallestWinningParameter {
+    start: usize,
+    limit: usize,
+    verify_monotonicity: bool,
+    try_every_parameter: bool,
+}
+
+impl SmallestWinningParameter {
+    pub fn starting_at(start: usize) -> Self {
+        SmallestWinningParameter {
+            start,
+            limit: usize::MAX,
+            verify_monotonicity: false,
+            try_every_parameter: false,
+        }
+    }
+    /// Gives up if there's no win with any parameter up to and including the limit
+    pub fn up_to(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+    /// Fails if any parameter smaller than the one found wins as well. This tries every smaller
+    /// parameter, so it's no cheaper than trying every parameter in the first place.
+    pub fn verifying_monotonicity(mut self) -> Self {
+        self.verify_monotonicity = true;
+        self
+    }
+    /// Tries one parameter after the other, for outcomes where a larger parameter
+    /// can turn a win back into a loss or stalemate
+    pub fn trying_every_parameter(mut self) -> Self {
+        self.try_every_parameter = true;
+        self
+    }
+
+    pub fn find<T>(
+        &self,
+        mut play: impl FnMut(usize) -> Outcome<T>,
+    ) -> Result<Winner<T>, SearchError> {
+        let mut outcomes = BTreeMap::new();
+        let mut outcome_of = |parameter: usize| -> bool {
+            outcomes
+                .entry(parameter)
+                .or_insert_with(|| play(parameter))
+                .is_win()
+        };
+
+        // Exponential search for a winning upper bound, or a linear one
+        let (mut no_win, mut win) = (None, self.start);
+        let mut step = 1;
+        while !outcome_of(win) {
+            if win == self.limit {
+                return Err(SearchError::NoWinUpTo(self.limit));
+            }
+            no_win = Some(win);
+            win = win.saturating_add(step).min(self.limit);
+            if !self.try_every_parameter {
+                step = step.saturating_mul(2);
+            }
+        }
+
+        // Binary search between the last non-win and the first win
+        if let Some(mut no_win) = no_win {
+            while win - no_win > 1 {
+                let mid = no_win + (win - no_win) / 2;
+                if outcome_of(mid) {
+                    win = mid;
+                } else {
+                    no_win = mid;
+                }
+            }
+        }
+
+        if self.verify_monotonicity {
+            if let Some(smaller) = (self.start..win).find(|&parameter| outcome_of(parameter)) {
+                return Err(SearchError::NotMonotonic {
+                    win: smaller,
+                    no_win: win - 1,
+                });
+            }
+        }
+
+        let stalemates = outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, Outcome::Stalemate))
+            .map(|(parameter, _)| *parameter)
+            .collect();
+        let Some(Outcome::Win(value)) = outcomes.remove(&win) else {
+            unreachable!("the parameter {} was found to win", win);
+        };
+        Ok(Winner {
+            parameter: win,
+            value,
+            stalemates,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threshold(min_win: usize) -> impl FnMut(usize) -> Outcome<usize> {
+        move |parameter| {
+            if parameter >= min_win {
+                Outcome::Win(parameter * 10)
+            } else {
+                Outcome::Loss
+            }
+        }
+    }
+
+    #[test]
+    fn finds_smallest_win_with_few_tries() {
+        for min_win in [0, 1, 2, 3, 7, 8, 9, 100, 1_000_000] {
+            let mut tries = 0;
+            let mut play = threshold(min_win);
+            let winner = SmallestWinningParameter::starting_at(0)
+                .find(|parameter| {
+                    tries += 1;
+                    play(parameter)
+                })
+                .unwrap();
+            assert_eq!(min_win, winner.parameter);
+            assert_eq!(min_win * 10, winner.value);
+            assert!(tries <= 2 * (min_win + 2).ilog2() as usize + 2, "{tries}");
+        }
+    }
+
+    #[test]
+    fn starts_at_the_given_parameter() {
+        let winner = SmallestWinningParameter::starting_at(4)
+            .find(threshold(2))
+            .unwrap();
+        assert_eq!(4, winner.parameter);
+    }
+
+    #[test]
+    fn gives_up_at_the_limit() {
+        let search = SmallestWinningParameter::starting_at(1).up_to(50);
+        assert_eq!(Err(SearchError::NoWinUpTo(50)), search.find(threshold(51)));
+        assert_eq!(50, search.find(threshold(50)).unwrap().parameter);
+    }
+
+    #[test]
+    fn reports_stalemates() {
+        let play = |parameter| match parameter {
+            0..=9 => Outcome::Loss,
+            10..=14 => Outcome::Stalemate,
+            _ => Outcome::Win(()),
+        };
+        let winner = SmallestWinningParameter::starting_at(1)
+            .trying_every_parameter()
+            .find(play)
+            .unwrap();
+        assert_eq!(15, winner.parameter);
+        assert_eq!(vec![10, 11, 12, 13, 14], winner.stalemates);
+    }
+
+    #[test]
+    fn tries_every_parameter_for_non_monotonic_outcomes() {
+        // Wins at 3, but not again until 9
+        let play = |parameter| match parameter {
+            3 | 9.. => Outcome::Win(()),
+            _ => Outcome::Loss,
+        };
+        let search = SmallestWinningParameter::starting_at(1);
+        assert_eq!(9, search.find(play).unwrap().parameter);
+        let mut tries = 0;
+        let winner = search
+            .trying_every_parameter()
+            .find(|parameter| {
+                tries += 1;
+                play(parameter)
+            })
+            .unwrap();
+        assert_eq!(3, winner.parameter);
+        assert_eq!(3, tries);
+    }
+
+    #[test]
+    fn detects_non_monotonic_outcomes() {
+        // Wins at 3, but not again until 9
+        let play = |parameter| match parameter {
+            3 | 9.. => Outcome::Win(()),
+            _ => Outcome::Loss,
+        };
+        let search = SmallestWinningParameter::starting_at(1).verifying_monotonicity();
+        assert_eq!(
+            Err(SearchError::NotMonotonic { win: 3, no_win: 8 }),
+            search.find(play)
+        );
+        assert_eq!(9, search.find(threshold(9)).unwrap().parameter);
+    }
+}
@@ -1,178 +1,68 @@
 use intcode::IntCodeComputer;
 use std::collections::HashMap;
+use terminal::{Animation, Color, Frame, Settings, Viewport};
 
-// Coffee example
-
-use coffee::graphics::{Color, Frame, Mesh, Point, Rectangle, Shape, Window, WindowSettings};
-use coffee::load::Task;
-use coffee::{Game, Result, Timer};
-use std::ops::RangeInclusive;
-
-const DARK_RED: Color = Color {
-    r: 0.5,
-    g: 0.0,
-    b: 0.0,
-    a: 1.0,
-};
-const RED: Color = Color {
-    r: 1.0,
-    g: 0.0,
-    b: 0.0,
-    a: 1.0,
-};
-const GREEN: Color = Color {
-    r: 0.25,
-    g: 1.0,
-    b: 0.25,
-    a: 1.0,
-};
-const BLUE: Color = Color {
-    r: 0.25,
-    g: 0.25,
-    b: 1.0,
-    a: 1.0,
-};
-const LIGHT_BLUE: Color = Color {
-    r: 0.5,
-    g: 0.5,
-    b: 1.0,
-    a: 1.0,
-};
-const GREY: Color = Color {
-    r: 0.5,
-    g: 0.5,
-    b: 0.5,
-    a: 1.0,
-};
-
-fn main() -> Result<()> {
-    RepairDroid::run(WindowSettings {
-        title: String::from("Advent of code 2019 day 15"),
-        size: (1200, 1200),
-        resizable: true,
-        fullscreen: false,
-    })
+fn main() -> std::io::Result<()> {
+    let settings = Settings::from_args("Advent of code 2019 day 15", 60);
+    settings.run(&mut RepairDroid::new(day_15_puzzle_input()))?;
+    Ok(())
 }
 
-impl Game for RepairDroid {
-    type Input = (); // No input data
-    type LoadingScreen = (); // No loading screen
-
-    fn load(_window: &Window) -> Task<RepairDroid> {
-        // Load your game assets here. Check out the `load` module!
-        Task::new(|| RepairDroid::new(day_15_puzzle_input()))
+impl Animation for RepairDroid {
+    fn update(&mut self) -> bool {
+        match self.droid_state {
+            DroidState::Exploring => {
+                self.explore();
+                true
+            }
+            DroidState::FullyExplored
+            | DroidState::PathFinding
+            | DroidState::ShortestPathFound
+            | DroidState::Oxygenating => self.find_shortest_path(),
+            DroidState::FullyOxygenated => false, // done
+        }
     }
 
-    fn draw(&mut self, frame: &mut Frame, _timer: &Timer) {
-        let ct = CoordinateTransformation::from_points(
-            frame.width(),
-            frame.height(),
-            &self.board_state.keys().collect::<Vec<&Point2D>>(),
-        );
-
-        frame.clear(GREY);
-        let mut mesh = Mesh::new();
+    fn draw(&self) -> Frame {
+        let viewport = Viewport::from_points(self.board_state.keys().map(|p| (p.0, p.1)), true);
+        let mut frame = Frame::new(viewport, Color::GREY);
         for (pos, state) in self.board_state.iter() {
-            match state {
-                BoardState::Clear => mesh.fill(ct.square_at(pos), Color::WHITE),
-                BoardState::Wall => mesh.fill(ct.square_at(pos), Color::BLACK),
-                BoardState::OxygenSystem => mesh.fill(ct.square_at(pos), BLUE),
-                BoardState::Unexplored => (),
-                BoardState::ShortestPath => mesh.fill(ct.square_at(pos), RED),
-                BoardState::Searched => mesh.fill(ct.square_at(pos), DARK_RED),
-                BoardState::Oxygenated => mesh.fill(ct.square_at(pos), LIGHT_BLUE),
-                BoardState::OxygenatedPath => mesh.fill(ct.square_at(pos), BLUE),
-            }
+            let (symbol, color) = match state {
+                BoardState::Clear => ('.', Color::WHITE),
+                BoardState::Wall => ('#', Color::BLACK),
+                BoardState::OxygenSystem => ('O', Color::BLUE),
+                BoardState::Unexplored => continue,
+                BoardState::ShortestPath => ('*', Color::RED),
+                BoardState::Searched => ('-', Color::DARK_RED),
+                BoardState::Oxygenated => ('~', Color::LIGHT_BLUE),
+                BoardState::OxygenatedPath => ('=', Color::BLUE),
+            };
+            frame.draw(pos.0, pos.1, symbol, color);
         }
-        let origin = ct.square_at(&Point2D::default());
-        mesh.fill(origin, GREEN);
+        frame.draw(0, 0, 'S', Color::GREEN);
 
         if let Some(oxygen_system) = self.goal_pos {
-            let oxygen_system = ct.square_at(&oxygen_system);
-            mesh.fill(oxygen_system, BLUE);
+            frame.draw(oxygen_system.0, oxygen_system.1, 'O', Color::BLUE);
         }
 
         if self.droid_state == DroidState::Exploring {
             // draw robot while exploring
-            let robot = ct.robot_at(&self.curr_pos, &self.dir);
-            mesh.fill(robot, RED);
+            frame.draw(
+                self.curr_pos.0,
+                self.curr_pos.1,
+                self.dir.arrow(),
+                Color::RED,
+            );
         }
-        mesh.draw(&mut frame.as_target());
-    }
-
-    fn update(&mut self, _window: &Window) {
-        match self.droid_state {
-            DroidState::Exploring => self.explore(),
-            DroidState::FullyExplored
-            | DroidState::PathFinding
-            | DroidState::ShortestPathFound
-            | DroidState::Oxygenating => self.find_shortest_path(),
-            DroidState::FullyOxygenated => (), // done
+        let mut status = format!("{:?}", self.droid_state);
+        if let Some(len) = self.shortest_path_len {
+            status += &format!(", shortest path has length {}", len);
         }
-    }
-}
-struct CoordinateTransformation {
-    x_range: RangeInclusive<f32>,
-    y_range: RangeInclusive<f32>,
-    tile_size: f32,
-}
-impl CoordinateTransformation {
-    fn from_points(w: f32, h: f32, points: &[&Point2D]) -> Self {
-        let min_x = points.iter().map(|p| p.0).min().unwrap() as f32;
-        let max_x = points.iter().map(|p| p.0).max().unwrap() as f32;
-        let min_y = points.iter().map(|p| p.1).min().unwrap() as f32;
-        let max_y = points.iter().map(|p| p.1).max().unwrap() as f32;
-        let x_range = min_x..=max_x;
-        let y_range = min_y..=max_y;
-
-        let board_width = (max_x - min_x) + 1.0;
-        let board_height = (max_y - min_y) + 1.0;
-        let tile_size = f32::min(w / board_width, h / board_height);
-        /*
-        println!(
-            "Board: w: {}, h: {}, x: {} to {}, y: {} to {}, tile_size: {}",
-            board_width, board_height, min_x, max_x, min_y, max_y, tile_size
-        );
-        */
-        CoordinateTransformation {
-            x_range,
-            y_range,
-            tile_size,
+        if let Some(ticks) = self.oxygenation_ticks {
+            status += &format!(", oxygenation took {} ticks", ticks);
         }
-    }
-    fn square_at(&self, pos: &Point2D) -> Shape {
-        let top_left = self.point_at(pos);
-        Shape::Rectangle(Rectangle {
-            x: top_left.x,
-            y: top_left.y,
-            width: self.tile_size,
-            height: self.tile_size,
-        })
-    }
-    fn point_at(&self, pos: &Point2D) -> Point {
-        Point::new(
-            self.tile_size * (pos.0 as f32 - *self.x_range.start()),
-            self.tile_size * (self.y_range.end() - pos.1 as f32),
-        )
-    }
-    fn robot_at(&self, pos: &Point2D, dir: &MovementCommand) -> Shape {
-        let top_left = self.point_at(pos);
-        let top_right = self.point_at(&pos.offset_by(1, 0));
-        let bottom_left = self.point_at(&pos.offset_by(0, 1));
-        let bottom_right = self.point_at(&pos.offset_by(1, 1));
-        let top = Point::new((top_left.x + top_right.x) / 2.0, top_left.y);
-        let bottom = Point::new((bottom_left.x + bottom_right.x) / 2.0, bottom_left.y);
-        let left = Point::new(top_left.x, (top_left.y + bottom_left.y) / 2.0);
-        let right = Point::new(top_right.x, (top_right.y + bottom_right.y) / 2.0);
-
-        let points = match dir {
-            MovementCommand::North => vec![top, left, bottom_left, bottom_right, right],
-            MovementCommand::South => vec![bottom, left, top_left, top_right, right],
-            MovementCommand::West => vec![left, top, top_right, bottom_right, bottom],
-            MovementCommand::East => vec![right, top, top_left, bottom_left, bottom],
-        };
-
-        Shape::Polyline { points }
+        frame.set_status(status);
+        frame
     }
 }
 
@@ -271,6 +161,8 @@ struct RepairDroid {
     goal_pos: Option<Point2D>,
     pathfinders: Vec<PathFinder>,
     counter: usize,
+    shortest_path_len: Option<usize>,
+    oxygenation_ticks: Option<usize>,
 }
 impl RepairDroid {
     fn new(input: Vec<isize>) -> Self {
@@ -286,16 +178,18 @@ impl RepairDroid {
             goal_pos: None,
             pathfinders: vec![],
             counter: 0,
+            shortest_path_len: None,
+            oxygenation_ticks: None,
         }
     }
 }
 impl RepairDroid {
-    fn find_shortest_path(&mut self) {
-        self.counter += 1;
-        if self.counter > 500 {
-            println!("Reached counter of {}", self.counter);
-            return;
+    /// Returns false once the tick limit is reached
+    fn find_shortest_path(&mut self) -> bool {
+        if self.counter >= 500 {
+            return false;
         };
+        self.counter += 1;
         if self.droid_state == DroidState::FullyExplored {
             // start finding shortest path
             let origin = Point2D::default();
@@ -321,6 +215,7 @@ impl RepairDroid {
         {
             let mut spawns: Vec<PathFinder> = vec![];
             let mut updated_board_states: HashMap<Point2D, BoardState> = HashMap::new();
+            let mut shortest_path_len = None;
             self.pathfinders.drain(0..).for_each(|mut pf| {
                 let mut next_starting_points = pf.find_path();
                 match pf.state {
@@ -328,7 +223,7 @@ impl RepairDroid {
                         updated_board_states.insert(pf.curr_pos(), BoardState::Searched);
                     }
                     PathFinderState::FoundOxygenSystem => {
-                        println!("Shortest path has length {}", pf.path.len() - 1);
+                        shortest_path_len = Some(pf.path.len() - 1);
                         pf.path.iter().rev().skip(1).for_each(|pos| {
                             updated_board_states.insert(*pos, BoardState::ShortestPath);
                         });
@@ -343,6 +238,9 @@ impl RepairDroid {
                     spawns.push(fork)
                 });
             });
+            if shortest_path_len.is_some() {
+                self.shortest_path_len = shortest_path_len;
+            }
             updated_board_states.into_iter().for_each(|(pos, state)| {
                 if self.board_state.get(&pos) == Some(&BoardState::ShortestPath)
                     && state == BoardState::Oxygenated
@@ -361,10 +259,11 @@ impl RepairDroid {
                     // oxygenating
                     assert_eq!(self.droid_state, DroidState::Oxygenating);
                     self.droid_state = DroidState::FullyOxygenated;
-                    println!("Oxygenation took {} ticks", self.counter);
+                    self.oxygenation_ticks = Some(self.counter);
                 }
             }
         }
+        true
     }
     #[allow(unused)]
     fn explore_full_maze(&mut self) {
@@ -486,6 +385,14 @@ impl MovementCommand {
             MovementCommand::East => MovementCommand::South,
         }
     }
+    fn arrow(&self) -> char {
+        match self {
+            MovementCommand::North => '^',
+            MovementCommand::South => 'v',
+            MovementCommand::West => '<',
+            MovementCommand::East => '>',
+        }
+    }
     fn counter_clockwise(&self) -> MovementCommand {
         match self {
             MovementCommand::North => MovementCommand::West,
@@ -637,9 +544,6 @@ impl Point2D {
             MovementCommand::East => Point2D(self.0 + 1, self.1),
         }
     }
-    fn offset_by(self, x: isize, y: isize) -> Point2D {
-        Point2D(self.0 + x, self.1 + y)
-    }
     fn neighbors(&self) -> Vec<Point2D> {
         vec![
             self.offset_by_1_into(&MovementCommand::North),
@@ -650,6 +554,7 @@ impl Point2D {
     }
 }
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -657,6 +562,24 @@ mod tests {
     fn day_15() {
         let mut droid = RepairDroid::new(day_15_puzzle_input());
         droid.explore_full_maze();
-        assert_eq!(droid.state_at_current_pos(), &BoardState::OxygenSystem);
+        // Exploration ends back at the origin, after having passed the oxygen system
+        assert_eq!(droid.curr_pos, Point2D::default());
+        let oxygen_system = droid.goal_pos.unwrap();
+        assert_eq!(droid.board_state[&oxygen_system], BoardState::OxygenSystem);
+    }
+
+    #[test]
+    fn record_until_fully_oxygenated() {
+        let mut droid = RepairDroid::new(day_15_puzzle_input());
+        let mut out = vec![];
+        Settings::new("day 15", 0)
+            .record(&mut droid, &mut out)
+            .unwrap();
+        assert_eq!(droid.droid_state, DroidState::FullyOxygenated);
+
+        let recording = String::from_utf8(out).unwrap();
+        let last_frame = recording.rsplit("\nframe ").next().unwrap();
+        assert!(last_frame.contains('O'));
+        assert!(last_frame.contains("FullyOxygenated, shortest path has length"));
     }
 }
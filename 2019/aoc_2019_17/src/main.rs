@@ -1,50 +1,22 @@
-use crate::point2d::Point2D;
-use coffee::graphics::{Color, Frame, Mesh, Point, Rectangle, Shape, Window, WindowSettings};
-use coffee::load::Task;
-use coffee::{Game, Result, Timer};
-use intcode::{IntCodeComputer, State, State::*};
-use std::collections::{HashMap, VecDeque};
-use std::ops::RangeInclusive;
+mod point2d;
 
-const RED: Color = Color {
-    r: 1.0,
-    g: 0.0,
-    b: 0.0,
-    a: 1.0,
-};
-const YELLOW: Color = Color {
-    r: 1.0,
-    g: 1.0,
-    b: 0.0,
-    a: 1.0,
-};
-const GREEN: Color = Color {
-    r: 0.25,
-    g: 1.0,
-    b: 0.25,
-    a: 1.0,
-};
-const BLUE: Color = Color {
-    r: 0.25,
-    g: 0.25,
-    b: 1.0,
-    a: 1.0,
-};
-const GREY: Color = Color {
-    r: 0.5,
-    g: 0.5,
-    b: 0.5,
-    a: 1.0,
-};
+use crate::point2d::Point2D;
+use intcode::{IntCodeComputer, State};
+use std::collections::HashMap;
+use terminal::{Animation, Color, Frame, Settings, Viewport};
 
-fn main() -> Result<()> {
-    let tile_size = 40;
-    ASCII::run(WindowSettings {
-        title: String::from("Advent of code 2019 day 17"),
-        size: ((58 + 1) * tile_size, (34 + 1) * tile_size),
-        resizable: true,
-        fullscreen: false,
-    })
+fn main() -> std::io::Result<()> {
+    let settings = Settings::from_args("Advent of code 2019 day 17", 30);
+    let mut ascii = ASCII::new();
+    settings.run(&mut ascii)?;
+    println!(
+        "alignment_parameter_sum = {}",
+        ascii.alignment_parameter_sum
+    );
+    if let Some(dust) = ascii.collected_dust {
+        println!("collected dust = {}", dust);
+    }
+    Ok(())
 }
 
 // day 17
@@ -83,59 +55,81 @@ impl From<isize> for CameraOutput {
 struct ASCII {
     icc: IntCodeComputer,
     camera_output: HashMap<Point2D, CameraOutput>,
+    alignment_parameter_sum: isize,
+    collected_dust: Option<isize>,
+    halted: bool,
 }
 impl ASCII {
     fn new() -> Self {
         let input = day_17_puzzle_input();
-        let icc = IntCodeComputer::new(input);
-        let camera_output = HashMap::new();
+        let mut icc = IntCodeComputer::new(input);
+        icc.run_until_halted();
+        let image: String = icc.outputs().into_iter().map(|o| o as u8 as char).collect();
 
-        let mut ascii = ASCII { icc, camera_output };
-        ascii.get_camera_output();
-        ascii.mark_intersections();
+        let mut ascii = ASCII {
+            icc,
+            camera_output: HashMap::new(),
+            alignment_parameter_sum: 0,
+            collected_dust: None,
+            halted: false,
+        };
+        ascii.show_camera_image(&image);
+        ascii.alignment_parameter_sum = ascii.mark_intersections();
         ascii.wake_robot_and_add_inputs();
         ascii
     }
-    fn get_camera_output(&mut self) {
-        let mut x = 0;
-        let mut y = 0;
-        let mut line = vec![];
-        self.icc.run_until_halted();
-        for output in self.icc.outputs() {
-            line.push(output as u8 as char);
-            let out = CameraOutput::from(output /*as u8 as char*/);
-            if out == CameraOutput::NewLine {
-                y += 1;
-                x = 0;
-                print!("{}", line.into_iter().collect::<String>());
-                line = vec![];
-            } else {
-                self.camera_output.insert(Point2D::new(x, 34 - y), out);
-                x += 1;
+    fn show_camera_image(&mut self, image: &str) {
+        self.camera_output.clear();
+        for (y, line) in image.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let out = CameraOutput::from(c as isize);
+                self.camera_output
+                    .insert(Point2D::new(x as isize, 34 - y as isize), out);
             }
         }
     }
-    fn mark_intersections(&mut self) {
+    /// Runs the robot until it sent a complete camera image, or returns None once it halted
+    fn next_camera_image(&mut self) -> Option<String> {
+        let mut image = String::new();
+        while !self.halted {
+            match self.icc.step() {
+                State::WroteOutput(output) if output > u8::MAX as isize => {
+                    self.collected_dust = Some(output);
+                }
+                State::WroteOutput(output) => {
+                    image.push(output as u8 as char);
+                    if image.ends_with("\n\n") {
+                        if image.trim().is_empty() {
+                            image.clear();
+                        } else {
+                            return Some(image);
+                        }
+                    }
+                }
+                State::Halted => self.halted = true,
+                State::Idle | State::ExpectingInput => {}
+            }
+        }
+        None
+    }
+    /// Marks the scaffold intersections and returns the sum of their alignment parameters
+    fn mark_intersections(&mut self) -> isize {
         let mut sum = 0;
         for y in 1..34 {
-            //            println!("y = {}, x's:", y);
             for x in 1..58 {
-                //                println!("{}", x);
                 let center = &Point2D::new(x, y);
                 if self.is_scaffold(center)
                     && center.neighbors().iter().all(|p| self.is_scaffold(p))
                 {
                     self.camera_output
                         .insert(*center, CameraOutput::ScaffoldCrossing);
-                    //                    println!("crossing ({}, {}), sum = {}", x, y, sum);
                     sum += y * x;
                 }
             }
         }
-        println!("alignment_parameter_sum = {}", sum);
+        sum
     }
     fn wake_robot_and_add_inputs(&mut self) {
-        println!("Creating new int code computer with woken up robot");
         let mut input = day_17_puzzle_input();
         input[0] = 2;
         self.icc = IntCodeComputer::new(input);
@@ -154,11 +148,11 @@ impl ASCII {
         let fn_a = "R,8,L,12,R,8\n";
         let fn_b = "L,10,L,10,R,8\n";
         let fn_c = "L,12,L,12,L,10,R,10\n";
-        let feed = "n\n";
+        // Continuous video feed, to animate the robot's movement
+        let feed = "y\n";
 
         self.icc.run_until_waiting_for_input();
 
-        println!("Adding main");
         self.icc.add_inputs(&ASCII::codes(main));
         self.icc.run_until_waiting_for_input();
 
@@ -188,68 +182,45 @@ impl ASCII {
     }
 }
 
-impl Game for ASCII {
-    type Input = (); // No input data
-    type LoadingScreen = (); // No loading screen
-
-    fn load(_window: &Window) -> Task<ASCII> {
-        // Load your game assets here. Check out the `load` module!
-        Task::new(|| ASCII::new())
+impl Animation for ASCII {
+    fn update(&mut self) -> bool {
+        match self.next_camera_image() {
+            Some(image) => {
+                self.show_camera_image(&image);
+                self.mark_intersections();
+                true
+            }
+            None => false,
+        }
     }
 
-    fn draw(&mut self, frame: &mut Frame, _timer: &Timer) {
-        let ct = CoordinateTransformation::from_points(
-            frame.width(),
-            frame.height(),
-            &self.camera_output.keys().collect::<Vec<&Point2D>>(),
-        );
-        // println!("{:?}", ct);
-
-        frame.clear(GREY);
-        let mut mesh = Mesh::new();
-
-        // empty space
-        self.camera_output
-            .iter()
-            .filter(|(_, state)| state == &&CameraOutput::EmptySpace)
-            .for_each(|(pos, _)| {
-                mesh.fill(ct.square_at(pos), Color::BLACK);
-            });
-
-        // robot and scaffolding
+    fn draw(&self) -> Frame {
+        let viewport =
+            Viewport::from_points(self.camera_output.keys().map(|p| (p.x(), p.y())), true);
+        let mut frame = Frame::new(viewport, Color::GREY);
         for (pos, state) in self.camera_output.iter() {
-            match state {
-                CameraOutput::Scaffold => mesh.stroke(ct.square_at(pos), Color::WHITE, 4),
-                CameraOutput::ScaffoldCrossing => mesh.fill(ct.square_at(pos), GREEN),
-                CameraOutput::RobotDown => {
-                    mesh.fill(ct.robot_at(pos, &MovementCommand::South), RED)
-                }
-                CameraOutput::RobotUp => mesh.fill(ct.robot_at(pos, &MovementCommand::North), RED),
-                CameraOutput::RobotLeft => mesh.fill(ct.robot_at(pos, &MovementCommand::West), RED),
-                CameraOutput::RobotRight => {
-                    mesh.fill(ct.robot_at(pos, &MovementCommand::East), RED)
-                }
-                CameraOutput::RobotFallenOff => mesh.fill(ct.square_at(pos), YELLOW),
-                CameraOutput::EmptySpace => {}
-                CameraOutput::NewLine => {}
-            }
+            let (symbol, color) = match state {
+                CameraOutput::Scaffold => ('#', Color::WHITE),
+                CameraOutput::ScaffoldCrossing => ('+', Color::GREEN),
+                CameraOutput::EmptySpace => ('.', Color::BLACK),
+                CameraOutput::RobotUp => ('^', Color::RED),
+                CameraOutput::RobotDown => ('v', Color::RED),
+                CameraOutput::RobotLeft => ('<', Color::RED),
+                CameraOutput::RobotRight => ('>', Color::RED),
+                CameraOutput::RobotFallenOff => ('X', Color::YELLOW),
+                CameraOutput::NewLine => continue,
+            };
+            frame.draw(pos.x(), pos.y(), symbol, color);
         }
-        // robot fallen off
-        self.camera_output
-            .iter()
-            .filter(|(_, state)| state == &&CameraOutput::RobotFallenOff)
-            .for_each(|(pos, _)| {
-                mesh.fill(ct.square_at(pos), YELLOW);
-            });
-
         // origin
-        mesh.fill(ct.square_at(&Point2D::default()), BLUE);
-
-        mesh.draw(&mut frame.as_target());
-    }
+        frame.draw(0, 0, 'O', Color::BLUE);
 
-    fn update(&mut self, _window: &Window) {
-        self.get_camera_output();
+        let mut status = format!("alignment parameter sum = {}", self.alignment_parameter_sum);
+        if let Some(dust) = self.collected_dust {
+            status += &format!(", collected dust = {}", dust);
+        }
+        frame.set_status(status);
+        frame
     }
 }
 
@@ -330,72 +301,6 @@ fn day_17_puzzle_input() -> Vec<isize> {
     ]
 }
 
-#[derive(Debug)]
-struct CoordinateTransformation {
-    x_range: RangeInclusive<f32>,
-    y_range: RangeInclusive<f32>,
-    tile_size: f32,
-}
-impl CoordinateTransformation {
-    fn from_points(width: f32, height: f32, points: &[&Point2D]) -> Self {
-        let min_x = points.iter().map(|p| p.x()).min().unwrap() as f32;
-        let max_x = points.iter().map(|p| p.x()).max().unwrap() as f32;
-        let min_y = points.iter().map(|p| p.y()).min().unwrap() as f32;
-        let max_y = points.iter().map(|p| p.y()).max().unwrap() as f32;
-        let x_range = min_x..=max_x;
-        let y_range = min_y..=max_y;
-
-        let w_count = (max_x - min_x) + 1.0;
-        let h_count = (max_y - min_y) + 1.0;
-        let ppp = f32::min(width / w_count, height / h_count); // pixels-per-point
-
-        //        println!(
-        //            "Board: w: {}, h: {}, x: {} to {}, y: {} to {}, ppd: {}",
-        //            w_count, h_count, min_x, max_x, min_y, max_y, ppp
-        //        );
-
-        CoordinateTransformation {
-            x_range,
-            y_range,
-            tile_size: ppp,
-        }
-    }
-    fn square_at(&self, pos: &Point2D) -> Shape {
-        let top_left = self.point_at(pos);
-        Shape::Rectangle(Rectangle {
-            x: top_left.x,
-            y: top_left.y,
-            width: self.tile_size,
-            height: self.tile_size,
-        })
-    }
-    fn point_at(&self, pos: &Point2D) -> Point {
-        Point::new(
-            self.tile_size * (pos.x() as f32 - *self.x_range.start()),
-            self.tile_size * (self.y_range.end() - pos.y() as f32),
-        )
-    }
-    fn robot_at(&self, pos: &Point2D, dir: &MovementCommand) -> Shape {
-        let top_left = self.point_at(pos);
-        let top_right = self.point_at(&pos.offset_by(1, 0));
-        let bottom_left = self.point_at(&pos.offset_by(0, -1));
-        let bottom_right = self.point_at(&pos.offset_by(1, -1));
-        let top = Point::new((top_left.x + top_right.x) / 2.0, top_left.y);
-        let bottom = Point::new((bottom_left.x + bottom_right.x) / 2.0, bottom_left.y);
-        let left = Point::new(top_left.x, (top_left.y + bottom_left.y) / 2.0);
-        let right = Point::new(top_right.x, (top_right.y + bottom_right.y) / 2.0);
-
-        let points = match dir {
-            MovementCommand::North => vec![top, left, bottom_left, bottom_right, right],
-            MovementCommand::South => vec![bottom, left, top_left, top_right, right],
-            MovementCommand::West => vec![left, top, top_right, bottom_right, bottom],
-            MovementCommand::East => vec![right, top, top_left, bottom_left, bottom],
-        };
-
-        Shape::Polyline { points }
-    }
-}
-
 #[derive(Debug)]
 enum MovementCommand {
     North,
@@ -415,8 +320,10 @@ impl From<isize> for MovementCommand {
     }
 }
 
+#[cfg(test)]
 mod tests {
     use crate::ASCII;
+    use terminal::Settings;
 
     #[test]
     fn ascii_from_char() {
@@ -429,4 +336,22 @@ mod tests {
         assert_eq!(ASCII::code('8'), 56);
         assert_eq!(ASCII::code('\n'), 10);
     }
+
+    #[test]
+    fn record_robot_walking_the_scaffold() {
+        let mut settings = Settings::new("day 17", 0);
+        settings.max_frames = Some(3);
+        let mut out = vec![];
+        let frame_count = settings.record(&mut ASCII::new(), &mut out).unwrap();
+        assert_eq!(frame_count, 3);
+
+        let recording = String::from_utf8(out).unwrap();
+        let frames: Vec<&str> = recording.split("\nframe ").skip(1).collect();
+        assert_eq!(frames.len(), 3);
+        for frame in frames {
+            assert!(frame.contains('+'));
+            assert_eq!(frame.matches(|c| "^v<>".contains(c)).count(), 1);
+            assert!(frame.contains("alignment parameter sum = 8444"));
+        }
+    }
 }
@@ -25,9 +25,6 @@ impl Point2D {
             MovementCommand::East => Point2D(self.0 + 1, self.1),
         }
     }
-    pub(crate) fn offset_by(self, x: isize, y: isize) -> Point2D {
-        Point2D(self.0 + x, self.1 + y)
-    }
     pub(crate) fn neighbors(&self) -> Vec<Point2D> {
         vec![
             self.offset_by_1_into(&MovementCommand::North),
@@ -1,151 +1,64 @@
-use coffee::graphics::{Color, Frame, Mesh, Point, Rectangle, Shape, Window, WindowSettings};
-use coffee::load::Task;
-use coffee::{Game, Result, Timer};
 use itertools::Itertools;
 use rayon::prelude::*;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::iter::FromIterator;
-use std::ops::RangeInclusive;
+use terminal::{Animation, Color, Frame, Settings, Viewport};
 
-const RED: Color = Color {
-    r: 1.0,
-    g: 0.0,
-    b: 0.0,
-    a: 1.0,
-};
-const YELLOW: Color = Color {
-    r: 1.0,
-    g: 1.0,
-    b: 0.0,
-    a: 1.0,
-};
-const GREEN: Color = Color {
-    r: 0.25,
-    g: 1.0,
-    b: 0.25,
-    a: 1.0,
-};
-const BLUE: Color = Color {
-    r: 0.25,
-    g: 0.25,
-    b: 1.0,
-    a: 1.0,
-};
-const GREY: Color = Color {
-    r: 0.5,
-    g: 0.5,
-    b: 0.5,
-    a: 1.0,
-};
-
-fn main() -> Result<()> {
-    UndergroundVault::run(WindowSettings {
-        title: String::from("Advent of code 2019 day 18"),
-        size: (1280, 1280),
-        resizable: true,
-        fullscreen: false,
-    })
+fn main() -> std::io::Result<()> {
+    let settings = Settings::from_args("Advent of code 2019 day 18", 1);
+    settings.run(&mut UndergroundVault::from(day_18_larger_example_3()))?;
+    Ok(())
 }
-impl Game for UndergroundVault {
-    type Input = (); // No input data
-    type LoadingScreen = (); // No loading screen
-
-    fn load(_window: &Window) -> Task<UndergroundVault> {
-        // Load your game assets here. Check out the `load` module!
-        Task::new(|| UndergroundVault::from(day_18_larger_example_3()))
-    }
-
-    fn draw(&mut self, frame: &mut Frame, _timer: &Timer) {
-        let ct = CoordinateTransformation::from_points(
-            frame.width(),
-            frame.height(),
-            &self.map.points(),
-        );
-        frame.clear(GREY);
-        let mut mesh = Mesh::new();
-
-        self.map
-            .point_obj_pairs()
-            .iter()
-            .for_each(|(pos, obj)| match obj {
-                Obj::Player => mesh.fill(ct.square_at(pos), YELLOW),
-                Obj::Wall => mesh.fill(ct.square_at(pos), Color::BLACK),
-                Obj::EmptySpace => mesh.fill(ct.square_at(pos), Color::WHITE),
-                Obj::Key(_) => mesh.fill(ct.square_at(pos), GREEN),
-                Obj::Door(_) => mesh.fill(ct.square_at(pos), RED),
-            });
-
-        self.ongoing
-            .iter()
-            .for_each(|search| mesh.fill(ct.square_at(&search.start), BLUE));
-
-        mesh.draw(&mut frame.as_target());
-    }
-
-    fn update(&mut self, _window: &Window) {
-        if !self.ongoing.is_empty() {
-            println!("\n\nRunning {} ongoing searches", self.ongoing.len());
-            self.run_searches();
-            if self.ongoing.len() > 300_000 {
-                panic!("Breaking because of too many searches()");
-            }
+impl Animation for UndergroundVault {
+    fn update(&mut self) -> bool {
+        if self.ongoing.is_empty() {
+            return false;
         }
-        std::thread::sleep(std::time::Duration::from_millis(1_000));
+        self.run_searches();
+        if self.ongoing.len() > 300_000 {
+            panic!("Breaking because of too many searches()");
+        }
+        true
     }
-}
-
-#[derive(Debug, PartialEq)]
-struct CoordinateTransformation {
-    x_range: RangeInclusive<f32>,
-    y_range: RangeInclusive<f32>,
-    tile_size: f32,
-}
-impl CoordinateTransformation {
-    fn from_points(width: f32, height: f32, points: &[Vec2]) -> Self {
-        let min_x = points.iter().map(|p| p.x).min().unwrap() as f32;
-        let max_x = points.iter().map(|p| p.x).max().unwrap() as f32;
-        let min_y = points.iter().map(|p| p.y).min().unwrap() as f32;
-        let max_y = points.iter().map(|p| p.y).max().unwrap() as f32;
-        let xr = min_x..=max_x;
-        let yr = min_y..=max_y;
 
-        let w_count = (max_x - min_x) + 1.0;
-        let h_count = (max_y - min_y) + 1.0;
-        let ppp = f32::min(width / w_count, height / h_count); // pixels-per-point
+    fn draw(&self) -> Frame {
+        let points = self
+            .map
+            .points()
+            .into_iter()
+            .map(|p| (p.x as isize, p.y as isize));
+        let mut frame = Frame::new(Viewport::from_points(points, false), Color::GREY);
 
-        // println!(
-        // "Board: w: {}, h: {}, x: {} to {}, y: {} to {}, ppd: {}",
-        // w_count,
-        // h_count,
-        // xr.start(),
-        // xr.end(),
-        // yr.start(),
-        // yr.end(),
-        // ppp
-        // );
+        for (pos, obj) in self.map.point_obj_pairs() {
+            let (symbol, color) = match obj {
+                Obj::Player => ('@', Color::YELLOW),
+                Obj::Wall => ('#', Color::BLACK),
+                Obj::EmptySpace => ('.', Color::WHITE),
+                Obj::Key(key) => (key, Color::GREEN),
+                Obj::Door(door) => (door, Color::RED),
+            };
+            frame.draw(pos.x as isize, pos.y as isize, symbol, color);
+        }
 
-        CoordinateTransformation {
-            x_range: xr,
-            y_range: yr,
-            tile_size: ppp,
+        for search in &self.ongoing {
+            frame.draw(
+                search.start.x as isize,
+                search.start.y as isize,
+                '*',
+                Color::BLUE,
+            );
         }
-    }
-    fn square_at(&self, pos: &Vec2) -> Shape {
-        let top_left = self.point_at(pos);
-        Shape::Rectangle(Rectangle {
-            x: top_left.x,
-            y: top_left.y,
-            width: self.tile_size,
-            height: self.tile_size,
-        })
-    }
-    fn point_at(&self, pos: &Vec2) -> Point {
-        Point::new(
-            self.tile_size * (pos.x as f32 - *self.x_range.start()),
-            self.tile_size * (pos.y as f32 - *self.y_range.start()),
-        )
+
+        let shortest = self.complete.iter().map(|search| search.path_len).min();
+        frame.set_status(format!(
+            "{} ongoing searches, {} complete, shortest path so far: {:?}",
+            self.ongoing.len(),
+            self.complete.len(),
+            shortest
+        ));
+        frame
     }
 }
 
@@ -255,7 +168,6 @@ impl From<&str> for Map {
     fn from(input: &str) -> Self {
         let map: Vec<Vec<Obj>> = input
             .split('\n')
-            // .inspect(|row| println!("{}", row))
             .map(|row| row.chars().map(|c| Obj::from(c)).collect())
             .collect();
         let width = map[0].len();
@@ -385,7 +297,6 @@ impl Map {
         while !keys_wanted.is_empty() {
             println!("Distances from {} to {:?}", source, keys_wanted);
             for (target, (dist, keys)) in self.distances_and_keys(source, &keys_wanted) {
-                //                println!(" to {}: {}", target, dist);
                 distances.insert((source, target), dist);
                 req_keys.insert((source, target), keys);
             }
@@ -429,7 +340,6 @@ impl Map {
             explorers = explorers
                 .drain(0..)
                 .filter_map(|mut explorer| {
-                    // println!("{:?}", explorer);
                     // Only keep better ones (this gets rid of duplicates as well)
                     if path_lengths.contains_key(&explorer.pos) {
                         // Position already visited by an earlier (=faster) explorer
@@ -537,12 +447,10 @@ impl GraphSearchBot {
             .iter()
             .min_by_key(|search| search.tot_distance)
             .unwrap();
-        // println!("Shortest = {:?}", shortest);
         shortest.tot_distance
     }
     fn run_searches(&mut self) {
         let key_count = self.graph.keys.len();
-        // println!("key count = {}", key_count);
         // Next line avoids "cannot borrow `self` as immutable because it is also borrowed as mutable"
         let graph = &self.graph;
         // Next line avoids "closure requires unique access to `self` but it is already borrowed"
@@ -564,7 +472,6 @@ impl GraphSearchBot {
             .collect();
         self.complete.extend(complete);
         ongoing.sort_unstable_by_key(|s| s.start);
-        //        println!("\n{} ongoing searches:", ongoing.len());
         let unmerged_len = ongoing.len();
         ongoing
             .iter()
@@ -585,15 +492,6 @@ impl GraphSearchBot {
                 }
             })
             .collect();
-        println!(
-            "\n{} merged (from {}) ongoing searches:",
-            merged.len(),
-            unmerged_len
-        );
-        //        merged
-        //            .iter()
-        //            .take(10)
-        //            .for_each(|s| println!("{}, {:?}, {}", s.start, s.keys, s.path_len));
 
         self.ongoing = merged;
 
@@ -641,12 +539,7 @@ impl From<&UndergroundVault> for Search {
 impl Search {
     // Return new Searches that can be started from keys reachable by this search
     fn new_searches_from_reachable_keys(&self, map: &Map) -> Vec<Search> {
-        //        println!(
-        //            "\nStarting new search from {} with path_len {} and keys {:?}",
-        //            self.start, self.path_len, self.keys
-        //        );
         let mut path_lengths: HashMap<Vec2, usize> = HashMap::new();
-        let mut steps = 0;
         let mut next_searches: Vec<Search> = vec![];
         let mut explorers: Vec<Explorer> = vec![Explorer::from(self)];
         while !explorers.is_empty() {
@@ -662,12 +555,6 @@ impl Search {
                     .collect::<Vec<Search>>(),
             );
             explorers = searching;
-
-            steps += 1;
-            if steps > 40 {
-                //                println!("breaking. explorers = {:?}", explorers);
-                //                return next_searches;
-            }
         }
         next_searches
     }
@@ -680,7 +567,6 @@ impl Search {
         Vec<Explorer>, // Finished (found a key)
         Vec<Explorer>, // Still searching for a key
     ) {
-        //        println!("Expanding key search with {} explorers.", explorers.len()); //, collectors);
         let mut finished: Vec<Explorer> = vec![];
         let mut searching: Vec<Explorer> = vec![];
 
@@ -689,7 +575,6 @@ impl Search {
                 path_lengths.insert(explorer.pos, explorer.path_len);
                 if let Some(key) = explorer.new_key_at_current_pos(&map) {
                     // This explorer reached a key and is finished
-                    //                    println!("New key {} at pos {:?}", key, explorer.pos);
                     explorer.coll_keys.insert(key);
                     finished.push(explorer);
                 } else {
@@ -748,12 +633,10 @@ impl UndergroundVault {
             .iter()
             .min_by_key(|search| search.path_len)
             .unwrap();
-        // println!("Shortest = {:?}", shortest);
         shortest.path_len
     }
     fn run_searches(&mut self) {
         let key_count = self.map.key_count();
-        // println!("key count = {}", key_count);
         // Next line avoids "cannot borrow `self` as immutable because it is also borrowed as mutable"
         let map = &self.map;
         // Next line avoids "closure requires unique access to `self` but it is already borrowed"
@@ -764,7 +647,6 @@ impl UndergroundVault {
             .flat_map(|curr| curr.new_searches_from_reachable_keys(map))
             .filter_map(|next| {
                 if next.coll_keys.len() == key_count {
-                    println!("Search finished with length {}", next.path_len);
                     complete.push(next);
                     None
                 } else {
@@ -775,12 +657,6 @@ impl UndergroundVault {
             .collect();
         self.complete.extend(complete);
         ongoing.sort_unstable_by_key(|s| s.start);
-        //        println!("\n{} ongoing searches:", ongoing.len());
-        let unmerged_len = ongoing.len();
-        ongoing
-            .iter()
-            .take(30)
-            .for_each(|s| println!("{}, {:?}, {}", s.start, s.coll_keys, s.path_len));
 
         let merged: Vec<Search> = ongoing
             .into_iter()
@@ -796,15 +672,6 @@ impl UndergroundVault {
                 }
             })
             .collect();
-        println!(
-            "\n{} merged (from {}) ongoing searches:",
-            merged.len(),
-            unmerged_len
-        );
-        //        merged
-        //            .iter()
-        //            .take(10)
-        //            .for_each(|s| println!("{}, {:?}, {}", s.start, s.keys, s.path_len));
 
         self.ongoing = merged;
     }
@@ -1079,6 +946,7 @@ fn day_18_puzzle_input() -> &'static str {
 #...#...#...............#...............#..d..........#.........#...........#..h#
 #################################################################################"
 }
+#[cfg(test)]
 mod tests {
     use crate::{
         almost_empty_map, challenging_input, day_18_example_1, day_18_larger_example_1,
@@ -1088,6 +956,23 @@ mod tests {
     };
     use std::collections::{BTreeMap, BTreeSet, HashSet};
     use std::iter::FromIterator;
+    use terminal::Settings;
+
+    #[test]
+    fn record_collecting_all_keys_example_1() {
+        let mut vault = UndergroundVault::from(day_18_example_1());
+        let mut out = vec![];
+        Settings::new("day 18", 0)
+            .record(&mut vault, &mut out)
+            .unwrap();
+
+        let recording = String::from_utf8(out).unwrap();
+        // The ongoing search starts at the player position
+        assert!(recording.contains("frame 0\n#########\n#b.A.*.a#\n"));
+        assert!(
+            recording.ends_with("0 ongoing searches, 1 complete, shortest path so far: Some(8)\n")
+        );
+    }
 
     #[test]
     fn object_from_char() {
@@ -1,3 +1,4 @@
+use geometry::Point2;
 use std::ops::RangeInclusive;
 
 pub(crate) fn day3_part1() -> usize {
@@ -26,8 +27,8 @@ fn combined_step_to_closest_intersection(path1: &str, path2: &str) -> usize {
                 if point != Point::origin() {
                     let steps = steps1
                         + steps2
-                        + line1.start.manhattan_distance_to(&point)
-                        + line2.start.manhattan_distance_to(&point);
+                        + line1.start.manhattan_distance(&point) as usize
+                        + line2.start.manhattan_distance(&point) as usize;
                     if steps < min_steps {
                         min_steps = steps;
                     }
@@ -55,11 +56,11 @@ fn distance_of_closest_intersection(path1: &str, path2: &str) -> usize {
     // println!("candidates = {:?}", candidates);
     point_closest_to_origin(candidates)
         .unwrap()
-        .manhattan_distance()
+        .manhattan_length() as usize
 }
 
 fn point_closest_to_origin(mut candidates: Vec<Point>) -> Option<Point> {
-    candidates.sort_by_key(|a| a.manhattan_distance());
+    candidates.sort_by_key(|a| a.manhattan_length());
     return candidates.get(0).map(Point::clone);
 }
 
@@ -74,22 +75,7 @@ fn path_to_lines(path: &str) -> Vec<Line> {
     lines
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-impl Point {
-    fn origin() -> Self {
-        Point { x: 0, y: 0 }
-    }
-    fn manhattan_distance(&self) -> usize {
-        (self.x.abs() + self.y.abs()) as usize
-    }
-    fn manhattan_distance_to(&self, o: &Point) -> usize {
-        ((self.x - o.x).abs() + (self.y - o.y).abs()) as usize
-    }
-}
+type Point = Point2<i32>;
 
 #[derive(PartialEq, Debug)]
 enum Direction {
@@ -112,10 +98,10 @@ impl Direction {
     }
     fn as_unit_vec(&self) -> Point {
         match self {
-            R => Point { x: 1, y: 0 },
-            L => Point { x: -1, y: 0 },
-            U => Point { x: 0, y: 1 },
-            D => Point { x: 0, y: -1 },
+            R => Point::new(1, 0),
+            L => Point::new(-1, 0),
+            U => Point::new(0, 1),
+            D => Point::new(0, -1),
         }
     }
     fn vertical(&self) -> bool {
@@ -153,104 +139,80 @@ impl Line {
     fn intersect(&self, o: &Line) -> Option<Point> {
         if self.dir.orthogonal(&o.dir) {
             if self.dir.vertical() {
-                if self.v().contains(&o.start.y) && o.h().contains(&self.start.x) {
-                    return Some(Point {
-                        x: self.start.x,
-                        y: o.start.y,
-                    });
+                if self.v().contains(&o.start.y()) && o.h().contains(&self.start.x()) {
+                    return Some(Point::new(self.start.x(), o.start.y()));
                 }
             } else {
                 // horizontal
-                if self.h().contains(&o.start.x) && o.v().contains(&self.start.y) {
-                    return Some(Point {
-                        x: o.start.x,
-                        y: self.start.y,
-                    });
+                if self.h().contains(&o.start.x()) && o.v().contains(&self.start.y()) {
+                    return Some(Point::new(o.start.x(), self.start.y()));
                 }
             }
             return None;
         }
         // both vertical or both horizontal
         if self.dir.vertical() {
-            if self.start.x != o.start.x {
+            if self.start.x() != o.start.x() {
                 return None;
             }
-            let x = self.start.x;
+            let x = self.start.x();
             let mut candidates: Vec<Point> = vec![];
             if self.v().contains(o.v().start()) {
-                candidates.push(Point {
-                    x,
-                    y: *o.v().start(),
-                });
+                candidates.push(Point::new(x, *o.v().start()));
             }
             if self.v().contains(o.v().end()) {
-                candidates.push(Point { x, y: *o.v().end() });
+                candidates.push(Point::new(x, *o.v().end()));
             }
             if o.v().contains(self.v().start()) {
-                candidates.push(Point {
-                    x,
-                    y: *self.v().start(),
-                });
+                candidates.push(Point::new(x, *self.v().start()));
             }
             if o.v().contains(self.v().end()) {
-                candidates.push(Point {
-                    x,
-                    y: *self.v().end(),
-                });
+                candidates.push(Point::new(x, *self.v().end()));
             }
-            candidates.sort_by(|a, b| a.y.abs().cmp(&b.y.abs()));
+            candidates.sort_by(|a, b| a.y().abs().cmp(&b.y().abs()));
             return candidates.get(0).map(Point::clone);
         }
         // horizontal
-        if self.start.y != o.start.y {
+        if self.start.y() != o.start.y() {
             return None;
         }
-        let y = self.start.y;
+        let y = self.start.y();
         let mut candidates: Vec<Point> = vec![];
         if self.h().contains(o.h().start()) {
-            candidates.push(Point {
-                x: *o.h().start(),
-                y,
-            });
+            candidates.push(Point::new(*o.h().start(), y));
         }
         if self.h().contains(o.h().end()) {
-            candidates.push(Point { x: *o.h().end(), y });
+            candidates.push(Point::new(*o.h().end(), y));
         }
         if o.h().contains(self.h().start()) {
-            candidates.push(Point {
-                x: *self.h().start(),
-                y,
-            });
+            candidates.push(Point::new(*self.h().start(), y));
         }
         if o.h().contains(self.h().end()) {
-            candidates.push(Point {
-                x: *self.h().end(),
-                y,
-            });
+            candidates.push(Point::new(*self.h().end(), y));
         }
-        candidates.sort_by(|a, b| a.x.abs().cmp(&b.x.abs()));
+        candidates.sort_by(|a, b| a.x().abs().cmp(&b.x().abs()));
         return candidates.get(0).map(Point::clone);
     }
     fn end(&self) -> Point {
-        Point {
-            x: self.start.x + self.dir.as_unit_vec().x * self.len as i32,
-            y: self.start.y + self.dir.as_unit_vec().y * self.len as i32,
-        }
+        Point::new(
+            self.start.x() + self.dir.as_unit_vec().x() * self.len as i32,
+            self.start.y() + self.dir.as_unit_vec().y() * self.len as i32,
+        )
     }
     /// x-range (horizontal)
     fn h(&self) -> RangeInclusive<i32> {
-        if self.start.x < self.end().x {
-            self.start.x..=self.end().x
+        if self.start.x() < self.end().x() {
+            self.start.x()..=self.end().x()
         } else {
-            self.end().x..=self.start.x
+            self.end().x()..=self.start.x()
         }
     }
     /// y-range (vertical)
     fn v(&self) -> RangeInclusive<i32> {
-        if self.start.y < self.end().y {
-            self.start.y..=self.end().y
+        if self.start.y() < self.end().y() {
+            self.start.y()..=self.end().y()
         } else {
-            self.end().y..=self.start.y
+            self.end().y()..=self.start.y()
         }
     }
 }
@@ -260,28 +222,25 @@ mod tests {
     use super::*;
 
     #[test]
-    fn manhattan_distance() {
-        assert_eq!(Point { x: 6, y: 5 }.manhattan_distance(), 11)
+    fn manhattan_length() {
+        assert_eq!(Point::new(6, 5).manhattan_length(), 11)
     }
 
     #[test]
-    fn manhattan_distance_to() {
-        assert_eq!(
-            Point { x: 6, y: 7 }.manhattan_distance_to(&Point { x: 6, y: 5 }),
-            2
-        )
+    fn manhattan_distance() {
+        assert_eq!(Point::new(6, 7).manhattan_distance(&Point::new(6, 5)), 2)
     }
 
     #[test]
     fn no_intersection() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: R,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 1, y: 1 },
+                start: Point::new(1, 1),
                 dir: R,
                 len: 2
             }),
@@ -293,64 +252,64 @@ mod tests {
     fn horizontal_intersection_1() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: R,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 1, y: 0 },
+                start: Point::new(1, 0),
                 dir: R,
                 len: 2
             }),
-            Some(Point { x: 1, y: 0 })
+            Some(Point::new(1, 0))
         )
     }
     #[test]
     fn horizontal_intersection_2() {
         assert_eq!(
             Line {
-                start: Point { x: 1, y: 0 },
+                start: Point::new(1, 0),
                 dir: R,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: R,
                 len: 2
             }),
-            Some(Point { x: 1, y: 0 })
+            Some(Point::new(1, 0))
         )
     }
     #[test]
     fn negative_horizontal_intersection_1() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: L,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: -1, y: 0 },
+                start: Point::new(-1, 0),
                 dir: L,
                 len: 2
             }),
-            Some(Point { x: -1, y: 0 })
+            Some(Point::new(-1, 0))
         )
     }
     #[test]
     fn negative_horizontal_intersection_2() {
         assert_eq!(
             Line {
-                start: Point { x: -1, y: 0 },
+                start: Point::new(-1, 0),
                 dir: L,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: L,
                 len: 2
             }),
-            Some(Point { x: -1, y: 0 })
+            Some(Point::new(-1, 0))
         )
     }
 
@@ -358,64 +317,64 @@ mod tests {
     fn vertical_intersection_1() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: U,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 0, y: 1 },
+                start: Point::new(0, 1),
                 dir: U,
                 len: 2
             }),
-            Some(Point { x: 0, y: 1 })
+            Some(Point::new(0, 1))
         )
     }
     #[test]
     fn vertical_intersection_2() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: 1 },
+                start: Point::new(0, 1),
                 dir: U,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: U,
                 len: 2
             }),
-            Some(Point { x: 0, y: 1 })
+            Some(Point::new(0, 1))
         )
     }
     #[test]
     fn negative_vertical_intersection_1() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: D,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 0, y: -1 },
+                start: Point::new(0, -1),
                 dir: D,
                 len: 2
             }),
-            Some(Point { x: 0, y: -1 })
+            Some(Point::new(0, -1))
         )
     }
     #[test]
     fn negative_vertical_intersection_2() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: -1 },
+                start: Point::new(0, -1),
                 dir: D,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: D,
                 len: 2
             }),
-            Some(Point { x: 0, y: -1 })
+            Some(Point::new(0, -1))
         )
     }
 
@@ -423,16 +382,16 @@ mod tests {
     fn intersection_1() {
         assert_eq!(
             Line {
-                start: Point { x: 1, y: 0 },
+                start: Point::new(1, 0),
                 dir: U,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 0, y: 1 },
+                start: Point::new(0, 1),
                 dir: R,
                 len: 2
             }),
-            Some(Point { x: 1, y: 1 })
+            Some(Point::new(1, 1))
         );
     }
 
@@ -440,16 +399,16 @@ mod tests {
     fn intersection_2() {
         assert_eq!(
             Line {
-                start: Point { x: 0, y: 1 },
+                start: Point::new(0, 1),
                 dir: R,
                 len: 2
             }
             .intersect(&Line {
-                start: Point { x: 1, y: 0 },
+                start: Point::new(1, 0),
                 dir: U,
                 len: 2
             }),
-            Some(Point { x: 1, y: 1 })
+            Some(Point::new(1, 1))
         )
     }
 
@@ -458,7 +417,7 @@ mod tests {
         assert_eq!(
             path_to_lines("R8"),
             vec![Line {
-                start: Point { x: 0, y: 0 },
+                start: Point::new(0, 0),
                 dir: R,
                 len: 8
             }]
@@ -470,12 +429,12 @@ mod tests {
             path_to_lines("R8,U5"),
             vec![
                 Line {
-                    start: Point { x: 0, y: 0 },
+                    start: Point::new(0, 0),
                     dir: R,
                     len: 8
                 },
                 Line {
-                    start: Point { x: 8, y: 0 },
+                    start: Point::new(8, 0),
                     dir: U,
                     len: 5
                 }
@@ -488,17 +447,17 @@ mod tests {
             path_to_lines("R8,U5,L5"),
             vec![
                 Line {
-                    start: Point { x: 0, y: 0 },
+                    start: Point::new(0, 0),
                     dir: R,
                     len: 8
                 },
                 Line {
-                    start: Point { x: 8, y: 0 },
+                    start: Point::new(8, 0),
                     dir: U,
                     len: 5
                 },
                 Line {
-                    start: Point { x: 8, y: 5 },
+                    start: Point::new(8, 5),
                     dir: L,
                     len: 5
                 }
@@ -511,22 +470,22 @@ mod tests {
             path_to_lines("R8,U5,L5,D3"),
             vec![
                 Line {
-                    start: Point { x: 0, y: 0 },
+                    start: Point::new(0, 0),
                     dir: R,
                     len: 8
                 },
                 Line {
-                    start: Point { x: 8, y: 0 },
+                    start: Point::new(8, 0),
                     dir: U,
                     len: 5
                 },
                 Line {
-                    start: Point { x: 8, y: 5 },
+                    start: Point::new(8, 5),
                     dir: L,
                     len: 5
                 },
                 Line {
-                    start: Point { x: 3, y: 5 },
+                    start: Point::new(3, 5),
                     dir: D,
                     len: 3
                 }
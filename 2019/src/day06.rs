@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use dot::{Graph, ToDot};
+use std::collections::{HashMap, HashSet};
 
 pub(crate) fn day6_part1() -> OrbitCount {
     count_orbits(day06_puzzle_input())
@@ -23,6 +24,44 @@ impl<'a> Orbiter<'a> {
     }
 }
 
+/// The tree of orbiters, in input order
+struct OrbitMap<'a> {
+    orbiters: Vec<Orbiter<'a>>,
+}
+impl<'a> OrbitMap<'a> {
+    fn from(input: &'a str) -> Self {
+        OrbitMap {
+            orbiters: input.split('\n').map(|s| Orbiter::from(s)).collect(),
+        }
+    }
+    fn center_by_name(&self) -> HashMap<&'a str, &'a str> {
+        self.orbiters.iter().map(|o| (o.name, o.center)).collect()
+    }
+}
+
+/// Renders the tree with an edge from each center to its orbiters, and the root(s) highlighted
+impl ToDot for OrbitMap<'_> {
+    fn to_dot(&self) -> String {
+        let mut graph = Graph::directed("orbits");
+        let names: HashSet<_> = self.orbiters.iter().map(|o| o.name).collect();
+        let mut roots: Vec<_> = self
+            .orbiters
+            .iter()
+            .map(|o| o.center)
+            .filter(|center| !names.contains(center))
+            .collect();
+        roots.sort_unstable();
+        roots.dedup();
+        for root in roots {
+            graph.node(root, &[("shape", &"doublecircle")]);
+        }
+        for orbiter in &self.orbiters {
+            graph.edge(orbiter.center, orbiter.name, &[]);
+        }
+        graph.to_dot()
+    }
+}
+
 #[derive(PartialEq, Debug)]
 pub(crate) struct OrbitCount {
     direct: usize,
@@ -42,10 +81,7 @@ impl OrbitCount {
 }
 
 fn count_orbit_transfers(input: &str, orbiter_1: &str, orbiter_2: &str) -> usize {
-    let mut orbiters: HashMap<&str, &str> = HashMap::new();
-    input.split('\n').map(|s| Orbiter::from(s)).for_each(|o| {
-        orbiters.insert(o.name, o.center);
-    });
+    let orbiters = OrbitMap::from(input).center_by_name();
 
     let mut count1 = 0usize;
     let mut name1 = orbiter_1;
@@ -73,10 +109,7 @@ fn count_orbits(input: &str) -> OrbitCount {
         return OrbitCount::empty();
     }
 
-    let mut orbiters: HashMap<&str, &str> = HashMap::new();
-    input.split('\n').map(|s| Orbiter::from(s)).for_each(|o| {
-        orbiters.insert(o.name, o.center);
-    });
+    let orbiters = OrbitMap::from(input).center_by_name();
 
     let total: usize = orbiters
         .keys()
@@ -1764,6 +1797,25 @@ I)SAN",
         assert_eq!(day6_part2(), 445);
     }
 
+    #[test]
+    fn orbit_map_to_dot() {
+        assert_eq!(
+            OrbitMap::from(
+                "AAA)BBB
+BBB)CCC
+AAA)DDD"
+            )
+            .to_dot(),
+            "digraph \"orbits\" {
+    \"AAA\" [shape=\"doublecircle\"];
+    \"AAA\" -> \"BBB\";
+    \"BBB\" -> \"CCC\";
+    \"AAA\" -> \"DDD\";
+}
+"
+        );
+    }
+
     // part 1
     #[test]
     fn empty_input() {
@@ -1,5 +1,6 @@
+use geometry::Point3;
+use number_theory::lcm_of;
 use regex::Regex;
-use std::cmp::Ordering;
 use std::fmt;
 
 pub(crate) fn day12_part1() -> usize {
@@ -18,51 +19,16 @@ pub(crate) fn day12_part2() -> usize {
     //     periods.1.to_formatted_string(&Locale::de_CH),
     //     periods.2.to_formatted_string(&Locale::de_CH)
     // );
-    lcm(periods.0, lcm(periods.1, periods.2))
+    lcm_of([periods.0, periods.1, periods.2])
 }
 
 // For example: <x=-1, y=0, z=2>
 const SINGLE_MOON_PATTERN: &str = r"<x=(-?\d+), y=(-?\d+), z=(-?\d+)>";
 
-#[derive(Default, Debug, PartialEq, Copy, Clone)]
-struct Vector(isize, isize, isize);
-impl fmt::Display for Vector {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "<x={}, y={}, z={}>", self.0, self.1, self.2)
-    }
-}
-impl std::ops::Add for Vector {
-    type Output = Vector;
+type Vector = Point3;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Vector(self.0 + rhs.0, self.1 + rhs.1, self.2 + rhs.2)
-    }
-}
-impl std::ops::AddAssign for Vector {
-    fn add_assign(&mut self, rhs: Self) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
-        self.2 += rhs.2;
-    }
-}
-impl Vector {
-    fn acceleration_from(&self, other: &Vector) -> Vector {
-        Vector(
-            Vector::acceleration(&self.0, &other.0),
-            Vector::acceleration(&self.1, &other.1),
-            Vector::acceleration(&self.2, &other.2),
-        )
-    }
-    fn acceleration(a: &isize, b: &isize) -> isize {
-        match a.cmp(b) {
-            Ordering::Less => 1,
-            Ordering::Equal => 0,
-            Ordering::Greater => -1,
-        }
-    }
-    fn abs_sum_parts(&self) -> usize {
-        (self.0.abs() + self.1.abs() + self.2.abs()) as usize
-    }
+fn fmt_vector(f: &mut fmt::Formatter<'_>, v: &Vector) -> fmt::Result {
+    write!(f, "<x={}, y={}, z={}>", v.x(), v.y(), v.z())
 }
 #[derive(Default, Debug, PartialEq)]
 struct Moon {
@@ -71,7 +37,10 @@ struct Moon {
 }
 impl fmt::Display for Moon {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "pos={}, vel={}", self.pos, self.vel)
+        write!(f, "pos=")?;
+        fmt_vector(f, &self.pos)?;
+        write!(f, ", vel=")?;
+        fmt_vector(f, &self.vel)
     }
 }
 impl From<&str> for Moon {
@@ -79,7 +48,7 @@ impl From<&str> for Moon {
         let re = Regex::new(SINGLE_MOON_PATTERN).unwrap();
         if let Some(caps) = re.captures(input) {
             Moon {
-                pos: Vector(
+                pos: Vector::new(
                     caps[1].parse().unwrap(),
                     caps[2].parse().unwrap(),
                     caps[3].parse().unwrap(),
@@ -95,7 +64,7 @@ impl Moon {
     #[cfg(test)]
     fn new(x: isize, y: isize, z: isize) -> Self {
         Moon {
-            pos: Vector(x, y, z),
+            pos: Vector::new(x, y, z),
             vel: Vector::default(),
         }
     }
@@ -104,7 +73,8 @@ impl Moon {
             .iter()
             .filter(|&moon| self != moon) // optional optimization: others only
             .fold(Vector::default(), |sum, moon| {
-                sum + self.pos.acceleration_from(&moon.pos)
+                // Each axis is pulled one step towards the other moon
+                sum + (moon.pos - self.pos).signum()
             })
     }
     fn apply_gravity(&mut self, gravity: Vector) {
@@ -114,19 +84,19 @@ impl Moon {
         self.pos += self.vel;
     }
     fn potential_energy(&self) -> usize {
-        self.pos.abs_sum_parts()
+        self.pos.manhattan_length() as usize
     }
     fn kinetic_energy(&self) -> usize {
-        self.vel.abs_sum_parts()
+        self.vel.manhattan_length() as usize
     }
     fn total_energy(&self) -> usize {
         self.potential_energy() * self.kinetic_energy()
     }
     fn value_pair(&self, axis: &Axis) -> (isize, isize) {
         match axis {
-            Axis::X => (self.pos.0, self.vel.0),
-            Axis::Y => (self.pos.1, self.vel.1),
-            Axis::Z => (self.pos.2, self.vel.2),
+            Axis::X => (self.pos.x(), self.vel.x()),
+            Axis::Y => (self.pos.y(), self.vel.y()),
+            Axis::Z => (self.pos.z(), self.vel.z()),
         }
     }
 }
@@ -215,20 +185,6 @@ impl Jupiter {
     }
 }
 
-/// greatest common denominator
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
-/// least common multiple
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
-}
-
 fn day12_puzzle_input() -> &'static str {
     "<x=-6, y=2, z=-9>
 <x=12, y=-14, z=-4>
@@ -278,13 +234,13 @@ pos=<x=3, y=5, z=-1>, vel=<x=0, y=0, z=0>"
         let moons = [left, middle, right];
 
         let left_acc = moons[0].acceleration_from(&moons);
-        assert_eq!(left_acc, Vector(2, 0, 0));
+        assert_eq!(left_acc, Vector::new(2, 0, 0));
 
         let middle_acc = moons[1].acceleration_from(&moons);
-        assert_eq!(middle_acc, Vector(0, 0, 0));
+        assert_eq!(middle_acc, Vector::new(0, 0, 0));
 
         let right_acc = moons[2].acceleration_from(&moons);
-        assert_eq!(right_acc, Vector(-2, 0, 0));
+        assert_eq!(right_acc, Vector::new(-2, 0, 0));
     }
     #[test]
     fn jupiter_step() {
@@ -332,7 +288,7 @@ pos=<x=2, y=0, z=4>, vel=<x=1, y=-1, z=-1>"
         assert_eq!(
             Moon {
                 pos: Vector::default(),
-                vel: Vector(2, 7, -3)
+                vel: Vector::new(2, 7, -3)
             }
             .kinetic_energy(),
             12
@@ -342,8 +298,8 @@ pos=<x=2, y=0, z=4>, vel=<x=1, y=-1, z=-1>"
     fn total_energy() {
         assert_eq!(
             Moon {
-                pos: Vector(1, 10, -5),
-                vel: Vector(2, 7, -3)
+                pos: Vector::new(1, 10, -5),
+                vel: Vector::new(2, 7, -3)
             }
             .total_energy(),
             192
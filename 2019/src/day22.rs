@@ -1,5 +1,7 @@
 use crate::parse;
-use std::mem::swap;
+use number_theory::AffineMap;
+#[cfg(test)]
+use number_theory::{inv_mod, mul_mod};
 
 const INPUT: &str = include_str!("../input/day22.txt");
 
@@ -11,10 +13,9 @@ pub(crate) fn day22_part1() -> usize {
     let input = parse(INPUT);
     let track_single_card_only = true;
     if track_single_card_only {
-        let mut deck = SingleCardPosTrackingDeck::new(2019, PART1_DECK_SIZE);
         let techniques = parse_shuffle_techniques(input);
-        deck.shuffle_with(&techniques);
-        deck.pos
+        let shuffle = ShuffleMap::compile(&techniques, PART1_DECK_SIZE);
+        shuffle.position_of(2019, 1)
     } else {
         let deck = shuffle_deck(PART1_DECK_SIZE, input);
         deck.iter().position(|&v| v == 2019).unwrap()
@@ -32,64 +33,14 @@ pub(crate) fn day22_part2() -> usize {
     // From <https://www.reddit.com/r/adventofcode/comments/ee0rqi/2019_day_22_solutions/fbnifwk/>:
     // All the operations applied to the deck are linear. Their composition is also linear.
     // Thus there exist integers A and B such that f(i) = (A * i + B) % M, where M is the deck_size.
-
-    // To determine the coefficients a and b, we can apply the shuffle twice and solve equations:
-    // X = 2020, f(X) = Y, f(Z) = f(Y) = f(f(X)), with Y = A * X + B and Z = A * Y + B
-    // Subtracting the latter from the former yields Y - Z = A * X + B - (A * Y + B) = A (X - Y)
-    // Thus A = (Y - Z) / (X - Y) = (Y - Z) * modular_inverse(X - Y)
-    // and B = Y - (A * X) or B = Z - (A * Y)
-    let initial = 2020;
-    let size = PART2_DECK_SIZE;
-
-    // Let's shuffle twice to get the values for y (once) and z (twice)
-    let results = shuffle_n_times(initial, size, 2);
-    let (a, b) = determine_coefficients(initial, results[1], results[2], size);
-
-    apply_linear_function_n_times(initial, size, a, b, PART2_SHUFFLE_COUNT)
-}
-
-fn shuffle_n_times(initial: usize, size: usize, n: usize) -> Vec<usize> {
-    let mut deck = SingleCardPosTrackingDeck::new(initial, size);
-    let input = parse(INPUT);
-    let techniques = parse_shuffle_techniques(input);
-    let inverted = invert(deck.size, techniques);
-
-    let mut result = vec![initial];
-
-    for _ in 0..n {
-        deck.shuffle_with(&inverted);
-        result.push(deck.pos);
-    }
-
-    result
-}
-
-fn determine_coefficients(x: usize, y: usize, z: usize, size: usize) -> (usize, usize) {
-    // let a = (y - z) / (x - y); -> (y - z) * modular_inverse(x - y)
-    let y_minus_z = modular_subtract(y, z, size);
-    let x_minus_y = modular_subtract(x, y, size);
-    let mod_inv_x_minus_y = modular_inverse(x_minus_y, size);
-    let a = modular_multiply(y_minus_z, mod_inv_x_minus_y, size);
-
-    // let b = y - (a * x)
-    let a_times_x = modular_multiply(a, x, size);
-    let b = modular_subtract(y, a_times_x, size);
-
-    (a, b)
-}
-
-fn apply_linear_function_n_times(init: usize, size: usize, a: usize, b: usize, n: usize) -> usize {
-    let a_pow_n = modular_power(a, n, size);
-    (modular_multiply(a_pow_n, init, size)
-        + modular_multiply(
-            a_pow_n - 1,
-            modular_multiply(modular_inverse(a - 1, size), b, size),
-            size,
-        ))
-        % size
+    // Such a function can be inverted, and applied n times with O(log n) compositions.
+    let techniques = parse_shuffle_techniques(parse(INPUT));
+    let shuffle = ShuffleMap::compile(&techniques, PART2_DECK_SIZE);
+    shuffle.card_at(2020, PART2_SHUFFLE_COUNT)
 }
 
 /// Invert the shuffle techniques
+#[cfg(test)]
 fn invert(deck_size: usize, techniques: Vec<ShuffleTechnique>) -> Vec<ShuffleTechnique> {
     techniques
         .into_iter()
@@ -98,86 +49,11 @@ fn invert(deck_size: usize, techniques: Vec<ShuffleTechnique>) -> Vec<ShuffleTec
         .collect()
 }
 
-/// Calculate x - y % size
-fn modular_subtract(x: usize, y: usize, size: usize) -> usize {
-    if x >= y {
-        x - y
-    } else {
-        x + size - y
-    }
-}
-
-/// calculate (x * y) % size, handling overflow
-fn modular_multiply(x: usize, y: usize, size: usize) -> usize {
-    if let Some(product) = x.checked_mul(y) {
-        product % size
-    } else {
-        let (x, y, size) = (x as u128, y as u128, size as u128);
-        ((x * y) % size) as usize
-    }
-}
-
 /// Calculate f2 = 1 / f1 in a modular way. f2 = modular_inverse(f1, size) means
 /// finding an f2 such that f1 * f2 % size == 1.
+#[cfg(test)]
 fn modular_inverse(f1: usize, size: usize) -> usize {
-    let naive = false;
-    if naive {
-        // Naive version
-        let mut f2 = 1;
-        while f2 % f1 != 0 {
-            // Easy to run into overflow here with large numbers like 106461317257343.
-            // Tried using u128 but was way too slow for this large number
-            f2 += size;
-        }
-        f2 /= f1;
-        f2
-    } else {
-        // Adapted from https://stackoverflow.com/q/68338719
-        if size == 1 {
-            return 1;
-        }
-        let p = size as i128;
-        let (mut o, mut m) = (f1 as i128, p as i128);
-        while o < 0 {
-            o += m;
-        }
-        let mut x = 0;
-        let mut inv = 1;
-        while o > 1 {
-            let div = o / m;
-            o %= m;
-            inv -= div * x;
-            swap(&mut o, &mut m);
-            swap(&mut x, &mut inv);
-        }
-        if inv < 0 {
-            inv += p;
-        }
-
-        inv as usize
-    }
-}
-
-/// Calculate base ^ exp % size
-fn modular_power(base: usize, exp: usize, size: usize) -> usize {
-    let mut bits: Vec<_> = format!("{:b}", exp)
-        .chars()
-        .map(|c| match c {
-            '1' => true,
-            '0' => false,
-            _ => unreachable!(),
-        })
-        .collect();
-
-    let mut result = 1;
-    let mut power_of_2 = base; // 2 ^ 1
-    while let Some(bit) = bits.pop() {
-        if bit {
-            result = modular_multiply(result, power_of_2, size);
-        }
-        power_of_2 = modular_multiply(power_of_2, power_of_2, size);
-    }
-    result
+    inv_mod(f1, size).expect("a number coprime to the deck size")
 }
 
 fn shuffle_deck(size: usize, input: Vec<&str>) -> FullDeck {
@@ -198,6 +74,7 @@ enum ShuffleTechnique {
     DealWithIncrement(usize),
 }
 impl ShuffleTechnique {
+    #[cfg(test)]
     fn invert(&self, size: usize) -> Self {
         match self {
             ShuffleTechnique::DealIntoNewStack => *self,
@@ -225,6 +102,19 @@ impl ShuffleTechnique {
             }
         }
     }
+    /// The map from a card's position before this technique to its position afterwards
+    fn to_affine_map(self, size: usize) -> AffineMap {
+        match self {
+            // p -> size - 1 - p
+            ShuffleTechnique::DealIntoNewStack => AffineMap::new(size - 1, size - 1, size),
+            // p -> p - n
+            ShuffleTechnique::CutNCards(n) => {
+                AffineMap::new(1, (-n).rem_euclid(size as isize) as usize, size)
+            }
+            // p -> p * i
+            ShuffleTechnique::DealWithIncrement(i) => AffineMap::new(i, 0, size),
+        }
+    }
 }
 impl From<&str> for ShuffleTechnique {
     fn from(s: &str) -> Self {
@@ -241,6 +131,62 @@ impl From<&str> for ShuffleTechnique {
     }
 }
 
+/// A sequence of shuffle techniques compiled into a single affine map,
+/// taking the position of a card before the shuffle to its position afterwards.
+/// In a factory order deck, each card's value is its initial position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ShuffleMap {
+    forward: AffineMap,
+}
+impl ShuffleMap {
+    fn compile(techniques: &[ShuffleTechnique], size: usize) -> Self {
+        let unshuffled = ShuffleMap {
+            forward: AffineMap::identity(size),
+        };
+        techniques.iter().fold(unshuffled, |shuffle, technique| {
+            shuffle.then(&ShuffleMap {
+                forward: technique.to_affine_map(size),
+            })
+        })
+    }
+    /// This shuffle followed by the `next` one
+    fn then(&self, next: &ShuffleMap) -> ShuffleMap {
+        ShuffleMap {
+            forward: self.forward.then(&next.forward),
+        }
+    }
+    /// The shuffle restoring the order from before this one
+    fn inverse(&self) -> ShuffleMap {
+        let forward = self
+            .forward
+            .inverse()
+            .expect("increments that are coprime to the deck size");
+        ShuffleMap { forward }
+    }
+    /// This shuffle performed `rounds` times in a row
+    fn repeated(&self, rounds: usize) -> ShuffleMap {
+        ShuffleMap {
+            forward: self.forward.pow(rounds),
+        }
+    }
+    /// The position of the card that started out at position `card`, after `rounds` shuffles
+    fn position_of(&self, card: usize, rounds: usize) -> usize {
+        self.repeated(rounds).forward.apply(card)
+    }
+    /// The card at `position` after `rounds` shuffles
+    fn card_at(&self, position: usize, rounds: usize) -> usize {
+        self.inverse().repeated(rounds).forward.apply(position)
+    }
+    /// The whole deck after `rounds` shuffles, only sensible for small decks
+    #[cfg(test)]
+    fn deck(&self, rounds: usize) -> FullDeck {
+        let undo = self.inverse().repeated(rounds).forward;
+        (0..self.forward.modulus)
+            .map(|pos| undo.apply(pos))
+            .collect()
+    }
+}
+
 trait Shuffle {
     fn shuffle_with(&mut self, techniques: &[ShuffleTechnique]) {
         for tech in techniques {
@@ -250,6 +196,7 @@ trait Shuffle {
     fn apply(&mut self, technique: &ShuffleTechnique);
 }
 
+#[cfg(test)]
 #[derive(Debug)]
 struct SingleCardPosTrackingDeck {
     // The position to track
@@ -257,11 +204,13 @@ struct SingleCardPosTrackingDeck {
     // The size of the deck
     size: usize,
 }
+#[cfg(test)]
 impl SingleCardPosTrackingDeck {
     fn new(pos: usize, size: usize) -> Self {
         SingleCardPosTrackingDeck { pos, size }
     }
 }
+#[cfg(test)]
 impl Shuffle for SingleCardPosTrackingDeck {
     fn apply(&mut self, technique: &ShuffleTechnique) {
         match technique {
@@ -274,7 +223,7 @@ impl Shuffle for SingleCardPosTrackingDeck {
                 }
             }
             ShuffleTechnique::DealWithIncrement(i) => {
-                self.pos = modular_multiply(self.pos, *i, self.size);
+                self.pos = mul_mod(self.pos, *i, self.size);
             }
         }
     }
@@ -308,6 +257,7 @@ impl Shuffle for FullDeck {
 mod tests {
     use super::*;
     use crate::parse;
+    use number_theory::pow_mod;
 
     #[test]
     fn deal_into_new_stack() {
@@ -536,6 +486,98 @@ cut -1";
         decks.into_iter().map(|d| d.pos).collect()
     }
 
+    /// A xorshift generator, for reproducible property tests
+    struct Rng(u64);
+    impl Rng {
+        fn below(&mut self, bound: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % bound as u64) as usize
+        }
+    }
+
+    fn random_techniques(rng: &mut Rng, size: usize) -> Vec<ShuffleTechnique> {
+        let count = 1 + rng.below(10);
+        (0..count)
+            .map(|_| match rng.below(3) {
+                0 => ShuffleTechnique::DealIntoNewStack,
+                1 => ShuffleTechnique::CutNCards(rng.below(2 * size) as isize - size as isize),
+                _ => loop {
+                    let increment = 1 + rng.below(size - 1);
+                    if number_theory::gcd(increment, size) == 1 {
+                        break ShuffleTechnique::DealWithIncrement(increment);
+                    }
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shuffle_map_example_4() {
+        let techniques = parse_shuffle_techniques(parse(EXAMPLE4));
+        let shuffle = ShuffleMap::compile(&techniques, 10);
+        assert_eq!(vec![9, 2, 5, 8, 1, 4, 7, 0, 3, 6], shuffle.deck(1));
+        assert_eq!(7, shuffle.position_of(0, 1));
+        assert_eq!(9, shuffle.card_at(0, 1));
+    }
+
+    #[test]
+    fn shuffle_map_matches_full_deck() {
+        let mut rng = Rng(2019);
+        for size in [2, 7, 10, 12, 97] {
+            for _ in 0..20 {
+                let techniques = random_techniques(&mut rng, size);
+                let shuffle = ShuffleMap::compile(&techniques, size);
+                let mut deck: FullDeck = (0..size).collect();
+                for rounds in 0..5 {
+                    assert_eq!(deck, shuffle.deck(rounds), "{techniques:?} x {rounds}");
+                    for card in 0..size {
+                        assert_eq!(card, deck[shuffle.position_of(card, rounds)]);
+                        assert_eq!(deck[card], shuffle.card_at(card, rounds));
+                    }
+                    deck.shuffle_with(&techniques);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shuffle_map_composition_and_inverse() {
+        let mut rng = Rng(22);
+        let size = 10_007;
+        let unshuffled = ShuffleMap::compile(&[], size);
+        for _ in 0..100 {
+            let first = random_techniques(&mut rng, size);
+            let second = random_techniques(&mut rng, size);
+            let shuffle = ShuffleMap::compile(&first, size);
+            let both: Vec<_> = first.iter().chain(&second).copied().collect();
+            assert_eq!(
+                ShuffleMap::compile(&both, size),
+                shuffle.then(&ShuffleMap::compile(&second, size))
+            );
+            assert_eq!(unshuffled, shuffle.then(&shuffle.inverse()));
+            assert_eq!(
+                shuffle.inverse(),
+                ShuffleMap::compile(&invert(size, first), size)
+            );
+            assert_eq!(shuffle.then(&shuffle).then(&shuffle), shuffle.repeated(3));
+        }
+    }
+
+    #[test]
+    fn shuffle_map_huge_number_of_rounds() {
+        let techniques = parse_shuffle_techniques(parse(EXAMPLE4));
+        let shuffle = ShuffleMap::compile(&techniques, PART2_DECK_SIZE);
+        let card = shuffle.card_at(2020, PART2_SHUFFLE_COUNT);
+        assert_eq!(2020, shuffle.position_of(card, PART2_SHUFFLE_COUNT));
+        let all_but_one = shuffle.repeated(PART2_SHUFFLE_COUNT - 1);
+        assert_eq!(
+            shuffle.repeated(PART2_SHUFFLE_COUNT),
+            all_but_one.then(&shuffle)
+        );
+    }
+
     #[test]
     fn part1() {
         assert_eq!(2519, day22_part1());
@@ -557,17 +599,14 @@ cut -1";
     }
 
     #[test]
-    fn test_modular_multiply() {
+    fn test_mul_mod() {
         assert_eq!(
-            modular_multiply(53029207790359, 93748063761037, 119315717514047),
+            mul_mod(53029207790359, 93748063761037, 119315717514047),
             46400556811471
         );
         // The following just fits within 64-bits:
         // u64::MAX = 3 * 5 * 17 * 257 * 641 * 65537 * 6700417
-        assert_eq!(
-            modular_multiply(usize::MAX / 6700417, 6700417, usize::MAX),
-            0
-        );
+        assert_eq!(mul_mod(usize::MAX / 6700417, 6700417, usize::MAX), 0);
     }
 
     #[test]
@@ -576,65 +615,35 @@ cut -1";
     }
 
     #[test]
-    fn test_determine_coefficients() {
-        let initial = 2020;
-        let size = PART2_DECK_SIZE;
-        let results = shuffle_n_times(initial, size, 2);
-        let (a, b) = determine_coefficients(initial, results[1], results[2], size);
-
-        assert_eq!(results[1], (modular_multiply(a, initial, size) + b) % size);
-        assert_eq!(
-            results[2],
-            (modular_multiply(a, results[1], size) + b) % size
-        );
-    }
-
-    #[test]
-    fn test_modular_power() {
+    fn test_pow_mod() {
         let base = 12854400258724;
         let size = PART2_DECK_SIZE;
 
         let mut res = base;
-        assert_eq!(res, modular_power(base, 1, size));
+        assert_eq!(res, pow_mod(base, 1, size));
 
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 2, size));
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 2, size));
 
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 3, size));
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 3, size));
 
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 4, size));
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 4, size));
 
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 5, size));
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 5, size));
 
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 6, size));
-
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 7, size));
-
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 8, size));
-
-        res = modular_multiply(res, base, size);
-        assert_eq!(res, modular_power(base, 9, size));
-    }
-    #[test]
-    fn test_apply_linear_function_n_times() {
-        let init = 2020;
-        let size = PART2_DECK_SIZE;
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 6, size));
 
-        let res = shuffle_n_times(init, size, 5);
-        let (a, b) = determine_coefficients(init, res[1], res[2], size);
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 7, size));
 
-        apply_linear_function_n_times(init, size, a, b, 1);
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 8, size));
 
-        assert_eq!(res[1], apply_linear_function_n_times(init, size, a, b, 1));
-        assert_eq!(res[2], apply_linear_function_n_times(init, size, a, b, 2));
-        assert_eq!(res[3], apply_linear_function_n_times(init, size, a, b, 3));
-        assert_eq!(res[4], apply_linear_function_n_times(init, size, a, b, 4));
-        assert_eq!(res[5], apply_linear_function_n_times(init, size, a, b, 5));
+        res = mul_mod(res, base, size);
+        assert_eq!(res, pow_mod(base, 9, size));
     }
 }
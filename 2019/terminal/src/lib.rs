@@ -0,0 +1,394 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+impl Color {
+    pub const BLACK: Color = Color::new(0, 0, 0);
+    pub const WHITE: Color = Color::new(255, 255, 255);
+    pub const GREY: Color = Color::new(128, 128, 128);
+    pub const RED: Color = Color::new(255, 0, 0);
+    pub const DARK_RED: Color = Color::new(128, 0, 0);
+    pub const GREEN: Color = Color::new(64, 255, 64);
+    pub const BLUE: Color = Color::new(64, 64, 255);
+    pub const LIGHT_BLUE: Color = Color::new(128, 128, 255);
+    pub const YELLOW: Color = Color::new(255, 255, 0);
+
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+    /// Black or white, whichever is easier to read on top of this color
+    fn contrasting(&self) -> Color {
+        let luma = 299 * self.r as u32 + 587 * self.g as u32 + 114 * self.b as u32;
+        if luma > 128_000 {
+            Color::BLACK
+        } else {
+            Color::WHITE
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cell {
+    pub symbol: char,
+    pub color: Color,
+}
+
+/// Maps puzzle coordinates onto the rows and columns of a frame
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Viewport {
+    min_x: isize,
+    max_x: isize,
+    min_y: isize,
+    max_y: isize,
+    y_axis_up: bool,
+}
+impl Viewport {
+    /// The smallest viewport containing all points. With `y_axis_up`,
+    /// larger y values are drawn closer to the top of the frame.
+    pub fn from_points<I: IntoIterator<Item = (isize, isize)>>(points: I, y_axis_up: bool) -> Self {
+        let mut viewport = Viewport {
+            min_x: isize::MAX,
+            max_x: isize::MIN,
+            min_y: isize::MAX,
+            max_y: isize::MIN,
+            y_axis_up,
+        };
+        for (x, y) in points {
+            viewport.min_x = viewport.min_x.min(x);
+            viewport.max_x = viewport.max_x.max(x);
+            viewport.min_y = viewport.min_y.min(y);
+            viewport.max_y = viewport.max_y.max(y);
+        }
+        if viewport.min_x > viewport.max_x {
+            // No points at all
+            viewport.min_x = 0;
+            viewport.max_x = 0;
+            viewport.min_y = 0;
+            viewport.max_y = 0;
+        }
+        viewport
+    }
+    pub fn width(&self) -> usize {
+        (self.max_x - self.min_x + 1) as usize
+    }
+    pub fn height(&self) -> usize {
+        (self.max_y - self.min_y + 1) as usize
+    }
+    /// Column and row of the given point, if it's within this viewport
+    pub fn cell_of(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        if x < self.min_x || x > self.max_x || y < self.min_y || y > self.max_y {
+            return None;
+        }
+        let col = (x - self.min_x) as usize;
+        let row = if self.y_axis_up {
+            (self.max_y - y) as usize
+        } else {
+            (y - self.min_y) as usize
+        };
+        Some((col, row))
+    }
+}
+
+/// A grid of colored symbols plus an optional status line below it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    viewport: Viewport,
+    cells: Vec<Option<Cell>>,
+    background: Color,
+    status: String,
+}
+impl Frame {
+    pub fn new(viewport: Viewport, background: Color) -> Self {
+        Frame {
+            viewport,
+            cells: vec![None; viewport.width() * viewport.height()],
+            background,
+            status: String::new(),
+        }
+    }
+    /// Draws a symbol at the given puzzle coordinates. Points outside the viewport are ignored.
+    pub fn draw(&mut self, x: isize, y: isize, symbol: char, color: Color) {
+        if let Some((col, row)) = self.viewport.cell_of(x, y) {
+            self.cells[row * self.viewport.width() + col] = Some(Cell { symbol, color });
+        }
+    }
+    pub fn cell_at(&self, x: isize, y: isize) -> Option<&Cell> {
+        self.viewport
+            .cell_of(x, y)
+            .and_then(|(col, row)| self.cells[row * self.viewport.width() + col].as_ref())
+    }
+    pub fn set_status<S: Into<String>>(&mut self, status: S) {
+        self.status = status.into();
+    }
+    fn rows(&self) -> impl Iterator<Item = &[Option<Cell>]> {
+        self.cells.chunks(self.viewport.width())
+    }
+    /// Just the symbols, one line per row, for recording to a file or for tests
+    pub fn to_plain_string(&self) -> String {
+        let mut s = String::new();
+        for row in self.rows() {
+            let line: String = row
+                .iter()
+                .map(|cell| cell.map(|cell| cell.symbol).unwrap_or(' '))
+                .collect();
+            s.push_str(line.trim_end());
+            s.push('\n');
+        }
+        if !self.status.is_empty() {
+            s.push_str(&self.status);
+            s.push('\n');
+        }
+        s
+    }
+    /// Colored output using 24-bit ANSI escape codes. Each cell is two characters
+    /// wide, to make up for terminal characters being about twice as high as wide.
+    pub fn to_ansi_string(&self) -> String {
+        let mut s = String::new();
+        for row in self.rows() {
+            for cell in row {
+                let (symbol, bg) = match cell {
+                    Some(cell) => (cell.symbol, cell.color),
+                    None => (' ', self.background),
+                };
+                let fg = bg.contrasting();
+                s.push_str(&format!(
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m{} ",
+                    fg.r, fg.g, fg.b, bg.r, bg.g, bg.b, symbol
+                ));
+            }
+            s.push_str("\x1b[0m\x1b[K\n");
+        }
+        s.push_str(&self.status);
+        s.push_str("\x1b[K\n");
+        s
+    }
+}
+
+/// Something that changes over time and can be drawn, driven one tick at a time
+pub trait Animation {
+    /// Advances by one tick. Returns false, without changing anything,
+    /// once there's nothing left to animate.
+    fn update(&mut self) -> bool;
+    fn draw(&self) -> Frame;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub title: String,
+    /// Upper limit of frames per second in the terminal. 0 means as fast as possible
+    pub fps: u32,
+    /// Stop after this many frames, even if the animation isn't done yet
+    pub max_frames: Option<usize>,
+    /// Write plain-text frames to this file instead of animating in the terminal
+    pub record_to: Option<PathBuf>,
+}
+impl Settings {
+    pub fn new(title: &str, fps: u32) -> Self {
+        Settings {
+            title: title.to_string(),
+            fps,
+            max_frames: None,
+            record_to: None,
+        }
+    }
+    /// Overrides the defaults with the command line arguments
+    /// `--fps <n>`, `--max-frames <n>` and `--record <file>`
+    pub fn from_args(title: &str, default_fps: u32) -> Self {
+        Settings::new(title, default_fps).with_args(std::env::args().skip(1))
+    }
+    fn with_args<I: IntoIterator<Item = String>>(mut self, args: I) -> Self {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .unwrap_or_else(|| panic!("Missing value for argument '{}'", arg));
+            match arg.as_str() {
+                "--fps" => self.fps = value.parse().expect("Invalid --fps"),
+                "--max-frames" => {
+                    self.max_frames = Some(value.parse().expect("Invalid --max-frames"))
+                }
+                "--record" => self.record_to = Some(PathBuf::from(value)),
+                _ => panic!("Unknown argument '{}'", arg),
+            }
+        }
+        self
+    }
+    /// Runs the animation until it's done (or `max_frames` is reached),
+    /// either live in the terminal or recorded to a file. Returns the number of frames drawn.
+    pub fn run<A: Animation>(&self, animation: &mut A) -> std::io::Result<usize> {
+        match &self.record_to {
+            Some(path) => self.record(animation, BufWriter::new(File::create(path)?)),
+            None => self.play(animation, std::io::stdout().lock()),
+        }
+    }
+    /// Writes every frame as plain text, each preceded by a header line with its number
+    pub fn record<A: Animation, W: Write>(
+        &self,
+        animation: &mut A,
+        mut out: W,
+    ) -> std::io::Result<usize> {
+        writeln!(out, "{}", self.title)?;
+        let frame_count = self.for_each_frame(animation, |number, frame| {
+            write!(out, "\nframe {}\n{}", number, frame.to_plain_string())
+        })?;
+        out.flush()?;
+        Ok(frame_count)
+    }
+    /// Redraws every frame in place in the terminal, limited to `fps` frames per second
+    pub fn play<A: Animation, W: Write>(
+        &self,
+        animation: &mut A,
+        mut out: W,
+    ) -> std::io::Result<usize> {
+        let frame_duration = if self.fps > 0 {
+            Duration::from_secs(1) / self.fps
+        } else {
+            Duration::ZERO
+        };
+        // Clear the screen and hide the cursor
+        write!(out, "\x1b[2J\x1b[?25l")?;
+        let result = self.for_each_frame(animation, |number, frame| {
+            let start = Instant::now();
+            write!(
+                out,
+                "\x1b[H{} (frame {})\x1b[K\n{}",
+                self.title,
+                number,
+                frame.to_ansi_string()
+            )?;
+            out.flush()?;
+            if let Some(remaining) = frame_duration.checked_sub(start.elapsed()) {
+                thread::sleep(remaining);
+            }
+            Ok(())
+        });
+        // Restore the cursor, even if drawing failed
+        write!(out, "\x1b[0m\x1b[?25h")?;
+        out.flush()?;
+        result
+    }
+    fn for_each_frame<A, F>(&self, animation: &mut A, mut on_frame: F) -> std::io::Result<usize>
+    where
+        A: Animation,
+        F: FnMut(usize, &Frame) -> std::io::Result<()>,
+    {
+        let mut number = 0;
+        loop {
+            on_frame(number, &animation.draw())?;
+            number += 1;
+            if self.max_frames.is_some_and(|max| number >= max) || !animation.update() {
+                break;
+            }
+        }
+        Ok(number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A dot moving to the right until it reaches x = 2
+    struct MovingDot {
+        x: isize,
+    }
+    impl Animation for MovingDot {
+        fn update(&mut self) -> bool {
+            if self.x < 2 {
+                self.x += 1;
+                true
+            } else {
+                false
+            }
+        }
+        fn draw(&self) -> Frame {
+            let viewport = Viewport::from_points(vec![(0, 0), (2, 0)], false);
+            let mut frame = Frame::new(viewport, Color::BLACK);
+            frame.draw(self.x, 0, 'o', Color::RED);
+            frame.set_status(format!("x = {}", self.x));
+            frame
+        }
+    }
+
+    #[test]
+    fn viewport_with_y_axis_down() {
+        let viewport = Viewport::from_points(vec![(-1, 5), (3, 7)], false);
+        assert_eq!(viewport.width(), 5);
+        assert_eq!(viewport.height(), 3);
+        assert_eq!(viewport.cell_of(-1, 5), Some((0, 0)));
+        assert_eq!(viewport.cell_of(3, 7), Some((4, 2)));
+        assert_eq!(viewport.cell_of(4, 7), None);
+    }
+
+    #[test]
+    fn viewport_with_y_axis_up() {
+        let viewport = Viewport::from_points(vec![(-1, 5), (3, 7)], true);
+        assert_eq!(viewport.cell_of(-1, 5), Some((0, 2)));
+        assert_eq!(viewport.cell_of(3, 7), Some((4, 0)));
+    }
+
+    #[test]
+    fn frame_to_plain_string() {
+        let mut frame = Frame::new(
+            Viewport::from_points(vec![(0, 0), (2, 1)], false),
+            Color::BLACK,
+        );
+        frame.draw(0, 0, '#', Color::WHITE);
+        frame.draw(2, 1, '@', Color::YELLOW);
+        frame.draw(3, 1, 'x', Color::YELLOW); // outside, ignored
+        assert_eq!(frame.to_plain_string(), "#\n  @\n");
+        assert_eq!(frame.cell_at(2, 1).map(|cell| cell.symbol), Some('@'));
+    }
+
+    #[test]
+    fn frame_to_ansi_string() {
+        let mut frame = Frame::new(Viewport::from_points(vec![(0, 0)], false), Color::BLACK);
+        frame.draw(0, 0, '#', Color::WHITE);
+        assert_eq!(
+            frame.to_ansi_string(),
+            "\x1b[38;2;0;0;0m\x1b[48;2;255;255;255m# \x1b[0m\x1b[K\n\x1b[K\n"
+        );
+    }
+
+    #[test]
+    fn settings_from_args() {
+        let args = vec!["--fps", "5", "--record", "out.txt", "--max-frames", "10"];
+        let settings = Settings::new("title", 30).with_args(args.into_iter().map(String::from));
+        assert_eq!(settings.fps, 5);
+        assert_eq!(settings.max_frames, Some(10));
+        assert_eq!(settings.record_to, Some(PathBuf::from("out.txt")));
+    }
+
+    #[test]
+    fn record_until_done() {
+        let mut out = vec![];
+        let frame_count = Settings::new("dot", 0)
+            .record(&mut MovingDot { x: 0 }, &mut out)
+            .unwrap();
+        assert_eq!(frame_count, 3);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "dot\n\nframe 0\no\nx = 0\n\nframe 1\n o\nx = 1\n\nframe 2\n  o\nx = 2\n"
+        );
+    }
+
+    #[test]
+    fn record_with_max_frames() {
+        let mut settings = Settings::new("dot", 0);
+        settings.max_frames = Some(2);
+        let mut out = vec![];
+        let frame_count = settings.record(&mut MovingDot { x: 0 }, &mut out).unwrap();
+        assert_eq!(frame_count, 2);
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with("frame 1\n o\nx = 1\n"));
+    }
+}
@@ -0,0 +1,48 @@
+//! Evaluates the expressions typed in, one per line, with the operator precedence of day 18.
+//!
+//! ```sh
+//! cargo run --bin day18 -- addition-first
+//! ```
+//!
+//! The rules are one of `same-precedence` (part 1, the default), `addition-first` (part 2)
+//! or `conventional`, and can be switched while running by entering `:rules <name>`.
+use advent_of_code_2020::day18::{Expression, Rules};
+use std::io::{stdin, stdout, BufRead, Write};
+
+fn rules_named(name: &str) -> Option<Rules> {
+    match name {
+        "same-precedence" => Some(Rules::same_precedence()),
+        "addition-first" => Some(Rules::addition_first()),
+        "conventional" => Some(Rules::conventional()),
+        _ => None,
+    }
+}
+
+fn main() {
+    let name = std::env::args()
+        .nth(1)
+        .unwrap_or("same-precedence".to_string());
+    let Some(mut rules) = rules_named(&name) else {
+        eprintln!("Unknown rules '{}'", name);
+        std::process::exit(1);
+    };
+    print!("> ");
+    stdout().flush().unwrap();
+    for line in stdin().lock().lines() {
+        let line = line.unwrap();
+        if let Some(name) = line.trim().strip_prefix(":rules") {
+            match rules_named(name.trim()) {
+                Some(named) => rules = named,
+                None => println!("Unknown rules '{}'", name.trim()),
+            }
+        } else if !line.trim().is_empty() {
+            match Expression::parse(&line, &rules) {
+                Ok(expression) => println!("{} = {}", expression, expression.value()),
+                Err(error) => println!("Error: {}", error),
+            }
+        }
+        print!("> ");
+        stdout().flush().unwrap();
+    }
+    println!();
+}
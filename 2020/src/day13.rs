@@ -1,3 +1,5 @@
+use number_theory::crt;
+
 pub(crate) fn day13_part1impl(input: &[String]) -> usize {
     let earliest_time: usize = input[0].parse().expect("a number");
     let bus_ids = input[1]
@@ -95,37 +97,12 @@ fn find_factors2(base: Vec<usize>, offset: Vec<usize>) -> (Vec<usize>, usize) {
 
 pub(crate) fn day13_part2impl(input: &[String]) -> usize {
     let (offsets, freqs) = get_offsets_and_bases_as_separate_vecs(input);
-    let mut bus_count = 2; // Start with two and add one more at a time
-    let mut time = 0;
-    while bus_count <= freqs.len() {
-        time = find_meet_time(&freqs[0..bus_count], &offsets[0..bus_count], time);
-        bus_count += 1;
-    }
-    time
-}
-
-/// Find integer factors f[i] for all `time + offset[i] = f[i] * freq[i]` equations
-fn find_meet_time(freq: &[usize], offset: &[usize], start: usize) -> usize {
-    assert_eq!(freq.len(), offset.len());
-    let mut time = start;
-
-    // We add elements one by one, and all but the last were already synced
-    // If these weren't all primes, one could have to use their least common multiple
-    let inc: usize = freq.iter().rev().skip(1).product();
-    // Increase time until all equations hold
-    while !freq
-        .iter()
-        .enumerate()
-        .all(|(i, base)| (time + offset[i]) % base == 0)
-    {
-        time += inc;
-    }
-    // println!(
-    //     "Time {} = {} steps @ size {}",
-    //     time,
-    //     (time - start) / inc,
-    //     inc
-    // );
+    // Bus i departs at time + offset[i], so time ≡ -offset[i] (mod freq[i])
+    let congruences = offsets
+        .into_iter()
+        .zip(freqs)
+        .map(|(offset, freq)| ((freq - offset % freq) % freq, freq));
+    let (time, _period) = crt(congruences).expect("busses that meet eventually");
     time
 }
 
@@ -159,16 +136,19 @@ mod tests {
     }
 
     #[test]
-    fn part2_example1_incremental_with_find_factors() {
+    fn part2_example1_incremental_with_crt() {
         // Find the earliest time where the first two busses meet
-        assert_eq!(find_meet_time(&[7, 13], &[0, 1], 0), 77);
-        // From there, find where they meet the next bus. The step size is increased by the
+        assert_eq!(crt([(0, 7), (12, 13)]), Some((77, 7 * 13)));
+        // From there, find where they meet the next bus. The period is the
         // common multiple of previous bus frequencies
-        assert_eq!(find_meet_time(&[7, 13, 59], &[0, 1, 4], 77), 350);
-        assert_eq!(find_meet_time(&[7, 13, 59, 31], &[0, 1, 4, 6], 350), 70147);
+        assert_eq!(crt([(77, 7 * 13), (55, 59)]), Some((350, 7 * 13 * 59)));
+        assert_eq!(
+            crt([(350, 7 * 13 * 59), (25, 31)]),
+            Some((70147, 7 * 13 * 59 * 31))
+        );
         assert_eq!(
-            find_meet_time(&[7, 13, 59, 31, 19], &[0, 1, 4, 6, 7], 70147),
-            1068781
+            crt([(70147, 7 * 13 * 59 * 31), (12, 19)]),
+            Some((1068781, 7 * 13 * 59 * 31 * 19))
         );
     }
 
@@ -295,7 +275,6 @@ mod tests {
         assert_eq!(day13_part2impl(&read_str_to_lines(EXAMPLE6)), 1202161486);
     }
 
-    // exceedingly slow…
     #[test]
     fn part2() {
         assert_eq!(
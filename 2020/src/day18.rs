@@ -1,262 +1,301 @@
+use num_bigint::BigInt;
 use std::fmt::{Display, Formatter};
+use Associativity::*;
+use Operator::*;
 
-pub(crate) fn evaluate_day18_part1(lines: &[String]) -> usize {
-    lines.iter().map(|line| evaluate_line_part1(line)).sum()
+pub fn evaluate_day18_part1(lines: &[String]) -> usize {
+    sum_of_lines(lines, &Rules::same_precedence())
 }
 
-pub(crate) fn evaluate_day18_part2(lines: &[String]) -> usize {
-    lines.iter().map(|line| evaluate_line_part2(line)).sum()
+pub fn evaluate_day18_part2(lines: &[String]) -> usize {
+    sum_of_lines(lines, &Rules::addition_first())
 }
 
-#[derive(Debug, PartialEq)]
-enum Op {
+fn sum_of_lines(lines: &[String], rules: &Rules) -> usize {
+    let sum: BigInt = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| evaluate(line, rules).unwrap())
+        .sum();
+    usize::try_from(sum).unwrap()
+}
+
+pub fn evaluate(line: &str, rules: &Rules) -> Result<BigInt, ParseError> {
+    Ok(Expression::parse(line, rules)?.value())
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Operator {
     Addition,
+    Subtraction,
     Multiplication,
 }
-impl Display for Op {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl Operator {
+    fn applied_to(&self, left: BigInt, right: BigInt) -> BigInt {
         match self {
-            Op::Addition => write!(f, " + "),
-            Op::Multiplication => write!(f, " * "),
+            Addition => left + right,
+            Subtraction => left - right,
+            Multiplication => left * right,
         }
     }
 }
-impl Op {
-    fn from(s: &str) -> Self {
-        match s {
-            "+" => Op::Addition,
-            "*" => Op::Multiplication,
-            _ => panic!("Illegal op \"{}\"", s),
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Addition => write!(f, "+"),
+            Subtraction => write!(f, "-"),
+            Multiplication => write!(f, "*"),
         }
     }
 }
 
-#[derive(Debug)]
-struct Left {
-    left: Option<usize>,
-    op: Option<Op>,
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Associativity {
+    Left,
+    Right,
 }
-impl Default for Left {
-    fn default() -> Self {
-        Left {
-            left: None,
-            op: None,
-        }
-    }
+
+#[derive(Debug, PartialEq, Clone)]
+struct Rule {
+    symbol: char,
+    operator: Operator,
+    precedence: u8,
+    associativity: Associativity,
 }
-impl Left {
-    fn value(&mut self, right: usize) {
-        match self.left {
-            // init
-            None => {
-                assert!(self.op.is_none());
-                self.left = Some(right);
-            }
-            // apply value
-            Some(left) => {
-                self.left = match self.op {
-                    Some(Op::Addition) => Some(left + right),
-                    Some(Op::Multiplication) => Some(left * right),
-                    None => panic!("Operation missing!"),
-                };
-                self.op = None;
-            }
-        }
+
+/// Which symbols are operators, and how tightly they bind. Higher precedences bind tighter,
+/// and operators of equal precedence are grouped according to their associativity.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+impl Rules {
+    /// Part 1: `+` and `*` are evaluated left to right
+    pub fn same_precedence() -> Self {
+        Rules::default()
+            .with('+', Addition, 1, Left)
+            .with('*', Multiplication, 1, Left)
     }
-    fn set_op(&mut self, op: Op) {
-        assert!(self.left.is_some());
-        self.op = Some(op);
+
+    /// Part 2: `+` is evaluated before `*`
+    pub fn addition_first() -> Self {
+        Rules::default()
+            .with('+', Addition, 2, Left)
+            .with('*', Multiplication, 1, Left)
     }
-}
 
-fn evaluate_line_part1(line: &dyn AsRef<str>) -> usize {
-    let mut result: Left = Left::default();
-    let mut intermediates: Vec<Left> = vec![];
-    for s in line
-        .as_ref()
-        .replace("(", "( ")
-        .replace(")", " )")
-        .split_ascii_whitespace()
-    // .inspect(|s| println!("{}", s))
-    {
-        if let Ok(value) = s.parse() {
-            if let Some(intermediate) = intermediates.last_mut() {
-                intermediate.value(value);
-            } else {
-                result.value(value);
-            }
-        } else {
-            match s {
-                "(" => intermediates.push(Left::default()),
-                ")" => match intermediates.pop() {
-                    Some(last_intermediate) => {
-                        if let Some(prev_intermediate) = intermediates.last_mut() {
-                            prev_intermediate.value(last_intermediate.left.unwrap());
-                        } else {
-                            result.value(last_intermediate.left.unwrap());
-                        }
-                    }
-                    expected => panic!("Expected match for {:?}", expected),
-                },
-                op @ "+" | op @ "*" => {
-                    if let Some(intermediate) = intermediates.last_mut() {
-                        intermediate.set_op(Op::from(op));
-                    } else {
-                        result.set_op(Op::from(op));
-                    }
-                }
-                s => {
-                    panic!("Invalid string \"{}\"", s);
-                }
-            };
-        }
+    /// The usual rules from school, including subtraction
+    pub fn conventional() -> Self {
+        Rules::default()
+            .with('+', Addition, 1, Left)
+            .with('-', Subtraction, 1, Left)
+            .with('*', Multiplication, 2, Left)
     }
-    if let Some(left) = result.left {
-        left
-    } else {
-        panic!("No result")
+
+    pub fn with(
+        mut self,
+        symbol: char,
+        operator: Operator,
+        precedence: u8,
+        associativity: Associativity,
+    ) -> Self {
+        self.rules.retain(|rule| rule.symbol != symbol);
+        self.rules.push(Rule {
+            symbol,
+            operator,
+            precedence,
+            associativity,
+        });
+        self
+    }
+
+    fn get(&self, symbol: char) -> Option<&Rule> {
+        self.rules.iter().find(|rule| rule.symbol == symbol)
     }
 }
 
-#[derive(Debug)]
-struct LeftTwo {
-    left: Option<usize>,
-    middle: Option<usize>,
-    op1: Option<Op>,
-    op2: Option<Op>,
+#[derive(Debug, PartialEq)]
+pub enum ParseError {
+    UnexpectedChar { pos: usize, c: char },
+    ExpectedNumber { pos: usize },
+    ExpectedOperator { pos: usize },
+    UnclosedParenthesis { pos: usize },
+    UnexpectedEnd,
 }
-impl Default for LeftTwo {
-    fn default() -> Self {
-        LeftTwo {
-            left: None,
-            middle: None,
-            op1: None,
-            op2: None,
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnexpectedChar { pos, c } => {
+                write!(f, "unexpected '{}' at position {}", c, pos)
+            }
+            ParseError::ExpectedNumber { pos } => {
+                write!(f, "expected a number or '(' at position {}", pos)
+            }
+            ParseError::ExpectedOperator { pos } => {
+                write!(f, "expected an operator at position {}", pos)
+            }
+            ParseError::UnclosedParenthesis { pos } => {
+                write!(f, "the '(' at position {} is never closed", pos)
+            }
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of expression"),
         }
     }
 }
-impl LeftTwo {
-    fn add_value(&mut self, right: usize) {
-        match (self.left, self.middle) {
-            // Init
-            (None, None) => {
-                assert!(self.op1.is_none());
-                self.left = Some(right);
-            }
-            (Some(left), None) => match self.op1 {
-                None => panic!("op1 not set when adding second value"),
-                Some(Op::Addition) => {
-                    // a + b -> Simplify to left: (a + b)
-                    self.left = Some(left + right);
-                    self.op1 = None;
-                }
-                // Next op could be an addition, which should take precedence
-                Some(Op::Multiplication) => self.middle = Some(right),
-            },
-            (Some(left), Some(middle)) => {
-                assert_eq!(self.op1, Some(Op::Multiplication));
-                match self.op2 {
-                    None => panic!("op2 not set when adding third value"),
-                    Some(Op::Addition) => {
-                        // a * b + c -> Simplify to middle: a * (b + c)
-                        self.middle = Some(middle + right);
-                        self.op2 = None;
-                    }
-                    Some(Op::Multiplication) => {
-                        // a * b * c -> Simplify to middle: (a * b) * c
-                        self.left = Some(left * middle);
-                        self.middle = Some(right);
-                        self.op2 = None;
-                    }
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Number(BigInt),
+    Symbol(char),
+    Open,
+    Close,
+}
+
+/// Splits the line into tokens, along with their position
+fn tokenize(line: &str, rules: &Rules) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = line.char_indices().peekable();
+    while let Some((pos, c)) = chars.next() {
+        let token = match c {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            c if c.is_ascii_digit() => {
+                let mut end = pos + 1;
+                while let Some((_, _)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    end += 1;
                 }
+                Token::Number(line[pos..end].parse().unwrap())
             }
-            (None, Some(middle)) => panic!("Illegal state (None, {})", middle),
+            c if c.is_whitespace() => continue,
+            c if c.is_ascii_punctuation() || rules.get(c).is_some() => Token::Symbol(c),
+            c => return Err(ParseError::UnexpectedChar { pos, c }),
+        };
+        tokens.push((pos, token));
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    Number(BigInt),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+}
+impl Expression {
+    pub fn parse(line: &str, rules: &Rules) -> Result<Self, ParseError> {
+        let tokens = tokenize(line, rules)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            next: 0,
+            rules,
+        };
+        let expression = parser.expression(0)?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some((pos, Token::Close)) => Err(ParseError::UnexpectedChar { pos, c: ')' }),
+            Some((pos, _)) => Err(ParseError::ExpectedOperator { pos }),
         }
     }
-    fn set_op(&mut self, op: Op) {
-        assert!(self.left.is_some());
-        match (&self.op1, &self.op2) {
-            // Init
-            (None, None) => self.op1 = Some(op),
-            (Some(_), None) => self.op2 = Some(op),
-            (None, Some(op2)) => panic!("Illegal state (None, {})", op2),
-            (Some(op1), Some(op2)) => panic!("Illegal state ({}, {})", op1, op2),
+
+    pub fn value(&self) -> BigInt {
+        match self {
+            Expression::Number(number) => number.clone(),
+            Expression::Binary(operator, left, right) => {
+                operator.applied_to(left.value(), right.value())
+            }
         }
     }
-    fn get_value(&self) -> usize {
-        match (&self.op1, &self.op2) {
-            (None, None) => {
-                assert!(self.middle.is_none());
-                match self.left {
-                    None => panic!("Missing left on empty op1 & op2"),
-                    Some(left) => left,
-                }
+}
+/// Fully parenthesized, to show how the rules grouped the operations
+impl Display for Expression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expression::Number(number) => write!(f, "{}", number),
+            Expression::Binary(operator, left, right) => {
+                write!(f, "({} {} {})", left, operator, right)
             }
-            (Some(op1), None) => match (self.left, self.middle) {
-                (None, None) => panic!("Illegal state (None, None) with op1 {}", op1),
-                (Some(left), None) => panic!("Illegal state ({}, None) with op1 {}", left, op1),
-                (Some(left), Some(middle)) => match op1 {
-                    Op::Addition => left + middle,
-                    Op::Multiplication => left * middle,
-                },
-                (None, Some(middle)) => panic!("Illegal state (None, {}) with op1 {}", middle, op1),
-            },
-            (Some(op1), Some(op2)) => panic!("Illegal state with ops ({}, {})", op1, op2),
-            (None, Some(op2)) => panic!("Illegal state (None, {})", op2),
         }
     }
 }
 
-fn evaluate_line_part2(line: &dyn AsRef<str>) -> usize {
-    let mut result = LeftTwo::default();
-    let mut intermediates: Vec<LeftTwo> = vec![];
-    for s in line
-        .as_ref()
-        .replace("(", "( ")
-        .replace(")", " )")
-        .split_ascii_whitespace()
-    // .inspect(|s| println!("{}", s))
-    {
-        if let Ok(value) = s.parse() {
-            if let Some(intermediate) = intermediates.last_mut() {
-                intermediate.add_value(value);
-            } else {
-                result.add_value(value);
+/// A Pratt parser: each operator binds its right operand as long as the operators within it
+/// bind tighter, or equally tight for right-associative ones.
+struct Parser<'a> {
+    tokens: &'a [(usize, Token)],
+    next: usize,
+    rules: &'a Rules,
+}
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<(usize, &'a Token)> {
+        self.tokens.get(self.next).map(|(pos, token)| (*pos, token))
+    }
+
+    fn expression(&mut self, min_precedence: u8) -> Result<Expression, ParseError> {
+        let mut left = self.operand()?;
+        while let Some((pos, token)) = self.peek() {
+            let rule = match token {
+                Token::Close => break,
+                Token::Symbol(c) => self
+                    .rules
+                    .get(*c)
+                    .ok_or(ParseError::UnexpectedChar { pos, c: *c })?,
+                _ => return Err(ParseError::ExpectedOperator { pos }),
+            };
+            if rule.precedence < min_precedence {
+                break;
             }
-        } else {
-            match s {
-                "(" => intermediates.push(LeftTwo::default()),
-                ")" => match intermediates.pop() {
-                    Some(last_intermediate) => {
-                        if let Some(prev_intermediate) = intermediates.last_mut() {
-                            prev_intermediate.add_value(last_intermediate.get_value());
-                        } else {
-                            result.add_value(last_intermediate.get_value());
-                        }
-                    }
-                    expected => panic!("Expected match for {:?}", expected),
-                },
-                op @ "+" | op @ "*" => {
-                    if let Some(intermediate) = intermediates.last_mut() {
-                        intermediate.set_op(Op::from(op));
-                    } else {
-                        result.set_op(Op::from(op));
+            self.next += 1;
+            let right_min_precedence = match rule.associativity {
+                Left => rule.precedence + 1,
+                Right => rule.precedence,
+            };
+            let right = self.expression(right_min_precedence)?;
+            left = Expression::Binary(rule.operator, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn operand(&mut self) -> Result<Expression, ParseError> {
+        let (pos, token) = self.peek().ok_or(ParseError::UnexpectedEnd)?;
+        self.next += 1;
+        match token {
+            Token::Number(number) => Ok(Expression::Number(number.clone())),
+            Token::Open => {
+                let expression = self.expression(0)?;
+                match self.peek() {
+                    Some((_, Token::Close)) => {
+                        self.next += 1;
+                        Ok(expression)
                     }
+                    _ => Err(ParseError::UnclosedParenthesis { pos }),
                 }
-                s => {
-                    panic!("Invalid string \"{}\"", s);
-                }
-            };
+            }
+            _ => Err(ParseError::ExpectedNumber { pos }),
         }
-        // println!("{:?}", result);
     }
-    result.get_value()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::line_reader::read_file_to_lines;
+
+    // Not using the line_reader, which belongs to the main binary rather than the library
+    fn read_input_lines() -> Vec<String> {
+        include_str!("../input/day18.txt")
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn evaluate_line_part1(line: &&str) -> usize {
+        usize::try_from(evaluate(line, &Rules::same_precedence()).unwrap()).unwrap()
+    }
+
+    fn evaluate_line_part2(line: &&str) -> usize {
+        usize::try_from(evaluate(line, &Rules::addition_first()).unwrap()).unwrap()
+    }
+
+    fn ast(line: &str, rules: &Rules) -> String {
+        Expression::parse(line, rules).unwrap().to_string()
+    }
 
     #[test]
     fn part1_example_1() {
@@ -310,10 +349,7 @@ mod tests {
     }
     #[test]
     fn part1() {
-        assert_eq!(
-            evaluate_day18_part1(&read_file_to_lines("input/day18.txt")),
-            3348222486398
-        );
+        assert_eq!(evaluate_day18_part1(&read_input_lines()), 3348222486398);
     }
 
     #[test]
@@ -368,9 +404,103 @@ mod tests {
     }
     #[test]
     fn part2() {
+        assert_eq!(evaluate_day18_part2(&read_input_lines()), 43423343619505);
+    }
+
+    #[test]
+    fn ast_of_both_parts() {
+        let line = "1 + 2 * 3 + 4 * 5 + 6";
+        assert_eq!(
+            "(((((1 + 2) * 3) + 4) * 5) + 6)",
+            ast(line, &Rules::same_precedence())
+        );
+        assert_eq!(
+            "(((1 + 2) * (3 + 4)) * (5 + 6))",
+            ast(line, &Rules::addition_first())
+        );
+        assert_eq!(
+            "((2 * (3 + 4)) * 5)",
+            ast("2 * (3 + 4) * ((5))", &Rules::addition_first())
+        );
+    }
+
+    #[test]
+    fn conventional_rules() {
+        let rules = Rules::conventional();
+        assert_eq!("((10 - (2 * 3)) + 4)", ast("10 - 2 * 3 + 4", &rules));
+        assert_eq!(Ok(BigInt::from(-7)), evaluate("1 - 2 * 4", &rules));
+    }
+
+    #[test]
+    fn right_associativity() {
+        let rules = Rules::conventional().with('-', Subtraction, 1, Right);
+        assert_eq!("(8 - (4 - 2))", ast("8 - 4 - 2", &rules));
+        assert_eq!(Ok(BigInt::from(6)), evaluate("8 - 4 - 2", &rules));
+        assert_eq!(
+            Ok(BigInt::from(2)),
+            evaluate("8 - 4 - 2", &Rules::conventional())
+        );
+    }
+
+    #[test]
+    fn custom_symbols() {
+        let rules = Rules::default()
+            .with('x', Multiplication, 1, Left)
+            .with('#', Addition, 2, Left);
+        assert_eq!(Ok(BigInt::from(14)), evaluate("2 x 3 # 4", &rules));
+        assert_eq!(
+            Err(ParseError::UnexpectedChar { pos: 2, c: 'y' }),
+            evaluate("2 y 3", &rules)
+        );
+        assert_eq!(
+            Ok(BigInt::from(14)),
+            evaluate("2 & 3 # 4", &rules.with('&', Multiplication, 1, Left))
+        );
+    }
+
+    #[test]
+    fn big_integers() {
+        let line = "(99999999999 * 99999999999) * (99999999999 * 99999999999) + 1";
+        assert_eq!(
+            Ok("99999999996000000000059999999999600000000002"
+                .parse()
+                .unwrap()),
+            evaluate(line, &Rules::same_precedence())
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let rules = Rules::same_precedence();
+        assert_eq!(Err(ParseError::UnexpectedEnd), evaluate("", &rules));
+        assert_eq!(Err(ParseError::UnexpectedEnd), evaluate("1 +", &rules));
+        assert_eq!(
+            Err(ParseError::ExpectedNumber { pos: 4 }),
+            evaluate("1 + * 2", &rules)
+        );
+        assert_eq!(
+            Err(ParseError::ExpectedOperator { pos: 2 }),
+            evaluate("1 2", &rules)
+        );
+        assert_eq!(
+            Err(ParseError::ExpectedOperator { pos: 2 }),
+            evaluate("1 (2)", &rules)
+        );
+        assert_eq!(
+            Err(ParseError::UnclosedParenthesis { pos: 4 }),
+            evaluate("1 + (2 * (3)", &rules)
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar { pos: 5, c: ')' }),
+            evaluate("1 + 2) * 3", &rules)
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedChar { pos: 2, c: '-' }),
+            evaluate("3 - 2", &rules)
+        );
         assert_eq!(
-            evaluate_day18_part2(&read_file_to_lines("input/day18.txt")),
-            43423343619505
+            Err(ParseError::UnexpectedChar { pos: 2, c: 'a' }),
+            evaluate("1 a 2", &rules)
         );
     }
 }
@@ -1,62 +1,13 @@
+use geometry::hex::{Axial, PointyTop};
 use std::collections::HashMap;
-use std::fmt::{Debug, Display, Formatter};
-use std::ops::Add;
-
-enum Direction {
-    East,
-    SouthEast,
-    SouthWest,
-    West,
-    NorthWest,
-    NorthEast,
-}
-impl Debug for Direction {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let s = match self {
-            Direction::East => "E",
-            Direction::SouthEast => "SE",
-            Direction::SouthWest => "SW",
-            Direction::West => "W",
-            Direction::NorthWest => "NW",
-            Direction::NorthEast => "NE",
-        };
-        write!(f, "{}", s)
-    }
-}
-impl Direction {
-    fn to_coord(&self) -> Coordinate {
-        match self {
-            Direction::East => Coordinate { x: 1, y: -1, z: 0 },
-            Direction::SouthEast => Coordinate { x: 0, y: -1, z: 1 },
-            Direction::SouthWest => Coordinate { x: -1, y: 0, z: 1 },
-            Direction::West => Coordinate { x: -1, y: 1, z: 0 },
-            Direction::NorthWest => Coordinate { x: 0, y: 1, z: -1 },
-            Direction::NorthEast => Coordinate { x: 1, y: 0, z: -1 },
-        }
-    }
-
-    fn all_direction_coordinates() -> [Coordinate; 6] {
-        [
-            Direction::East.to_coord(),
-            Direction::SouthEast.to_coord(),
-            Direction::SouthWest.to_coord(),
-            Direction::West.to_coord(),
-            Direction::NorthWest.to_coord(),
-            Direction::NorthEast.to_coord(),
-        ]
-    }
-}
+use std::fmt::{Debug, Formatter};
 
 struct Path {
-    path: Vec<Direction>,
+    path: Vec<PointyTop>,
 }
 impl<T: AsRef<str>> From<T> for Path {
     fn from(line: T) -> Self {
-        let path = line
-            .as_ref()
-            .split_inclusive(|c| c == 'e' || c == 'w')
-            .map(Direction::from)
-            .collect();
+        let path = PointyTop::parse_path(line.as_ref()).unwrap();
         Path { path }
     }
 }
@@ -87,56 +38,11 @@ impl Color {
         self == &Color::Black
     }
 }
-#[derive(PartialEq, Debug, Eq, Hash, Copy, Clone)]
-struct Coordinate {
-    x: isize,
-    y: isize,
-    z: isize,
-}
-impl Default for Coordinate {
-    fn default() -> Self {
-        Coordinate { x: 0, y: 0, z: 0 }
-    }
-}
-impl Add for Coordinate {
-    type Output = Coordinate;
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Coordinate {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-            z: self.z + rhs.z,
-        }
-    }
-}
+type Coordinate = Axial;
 impl From<Path> for Coordinate {
     fn from(path: Path) -> Self {
-        path.path
-            .iter()
-            .map(|dir| dir.to_coord())
-            .fold(Coordinate::default(), |pos, coord| pos + coord)
-    }
-}
-impl Coordinate {
-    fn neighbors(&self) -> Vec<Coordinate> {
-        Direction::all_direction_coordinates()
-            .iter()
-            .map(|dir| *self + *dir)
-            .collect()
-    }
-}
-
-impl<T: AsRef<str> + Display> From<T> for Direction {
-    fn from(s: T) -> Self {
-        match s.as_ref() {
-            "e" => Direction::East,
-            "se" => Direction::SouthEast,
-            "sw" => Direction::SouthWest,
-            "w" => Direction::West,
-            "nw" => Direction::NorthWest,
-            "ne" => Direction::NorthEast,
-            _ => panic!("Invalid Direction {}", s),
-        }
+        path.path.into_iter().fold(Axial::ORIGIN, Axial::step)
     }
 }
 
@@ -170,7 +76,7 @@ fn iterate(floor: Floor) -> Floor {
 fn extend_with_missing_neighbors(prev: &Floor) -> Floor {
     let mut new = prev.clone();
     prev.iter().for_each(|(pos, _)| {
-        pos.neighbors().into_iter().for_each(|neighbor| {
+        pos.neighbors().for_each(|neighbor| {
             new.entry(neighbor).or_insert_with(Color::default);
         })
     });
@@ -189,8 +95,7 @@ fn apply_iteration_rules(prev: &Floor) -> Floor {
     prev.iter().for_each(|(pos, my_color)| {
         let black_count = pos
             .neighbors()
-            .iter()
-            .filter_map(|neighbor| prev.get(neighbor))
+            .filter_map(|neighbor| prev.get(&neighbor))
             .filter(|c| c.is_black())
             .count();
         match (my_color, black_count) {
@@ -250,21 +155,21 @@ wseweeenwnesenwwwswnew";
 
     #[test]
     fn coordinate_of_path_esenee() {
-        let expected = Coordinate { x: 3, y: -3, z: 0 };
+        let expected = Coordinate::new(3, 0);
         let actual = Coordinate::from(Path::from("esenee"));
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn coordinate_of_path_esew() {
-        let expected = Coordinate { x: 0, y: -1, z: 1 };
+        let expected = Coordinate::new(0, 1);
         let actual = Coordinate::from(Path::from("esew"));
         assert_eq!(expected, actual);
     }
 
     #[test]
     fn coordinate_of_path_nwwswee() {
-        let expected = Coordinate { x: 0, y: 0, z: 0 };
+        let expected = Coordinate::new(0, 0);
         let actual = Coordinate::from(Path::from("nwwswee"));
         assert_eq!(expected, actual);
     }
@@ -25,7 +25,6 @@ use crate::day16::{invalid_error_sum, multiply_departure_fields};
 use crate::day17::pocket_dimension_map_4d::PocketDimensionMap4D;
 use crate::day17::pocket_dimension_vec::PocketDimensionVec;
 use crate::day17::{day17_input, ExecutableCycle, PocketDimension};
-use crate::day18::{evaluate_day18_part1, evaluate_day18_part2};
 use crate::day19::alternate_number_of_messages_matching_rule_0;
 use crate::day20::{count_hashes_not_part_of_sea_monsters, product_of_corner_tile_ids};
 use crate::day21::{
@@ -38,6 +37,7 @@ use crate::day23::{
 use crate::day24::{black_tile_count, iterate_for_given_number_of_days};
 use crate::day25::{find_encryption_key, DAY_25_PUZZLE_INPUT};
 use crate::line_reader::read_file_to_lines;
+use advent_of_code_2020::day18::{evaluate_day18_part1, evaluate_day18_part2};
 
 mod day01;
 mod day02;
@@ -56,7 +56,6 @@ mod day14;
 mod day15;
 mod day16;
 mod day17;
-mod day18;
 mod day19;
 mod day20;
 mod day21;
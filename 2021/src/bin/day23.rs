@@ -0,0 +1,70 @@
+//! Prints the cheapest way to organize the amphipods of day 23, one move at a time.
+//!
+//! ```sh
+//! cargo run --release --bin day23 -- --energy 1,10,100,1000 burrow.txt
+//! ```
+//!
+//! All options are optional:
+//! - `--energy <costs>` is the energy per step of each type of amphipod, starting with `A`,
+//!   and defaults to 1, 10, 100 and so on
+//! - the burrow is read from the given file, with any number of rooms of any depth,
+//!   and defaults to the part 1 puzzle input
+use advent_of_code_2021::day23::Burrow;
+
+const INPUT: &str = include_str!("../../input/day23.txt");
+
+fn numbers(value: &str) -> Vec<usize> {
+    value
+        .split(',')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .unwrap_or_else(|_| exit_with(&format!("Expected a number, got '{}'", n)))
+        })
+        .collect()
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut energy_per_step = None;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--energy" => {
+                let value = args
+                    .next()
+                    .unwrap_or_else(|| exit_with(&format!("Missing value for {}", arg)));
+                energy_per_step = Some(numbers(&value));
+            }
+            _ if arg.starts_with("--") => exit_with(&format!("Unknown option '{}'", arg)),
+            _ => path = Some(arg),
+        }
+    }
+
+    let input = match &path {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't read {}: {}", path, e))),
+        None => INPUT.to_string(),
+    };
+    let mut burrow = Burrow::from(input.as_str());
+    if let Some(energy_per_step) = energy_per_step {
+        if energy_per_step.len() != burrow.amphipod_types() {
+            exit_with(&format!(
+                "Expected the energy of {} types of amphipods, got {}",
+                burrow.amphipod_types(),
+                energy_per_step.len()
+            ));
+        }
+        burrow = burrow.with_energy_costs(&energy_per_step);
+    }
+    match burrow.solution_as_text() {
+        Some(text) => println!("{}", text),
+        None => exit_with("The amphipods can't be organized"),
+    }
+}
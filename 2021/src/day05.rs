@@ -1,3 +1,4 @@
+use geometry::Point2;
 use std::collections::HashMap;
 
 const INPUT: &str = include_str!("../input/day05.txt");
@@ -28,27 +29,11 @@ fn count_overlaps(lines: Vec<Line>, include_diagonals: bool) -> usize {
     overlaps.values().filter(|&&count| count > 1).count()
 }
 
-#[derive(Eq, Hash, PartialEq, Clone, Copy)]
-struct Point {
-    x: i16,
-    y: i16,
-}
-impl From<&str> for Point {
-    fn from(s: &str) -> Self {
-        let (x, y) = s.split_once(',').unwrap();
-        Point {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        }
-    }
-}
-impl Point {
-    fn new(x: i16, y: i16) -> Self {
-        Point { x, y }
-    }
-    fn offset_by(&self, dx: i16, dy: i16) -> Self {
-        Point::new((self.x + dx) as i16, (self.y + dy) as i16)
-    }
+type Point = Point2<i16>;
+
+fn parse_point(s: &str) -> Point {
+    let (x, y) = s.split_once(',').unwrap();
+    Point::new(x.parse().unwrap(), y.parse().unwrap())
 }
 
 struct Line {
@@ -61,18 +46,18 @@ impl From<&str> for Line {
     fn from(s: &str) -> Self {
         let (start, end) = s.split_once(" -> ").unwrap();
         Line {
-            start: Point::from(start),
-            end: Point::from(end),
+            start: parse_point(start),
+            end: parse_point(end),
             it: None,
         }
     }
 }
 impl Line {
     fn is_horizontal(&self) -> bool {
-        self.start.x.eq(&self.end.x)
+        self.start.x() == self.end.x()
     }
     fn is_vertical(&self) -> bool {
-        self.start.y.eq(&self.end.y)
+        self.start.y() == self.end.y()
     }
 }
 impl Iterator for Line {
@@ -82,10 +67,7 @@ impl Iterator for Line {
         self.it = match self.it {
             None => Some(self.start),
             Some(prev) if prev == self.end => None,
-            Some(prev) => Some(prev.offset_by(
-                (self.end.x - self.start.x).signum(),
-                (self.end.y - self.start.y).signum(),
-            )),
+            Some(prev) => Some(prev + (self.end - self.start).signum()),
         };
         self.it
     }
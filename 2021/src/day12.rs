@@ -1,3 +1,4 @@
+use dot::{Graph, ToDot};
 use std::collections::{HashMap, HashSet};
 use SmallCaveConstraint::*;
 
@@ -45,6 +46,30 @@ impl<'a> From<&'a str> for CaveSystem<'a> {
     }
 }
 
+/// Renders the passages as undirected edges. Large caves are boxes,
+/// and the start and end caves are circled twice.
+impl ToDot for CaveSystem<'_> {
+    fn to_dot(&self) -> String {
+        let mut graph = Graph::undirected("caves");
+        let mut caves: Vec<_> = self.neighbors.keys().copied().collect();
+        caves.sort_unstable();
+        for cave in caves {
+            let shape = match cave {
+                "start" | "end" => "doublecircle",
+                cave if cave.is_large() => "box",
+                _ => "ellipse",
+            };
+            graph.node(cave, &[("shape", &shape)]);
+            for &neighbor in &self.neighbors[cave] {
+                if cave < neighbor {
+                    graph.edge(cave, neighbor, &[]);
+                }
+            }
+        }
+        graph.to_dot()
+    }
+}
+
 #[derive(Clone)]
 struct Explorer<'a> {
     path: Vec<&'a str>,
@@ -149,6 +174,29 @@ zg-he
 pj-fs
 start-RW";
 
+    #[test]
+    fn example1_to_dot() {
+        assert_eq!(
+            "graph \"caves\" {
+    \"A\" [shape=\"box\"];
+    \"b\" [shape=\"ellipse\"];
+    \"c\" [shape=\"ellipse\"];
+    \"d\" [shape=\"ellipse\"];
+    \"end\" [shape=\"doublecircle\"];
+    \"start\" [shape=\"doublecircle\"];
+    \"A\" -- \"start\";
+    \"A\" -- \"c\";
+    \"A\" -- \"b\";
+    \"A\" -- \"end\";
+    \"b\" -- \"start\";
+    \"b\" -- \"d\";
+    \"b\" -- \"end\";
+}
+",
+            CaveSystem::from(EXAMPLE1).to_dot()
+        );
+    }
+
     #[test]
     fn part1_example1() {
         let cave = CaveSystem::from(EXAMPLE1);
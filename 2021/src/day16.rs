@@ -1,145 +1,315 @@
-use PayloadType::*;
-use TypeId::*;
+use std::fmt::{Display, Formatter};
+use Operator::*;
+use Packet::*;
+use PacketError::*;
 
 const INPUT: &str = include_str!("../input/day16.txt");
 
-pub(crate) fn day16_part1() -> usize {
-    Transmission::from(INPUT).version_sum
+pub fn day16_part1() -> usize {
+    Packet::from_hex(INPUT).unwrap().version_sum()
 }
 
-pub(crate) fn day16_part2() -> usize {
-    Transmission::from(INPUT).packet.value
+pub fn day16_part2() -> usize {
+    Packet::from_hex(INPUT).unwrap().value()
+}
+
+const VERSION_BITS: usize = 3;
+const TYPE_ID_BITS: usize = 3;
+const LITERAL_TYPE_ID: usize = 4;
+const LITERAL_GROUP_BITS: usize = 4;
+const BIT_LENGTH_BITS: usize = 15;
+const PACKET_COUNT_BITS: usize = 11;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Packet {
+    Literal {
+        version: u8,
+        value: usize,
+    },
+    Operation {
+        version: u8,
+        operator: Operator,
+        packets: Vec<Packet>,
+    },
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Operator {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
 }
 
 #[derive(Debug, PartialEq)]
-struct Transmission {
-    packet: Packet,
-    version_sum: usize,
+pub enum PacketError {
+    InvalidHexDigit(char),
+    UnexpectedEnd {
+        pos: usize,
+    },
+    LengthMismatch {
+        pos: usize,
+        expected: usize,
+        actual: usize,
+    },
+    TrailingData {
+        pos: usize,
+    },
+    LiteralTooLarge {
+        pos: usize,
+    },
+    WrongOperandCount {
+        operator: Operator,
+        count: usize,
+    },
+    VersionTooLarge(u8),
+    TooManyPackets(usize),
 }
-impl From<&str> for Transmission {
-    fn from(input: &str) -> Self {
-        let bits = hex_to_bits(input);
-        let (packet, _, version_sum) = parse_packet(&bits);
-        Transmission {
-            packet,
-            version_sum,
+impl Display for PacketError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidHexDigit(c) => write!(f, "invalid hex digit '{}'", c),
+            UnexpectedEnd { pos } => write!(f, "unexpected end of transmission at bit {}", pos),
+            LengthMismatch {
+                pos,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "sub-packets starting at bit {} take {} bits instead of {}",
+                pos, actual, expected
+            ),
+            TrailingData { pos } => write!(f, "non-zero padding at bit {}", pos),
+            LiteralTooLarge { pos } => write!(f, "literal at bit {} does not fit a usize", pos),
+            WrongOperandCount { operator, count } => {
+                write!(f, "{} can't be applied to {} packets", operator, count)
+            }
+            VersionTooLarge(version) => write!(f, "version {} doesn't fit 3 bits", version),
+            TooManyPackets(count) => write!(f, "{} sub-packets are too many to encode", count),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Clone)]
-struct Packet {
-    value: usize,
-    packets: Vec<Packet>,
-}
 impl Packet {
-    fn new(value: usize) -> Self {
-        let packets = Vec::new();
-        Packet { value, packets }
+    pub fn from_hex(input: &str) -> Result<Self, PacketError> {
+        let bits = hex_to_bits(input)?;
+        let mut reader = BitReader::new(&bits);
+        let packet = reader.read_packet()?;
+        // The transmission is padded to whole hex digits, but only with zeros
+        if let Some(pos) = (reader.pos..bits.len()).find(|&pos| bits[pos] != 0) {
+            return Err(TrailingData { pos });
+        }
+        Ok(packet)
     }
-}
 
-type BitDigit = u8;
+    pub fn to_hex(&self) -> Result<String, PacketError> {
+        let mut bits = vec![];
+        self.write_bits(&mut bits)?;
+        Ok(bits_to_hex(&bits))
+    }
 
-fn hex_to_bits(input: &str) -> Vec<BitDigit> {
-    input
-        .trim()
-        .chars()
-        .map(|c| c.to_digit(16).unwrap() as u8)
-        .flat_map(|n| format!("{:04b}", n).chars().collect::<Vec<_>>())
-        .map(|c| c.to_digit(10).unwrap() as BitDigit)
-        .collect()
-}
+    pub fn version(&self) -> u8 {
+        match self {
+            Literal { version, .. } | Operation { version, .. } => *version,
+        }
+    }
 
-fn parse_packet(bits: &[BitDigit]) -> (Packet, usize, usize) {
-    let (version, packet_type) = parse_header(bits);
-    match packet_type {
-        Literal => parse_literal_payload(bits, version),
-        _ => parse_operator_payload(bits, version, packet_type),
+    pub fn version_sum(&self) -> usize {
+        match self {
+            Literal { version, .. } => *version as usize,
+            Operation {
+                version, packets, ..
+            } => *version as usize + packets.iter().map(Packet::version_sum).sum::<usize>(),
+        }
     }
-}
 
-fn parse_header(bits: &[BitDigit]) -> (usize, TypeId) {
-    let version = bits_to_number(&bits[0..3]);
-    let type_id = TypeId::from(bits_to_number(&bits[3..6]));
-    (version, type_id)
+    pub fn value(&self) -> usize {
+        match self {
+            Literal { value, .. } => *value,
+            Operation {
+                operator, packets, ..
+            } => operator.applied_to(packets),
+        }
+    }
+
+    /// Appends this packet to `bits`. Operators use length type 1 (packet count) when possible,
+    /// as its 11 bit count is more compact than the 15 bit length of length type 0.
+    fn write_bits(&self, bits: &mut Vec<BitDigit>) -> Result<(), PacketError> {
+        let version = self.version();
+        if version as usize >= 1 << VERSION_BITS {
+            return Err(VersionTooLarge(version));
+        }
+        push_number(bits, version as usize, VERSION_BITS);
+        match self {
+            Literal { value, .. } => {
+                push_number(bits, LITERAL_TYPE_ID, TYPE_ID_BITS);
+                let group_count = (usize::BITS - value.leading_zeros()).max(1) as usize;
+                let group_count = group_count.div_ceil(LITERAL_GROUP_BITS);
+                for group in (0..group_count).rev() {
+                    bits.push(if group > 0 { 1 } else { 0 });
+                    let nibble = (value >> (group * LITERAL_GROUP_BITS)) & 0xF;
+                    push_number(bits, nibble, LITERAL_GROUP_BITS);
+                }
+            }
+            Operation {
+                operator, packets, ..
+            } => {
+                operator.check_operand_count(packets.len())?;
+                push_number(bits, operator.type_id(), TYPE_ID_BITS);
+                if packets.len() < 1 << PACKET_COUNT_BITS {
+                    bits.push(1);
+                    push_number(bits, packets.len(), PACKET_COUNT_BITS);
+                    for packet in packets {
+                        packet.write_bits(bits)?;
+                    }
+                } else {
+                    let mut payload = vec![];
+                    for packet in packets {
+                        packet.write_bits(&mut payload)?;
+                    }
+                    if payload.len() >= 1 << BIT_LENGTH_BITS {
+                        return Err(TooManyPackets(packets.len()));
+                    }
+                    bits.push(0);
+                    push_number(bits, payload.len(), BIT_LENGTH_BITS);
+                    bits.extend(payload);
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
-fn parse_literal_payload(bits: &[BitDigit], version: usize) -> (Packet, usize, usize) {
-    let mut pos = 6;
-    let mut value = vec![];
-    for window in bits[pos..].windows(5).step_by(5) {
-        value.extend_from_slice(&window[1..]);
-        pos += 5;
-        if window[0] == 0 {
-            break;
+/// Pretty-prints the packet as an expression, such as `sum(product(3, 4), min(1, 2))`
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal { value, .. } => write!(f, "{}", value),
+            Operation {
+                operator, packets, ..
+            } => {
+                write!(f, "{}(", operator)?;
+                for (i, packet) in packets.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", packet)?;
+                }
+                write!(f, ")")
+            }
         }
     }
-    let value = bits_to_number(&value);
-    (Packet::new(value), pos, version as usize)
 }
 
-fn parse_operator_payload(
-    bits: &[BitDigit],
-    version: usize,
-    operator: TypeId,
-) -> (Packet, usize, usize) {
-    let (packets, pos, sum) = match PayloadType::from(&bits[6..]) {
-        Bits(total_len) => parse_payload_bits(bits, total_len),
-        Packets(count) => parse_payload_packets(bits, count),
-    };
-    let value = operator.applied_to(&packets);
-    let packet = Packet { value, packets };
-    (packet, pos, version as usize + sum)
+type BitDigit = u8;
+
+fn hex_to_bits(input: &str) -> Result<Vec<BitDigit>, PacketError> {
+    let mut bits = vec![];
+    for c in input.trim().chars() {
+        let digit = c.to_digit(16).ok_or(InvalidHexDigit(c))? as usize;
+        push_number(&mut bits, digit, 4);
+    }
+    Ok(bits)
 }
 
-fn parse_payload_bits(bits: &[BitDigit], total_len: usize) -> (Vec<Packet>, usize, usize) {
-    let mut pos = 7 + 15;
-    let mut version_sum = 0;
-    let mut packets = vec![];
-    let mut read_len = 0;
-    while read_len < total_len {
-        let (packet, len, sum) = parse_packet(&bits[pos..]);
-        pos += len;
-        read_len += len;
-        version_sum += sum;
-        packets.push(packet);
-    }
-    (packets, pos, version_sum)
+/// Converts bits to hex digits, padding with zeros to whole bytes like the puzzle examples
+fn bits_to_hex(bits: &[BitDigit]) -> String {
+    let mut bits = bits.to_vec();
+    bits.resize(bits.len().next_multiple_of(8), 0);
+    bits.chunks(4)
+        .map(|chunk| char::from_digit(bits_to_number(chunk) as u32, 16).unwrap())
+        .collect::<String>()
+        .to_ascii_uppercase()
 }
 
-fn parse_payload_packets(bits: &[BitDigit], packet_count: usize) -> (Vec<Packet>, usize, usize) {
-    let mut pos = 7 + 11;
-    let mut version_sum = 0;
-    let mut packets = vec![];
-    for _ in 0..packet_count {
-        let (packet, len, sum) = parse_packet(&bits[pos..]);
-        pos += len;
-        version_sum += sum;
-        packets.push(packet);
-    }
-    (packets, pos, version_sum)
+fn push_number(bits: &mut Vec<BitDigit>, number: usize, len: usize) {
+    bits.extend((0..len).rev().map(|i| ((number >> i) & 1) as BitDigit));
 }
 
-#[derive(Debug, PartialEq, Clone)]
-enum TypeId {
-    Sum,
-    Product,
-    Minimum,
-    Maximum,
-    Literal,
-    GreaterThan,
-    LessThan,
-    EqualTo,
+struct BitReader<'a> {
+    bits: &'a [BitDigit],
+    pos: usize,
+}
+impl<'a> BitReader<'a> {
+    fn new(bits: &'a [BitDigit]) -> Self {
+        BitReader { bits, pos: 0 }
+    }
+
+    fn read(&mut self, len: usize) -> Result<usize, PacketError> {
+        let end = self.pos + len;
+        let bits = self.bits.get(self.pos..end).ok_or(UnexpectedEnd {
+            pos: self.bits.len(),
+        })?;
+        self.pos = end;
+        Ok(bits_to_number(bits))
+    }
+
+    fn read_packet(&mut self) -> Result<Packet, PacketError> {
+        let version = self.read(VERSION_BITS)? as u8;
+        match self.read(TYPE_ID_BITS)? {
+            LITERAL_TYPE_ID => self.read_literal(version),
+            type_id => self.read_operation(version, Operator::from(type_id)),
+        }
+    }
+
+    fn read_literal(&mut self, version: u8) -> Result<Packet, PacketError> {
+        let start = self.pos;
+        let mut value = 0_usize;
+        loop {
+            let is_last = self.read(1)? == 0;
+            let group = self.read(LITERAL_GROUP_BITS)?;
+            if value.leading_zeros() < LITERAL_GROUP_BITS as u32 {
+                return Err(LiteralTooLarge { pos: start });
+            }
+            value = (value << LITERAL_GROUP_BITS) | group;
+            if is_last {
+                return Ok(Literal { version, value });
+            }
+        }
+    }
+
+    fn read_operation(&mut self, version: u8, operator: Operator) -> Result<Packet, PacketError> {
+        let mut packets = vec![];
+        if self.read(1)? == 0 {
+            let expected = self.read(BIT_LENGTH_BITS)?;
+            let start = self.pos;
+            while self.pos < start + expected {
+                packets.push(self.read_packet()?);
+            }
+            let actual = self.pos - start;
+            if actual != expected {
+                let pos = start;
+                return Err(LengthMismatch {
+                    pos,
+                    expected,
+                    actual,
+                });
+            }
+        } else {
+            let count = self.read(PACKET_COUNT_BITS)?;
+            for _ in 0..count {
+                packets.push(self.read_packet()?);
+            }
+        }
+        operator.check_operand_count(packets.len())?;
+        Ok(Operation {
+            version,
+            operator,
+            packets,
+        })
+    }
 }
-impl From<usize> for TypeId {
-    fn from(v: usize) -> Self {
-        match v {
+
+impl From<usize> for Operator {
+    fn from(type_id: usize) -> Self {
+        match type_id {
             0 => Sum,
             1 => Product,
             2 => Minimum,
             3 => Maximum,
-            4 => Literal,
             5 => GreaterThan,
             6 => LessThan,
             7 => EqualTo,
@@ -147,33 +317,61 @@ impl From<usize> for TypeId {
         }
     }
 }
-impl TypeId {
+impl Operator {
+    fn type_id(&self) -> usize {
+        match self {
+            Sum => 0,
+            Product => 1,
+            Minimum => 2,
+            Maximum => 3,
+            GreaterThan => 5,
+            LessThan => 6,
+            EqualTo => 7,
+        }
+    }
+
+    fn check_operand_count(&self, count: usize) -> Result<(), PacketError> {
+        let is_valid = match self {
+            Sum | Product => true,
+            Minimum | Maximum => count > 0,
+            GreaterThan | LessThan | EqualTo => count == 2,
+        };
+        if is_valid {
+            Ok(())
+        } else {
+            Err(WrongOperandCount {
+                operator: *self,
+                count,
+            })
+        }
+    }
+
     fn applied_to(&self, packets: &[Packet]) -> usize {
         let bool_to_num = |b| if b { 1 } else { 0 };
+        let mut values = packets.iter().map(Packet::value);
         match self {
-            Sum => packets.iter().map(|p| p.value).sum(),
-            Product => packets.iter().map(|p| p.value).product(),
-            Minimum => packets.iter().map(|p| p.value).min().unwrap(),
-            Maximum => packets.iter().map(|p| p.value).max().unwrap(),
-            GreaterThan => bool_to_num(packets[0].value > packets[1].value),
-            LessThan => bool_to_num(packets[0].value < packets[1].value),
-            EqualTo => bool_to_num(packets[0].value == packets[1].value),
-            Literal => unreachable!(),
+            Sum => values.sum(),
+            Product => values.product(),
+            Minimum => values.min().unwrap(),
+            Maximum => values.max().unwrap(),
+            GreaterThan => bool_to_num(values.next() > values.next()),
+            LessThan => bool_to_num(values.next() < values.next()),
+            EqualTo => bool_to_num(values.next() == values.next()),
         }
     }
 }
-
-#[derive(Debug)]
-enum PayloadType {
-    Bits(usize),
-    Packets(usize),
-}
-impl From<&[BitDigit]> for PayloadType {
-    fn from(bits: &[BitDigit]) -> Self {
-        match bits[0] {
-            0 => Bits(bits_to_number(&bits[1..1 + 15])),
-            _ => Packets(bits_to_number(&bits[1..1 + 11])),
-        }
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Sum => "sum",
+            Product => "product",
+            Minimum => "min",
+            Maximum => "max",
+            GreaterThan => "gt",
+            LessThan => "lt",
+            EqualTo => "eq",
+        };
+        write!(f, "{}", name)
     }
 }
 
@@ -189,6 +387,40 @@ fn bits_to_number(bits: &[BitDigit]) -> usize {
 mod tests {
     use super::*;
 
+    const EXAMPLES: [&str; 15] = [
+        "D2FE28",
+        "38006F45291200",
+        "EE00D40C823060",
+        "8A004A801A8002F478",
+        "620080001611562C8802118E34",
+        "C0015000016115A2E0802F182340",
+        "A0016C880162017C3686B18A3D4780",
+        "C200B40A82",
+        "04005AC33890",
+        "880086C3E88112",
+        "CE00C43D881120",
+        "D8005AC2A8F0",
+        "F600BC2D8F",
+        "9C005AC2F8F0",
+        "9C0141080250320F1802104A08",
+    ];
+
+    fn decode(hex: &str) -> Packet {
+        Packet::from_hex(hex).unwrap()
+    }
+
+    fn literal(value: usize) -> Packet {
+        Literal { version: 0, value }
+    }
+
+    fn operation(operator: Operator, packets: Vec<Packet>) -> Packet {
+        Operation {
+            version: 0,
+            operator,
+            packets,
+        }
+    }
+
     #[test]
     fn test_bits_to_number() {
         assert_eq!(6, bits_to_number(&[1, 1, 0]));
@@ -199,125 +431,216 @@ mod tests {
     fn test_hex_to_bits() {
         assert_eq!(
             hex_to_bits("D2FE28"),
-            vec![1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0],
+            Ok(vec![
+                1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 1, 0, 1, 0, 0, 0
+            ]),
         );
     }
 
+    #[test]
+    fn test_bits_to_hex() {
+        assert_eq!("D2FE28", bits_to_hex(&hex_to_bits("D2FE28").unwrap()));
+        assert_eq!("A8", bits_to_hex(&[1, 0, 1, 0, 1]));
+    }
+
     #[test]
     fn test_version_sum() {
-        assert_eq!(Transmission::from("D2FE28").version_sum, 6);
-        assert_eq!(Transmission::from("38006F45291200").version_sum, 9);
-        assert_eq!(Transmission::from("EE00D40C823060").version_sum, 14);
+        assert_eq!(decode("D2FE28").version_sum(), 6);
+        assert_eq!(decode("38006F45291200").version_sum(), 9);
+        assert_eq!(decode("EE00D40C823060").version_sum(), 14);
     }
 
     #[test]
     fn parse_literal_packet() {
-        let (packet, _, _) = parse_packet(&hex_to_bits("D2FE28"));
-        assert_eq!(packet, Packet::new(2021));
+        assert_eq!(
+            decode("D2FE28"),
+            Literal {
+                version: 6,
+                value: 2021
+            }
+        );
     }
 
     #[test]
     fn parse_operator_packet_length_type_id_0() {
-        let (packet, _, _) = parse_packet(&hex_to_bits("38006F45291200"));
         assert_eq!(
-            packet,
-            Packet {
-                value: 1,
-                packets: vec![Packet::new(10), Packet::new(20)],
+            decode("38006F45291200"),
+            Operation {
+                version: 1,
+                operator: LessThan,
+                packets: vec![
+                    Literal {
+                        version: 6,
+                        value: 10
+                    },
+                    Literal {
+                        version: 2,
+                        value: 20
+                    }
+                ],
             }
         );
     }
 
     #[test]
     fn parse_operator_packet_length_type_id_1() {
-        let (packet, _, _) = parse_packet(&hex_to_bits("EE00D40C823060"));
-        assert_eq!(
-            packet,
-            Packet {
-                value: 3,
-                packets: vec![Packet::new(1), Packet::new(2), Packet::new(3)],
-            }
-        );
+        let packet = decode("EE00D40C823060");
+        assert_eq!("max(1, 2, 3)", packet.to_string());
+        assert_eq!(3, packet.value());
     }
 
     #[test]
     fn parse_packet_example_1() {
-        let (packet, _, _) = parse_packet(&hex_to_bits("8A004A801A8002F478"));
-        assert_eq!(
-            packet,
-            Packet {
-                value: 15,
-                packets: vec![Packet {
-                    value: 15,
-                    packets: vec![Packet {
-                        value: 15,
-                        packets: vec![Packet::new(15)],
-                    }],
-                }],
-            }
-        );
+        let packet = decode("8A004A801A8002F478");
+        assert_eq!("min(min(min(15)))", packet.to_string());
+        assert_eq!(15, packet.value());
     }
 
     #[test]
     fn parse_packet_example_2() {
-        let (packet, _, _) = parse_packet(&hex_to_bits("620080001611562C8802118E34"));
-        assert_eq!(
-            packet,
-            Packet {
-                packets: vec![
-                    Packet {
-                        value: 10 + 11,
-                        packets: vec![Packet::new(10), Packet::new(11)],
-                    },
-                    Packet {
-                        value: 12 + 13,
-                        packets: vec![Packet::new(12), Packet::new(13),],
-                    }
-                ],
-                value: 21 + 25,
-            }
-        );
+        let packet = decode("620080001611562C8802118E34");
+        assert_eq!("sum(sum(10, 11), sum(12, 13))", packet.to_string());
+        assert_eq!(21 + 25, packet.value());
     }
 
     #[test]
     fn parse_packet_example_3() {
-        let (packet, _, _) = parse_packet(&hex_to_bits("C0015000016115A2E0802F182340"));
+        let packet = decode("C0015000016115A2E0802F182340");
+        assert_eq!("sum(sum(10, 11), sum(12, 13))", packet.to_string());
+        assert_eq!(21 + 25, packet.value());
+    }
+
+    #[test]
+    fn pretty_print() {
+        let packet = operation(
+            Sum,
+            vec![
+                operation(Product, vec![literal(3), literal(4)]),
+                operation(Minimum, vec![literal(1), literal(2)]),
+            ],
+        );
+        assert_eq!("sum(product(3, 4), min(1, 2))", packet.to_string());
+        assert_eq!(13, packet.value());
         assert_eq!(
-            packet,
-            Packet {
-                packets: vec![
-                    Packet {
-                        value: 10 + 11,
-                        packets: vec![Packet::new(10), Packet::new(11)],
-                    },
-                    Packet {
-                        value: 12 + 13,
-                        packets: vec![Packet::new(12), Packet::new(13),],
-                    }
-                ],
-                value: 21 + 25,
-            }
+            "eq(sum(1, 3), product(2, 2))",
+            decode("9C0141080250320F1802104A08").to_string()
         );
     }
 
     #[test]
-    fn part1_examples() {
+    fn decode_errors() {
+        assert_eq!(Err(InvalidHexDigit('G')), Packet::from_hex("D2FG28"));
+        // Literal 2021 cut off before its last group
+        assert_eq!(Err(UnexpectedEnd { pos: 16 }), Packet::from_hex("D2FE"));
+        assert_eq!(Err(TrailingData { pos: 21 }), Packet::from_hex("D2FE2C"));
+        // The example with length type 0, declaring 26 instead of 27 bits of sub-packets
         assert_eq!(
-            4 + 1 + 5 + 6,
-            Transmission::from("8A004A801A8002F478").version_sum
+            Err(LengthMismatch {
+                pos: 22,
+                expected: 26,
+                actual: 27
+            }),
+            Packet::from_hex("38006B45291200")
         );
+        // A less than packet with a single sub-packet
+        let mut bits = vec![];
+        push_number(&mut bits, 6, VERSION_BITS);
+        push_number(&mut bits, LessThan.type_id(), TYPE_ID_BITS);
+        bits.push(1);
+        push_number(&mut bits, 1, PACKET_COUNT_BITS);
+        literal(5).write_bits(&mut bits).unwrap();
         assert_eq!(
-            12,
-            Transmission::from("620080001611562C8802118E34").version_sum
+            Err(WrongOperandCount {
+                operator: LessThan,
+                count: 1
+            }),
+            Packet::from_hex(&bits_to_hex(&bits))
         );
+        // 17 groups of 4 bits don't fit into 64 bits
+        let mut bits = vec![];
+        push_number(&mut bits, LITERAL_TYPE_ID, VERSION_BITS + TYPE_ID_BITS);
+        for _ in 0..16 {
+            push_number(&mut bits, 0b11111, 5);
+        }
+        push_number(&mut bits, 0b01111, 5);
+        assert_eq!(
+            Err(LiteralTooLarge { pos: 6 }),
+            Packet::from_hex(&bits_to_hex(&bits))
+        );
+        bits.drain(6..11);
         assert_eq!(
-            23,
-            Transmission::from("C0015000016115A2E0802F182340").version_sum
+            Ok(usize::MAX),
+            Packet::from_hex(&bits_to_hex(&bits)).map(|p| p.value())
         );
+    }
+
+    #[test]
+    fn encode_errors() {
+        let packet = Literal {
+            version: 8,
+            value: 1,
+        };
+        assert_eq!(Err(VersionTooLarge(8)), packet.to_hex());
+        let packet = operation(EqualTo, vec![literal(1)]);
+        assert_eq!(
+            Err(WrongOperandCount {
+                operator: EqualTo,
+                count: 1
+            }),
+            packet.to_hex()
+        );
+    }
+
+    #[test]
+    fn encode_literal() {
+        assert_eq!(Ok("D2FE28".to_string()), decode("D2FE28").to_hex());
+        assert_eq!(Ok("1000".to_string()), literal(0).to_hex());
+        let max = literal(usize::MAX);
+        assert_eq!(Ok(max.clone()), Packet::from_hex(&max.to_hex().unwrap()));
+    }
+
+    #[test]
+    fn encode_uses_packet_count_if_possible() {
         assert_eq!(
-            31,
-            Transmission::from("A0016C880162017C3686B18A3D4780").version_sum
+            Ok("EE00D40C823060".to_string()),
+            decode("EE00D40C823060").to_hex()
         );
+        // Originally encoded with length type 0
+        let packet = decode("38006F45291200");
+        let hex = packet.to_hex().unwrap();
+        assert_ne!("38006F45291200", hex);
+        assert_eq!(packet, decode(&hex));
+    }
+
+    #[test]
+    fn encode_uses_bit_length_for_many_packets() {
+        let packet = operation(Sum, (0..2048).map(|_| literal(1)).collect());
+        let bits = hex_to_bits(&packet.to_hex().unwrap()).unwrap();
+        assert_eq!(0, bits[6]);
+        assert_eq!(Ok(packet.clone()), Packet::from_hex(&bits_to_hex(&bits)));
+        assert_eq!(2048, packet.value());
+
+        let packet = operation(Sum, (0..4096).map(|_| literal(1)).collect());
+        assert_eq!(Err(TooManyPackets(4096)), packet.to_hex());
+    }
+
+    #[test]
+    fn round_trip_examples() {
+        for hex in EXAMPLES {
+            let packet = decode(hex);
+            let encoded = packet.to_hex().unwrap();
+            assert_eq!(packet, decode(&encoded), "{}", hex);
+            assert_eq!(packet.to_string(), decode(&encoded).to_string());
+            assert_eq!(encoded, decode(&encoded).to_hex().unwrap());
+        }
+    }
+
+    #[test]
+    fn part1_examples() {
+        assert_eq!(4 + 1 + 5 + 6, decode("8A004A801A8002F478").version_sum());
+        assert_eq!(12, decode("620080001611562C8802118E34").version_sum());
+        assert_eq!(23, decode("C0015000016115A2E0802F182340").version_sum());
+        assert_eq!(31, decode("A0016C880162017C3686B18A3D4780").version_sum());
     }
 
     #[test]
@@ -327,40 +650,35 @@ mod tests {
 
     #[test]
     fn part2_examples_sum() {
-        assert_eq!(1 + 2, Transmission::from("C200B40A82").packet.value);
+        assert_eq!(1 + 2, decode("C200B40A82").value());
     }
     #[test]
     fn part2_example_product() {
-        assert_eq!(6 * 9, Transmission::from("04005AC33890").packet.value);
+        assert_eq!(6 * 9, decode("04005AC33890").value());
     }
     #[test]
     fn part2_example_min() {
-        assert_eq!(7, Transmission::from("880086C3E88112").packet.value);
+        assert_eq!(7, decode("880086C3E88112").value());
     }
     #[test]
     fn part2_example_max() {
-        assert_eq!(9, Transmission::from("CE00C43D881120").packet.value);
+        assert_eq!(9, decode("CE00C43D881120").value());
     }
     #[test]
     fn part2_example_less_than() {
-        assert_eq!(1, Transmission::from("D8005AC2A8F0").packet.value);
+        assert_eq!(1, decode("D8005AC2A8F0").value());
     }
     #[test]
     fn part2_example_greater_than() {
-        assert_eq!(0, Transmission::from("F600BC2D8F").packet.value);
+        assert_eq!(0, decode("F600BC2D8F").value());
     }
     #[test]
     fn part2_example_equal() {
-        assert_eq!(0, Transmission::from("9C005AC2F8F0").packet.value);
+        assert_eq!(0, decode("9C005AC2F8F0").value());
     }
     #[test]
     fn part2_example_combination() {
-        assert_eq!(
-            1,
-            Transmission::from("9C0141080250320F1802104A08")
-                .packet
-                .value
-        );
+        assert_eq!(1, decode("9C0141080250320F1802104A08").value());
     }
 
     #[test]
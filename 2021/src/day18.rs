@@ -1,6 +1,7 @@
-use std::fmt::{Debug, Display, Formatter};
+use nested::Value;
+use nested::Value::*;
+use std::fmt::{Display, Formatter};
 use std::ops::Add;
-use Element::*;
 
 const INPUT: &str = include_str!("../input/day18.txt");
 
@@ -43,221 +44,127 @@ impl Homework {
     }
 }
 
-type Value = usize;
-
-#[derive(Debug, PartialEq, Clone)]
-enum Element {
-    Open,  // Open
-    Close, // Close
-    Num(Value),
-    Pair { left: Value, right: Value },
-}
-impl Element {
-    fn pair(left: Value, right: Value) -> Self {
-        Pair { left, right }
-    }
-}
-impl From<char> for Element {
-    fn from(c: char) -> Self {
-        match c {
-            '[' => Open,
-            ']' => Close,
-            n => Num(n.to_digit(10).unwrap() as Value),
-        }
-    }
-}
-
+/// A pair of two elements, each either a regular number or another pair
 #[derive(Debug, PartialEq, Clone)]
-struct SnailfishNumber {
-    elements: Vec<Element>,
-}
+struct SnailfishNumber(Value);
 impl Display for SnailfishNumber {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut s = String::new();
-        for w in self.elements.windows(2) {
-            match &w {
-                [Open, ..] => s.push('['),
-                [Close, Close, ..] => s.push(']'),
-                [Close, ..] => s.push_str("],"),
-                [Num(val), Close, ..] => s.push_str(&val.to_string()),
-                [Num(val), ..] => s.push_str(&format!("{},", val)),
-                [Pair { left, right }, Close] => s.push_str(&format!("[{},{}]", left, right)),
-                [Pair { left, right }, ..] => s.push_str(&format!("[{},{}],", left, right)),
-                _ => unreachable!(),
-            }
-        }
-        if let Some(Pair { left, right }) = self.elements.last() {
-            if self.elements.len() == 1 {
-                s.push_str(&format!("[{},{}]", left, right));
-            }
-        } else {
-            s.push(']');
-        }
-        write!(f, "{}", s)
+        write!(f, "{}", self.0)
     }
 }
-// impl Debug for SnailfishNumber {
-//     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-//         write!(f, "{}", self.to_string())
-//     }
-// }
 impl SnailfishNumber {
     #[cfg(test)]
-    fn pair(left: Value, right: Value) -> Self {
-        Self {
-            elements: vec![Pair { left, right }],
-        }
+    fn pair(left: usize, right: usize) -> Self {
+        SnailfishNumber(Value::pair(left.into(), right.into()))
     }
     fn reduce(mut self) -> Self {
-        let mut prev = String::new();
-        // println!("reduce  {}", self.to_string());
-        while self.to_string() != prev {
-            prev = self.to_string();
-            self = self.explode();
-            if self.to_string() != prev {
-                // println!("explode {}", self.to_string());
-                continue; // at most one action per cycle
-            }
-            self = self.split();
-            // println!("split   {}", self.to_string());
-        }
+        // at most one action per cycle, and explosions go first
+        while explode_first(&mut self.0, 0).is_some() || split_first(&mut self.0) {}
         self
     }
-    fn remove(&mut self, i: usize) {
-        let _removed = self.elements.remove(i);
-        // println!("removed {:?} @ {}", _removed, i);
-    }
+    #[cfg(test)]
     fn explode(mut self) -> Self {
-        if let Some((i, left, right)) = self.find_pair() {
-            self.elements[i] = Num(0);
-            self.propagate_left(i - 1, left);
-            self.propagate_right(i + 1, right);
-        }
-        self.consolidate_pairs()
-    }
-    /// finds a pair that is nested within 4+ others
-    fn find_pair(&self) -> Option<(usize, Value, Value)> {
-        let mut open = 0;
-        for (i, e) in self.elements.iter().enumerate() {
-            match (open, e) {
-                // Open range 4.. works fine, but IntelliJ still warns about it
-                (4..=usize::MAX, Pair { left, right }) => return Some((i, *left, *right)),
-                (_, Open) => open += 1,
-                (_, Close) => open -= 1,
-                (_, _) => (),
-            }
-        }
-        None
-    }
-    fn propagate_left(&mut self, i: usize, to_left: Value) {
-        let skip = self.elements.len() - 1 - i;
-        if let Some(old) = self
-            .elements
-            .iter_mut()
-            .rev()
-            .skip(skip)
-            .find_map(|e| match e {
-                Num(num) => Some(num),
-                Pair { left: _, right } => Some(right),
-                _ => None,
-            })
-        {
-            *old += to_left;
-        }
-    }
-    fn propagate_right(&mut self, i: usize, to_right: Value) {
-        if let Some(old) = self.elements.iter_mut().skip(i).find_map(|e| match e {
-            Num(num) => Some(num),
-            Pair { left, right: _ } => Some(left),
-            _ => None,
-        }) {
-            *old += to_right;
-        }
+        explode_first(&mut self.0, 0);
+        self
     }
+    #[cfg(test)]
     fn split(mut self) -> Self {
-        let split_to_pair = |v: Value| Element::pair(v / 2, (v + 1) / 2);
-        // Look for Num and Pair, only the first one matters, whichever it is
-        if let Some(i) = self.elements.iter().enumerate().position(|(_, e)| match e {
-            Num(n) => *n >= 10,
-            Pair { left, right } => *left >= 10 || *right >= 10,
-            _ => false,
-        }) {
-            match self.elements[i] {
-                // Large value in a Num is easy, just replace it with a pair
-                Num(val) => self.elements[i] = split_to_pair(val),
-                // Otherwise it will be part of a pair, that's more complicated
-                Pair { left, right } => {
-                    self.elements.insert(i + 1, Close);
-                    if left >= 10 {
-                        self.elements.insert(i + 1, Num(right));
-                        self.elements[i] = split_to_pair(left);
-                    } else {
-                        assert!(right >= 10);
-                        self.elements[i] = split_to_pair(right);
-                        self.elements.insert(i, Num(left));
-                    }
-                    self.elements.insert(i, Open);
-                }
-                _ => {}
-            };
-        }
+        split_first(&mut self.0);
         self
     }
-    fn magnitude(mut self) -> usize {
-        self = self.reduce();
+    fn magnitude(self) -> usize {
+        magnitude(&self.reduce().0)
+    }
+}
+impl Add for SnailfishNumber {
+    type Output = SnailfishNumber;
 
-        while let Some(e) = self.elements.iter_mut().find(|e| matches!(e, Pair { .. })) {
-            if let Pair { left, right } = e {
-                *e = Num(3 * *left + 2 * *right);
-                self = self.consolidate_pairs();
-            }
+    fn add(self, rhs: Self) -> Self::Output {
+        SnailfishNumber(Value::pair(self.0, rhs.0))
+    }
+}
+impl From<&str> for SnailfishNumber {
+    fn from(line: &str) -> Self {
+        let value = Value::parse(line.trim()).unwrap();
+        assert!(
+            value.as_list().is_some() && is_pair_tree(&value),
+            "{} is not a snailfish number",
+            value
+        );
+        SnailfishNumber(value)
+    }
+}
+
+fn is_pair_tree(value: &Value) -> bool {
+    match value {
+        Integer(_) => true,
+        List(pair) => pair.len() == 2 && pair.iter().all(is_pair_tree),
+    }
+}
+
+type Remainders = (Option<usize>, Option<usize>);
+
+/// Explodes the leftmost pair of regular numbers nested within 4+ others. Returns the numbers
+/// still to be added to the closest regular numbers to its left and right, if any
+fn explode_first(value: &mut Value, depth: usize) -> Option<Remainders> {
+    let List(pair) = value else {
+        return None;
+    };
+    if depth >= 4 {
+        if let [Integer(left), Integer(right)] = pair[..] {
+            *value = Integer(0);
+            return Some((Some(left), Some(right)));
         }
-        match self.elements[..] {
-            [Num(magnitude)] => magnitude,
-            [Open, Num(left), Num(right), Close] => 3 * left + 2 * right,
-            _ => unreachable!("leftover {:?} or {}", self, self),
+    }
+    let [left, right] = &mut pair[..] else {
+        unreachable!("{} is not a pair", value)
+    };
+    if let Some((to_left, to_right)) = explode_first(left, depth + 1) {
+        if let Some(to_right) = to_right {
+            *leftmost_number(right) += to_right;
         }
+        return Some((to_left, None));
     }
-    fn consolidate_pairs(mut self) -> Self {
-        while let Some((i, left, right)) =
-            self.elements
-                .windows(4)
-                .enumerate()
-                .find_map(|(i, w)| match w {
-                    [Open, Num(left), Num(right), Close] => Some((i, *left, *right)),
-                    _ => None,
-                })
-        {
-            self.remove(i);
-            self.remove(i);
-            self.remove(i);
-            self.elements[i] = Pair { left, right };
+    if let Some((to_left, to_right)) = explode_first(right, depth + 1) {
+        if let Some(to_left) = to_left {
+            *rightmost_number(left) += to_left;
         }
-        self
+        return Some((None, to_right));
     }
+    None
 }
-impl Add for SnailfishNumber {
-    type Output = SnailfishNumber;
 
-    fn add(self, mut rhs: Self) -> Self::Output {
-        let mut elements = self.elements;
+fn leftmost_number(value: &mut Value) -> &mut usize {
+    match value {
+        Integer(number) => number,
+        List(pair) => leftmost_number(&mut pair[0]),
+    }
+}
 
-        elements.insert(0, Open);
-        elements.append(&mut rhs.elements);
-        elements.push(Close);
+fn rightmost_number(value: &mut Value) -> &mut usize {
+    match value {
+        Integer(number) => number,
+        List(pair) => rightmost_number(&mut pair[1]),
+    }
+}
 
-        SnailfishNumber { elements }
+/// Splits the leftmost regular number of 10 or more into a pair
+fn split_first(value: &mut Value) -> bool {
+    match value {
+        Integer(number) if *number >= 10 => {
+            let number = *number;
+            *value = Value::pair((number / 2).into(), number.div_ceil(2).into());
+            true
+        }
+        Integer(_) => false,
+        List(pair) => pair.iter_mut().any(split_first),
     }
 }
-impl From<&str> for SnailfishNumber {
-    fn from(line: &str) -> Self {
-        let elements: Vec<Element> = line
-            .trim()
-            .chars()
-            .filter(|c| c != &',')
-            .map(Element::from)
-            .collect();
-        SnailfishNumber { elements }.consolidate_pairs()
+
+fn magnitude(value: &Value) -> usize {
+    match value {
+        Integer(number) => *number,
+        List(pair) => 3 * magnitude(&pair[0]) + 2 * magnitude(&pair[1]),
     }
 }
 
@@ -293,8 +200,7 @@ mod tests {
     fn test_pair_of_two_regular_numbers() {
         let line = "[1,2]";
         let number = SnailfishNumber::from(line);
-        let elements = vec![Pair { left: 1, right: 2 }];
-        assert_eq!(number, SnailfishNumber { elements });
+        assert_eq!(number, SnailfishNumber(Value::pair(1.into(), 2.into())));
         assert_eq!(number, SnailfishNumber::pair(1, 2));
         assert_eq!(number.to_string(), line);
     }
@@ -313,9 +219,7 @@ mod tests {
         assert_eq!(line, number.to_string());
         assert_eq!(
             number,
-            SnailfishNumber {
-                elements: vec![Open, Pair { left: 1, right: 2 }, Num(3), Close]
-            }
+            SnailfishNumber(Value::pair(Value::pair(1.into(), 2.into()), 3.into()))
         );
     }
 
@@ -326,9 +230,7 @@ mod tests {
         assert_eq!(line, number.to_string());
         assert_eq!(
             number,
-            SnailfishNumber {
-                elements: vec![Open, Num(9), Pair { left: 8, right: 7 }, Close]
-            }
+            SnailfishNumber(Value::pair(9.into(), Value::pair(8.into(), 7.into())))
         );
     }
 
@@ -339,14 +241,10 @@ mod tests {
         assert_eq!(number.to_string(), line);
         assert_eq!(
             number,
-            SnailfishNumber {
-                elements: vec![
-                    Open,
-                    Pair { left: 1, right: 9 },
-                    Pair { left: 8, right: 5 },
-                    Close
-                ]
-            }
+            SnailfishNumber(Value::pair(
+                Value::pair(1.into(), 9.into()),
+                Value::pair(8.into(), 5.into())
+            ))
         );
     }
 
@@ -370,11 +268,12 @@ mod tests {
     }
 
     #[test]
-    fn test_find_pair() {
-        assert_eq!(
-            SnailfishNumber::from("[[[[[9,8],1],2],3],4]").find_pair(),
-            Some((4, 9, 8))
-        );
+    fn test_explode_first() {
+        let mut number = SnailfishNumber::from("[[[[[9,8],1],2],3],4]");
+        assert_eq!(5, number.0.depth());
+        assert_eq!(Some((Some(9), None)), explode_first(&mut number.0, 0));
+        assert_eq!(4, number.0.depth());
+        assert_eq!(None, explode_first(&mut number.0, 0));
     }
     #[test]
     fn test_explode() {
@@ -403,19 +302,11 @@ mod tests {
     #[test]
     fn test_split() {
         assert_eq!(
-            SnailfishNumber {
-                elements: vec![Open, Num(10), Pair { left: 2, right: 3 }, Close]
-            }
-            .split(),
-            // [10,[2,3]]
+            SnailfishNumber::from("[10,[2,3]]").split(),
             SnailfishNumber::from("[[5,5],[2,3]]")
         );
         assert_eq!(
-            SnailfishNumber {
-                elements: vec![Open, Pair { left: 2, right: 3 }, Num(11), Close]
-            }
-            .split(),
-            //[[2,3],11]
+            SnailfishNumber::from("[[2,3],11]").split(),
             SnailfishNumber::from("[[2,3],[5,6]]")
         );
         assert_eq!(
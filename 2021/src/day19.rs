@@ -1,3 +1,4 @@
+use geometry::{Point3, Rotation};
 use std::collections::{HashMap, HashSet, VecDeque};
 
 const INPUT: &str = include_str!("../input/day19.txt");
@@ -11,7 +12,7 @@ pub(crate) fn day19_part2() -> usize {
 }
 
 type Coordinate = isize;
-type Vector = [Coordinate; 3];
+type Vector = Point3<Coordinate>;
 
 #[derive(Debug)]
 struct System {
@@ -31,7 +32,7 @@ impl System {
         self.align_beacons().1
     }
     fn align_beacons(mut self) -> (usize, usize) {
-        let mut distances_to_ref = vec![[0, 0, 0]; self.scanners.len()];
+        let mut distances_to_ref = vec![Vector::origin(); self.scanners.len()];
 
         // The first scanner saves as the reference for position and orientation
         let reference = self.scanners.remove(0);
@@ -76,7 +77,7 @@ impl System {
             .map(|(d, _)| d);
         // println!("distances {:?}", distances_between_scanners);
         let max_manhattan_distance = distances_between_scanners
-            .map(|[a, b, c]| (a.abs() + b.abs() + c.abs()) as usize)
+            .map(|d| d.manhattan_length() as usize)
             .max()
             .unwrap();
 
@@ -93,7 +94,7 @@ impl From<&str> for Scanner {
     fn from(lines: &str) -> Self {
         let to_position = |line: &str| {
             let pos: Vec<Coordinate> = line.split(',').map(|n| n.parse().unwrap()).collect();
-            [pos[0], pos[1], pos[2]]
+            Vector::new(pos[0], pos[1], pos[2])
         };
         let mut lines = lines.trim().lines();
         let header = lines.next().unwrap();
@@ -110,9 +111,9 @@ impl From<&str> for Scanner {
 
 impl Scanner {
     fn align_with(&mut self, ref_beacons: &[Vector]) -> Option<Vector> {
-        for orientation in Orientation::all() {
+        for rotation in Rotation::all() {
             let mut offset_frequencies: HashMap<Vector, usize> = HashMap::new();
-            let aligned_beacons = self.aligned_beacons(&orientation);
+            let aligned_beacons = self.aligned_beacons(&rotation);
             for own_beacon in &aligned_beacons {
                 for ref_beacon in ref_beacons {
                     let offset = Scanner::offset_between(ref_beacon, own_beacon);
@@ -129,20 +130,17 @@ impl Scanner {
         }
         None
     }
-    fn aligned_beacons(&self, orientation: &Orientation) -> Vec<Vector> {
+    fn aligned_beacons(&self, rotation: &Rotation) -> Vec<Vector> {
         self.beacons
             .iter()
-            .map(|pos| orientation.align(*pos))
+            .map(|pos| rotation.apply(*pos))
             .collect()
     }
     fn translate_beacons(beacons: &[Vector], offset: Vector) -> Vec<Vector> {
-        beacons
-            .iter()
-            .map(|pos| [pos[0] + offset[0], pos[1] + offset[1], pos[2] + offset[2]])
-            .collect()
+        beacons.iter().map(|pos| *pos + offset).collect()
     }
     fn offset_between(a: &Vector, b: &Vector) -> Vector {
-        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+        *a - *b
     }
     fn offsets_between(beacons: &[Vector]) -> HashMap<Vector, Vec<Vector>> {
         let mut distances: HashMap<Vector, Vec<Vector>> = HashMap::new();
@@ -159,55 +157,6 @@ impl Scanner {
         distances
     }
 }
-#[derive(Debug, Copy, Clone)]
-struct Orientation {
-    index: [usize; 3], // The order of the x, y and z coordinates
-    multi: [isize; 3], // Multiplier of x, y and z coordinates
-}
-impl Orientation {
-    fn new(index: [usize; 3], multi: [isize; 3]) -> Self {
-        Orientation { index, multi }
-    }
-    fn all() -> Vec<Orientation> {
-        vec![
-            // + x
-            Orientation::new([0, 1, 2], [1, 1, 1]),
-            Orientation::new([0, 2, 1], [1, 1, -1]),
-            Orientation::new([0, 1, 2], [1, -1, -1]),
-            Orientation::new([0, 2, 1], [1, -1, 1]),
-            // - x
-            Orientation::new([0, 2, 1], [-1, 1, 1]),
-            Orientation::new([0, 1, 2], [-1, 1, -1]),
-            Orientation::new([0, 2, 1], [-1, -1, -1]),
-            Orientation::new([0, 1, 2], [-1, -1, 1]),
-            // + y
-            Orientation::new([1, 2, 0], [1, 1, 1]),
-            Orientation::new([1, 0, 2], [1, 1, -1]),
-            Orientation::new([1, 2, 0], [1, -1, -1]),
-            Orientation::new([1, 0, 2], [1, -1, 1]),
-            // - y
-            Orientation::new([1, 0, 2], [-1, 1, 1]),
-            Orientation::new([1, 2, 0], [-1, 1, -1]),
-            Orientation::new([1, 0, 2], [-1, -1, -1]),
-            Orientation::new([1, 2, 0], [-1, -1, 1]),
-            // + z
-            Orientation::new([2, 0, 1], [1, 1, 1]),
-            Orientation::new([2, 1, 0], [1, 1, -1]),
-            Orientation::new([2, 0, 1], [1, -1, -1]),
-            Orientation::new([2, 1, 0], [1, -1, 1]),
-            // - z
-            Orientation::new([2, 1, 0], [-1, 1, 1]),
-            Orientation::new([2, 0, 1], [-1, 1, -1]),
-            Orientation::new([2, 1, 0], [-1, -1, -1]),
-            Orientation::new([2, 0, 1], [-1, -1, 1]),
-        ]
-    }
-    fn align(&self, mut pos: Vector) -> Vector {
-        pos = [pos[self.index[0]], pos[self.index[1]], pos[self.index[2]]];
-        (0..3).for_each(|i| pos[i] *= self.multi[i]);
-        pos
-    }
-}
 
 #[cfg(test)]
 mod tests {
@@ -215,11 +164,8 @@ mod tests {
 
     #[test]
     fn test_orientations() {
-        let pos = [5, 6, -4];
-        let mut orientations: Vec<_> = Orientation::all()
-            .into_iter()
-            .map(|o| o.align(pos))
-            .collect();
+        let pos = Vector::new(5, 6, -4);
+        let mut orientations: Vec<_> = Rotation::all().into_iter().map(|r| r.apply(pos)).collect();
         orientations.sort_unstable();
         assert_eq!(
             24,
@@ -229,12 +175,12 @@ mod tests {
                 .collect::<HashSet<Vector>>()
                 .len()
         );
-        assert!(orientations.contains(&[5, 6, -4]));
-        assert!(orientations.contains(&[-5, 4, -6]));
-        assert!(orientations.contains(&[4, 6, 5]));
-        assert!(orientations.contains(&[-4, -6, 5]));
-        assert!(orientations.contains(&[-6, -4, -5]));
-        let expected = vec![
+        assert!(orientations.contains(&Vector::new(5, 6, -4)));
+        assert!(orientations.contains(&Vector::new(-5, 4, -6)));
+        assert!(orientations.contains(&Vector::new(4, 6, 5)));
+        assert!(orientations.contains(&Vector::new(-4, -6, 5)));
+        assert!(orientations.contains(&Vector::new(-6, -4, -5)));
+        let expected: Vec<Vector> = [
             [-6, -5, 4],
             [-6, -4, -5],
             [-6, 4, 5],
@@ -259,7 +205,9 @@ mod tests {
             [6, -4, 5],
             [6, 4, -5],
             [6, 5, 4],
-        ];
+        ]
+        .map(Vector::from)
+        .to_vec();
         assert_eq!(expected, orientations);
     }
 
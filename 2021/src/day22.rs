@@ -1,3 +1,4 @@
+use geometry::{BoundingBox, BoxSet, Point3};
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 use std::ops::RangeInclusive;
@@ -24,7 +25,7 @@ impl ReactorCore {
             for x in cuboid.ranges[0].initial_only() {
                 for y in cuboid.ranges[1].initial_only() {
                     for z in cuboid.ranges[2].initial_only() {
-                        *cubes.entry(Vector { x, y, z }).or_default() = *state;
+                        *cubes.entry(Point3::new(x, y, z)).or_default() = *state;
                     }
                 }
             }
@@ -99,42 +100,15 @@ impl ReactorCore {
         count
     }
     fn turned_on_cubes_anywhere(&self) -> usize {
-        let mut counts: HashMap<Cuboid, isize> = HashMap::new();
-        for (turn_on, current) in self.instructions.iter() {
-            // Find overlaps with previous cuboids
-            let mut overlaps: HashMap<Cuboid, isize> = HashMap::new();
-            for (previous, previous_count) in &counts {
-                if let Some(overlap) = current.overlap(previous) {
-                    *overlaps.entry(overlap).or_default() -= *previous_count;
-                }
-            }
+        let mut turned_on = BoxSet::new();
+        for (turn_on, cuboid) in &self.instructions {
             if *turn_on {
-                *counts.entry(current.clone()).or_default() += 1;
-            }
-            // Overlapping cuboids will have to be subtracted, otherwise we'd count them twice
-            for (sub, sub_count) in overlaps {
-                *counts.entry(sub).or_default() += sub_count;
+                turned_on.insert(cuboid.to_box());
+            } else {
+                turned_on.remove(&cuboid.to_box());
             }
         }
-        // Optionally remove 0 counts, if only to clean up
-        // counts = counts.into_iter().filter(|(_, cnt)| *cnt != 0).collect();
-
-        // println!("{} counts", counts.len());
-        // for (cube, count) in &counts {
-        //     println!(
-        //         "{} cuboids of expanse {}, total {}",
-        //         count,
-        //         cube.expanse(),
-        //         count * cube.expanse() as isize
-        //     );
-        // }
-
-        // Sum up all the counts
-        counts
-            .into_iter()
-            .filter(|(_, cnt)| *cnt != 0)
-            .map(|(cuboid, cnt)| cnt * cuboid.expanse() as isize)
-            .sum::<isize>() as usize
+        turned_on.volume() as usize
     }
     fn instruction(line: &str) -> Instruction {
         let (turn_on, ranges) = line.split_once(' ').unwrap();
@@ -150,25 +124,11 @@ impl From<&str> for ReactorCore {
 
 trait Range {
     fn initial_only(&self) -> Self;
-    fn overlap(&self, other: &Self) -> Option<Self>
-    where
-        Self: Sized;
-    fn expanse(&self) -> usize;
 }
 impl Range for RangeInclusive<Coord> {
     fn initial_only(&self) -> RangeInclusive<Coord> {
         max(-50, *self.start())..=min(50, *self.end())
     }
-    fn overlap(&self, other: &Self) -> Option<Self> {
-        if self.start() <= other.end() && other.start() <= self.end() {
-            Some(max(*self.start(), *other.start())..=min(*self.end(), *other.end()))
-        } else {
-            None
-        }
-    }
-    fn expanse(&self) -> usize {
-        (self.end() - self.start() + 1) as usize
-    }
 }
 
 trait ToRange {
@@ -187,22 +147,12 @@ struct Cuboid {
     ranges: [RangeInclusive<Coord>; 3],
 }
 impl Cuboid {
-    fn overlap(&self, other: &Cuboid) -> Option<Cuboid> {
-        let mut overlaps: Vec<_> = (0..3)
-            .into_iter()
-            .filter_map(|i| self.ranges[i].overlap(&other.ranges[i]))
-            .collect();
-        if overlaps.len() == 3 {
-            let z = overlaps.remove(2);
-            let y = overlaps.remove(1);
-            let x = overlaps.remove(0);
-            Some(Cuboid { ranges: [x, y, z] })
-        } else {
-            None
-        }
-    }
-    fn expanse(&self) -> usize {
-        self.ranges.iter().map(Range::expanse).product()
+    fn to_box(&self) -> BoundingBox<3, Coord> {
+        let [x, y, z] = &self.ranges;
+        BoundingBox::new(
+            Point3::new(*x.start(), *y.start(), *z.start()),
+            Point3::new(*x.end(), *y.end(), *z.end()),
+        )
     }
 }
 impl From<&str> for Cuboid {
@@ -220,13 +170,6 @@ impl From<&str> for Cuboid {
 
 type Coord = isize;
 
-#[derive(Debug, PartialEq, Eq, Hash)]
-struct Vector {
-    x: Coord,
-    y: Coord,
-    z: Coord,
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -343,92 +286,62 @@ off x=-93533..-4276,y=-16170..68771,z=-104985..-24507";
         assert_eq!(576_028, day22_part1());
     }
 
-    #[test]
-    fn test_range_no_overlap() {
-        assert_eq!(None, "x=1..3".to_range().overlap(&"x=4..7".to_range()));
-    }
-
-    #[test]
-    fn test_range_overlaps_end() {
-        assert_eq!(
-            Some("x=3..4".to_range()),
-            "x=1..4".to_range().overlap(&"x=3..7".to_range())
-        );
-    }
-
-    #[test]
-    fn test_range_overlaps_start() {
-        assert_eq!(
-            Some("x=3..4".to_range()),
-            "x=3..7".to_range().overlap(&"x=1..4".to_range())
-        );
-    }
-
-    #[test]
-    fn test_range_overlap_enclosing() {
-        assert_eq!(
-            Some("x=3..4".to_range()),
-            "x=1..7".to_range().overlap(&"x=3..4".to_range())
-        );
-    }
-
-    #[test]
-    fn test_range_overlap_enclosed() {
-        assert_eq!(
-            Some("x=3..4".to_range()),
-            "x=3..4".to_range().overlap(&"x=1..7".to_range())
-        );
-    }
-
-    #[test]
-    fn test_range_overlap_identical() {
-        assert_eq!(
-            Some("x=0..0".to_range()),
-            "x=0..0".to_range().overlap(&"x=0..0".to_range())
-        );
-    }
-
     #[test]
     fn test_cuboid_no_overlap() {
         assert_eq!(
             None,
-            Cuboid::from("x=0..4,y=0..4,z=0..4").overlap(&Cuboid::from("x=5..6,y=5..6,z=5..6"))
+            Cuboid::from("x=0..4,y=0..4,z=0..4")
+                .to_box()
+                .intersection(&Cuboid::from("x=5..6,y=5..6,z=5..6").to_box())
         );
     }
 
     #[test]
     fn test_cuboid_overlaps() {
         assert_eq!(
-            Some(Cuboid::from("on x=1..1,y=0..0,z=0..0")),
+            Some(Cuboid::from("on x=1..1,y=0..0,z=0..0").to_box()),
             Cuboid::from("on x=0..1,y=0..0,z=0..0")
-                .overlap(&Cuboid::from("on x=1..2,y=0..0,z=0..0"))
+                .to_box()
+                .intersection(&Cuboid::from("on x=1..2,y=0..0,z=0..0").to_box())
         );
         assert_eq!(
-            Some(Cuboid::from("x=3..4,y=3..4,z=3..4")),
-            Cuboid::from("x=0..4,y=0..4,z=0..4").overlap(&Cuboid::from("x=3..5,y=3..5,z=3..5"))
+            Some(Cuboid::from("x=3..4,y=3..4,z=3..4").to_box()),
+            Cuboid::from("x=0..4,y=0..4,z=0..4")
+                .to_box()
+                .intersection(&Cuboid::from("x=3..5,y=3..5,z=3..5").to_box())
         );
     }
 
     #[test]
     fn test_cuboid_overlap_enclosing() {
         assert_eq!(
-            Some(Cuboid::from("x=1..3,y=1..3,z=1..3")),
-            Cuboid::from("x=0..4,y=0..4,z=0..4").overlap(&Cuboid::from("x=1..3,y=1..3,z=1..3"))
+            Some(Cuboid::from("x=1..3,y=1..3,z=1..3").to_box()),
+            Cuboid::from("x=0..4,y=0..4,z=0..4")
+                .to_box()
+                .intersection(&Cuboid::from("x=1..3,y=1..3,z=1..3").to_box())
         );
     }
 
     #[test]
     fn test_cuboid_overlap_enclosed() {
         assert_eq!(
-            Some(Cuboid::from("x=1..3,y=1..3,z=1..3")),
-            Cuboid::from("x=1..3,y=1..3,z=1..3").overlap(&Cuboid::from("x=0..4,y=0..4,z=0..4"))
+            Some(Cuboid::from("x=1..3,y=1..3,z=1..3").to_box()),
+            Cuboid::from("x=1..3,y=1..3,z=1..3")
+                .to_box()
+                .intersection(&Cuboid::from("x=0..4,y=0..4,z=0..4").to_box())
         );
     }
 
     #[test]
-    fn test_cuboid_expanse() {
-        assert_eq!(3 * 3 * 3, Cuboid::from("x=1..3,y=1..3,z=1..3").expanse());
-        assert_eq!(3 * 4 * 5, Cuboid::from("x=1..3,y=2..5,z=3..7").expanse());
+    fn test_cuboid_volume() {
+        assert_eq!(
+            3 * 3 * 3,
+            Cuboid::from("x=1..3,y=1..3,z=1..3").to_box().volume()
+        );
+        assert_eq!(
+            3 * 4 * 5,
+            Cuboid::from("x=1..3,y=2..5,z=3..7").to_box().volume()
+        );
     }
 
     #[test]
@@ -1,67 +1,163 @@
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, HashMap};
+use state_space::{Puzzle, Solver};
 use std::fmt::{Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::ops::Range;
-use Amphipod::*;
+use std::rc::Rc;
 
 const INPUT: &str = include_str!("../input/day23.txt");
 const INPUT2: &str = include_str!("../input/day23_2.txt");
 
-pub(crate) fn day23_part1() -> usize {
+pub fn day23_part1() -> usize {
     Burrow::from(INPUT).solve()
 }
 
-pub(crate) fn day23_part2() -> usize {
+pub fn day23_part2() -> usize {
     Burrow::from(INPUT2).solve()
 }
 
 impl Burrow {
-    fn solve(self) -> Energy {
-        let mut candidates = BinaryHeap::new();
-        candidates.push(Reverse((0, self)));
+    pub fn solve(self) -> Energy {
+        Solver::new(&self).minimize().unwrap().value
+    }
+    /// The burrow after each move of a cheapest solution, starting with this one,
+    /// along with the energy spent so far
+    fn solution_frames(&self) -> Option<Vec<(Energy, Burrow)>> {
+        let solution = Solver::new(self).minimize()?;
+        let mut frames = vec![(0, self.clone())];
+        for mv in &solution.moves {
+            let (energy, burrow) = frames.last().unwrap();
+            frames.push((energy + burrow.energy_for(mv), burrow.after(mv)));
+        }
+        Some(frames)
+    }
+    /// Every burrow of a cheapest solution, each with the energy spent to get there
+    pub fn solution_as_text(&self) -> Option<String> {
+        let frames = self.solution_frames()?;
+        let frames: Vec<_> = (frames.iter())
+            .map(|(energy, burrow)| format!("Energy spent: {energy}\n{burrow}"))
+            .collect();
+        Some(frames.join("\n\n"))
+    }
+}
 
-        let mut best: HashMap<Burrow, Energy> = HashMap::new();
+impl Puzzle for Burrow {
+    type State = Burrow;
+    type Move = Move;
 
-        while let Some(Reverse((energy, current))) = candidates.pop() {
-            // 1. check if goal reached
-            if current.is_finished() {
-                return energy;
-            }
-            // Discard positions that have previously been reached with lower energy
-            if let Some(prev) = best.get(&current) {
-                if *prev < energy {
-                    continue;
-                }
-            }
-
-            // 2. find amphipods that can move, and add their moves to the candidates
-            for (cost, next) in current.get_next_possible_moves(energy, &best) {
-                if let Some(prev) = best.get(&next) {
-                    if *prev < energy + cost {
-                        continue;
-                    }
-                }
-                best.insert(next.clone(), energy + cost);
-                candidates.push(Reverse((energy + cost, next)));
+    fn start(&self) -> Burrow {
+        self.clone()
+    }
+    fn moves(&self, burrow: &Burrow) -> Vec<Move> {
+        burrow.get_next_possible_moves()
+    }
+    fn apply(&self, burrow: &Burrow, mv: &Move) -> Burrow {
+        burrow.after(mv)
+    }
+    fn cost(&self, burrow: &Burrow, mv: &Move) -> Energy {
+        burrow.energy_for(mv)
+    }
+    fn is_goal(&self, burrow: &Burrow) -> bool {
+        burrow.is_finished()
+    }
+    fn heuristic(&self, burrow: &Burrow) -> Option<Energy> {
+        let layout = &burrow.layout;
+        let mut energy = 0;
+        let mut entering = vec![0; layout.room_xs.len()];
+        for (x, y, pod) in burrow.pods() {
+            if burrow.is_settled(x, y) {
+                continue;
             }
+            // Every other amphipod still has to go up into the hallway and over to its room,
+            // or step aside and back if it's blocking others in its own room
+            let room_x = layout.room_xs[pod.0];
+            let along = if x == room_x { 2 } else { x.abs_diff(room_x) };
+            energy += burrow.energy_per_step(pod) * (y - HALLWAY_Y + along);
+            entering[pod.0] += 1;
         }
-        unreachable!()
+        // And down into its room, which is filled from the bottom
+        for (kind, count) in entering.into_iter().enumerate() {
+            energy += burrow.energy_per_step(Amphipod(kind)) * count * (count + 1) / 2;
+        }
+        Some(energy)
     }
 }
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Hash)]
-struct Burrow {
+/// An amphipod moving from one position to another, without stopping in between
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Move {
+    from: (X, Y),
+    to: (X, Y),
+}
+
+/// The shape of a burrow: where its hallway and rooms are,
+/// and how much energy each type of amphipod needs per step
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Layout {
+    /// The input without any amphipods, to draw the burrow
+    walls: Vec<Vec<char>>,
+    hallway_xs: Vec<X>,
+    /// The room at `room_xs[i]` is the destination of the amphipods of type `i`
+    room_xs: Vec<X>,
+    room_depth: usize,
+    energy_per_step: Vec<Energy>,
+}
+
+#[derive(Clone)]
+pub struct Burrow {
+    layout: Rc<Layout>,
     grid: Grid,
 }
+// Burrows are only ever compared to others with the same layout
+impl PartialEq for Burrow {
+    fn eq(&self, other: &Self) -> bool {
+        self.grid == other.grid
+    }
+}
+impl Eq for Burrow {}
+impl Hash for Burrow {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.grid.hash(state);
+    }
+}
 impl From<&str> for Burrow {
     fn from(input: &str) -> Self {
-        Burrow {
-            grid: input
-                .trim()
-                .lines()
-                .map(|line| line.chars().map(|c| Amphipod::try_from(c).ok()).collect())
-                .collect(),
+        let rows: Vec<Vec<char>> = input
+            .trim()
+            .lines()
+            .map(|line| line.trim_end().chars().collect())
+            .collect();
+        let is_open = |y: Y, x: X| rows[y].get(x).is_some_and(|&c| c != '#' && c != ' ');
+        let hallway_xs = (0..rows[HALLWAY_Y].len())
+            .filter(|&x| is_open(HALLWAY_Y, x))
+            .collect();
+        let room_xs: Vec<_> = (0..rows[HALLWAY_Y + 1].len())
+            .filter(|&x| is_open(HALLWAY_Y + 1, x))
+            .collect();
+        let room_depth = (HALLWAY_Y + 1..rows.len())
+            .take_while(|&y| room_xs.iter().all(|&x| is_open(y, x)))
+            .count();
+        let energy_per_step = (0..room_xs.len() as u32).map(|i| 10_usize.pow(i)).collect();
+        let grid: Grid = (rows.iter())
+            .map(|row| row.iter().map(|&c| Amphipod::try_from(c).ok()).collect())
+            .collect();
+        let walls = (rows.iter())
+            .map(|row| (row.iter()).map(|&c| if c.is_ascii_uppercase() { '.' } else { c }))
+            .map(|row| row.collect())
+            .collect();
+        let burrow = Burrow {
+            layout: Rc::new(Layout {
+                walls,
+                hallway_xs,
+                room_xs,
+                room_depth,
+                energy_per_step,
+            }),
+            grid,
+        };
+        for (_, _, pod) in burrow.pods() {
+            assert!(pod.0 < burrow.layout.room_xs.len(), "No room for {pod}");
         }
+        burrow
     }
 }
 impl Display for Burrow {
@@ -69,21 +165,9 @@ impl Display for Burrow {
         write!(
             f,
             "{}",
-            self.grid
-                .iter()
-                .enumerate()
-                .map(|(y, row)| row
-                    .iter()
-                    .enumerate()
-                    .map(|(x, pod)| pod
-                        .map(|pod| pod.to_char())
-                        .unwrap_or_else(|| match (x, y) {
-                            (_, 0) => '#',
-                            (1..=11, HALLWAY_Y) => '.',
-                            (0 | 1 | 11 | 12, 3..=6) => ' ',
-                            (3 | 5 | 7 | 9, 2..=3) => '.',
-                            (_, _) => '#',
-                        }))
+            (self.layout.walls.iter().zip(self.grid.iter()))
+                .map(|(walls, row)| (walls.iter().zip(row.iter()))
+                    .map(|(wall, pod)| pod.map(|pod| pod.to_char()).unwrap_or(*wall))
                     .collect::<String>())
                 .collect::<Vec<_>>()
                 .join("\n")
@@ -91,36 +175,58 @@ impl Display for Burrow {
     }
 }
 
-type Energy = usize;
+pub type Energy = usize;
 type Grid = Vec<Vec<Option<Amphipod>>>;
-type Move = (Energy, Burrow);
 type X = usize;
 type Y = usize;
 
-const ROOM_XS: [usize; 4] = [3, 5, 7, 9];
-const VALID_HALLWAY_XS: [usize; 7] = [1, 2, 4, 6, 8, 10, 11];
-const ALL_HALLWAY_XS: [usize; 11] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11];
 const HALLWAY_Y: usize = 1;
 
 impl Burrow {
+    /// Uses the given energy per step for each type of amphipod, instead of
+    /// the default of 1, 10, 100 and so on
+    pub fn with_energy_costs(mut self, energy_per_step: &[Energy]) -> Self {
+        assert_eq!(self.layout.room_xs.len(), energy_per_step.len());
+        Rc::make_mut(&mut self.layout).energy_per_step = energy_per_step.to_vec();
+        self
+    }
+    /// How many types of amphipods there are, one for each room
+    pub fn amphipod_types(&self) -> usize {
+        self.layout.room_xs.len()
+    }
     fn is_finished(&self) -> bool {
-        ROOM_XS
-            .iter()
-            .all(|&room_x| self.is_room_full_and_sorted(room_x))
+        (self.layout.room_xs.iter()).all(|&room_x| self.is_room_full_and_sorted(room_x))
     }
     fn is_room_full_and_sorted(&self, room_x: usize) -> bool {
         self.room_ys().all(|room_y| {
             if let Some(pod) = self.grid[room_y][room_x] {
-                pod.has_destination(room_x)
+                self.has_destination(pod, room_x)
             } else {
                 false
             }
         })
     }
+    /// Whether the amphipod at this position is in its room, and doesn't block any others
+    fn is_settled(&self, x: X, y: Y) -> bool {
+        y > HALLWAY_Y
+            && (y..self.room_ys().end)
+                .all(|below| self.grid[below][x].is_some_and(|pod| self.has_destination(pod, x)))
+    }
+    fn has_destination(&self, pod: Amphipod, room_x: X) -> bool {
+        self.layout.room_xs[pod.0] == room_x
+    }
+    fn energy_per_step(&self, pod: Amphipod) -> Energy {
+        self.layout.energy_per_step[pod.0]
+    }
+    fn pods(&self) -> impl Iterator<Item = (X, Y, Amphipod)> + '_ {
+        self.grid.iter().enumerate().flat_map(|(y, row)| {
+            (row.iter().enumerate()).filter_map(move |(x, pod)| pod.map(|pod| (x, y, pod)))
+        })
+    }
     fn room_ys(&self) -> Range<usize> {
-        2..self.grid.len() - 1
+        HALLWAY_Y + 1..HALLWAY_Y + 1 + self.layout.room_depth
     }
-    fn get_next_possible_moves(&self, energy: Energy, best: &HashMap<Burrow, Energy>) -> Vec<Move> {
+    fn get_next_possible_moves(&self) -> Vec<Move> {
         let mut moves = vec![];
 
         for (from_x, from_y, pod) in self.occupied_rooms() {
@@ -128,34 +234,48 @@ impl Burrow {
             // Prioritize rooms over hallways
             if !rooms.is_empty() {
                 for to in rooms {
-                    moves.push(self.move_from_room_to_room(from_x, from_y, to, pod));
+                    moves.push(Move {
+                        from: (from_x, from_y),
+                        to: (to, self.top_empty_room_y(to)),
+                    });
                 }
             } else {
                 for to in self.reachable_hallways_from_room(from_x) {
-                    moves.push(self.move_from_room_to_hallway(from_x, from_y, to, pod));
+                    moves.push(Move {
+                        from: (from_x, from_y),
+                        to: (to, HALLWAY_Y),
+                    });
                 }
             }
         }
         for (from, pod) in self.occupied_hallways() {
             for to in self.reachable_rooms_from_hallway(from, pod) {
-                moves.push(self.move_from_hallway_to_room(from, to, pod));
+                moves.push(Move {
+                    from: (from, HALLWAY_Y),
+                    to: (to, self.top_empty_room_y(to)),
+                });
             }
         }
-
-        // Return only the moves that lead to better results
         moves
-            .iter()
-            .filter(|(cost, next)| {
-                best.get(next)
-                    .map(|total| energy + *cost < *total)
-                    .unwrap_or(true)
-            })
-            .cloned()
-            .collect()
+    }
+
+    fn after(&self, mv: &Move) -> Burrow {
+        let ((from_x, from_y), (to_x, to_y)) = (mv.from, mv.to);
+        let mut next = self.clone();
+        next.grid[to_y][to_x] = next.grid[from_y][from_x].take();
+        next
+    }
+
+    fn energy_for(&self, mv: &Move) -> Energy {
+        let ((from_x, from_y), (to_x, to_y)) = (mv.from, mv.to);
+        let pod = self.grid[from_y][from_x].unwrap();
+        // Up into the hallway, along it, and down again
+        let steps = (from_y - HALLWAY_Y) + from_x.abs_diff(to_x) + (to_y - HALLWAY_Y);
+        self.energy_per_step(pod) * steps
     }
 
     fn occupied_hallways(&self) -> Vec<(X, Amphipod)> {
-        self.grid[1]
+        self.grid[HALLWAY_Y]
             .iter()
             .enumerate()
             .filter_map(|(x, pod)| pod.map(|pod| (x, pod)))
@@ -163,9 +283,8 @@ impl Burrow {
     }
 
     fn occupied_rooms(&self) -> Vec<(X, Y, Amphipod)> {
-        ROOM_XS
-            .into_iter()
-            .filter_map(|x| self.topmost_occupied_room(x).map(|(y, pod)| (x, y, pod)))
+        (self.layout.room_xs.iter())
+            .filter_map(|&x| self.topmost_occupied_room(x).map(|(y, pod)| (x, y, pod)))
             .filter(|(x, _y, _pod)| !self.is_room_full_and_sorted(*x))
             .collect()
     }
@@ -177,18 +296,22 @@ impl Burrow {
             .collect()
     }
 
+    /// The hallway positions that can be reached from the given one without passing anyone
+    fn reachable_hallways_from(&self, from: X) -> impl Iterator<Item = X> + '_ {
+        let hallway_xs = &self.layout.hallway_xs;
+        let left = (hallway_xs.iter().rev())
+            .skip_while(move |&&x| from <= x)
+            .take_while(|&&left| self.is_empty_hallway(left));
+        let right = (hallway_xs.iter())
+            .skip_while(move |&&x| x <= from)
+            .take_while(|&&right| self.is_empty_hallway(right));
+        left.chain(right).copied()
+    }
+
     fn reachable_hallways_from_room(&self, from: usize) -> Vec<usize> {
-        VALID_HALLWAY_XS
-            .into_iter()
-            .rev()
-            .skip_while(|&x| from <= x)
-            .take_while(|&left| self.is_empty_hallway(left))
-            .chain(
-                VALID_HALLWAY_XS
-                    .into_iter()
-                    .skip_while(|&x| x <= from)
-                    .take_while(|&right| self.is_empty_hallway(right)),
-            )
+        // Amphipods never stop right outside a room
+        self.reachable_hallways_from(from)
+            .filter(|x| !self.layout.room_xs.contains(x))
             .collect()
     }
 
@@ -197,50 +320,51 @@ impl Burrow {
     }
 
     fn reachable_rooms_from_hallway(&self, from: X, pod: Amphipod) -> Vec<X> {
-        let left = ALL_HALLWAY_XS
-            .into_iter()
-            .rev()
-            .skip_while(|&x| from <= x)
-            .take_while(|&left| self.is_empty_hallway(left));
-        let right = ALL_HALLWAY_XS
-            .into_iter()
-            .skip_while(|&x| x <= from)
-            .take_while(|&right| self.is_empty_hallway(right));
-        left.chain(right)
-            .filter(|x| ROOM_XS.contains(x))
-            .filter(|room| pod.has_destination(*room))
+        self.reachable_hallways_from(from)
+            .filter(|&room| self.has_destination(pod, room))
             .filter(|x| self.all_occupants_match(*x, &pod))
             .collect()
     }
 
+    #[cfg(test)]
     fn move_from_room_to_room(&self, from: X, y: Y, to: X, pod: Amphipod) -> (Energy, Self) {
-        let via_hallway = (from + to) / 2;
-        let (e1, intermediate) = self.move_from_room_to_hallway(from, y, via_hallway, pod);
-        let (e2, next) = intermediate.move_from_hallway_to_room(via_hallway, to, pod);
-        (e1 + e2, next)
+        let to = (to, self.top_empty_room_y(to));
+        self.move_pod(
+            pod,
+            Move {
+                from: (from, y),
+                to,
+            },
+        )
     }
 
+    #[cfg(test)]
     fn move_from_room_to_hallway(&self, from: X, y: Y, to: X, pod: Amphipod) -> (Energy, Self) {
-        let mut next = self.clone();
-        next.grid[y][from] = None;
-        next.grid[HALLWAY_Y][to] = Some(pod);
-
-        let y_steps = y - HALLWAY_Y;
-        let x_steps = if from < to { to - from } else { from - to };
-
-        (pod.energy_for(y_steps + x_steps), next)
+        self.move_pod(
+            pod,
+            Move {
+                from: (from, y),
+                to: (to, HALLWAY_Y),
+            },
+        )
     }
 
+    #[cfg(test)]
     fn move_from_hallway_to_room(&self, from: X, to: X, pod: Amphipod) -> (Energy, Self) {
-        let mut next = self.clone();
-        let room_y = next.top_empty_room_y(to);
-        next.grid[HALLWAY_Y][from] = None;
-        next.grid[room_y][to] = Some(pod);
-
-        let x_steps = if from < to { to - from } else { from - to };
-        let y_steps = room_y - HALLWAY_Y;
+        let to = (to, self.top_empty_room_y(to));
+        self.move_pod(
+            pod,
+            Move {
+                from: (from, HALLWAY_Y),
+                to,
+            },
+        )
+    }
 
-        (pod.energy_for(y_steps + x_steps), next)
+    #[cfg(test)]
+    fn move_pod(&self, pod: Amphipod, mv: Move) -> (Energy, Self) {
+        assert_eq!(Some(pod), self.grid[mv.from.1][mv.from.0]);
+        (self.energy_for(&mv), self.after(&mv))
     }
 
     fn topmost_occupied_room(&self, x: X) -> Option<(Y, Amphipod)> {
@@ -260,7 +384,7 @@ impl Burrow {
     fn top_empty_room_y(&self, room: X) -> usize {
         self.topmost_occupied_room(room)
             .map(|(y, _)| y)
-            .unwrap_or(self.grid.len() - 1)
+            .unwrap_or(self.room_ys().end)
             - 1
     }
     #[cfg(test)]
@@ -273,34 +397,22 @@ trait ToChar {
     fn to_char(&self) -> char;
 }
 
-#[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Copy, Clone, Hash)]
-enum Amphipod {
-    Amber,
-    Bronze,
-    Copper,
-    Desert,
-}
+/// The type of an amphipod, which is also the index of its destination room
+#[derive(Debug, Eq, PartialEq, Copy, Clone, Hash)]
+struct Amphipod(usize);
 impl TryFrom<char> for Amphipod {
     type Error = String;
 
     fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'A' => Ok(Amber),
-            'B' => Ok(Bronze),
-            'C' => Ok(Copper),
-            'D' => Ok(Desert),
+            'A'..='Z' => Ok(Amphipod((c as u8 - b'A') as usize)),
             c => Err(format!("Illegal Amphipod char '{}'", c)),
         }
     }
 }
 impl ToChar for Amphipod {
     fn to_char(&self) -> char {
-        match self {
-            Amber => 'A',
-            Bronze => 'B',
-            Copper => 'C',
-            Desert => 'D',
-        }
+        (b'A' + self.0 as u8) as char
     }
 }
 impl Display for Amphipod {
@@ -308,30 +420,6 @@ impl Display for Amphipod {
         write!(f, "{}", self.to_char())
     }
 }
-impl Amphipod {
-    fn has_destination(&self, room_x: usize) -> bool {
-        room_x == self.destination_room()
-    }
-    fn destination_room(&self) -> usize {
-        match self {
-            Amber => 3,
-            Bronze => 5,
-            Copper => 7,
-            Desert => 9,
-        }
-    }
-    fn energy_per_step(&self) -> Energy {
-        match self {
-            Amber => 1,
-            Bronze => 10,
-            Copper => 100,
-            Desert => 1000,
-        }
-    }
-    fn energy_for(&self, steps: usize) -> Energy {
-        steps * self.energy_per_step()
-    }
-}
 
 #[cfg(test)]
 mod tests {
@@ -472,7 +560,8 @@ mod tests {
         for (exp_e, exp) in expecteds {
             let mut one_matched = false;
             println!("expected {}\n{}\n", exp_e, exp);
-            for (next_e, next) in burrow.get_next_possible_moves(0, &HashMap::new()) {
+            for mv in burrow.get_next_possible_moves() {
+                let (next_e, next) = (burrow.energy_for(&mv), burrow.after(&mv));
                 if next == exp {
                     assert_eq!(next_e, exp_e);
                     one_matched = true;
@@ -485,12 +574,12 @@ mod tests {
                     "did not get match for next of\n{}\n\nbut instead got\n",
                     burrow
                 );
-                let next = burrow.get_next_possible_moves(0, &HashMap::new());
+                let next = burrow.get_next_possible_moves();
                 if next.is_empty() {
                     println!("nothing")
                 } else {
-                    for (next_e, next_pos) in next {
-                        println!("{}\n{}\n", next_e, next_pos)
+                    for mv in next {
+                        println!("{}\n{}\n", burrow.energy_for(&mv), burrow.after(&mv))
                     }
                 }
             }
@@ -509,9 +598,10 @@ mod tests {
   #########  ",
         );
         println!("{}\n", burrow);
-        let next = burrow.get_next_possible_moves(0, &HashMap::new());
+        let next = burrow.get_next_possible_moves();
         let len = next.len();
-        for (i, (e, next)) in next.into_iter().enumerate() {
+        for (i, mv) in next.into_iter().enumerate() {
+            let (e, next) = (burrow.energy_for(&mv), burrow.after(&mv));
             println!("{}/{} @ {}:\n{}\n", i + 1, len, e, next);
         }
     }
@@ -630,6 +720,103 @@ mod tests {
         assert_eq!(12521, Burrow::from(EXAMPLE).solve());
     }
 
+    #[test]
+    fn part1_example_moves() {
+        let burrow = Burrow::from(EXAMPLE);
+        let solution = Solver::new(&burrow).minimize().unwrap();
+        let (energy, end) = (solution.moves.iter()).fold((0, burrow), |(energy, burrow), mv| {
+            assert!(burrow.get_next_possible_moves().contains(mv));
+            (energy + burrow.energy_for(mv), burrow.after(mv))
+        });
+        assert_eq!(12521, energy);
+        assert!(end.is_finished());
+    }
+
+    #[test]
+    fn heuristic_never_overestimates() {
+        let burrow = Burrow::from(EXAMPLE);
+        let solution = Solver::new(&burrow).minimize().unwrap();
+        let mut remaining = solution.value;
+        let mut burrow = burrow;
+        for mv in &solution.moves {
+            assert!(burrow.heuristic(&burrow).unwrap() <= remaining);
+            remaining -= burrow.energy_for(mv);
+            burrow = burrow.after(mv);
+        }
+        assert_eq!(Some(0), burrow.heuristic(&burrow));
+    }
+
+    const SMALL: &str = "\
+#########
+#.......#
+###B#A###
+  #A#B#
+  #####";
+
+    const WIDE: &str = "\
+###############
+#.............#
+###C#A#B#E#D###
+  #A#B#C#D#E#
+  ###########";
+
+    #[test]
+    fn display_draws_any_layout() {
+        assert_eq!(SMALL, Burrow::from(SMALL).to_string());
+        assert_eq!(WIDE, Burrow::from(WIDE).to_string());
+    }
+
+    #[test]
+    fn smaller_layout() {
+        let burrow = Burrow::from(SMALL);
+        assert_eq!(vec![3, 5], burrow.layout.room_xs);
+        assert_eq!(2, burrow.layout.room_depth);
+        // A steps aside for B to go home directly
+        assert_eq!(2 + 40 + 4, burrow.clone().solve());
+        // B steps aside instead if that's cheaper
+        assert_eq!(2 + 400 + 4, burrow.with_energy_costs(&[100, 1]).solve());
+    }
+
+    #[test]
+    fn wider_layout() {
+        let burrow = Burrow::from(WIDE);
+        assert_eq!(vec![3, 5, 7, 9, 11], burrow.layout.room_xs);
+        assert_eq!(2, burrow.layout.room_depth);
+        let frames = burrow.solution_frames().unwrap();
+        let (energy, finished) = frames.last().unwrap();
+        assert!(finished.is_finished());
+        assert_eq!(*energy, burrow.clone().solve());
+        // Two cycles of amphipods blocking each other, each needing a detour of 2 steps
+        assert_eq!(6 + 4 * 4 + 2 * 2, burrow.with_energy_costs(&[1; 5]).solve());
+    }
+
+    #[test]
+    fn part1_example_frames() {
+        let burrow = Burrow::from(EXAMPLE);
+        let frames = burrow.solution_frames().unwrap();
+        assert!(frames[0] == (0, burrow.clone()));
+        for pair in frames.windows(2) {
+            let ((energy, before), (next_energy, after)) = (&pair[0], &pair[1]);
+            let mv = (before.get_next_possible_moves().into_iter())
+                .find(|mv| &before.after(mv) == after)
+                .unwrap();
+            assert_eq!(*next_energy, energy + before.energy_for(&mv));
+        }
+        assert_eq!(12521, frames.last().unwrap().0);
+
+        let text = burrow.solution_as_text().unwrap();
+        assert!(text.starts_with(&format!("Energy spent: 0\n{}\n\n", burrow)));
+        assert!(text.ends_with(
+            "\
+Energy spent: 12521
+#############
+#...........#
+###A#B#C#D###
+  #A#B#C#D#
+  #########"
+        ));
+    }
+
     #[test]
     fn part1() {
         assert_eq!(16059, day23_part1());
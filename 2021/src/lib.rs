@@ -0,0 +1,2 @@
+pub mod day16;
+pub mod day23;
@@ -103,8 +103,7 @@ fn day15() {
     assert_eq!(3002, day15_part2());
 }
 
-use crate::day16::{day16_part1, day16_part2};
-mod day16;
+use advent_of_code_2021::day16::{day16_part1, day16_part2};
 fn day16() {
     assert_eq!(908, day16_part1());
     assert_eq!(10_626_195_124_371, day16_part2());
@@ -152,8 +151,7 @@ fn day22() {
     assert_eq!(1_387_966_280_636_636, day22_part2());
 }
 
-use crate::day23::{day23_part1, day23_part2};
-mod day23;
+use advent_of_code_2021::day23::{day23_part1, day23_part2};
 fn day23() {
     assert_eq!(16059, day23_part1());
     assert_eq!(43117, day23_part2());
@@ -0,0 +1,103 @@
+//! Lets the sand of day 14 fall into the cave, printing the cave or saving how it fills up
+//! as images.
+//!
+//! ```sh
+//! cargo run --release --bin day14 -- --floor --every 100 --gif cave.gif rocks.txt
+//! ```
+//!
+//! All options are optional:
+//! - `--floor` adds the floor of part 2 below the lowest rock
+//! - `--frames` prints the cave after every grain of sand came to rest, instead of saving a GIF
+//! - `--gif <path>` saves an animation of the cave filling up
+//! - `--every <count>` only adds a frame to the animation after this many grains, and
+//!   defaults to 1
+//! - `--scale <pixels>` is the size of each cell in images, and defaults to 4
+//! - `--png <path>` saves an image of the cave once the sand stopped falling
+//! - the rock paths are read from the given file, and default to the puzzle input
+use advent_of_code_2022::day14::FallingSandCave;
+use image_export::Animation;
+
+const INPUT: &str = include_str!("../../input/day14.txt");
+
+fn number(value: &str) -> usize {
+    value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| exit_with(&format!("Expected a number, got '{}'", value)))
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut floor = false;
+    let mut frames = false;
+    let mut gif_path = None;
+    let mut every = 1;
+    let mut scale = 4;
+    let mut png_path = None;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| exit_with(&format!("Missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--floor" => floor = true,
+            "--frames" => frames = true,
+            "--gif" => gif_path = Some(value()),
+            "--every" => every = number(&value()),
+            "--scale" => scale = number(&value()),
+            "--png" => png_path = Some(value()),
+            _ if arg.starts_with("--") => exit_with(&format!("Unknown option '{}'", arg)),
+            _ => path = Some(arg),
+        }
+    }
+    if every == 0 || scale == 0 {
+        exit_with("Frames need at least one grain and one pixel per cell");
+    }
+    if frames && gif_path.is_some() {
+        exit_with("Either print the frames or save them as a GIF");
+    }
+
+    let input = match &path {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't read {}: {}", path, e))),
+        None => INPUT.to_string(),
+    };
+    let mut cave = FallingSandCave::from(input.trim());
+    if floor {
+        cave = cave.add_floor();
+    }
+
+    if frames {
+        println!("{}", cave);
+        for (grain, frame) in cave.frames().enumerate() {
+            println!("\nAfter {} grains:\n{}", grain + 1, frame);
+        }
+        println!();
+    } else if let Some(path) = &gif_path {
+        let mut animation = Animation::new(5);
+        animation.push(cave.image(scale));
+        while cave.let_sand_fall(every) > 0 {
+            animation.push(cave.image(scale));
+        }
+        animation
+            .save_gif(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't save {}: {}", path, e)));
+    } else {
+        cave = cave.let_sand_fall_until_stable();
+        println!("{}\n", cave);
+    }
+
+    if let Some(path) = &png_path {
+        cave.image(scale)
+            .save_png(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't save {}: {}", path, e)));
+    }
+    println!("{} grains of sand came to rest", cave.sand_grain_count());
+}
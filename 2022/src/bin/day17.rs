@@ -0,0 +1,93 @@
+//! Drops the rocks of day 17 into the chamber, printing the top of the tower or saving
+//! how it grows as images.
+//!
+//! ```sh
+//! cargo run --release --bin day17 -- --rocks 100 --rows 40 --gif tower.gif jets.txt
+//! ```
+//!
+//! All options are optional:
+//! - `--rocks <count>` is the number of rocks to drop, and defaults to 2022 like in part 1
+//! - `--rows <count>` is the number of rows shown from the top of the tower, and defaults to 20
+//! - `--scale <pixels>` is the size of each unit in images, and defaults to 4
+//! - `--gif <path>` saves an animation with a frame after every rock
+//! - `--png <path>` saves an image of the tower after the last rock
+//! - the jets are read from the given file, and default to the puzzle input
+use advent_of_code_2022::day17::{parse, parse_shapes, Chamber, Tower, ROCKS};
+use image_export::Animation;
+
+const INPUT: &str = include_str!("../../input/day17.txt");
+
+fn number(value: &str) -> usize {
+    value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| exit_with(&format!("Expected a number, got '{}'", value)))
+}
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut rock_count = 2022;
+    let mut row_count = 20;
+    let mut scale = 4;
+    let mut gif_path = None;
+    let mut png_path = None;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| exit_with(&format!("Missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--rocks" => rock_count = number(&value()),
+            "--rows" => row_count = number(&value()),
+            "--scale" => scale = number(&value()),
+            "--gif" => gif_path = Some(value()),
+            "--png" => png_path = Some(value()),
+            _ if arg.starts_with("--") => exit_with(&format!("Unknown option '{}'", arg)),
+            _ => path = Some(arg),
+        }
+    }
+    if row_count == 0 || scale == 0 {
+        exit_with("Images need at least one row and one pixel per unit");
+    }
+
+    let input = match &path {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't read {}: {}", path, e))),
+        None => INPUT.to_string(),
+    };
+    let jets = parse(&input);
+    if jets.is_empty() {
+        exit_with("Expected at least one jet");
+    }
+    let shapes = parse_shapes(ROCKS);
+    let mut tower = Tower::new(Chamber::default(), &shapes, &jets);
+
+    let mut animation = gif_path.as_ref().map(|_| Animation::new(5));
+    for _ in 0..rock_count {
+        tower.drop_rock();
+        if let Some(animation) = &mut animation {
+            animation.push(tower.image(row_count, scale));
+        }
+    }
+
+    if let (Some(path), Some(animation)) = (&gif_path, &animation) {
+        animation
+            .save_gif(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't save {}: {}", path, e)));
+    }
+    if let Some(path) = &png_path {
+        tower
+            .image(row_count, scale)
+            .save_png(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't save {}: {}", path, e)));
+    }
+    println!("{}", tower.frame(row_count));
+    println!("The tower is {} units tall", tower.height());
+}
@@ -0,0 +1,66 @@
+//! Prints the best build order of every blueprint of day 19, to crack as many geodes as
+//! possible, or to collect some resources as quickly as possible.
+//!
+//! ```sh
+//! cargo run --release --bin day19 -- --minutes 24 --fastest "1 geode and 10 ore" blueprints.txt
+//! ```
+//!
+//! All options are optional:
+//! - `--minutes <count>` is the time limit, and defaults to 24 minutes like in part 1
+//! - `--fastest <resources>` collects these resources, written like the blueprints' costs,
+//!   instead of cracking as many geodes as possible
+//! - the blueprints are read from the given file, and default to the puzzle input
+use advent_of_code_2022::day19::{optimize_all, parse_blueprints, Objective, ResourceCounts};
+
+const INPUT: &str = include_str!("../../input/day19.txt");
+
+fn exit_with(message: &str) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn main() {
+    let mut minutes = 24;
+    let mut objective = Objective::MostGeodes;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .unwrap_or_else(|| exit_with(&format!("Missing value for {}", arg)))
+        };
+        match arg.as_str() {
+            "--minutes" => {
+                let value = value();
+                minutes = value
+                    .parse()
+                    .unwrap_or_else(|_| exit_with(&format!("Expected minutes, got '{}'", value)))
+            }
+            "--fastest" => {
+                let target =
+                    ResourceCounts::try_from(value().as_str()).unwrap_or_else(|e| exit_with(&e));
+                objective = Objective::FastestTo(target);
+            }
+            _ if arg.starts_with("--") => exit_with(&format!("Unknown option '{}'", arg)),
+            _ => path = Some(arg),
+        }
+    }
+
+    let input = match &path {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|e| exit_with(&format!("Can't read {}: {}", path, e))),
+        None => INPUT.to_string(),
+    };
+    let blueprints = parse_blueprints(input.trim());
+    for (i, plan) in optimize_all(&blueprints, objective, minutes)
+        .into_iter()
+        .enumerate()
+    {
+        println!("Blueprint {}:", i + 1);
+        match plan {
+            Some(plan) => println!("{}\n", plan),
+            None => println!("Not possible within {} minutes\n", minutes),
+        }
+    }
+}
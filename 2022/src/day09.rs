@@ -1,3 +1,4 @@
+use geometry::{Direction, Point2};
 use std::collections::HashSet;
 
 const INPUT: &str = include_str!("../input/day09.txt");
@@ -18,20 +19,18 @@ fn parse(input: &str) -> Vec<Command> {
 
 fn number_of_unique_positions_visited_by_tail(commands: Vec<Command>, rope_length: usize) -> usize {
     let mut visited: HashSet<Pos> = HashSet::new();
-    let mut rope = vec![Pos::default(); rope_length];
+    let mut rope = vec![Pos::origin(); rope_length];
     visited.insert(*rope.last().unwrap());
 
     for Command { steps, direction } in commands {
         for _ in 0..steps {
-            let head = &mut rope[0];
-            head.move_in(&direction);
+            rope[0] += direction.offset();
 
             for i in 1..rope_length {
                 let head = rope[i - 1];
                 let tail = &mut rope[i];
-                if !head.is_neighbor_of(tail) {
-                    tail.x += (head.x - tail.x).signum();
-                    tail.y += (head.y - tail.y).signum();
+                if !head.is_adjacent_to(tail) {
+                    *tail += (head - *tail).signum();
                 }
             }
             visited.insert(*rope.last().unwrap());
@@ -40,73 +39,7 @@ fn number_of_unique_positions_visited_by_tail(commands: Vec<Command>, rope_lengt
     visited.len()
 }
 
-trait MoveInDirection {
-    fn move_in(&mut self, direction: &Direction);
-}
-impl MoveInDirection for Pos {
-    fn move_in(&mut self, direction: &Direction) {
-        match direction {
-            Direction::Up => self.y += 1,
-            Direction::Down => self.y -= 1,
-            Direction::Left => self.x -= 1,
-            Direction::Right => self.x += 1,
-        }
-    }
-}
-
-trait IsNeighbor {
-    fn is_neighbor_of(&self, other: &Self) -> bool;
-}
-impl IsNeighbor for Pos {
-    fn is_neighbor_of(&self, other: &Self) -> bool {
-        self.neighbors().contains(other)
-    }
-}
-
-#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
-struct Pos {
-    x: isize,
-    y: isize,
-}
-impl Pos {
-    fn neighbors(&self) -> Vec<Pos> {
-        vec![
-            (-1, 1),
-            (-1, 0),
-            (-1, -1),
-            (0, 1),
-            (0, 0),
-            (0, -1),
-            (1, 1),
-            (1, 0),
-            (1, -1),
-        ]
-        .into_iter()
-        .map(|(x, y)| Pos {
-            x: self.x + x,
-            y: self.y + y,
-        })
-        .collect()
-    }
-}
-
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-impl From<&str> for Direction {
-    fn from(c: &str) -> Self {
-        match c {
-            "U" => Direction::Up,
-            "D" => Direction::Down,
-            "L" => Direction::Left,
-            "R" => Direction::Right,
-            _ => unreachable!("Unknown direction {}", c),
-        }
-    }
-}
+type Pos = Point2<isize>;
 
 struct Command {
     steps: u8,
@@ -115,7 +48,8 @@ struct Command {
 impl From<&str> for Command {
     fn from(s: &str) -> Self {
         let (left, right) = s.split_once(' ').expect("a space");
-        let direction = Direction::from(left);
+        let direction = Direction::try_from(left.chars().next().unwrap())
+            .unwrap_or_else(|c| unreachable!("Unknown direction {}", c));
         let step_count = right.parse().expect("a valid u8");
         Command {
             steps: step_count,
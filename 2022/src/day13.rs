@@ -1,5 +1,5 @@
+use nested::Value;
 use std::cmp::Ordering;
-use PacketData::*;
 
 const INPUT: &str = include_str!("../input/day13.txt");
 
@@ -13,11 +13,13 @@ pub(crate) fn day13_part2() -> usize {
     calculate_decoder_key(packets)
 }
 
+type Packet = Value;
+
 fn parse_packets(input: &str) -> Vec<Packet> {
     input
         .lines()
         .filter(|line| !line.is_empty())
-        .map(Packet::from)
+        .map(|line| Packet::parse(line).unwrap())
         .collect()
 }
 
@@ -25,14 +27,14 @@ fn sum_of_indices_of_pairs_in_the_right_order(packets: Vec<Packet>) -> usize {
     packets
         .chunks(2)
         .enumerate()
-        .filter(|(_, c)| c[0].cmp(&c[1]).is_in_right_order())
+        .filter(|(_, c)| c[0].cmp_as_packets(&c[1]).is_in_right_order())
         .map(|(i, _)| i + 1)
         .sum()
 }
 
 fn calculate_decoder_key(mut packets: Vec<Packet>) -> usize {
-    let divider1 = Packet::from("[[2]]");
-    let divider2 = Packet::from("[[6]]");
+    let divider1 = Packet::parse("[[2]]").unwrap();
+    let divider2 = Packet::parse("[[6]]").unwrap();
     packets.push(divider1.clone());
     packets.push(divider2.clone());
     packets.sort_unstable();
@@ -55,75 +57,18 @@ impl IsInRightOrder for Ordering {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum PacketData {
-    Integer(u8),
-    List(Vec<PacketData>),
-}
-impl Ord for PacketData {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // println!("left {:?} right {:?}", left, right);
-        match (self, other) {
-            (Integer(l), Integer(r)) => l.cmp(r),
-            (List(l), List(r)) => l.cmp(r),
-            (l @ List(_), Integer(r)) => l.cmp(&List(vec![Integer(*r)])),
-            (Integer(l), r @ List(_)) => List(vec![Integer(*l)]).cmp(r),
-        }
-    }
-}
-impl PartialOrd<Self> for PacketData {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone)]
-struct Packet {
-    contents: Vec<PacketData>,
-}
-impl From<&str> for Packet {
-    fn from(s: &str) -> Self {
-        let mut contents_stack = vec![vec![]];
-        let mut i = 1;
-        let mut start = i;
-        while i < s.len() {
-            let c = &s[i..=i].chars().next().unwrap();
-            match c {
-                '[' => {
-                    contents_stack.push(vec![]);
-                }
-                ']' => {
-                    if let Ok(num) = &s[start..i].parse() {
-                        contents_stack.last_mut().unwrap().push(Integer(*num));
-                    }
-                    let list = contents_stack.pop().unwrap();
-                    if contents_stack.is_empty() {
-                        // This was the outer-most/last list, and thus the packet is done
-                        return Packet { contents: list };
-                    }
-                    let list = List(list);
-                    contents_stack.last_mut().unwrap().push(list);
-                }
-                ',' => {
-                    if let Ok(num) = &s[start..i].parse() {
-                        contents_stack.last_mut().unwrap().push(Integer(*num));
-                    }
-                    start = i;
-                }
-                _ => { /* digit */ }
-            }
-            if !c.is_ascii_digit() {
-                start += 1;
-            }
-            i += 1;
-        }
-        unreachable!();
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nested::Value::*;
+
+    fn packet(line: &str) -> Packet {
+        Packet::parse(line).unwrap()
+    }
+
+    fn contents(line: &str) -> Vec<Value> {
+        packet(line).as_list().unwrap().to_vec()
+    }
 
     const EXAMPLE: &str = "\
 [1,1,3,1,1]
@@ -160,8 +105,7 @@ mod tests {
     #[test]
     fn packet_from_pair_1() {
         let (left, right) = parse(EXAMPLE)[0];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
+        let (left, right) = (contents(left), contents(right));
         assert_eq!(
             vec![Integer(1), Integer(1), Integer(3), Integer(1), Integer(1)],
             left
@@ -174,8 +118,7 @@ mod tests {
     #[test]
     fn packet_from_pair_2() {
         let (left, right) = parse(EXAMPLE)[1];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
+        let (left, right) = (contents(left), contents(right));
         assert_eq!(
             vec![
                 List(vec![Integer(1)]),
@@ -188,16 +131,14 @@ mod tests {
     #[test]
     fn packet_from_pair_3() {
         let (left, right) = parse(EXAMPLE)[2];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
+        let (left, right) = (contents(left), contents(right));
         assert_eq!(vec![Integer(9)], left);
         assert_eq!(vec![List(vec![Integer(8), Integer(7), Integer(6)])], right);
     }
     #[test]
     fn packet_from_pair_4() {
         let (left, right) = parse(EXAMPLE)[3];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
+        let (left, right) = (contents(left), contents(right));
         assert_eq!(
             vec![List(vec![Integer(4), Integer(4)]), Integer(4), Integer(4)],
             left
@@ -216,58 +157,50 @@ mod tests {
     #[test]
     fn is_in_right_order_work_for_packet_pair_1() {
         let (left, right) = parse(EXAMPLE)[0];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(left.cmp_as_packets(&right).is_in_right_order());
     }
     #[test]
     fn is_in_right_order_work_for_packet_pair_2() {
         let (left, right) = parse(EXAMPLE)[1];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(left.cmp_as_packets(&right).is_in_right_order());
     }
     #[test]
     fn is_in_right_order_work_for_packet_pair_3() {
         let (left, right) = parse(EXAMPLE)[2];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(!left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(!left.cmp_as_packets(&right).is_in_right_order());
     }
     #[test]
     fn is_in_right_order_work_for_packet_pair_4() {
         let (left, right) = parse(EXAMPLE)[3];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(left.cmp_as_packets(&right).is_in_right_order());
     }
     #[test]
     fn is_in_right_order_work_for_packet_pair_5() {
         let (left, right) = parse(EXAMPLE)[4];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(!left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(!left.cmp_as_packets(&right).is_in_right_order());
     }
     #[test]
     fn is_in_right_order_work_for_packet_pair_6() {
         let (left, right) = parse(EXAMPLE)[5];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(left.cmp_as_packets(&right).is_in_right_order());
     }
     #[test]
     fn is_in_right_order_work_for_packet_pair_7() {
         let (left, right) = parse(EXAMPLE)[6];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(!left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(!left.cmp_as_packets(&right).is_in_right_order());
     }
     #[test]
     fn is_in_right_order_work_for_packet_pair_8() {
         let (left, right) = parse(EXAMPLE)[7];
-        let left = Packet::from(left).contents;
-        let right = Packet::from(right).contents;
-        assert!(!left.cmp(&right).is_in_right_order());
+        let (left, right) = (packet(left), packet(right));
+        assert!(!left.cmp_as_packets(&right).is_in_right_order());
     }
 
     #[test]
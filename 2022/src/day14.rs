@@ -1,180 +1,108 @@
+use image_export::{Image, Rgb};
+use particles::{Cell, Grid, Pos, Sand, Simulation};
 use std::cmp::{max, min};
-use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::ops::RangeInclusive;
 
 const INPUT: &str = include_str!("../input/day14.txt");
 
-pub(crate) fn day14_part1() -> usize {
+pub fn day14_part1() -> usize {
     FallingSandCave::from(INPUT)
         .let_sand_fall_until_stable()
         .sand_grain_count()
 }
 
-pub(crate) fn day14_part2() -> usize {
+pub fn day14_part2() -> usize {
     FallingSandCave::from(INPUT)
         .add_floor()
         .let_sand_fall_until_stable()
         .sand_grain_count()
 }
 
-type Coord = usize;
+const SOURCE: Pos = Pos::new(500, 0);
 
-#[derive(Debug, Eq, PartialEq, Hash)]
-struct Pos {
-    x: Coord,
-    y: Coord,
-}
-impl From<&str> for Pos {
-    fn from(s: &str) -> Self {
-        let (x, y) = s.split_once(',').unwrap();
-        Pos {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        }
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Hash)]
-enum Tile {
-    Rock,   // #
-    Sand,   // o
-    Source, // +
-    Air,    // .
-}
-impl Tile {
-    fn to_char(&self) -> char {
-        match self {
-            Tile::Rock => '#',
-            Tile::Sand => 'o',
-            Tile::Source => '+',
-            Tile::Air => '.',
-        }
-    }
+fn parse_pos(s: &str) -> Pos {
+    let (x, y) = s.split_once(',').unwrap();
+    Pos::new(x.parse().unwrap(), y.parse().unwrap())
 }
 
 #[derive(Debug)]
-struct FallingSandCave {
-    obstacles: HashMap<Pos, Tile>,
-    x_range: RangeInclusive<Coord>,
-    y_range: RangeInclusive<Coord>,
+pub struct FallingSandCave {
+    // The rock (#), sand (o), source (+) and air (.)
+    simulation: Simulation<Sand>,
 }
 impl FallingSandCave {
-    fn can_flow_to(&self, x: Coord, y: Coord) -> bool {
-        [Tile::Air, Tile::Source].contains(self.tile_at(x, y))
-    }
-    fn tile_at(&self, x: Coord, y: Coord) -> &Tile {
-        self.obstacles.get(&Pos { x, y }).unwrap_or(&Tile::Air)
+    pub fn sand_grain_count(&self) -> usize {
+        self.simulation.grid().count(Cell::Settled)
     }
-    fn sand_grain_count(&self) -> usize {
-        self.obstacles
-            .values()
-            .filter(|&tile| tile == &Tile::Sand)
-            .count()
+    pub fn let_sand_fall_until_stable(mut self) -> Self {
+        self.simulation.run();
+        self
     }
-    fn let_sand_fall_until_stable(mut self) -> Self {
-        'outer: loop {
-            let mut x = 500;
-            for y in self.y_range.clone() {
-                match (
-                    self.can_flow_to(x - 1, y + 1),
-                    self.can_flow_to(x, y + 1),
-                    self.can_flow_to(x + 1, y + 1),
-                ) {
-                    (_, true, _) => {}
-                    (true, false, _) => {
-                        x -= 1;
-                    }
-                    (false, false, true) => {
-                        x += 1;
-                    }
-                    (false, false, false) => {
-                        self.obstacles.insert(Pos { x, y }, Tile::Sand);
-                        // println!("\n{}", self);
-                        if x == 500 && y == 0 {
-                            break 'outer;
-                        }
-                        continue 'outer;
-                    }
-                }
-            }
-            break 'outer;
+    pub fn add_floor(self) -> Self {
+        let grid = self.simulation.into_grid().with_floor(2);
+        FallingSandCave {
+            simulation: Simulation::new(grid, Sand::default()),
         }
-        self
     }
-
-    fn add_floor(mut self) -> Self {
-        let y = self.y_range.end() + 2;
-        self.y_range = *self.y_range.start()..=y;
-        self.x_range = (*self.x_range.start() - y)..=(*self.x_range.end() + y);
-        for x in self.x_range.clone() {
-            self.obstacles.insert(Pos { x, y }, Tile::Rock);
+    /// Lets at most the given number of grains of sand fall, and returns how many came to rest
+    pub fn let_sand_fall(&mut self, grain_count: usize) -> usize {
+        self.simulation.run_for(grain_count)
+    }
+    /// The cave after each grain of sand came to rest
+    pub fn frames(&mut self) -> impl Iterator<Item = String> + '_ {
+        self.simulation.frames()
+    }
+    /// The cave with each cell drawn as a `scale` × `scale` square
+    pub fn image(&self, scale: usize) -> Image {
+        let grid = self.simulation.grid();
+        let mut image = Image::new(grid.width() * scale, grid.height() * scale, Rgb::BLACK);
+        for (pos, cell) in grid.cells() {
+            let color = match cell {
+                Cell::Empty => continue,
+                Cell::Solid => Rgb(128, 128, 128),
+                Cell::Source => Rgb(255, 0, 0),
+                Cell::Flowing => Rgb(100, 149, 237),
+                Cell::Settled => Rgb(238, 214, 175),
+            };
+            let left = (pos.x - grid.x_min()) * scale;
+            image.fill_square(left, pos.y * scale, scale, color);
         }
-        // println!("\n{}", self);
-        self
+        image
     }
 }
 impl From<&str> for FallingSandCave {
     fn from(input: &str) -> Self {
-        let mut obstacles = HashMap::new();
-        let (mut min_x, mut max_x, mut max_y) = (usize::MAX, 0, 0);
+        let mut rocks = vec![];
         for points in input
             .lines()
-            .map(|line| line.split(" -> ").map(Pos::from).collect::<Vec<_>>())
+            .map(|line| line.split(" -> ").map(parse_pos).collect::<Vec<_>>())
         {
-            min_x = min(min_x, points[0].x);
-            max_x = max(max_x, points[0].x);
-            max_y = max(max_y, points[0].y);
             for pos in points.windows(2) {
                 let a = &pos[0];
                 let b = &pos[1];
-                min_x = min(min_x, b.x);
-                max_x = max(max_x, b.x);
-                max_y = max(max_y, b.y);
                 if a.x == b.x {
                     let x = a.x;
                     for y in min(a.y, b.y)..=max(a.y, b.y) {
-                        obstacles.insert(Pos { x, y }, Tile::Rock);
+                        rocks.push(Pos { x, y });
                     }
                 } else if a.y == b.y {
                     let y = a.y;
                     for x in min(a.x, b.x)..=max(a.x, b.x) {
-                        obstacles.insert(Pos { x, y }, Tile::Rock);
+                        rocks.push(Pos { x, y });
                     }
                 } else {
                     panic!("Input points not horizontally or vertically aligned");
                 }
             }
         }
-        obstacles.insert(Pos { x: 500, y: 0 }, Tile::Source);
         FallingSandCave {
-            obstacles,
-            x_range: (min_x - 1)..=(max_x + 1),
-            y_range: 0..=max_y,
+            simulation: Simulation::new(Grid::new(SOURCE, rocks), Sand::default()),
         }
     }
 }
 impl Display for FallingSandCave {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            self.y_range
-                .clone()
-                .into_iter()
-                .map(|y| self
-                    .x_range
-                    .clone()
-                    .into_iter()
-                    .map(|x| self
-                        .obstacles
-                        .get(&Pos { x, y })
-                        .unwrap_or(&Tile::Air)
-                        .to_char())
-                    .collect::<String>())
-                .collect::<Vec<_>>()
-                .join("\n")
-        )
+        write!(f, "{}", self.simulation)
     }
 }
 
@@ -200,6 +128,42 @@ mod tests {
         assert_eq!(913, day14_part1());
     }
 
+    #[test]
+    fn part1_example_frames() {
+        let frames: Vec<_> = FallingSandCave::from(EXAMPLE).frames().collect();
+        assert_eq!(24, frames.len());
+        assert_eq!(
+            "\
+.......+....
+............
+............
+............
+.....#...##.
+.....#...#..
+...###...#..
+.........#..
+.......o.#..
+.#########..",
+            frames[0]
+        );
+        let cave = FallingSandCave::from(EXAMPLE).let_sand_fall_until_stable();
+        assert_eq!(cave.to_string(), frames[23]);
+    }
+
+    #[test]
+    fn part1_example_image() {
+        let mut cave = FallingSandCave::from(EXAMPLE);
+        assert_eq!(1, cave.let_sand_fall(1));
+        let image = cave.image(2);
+        assert_eq!((24, 20), (image.width(), image.height()));
+        assert_eq!(Some(Rgb(255, 0, 0)), image.pixel_at(15, 1));
+        assert_eq!(Some(Rgb(238, 214, 175)), image.pixel_at(15, 17));
+        assert_eq!(Some(Rgb(128, 128, 128)), image.pixel_at(2, 18));
+        assert_eq!(Some(Rgb::BLACK), image.pixel_at(0, 0));
+
+        assert_eq!(23, cave.let_sand_fall(usize::MAX));
+    }
+
     #[test]
     fn part2_example() {
         let cave = FallingSandCave::from(EXAMPLE).add_floor();
@@ -1,3 +1,5 @@
+use geometry::IntervalSet;
+use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 use std::ops::RangeInclusive;
 
@@ -67,55 +69,34 @@ struct ScanResult {
 }
 impl ScanResult {
     fn no_beacon_pos_count_at_y(&mut self, y: isize) -> usize {
-        let x_ranges = self.no_beacon_x_ranges_at_y(y);
-        let x_min = *x_ranges.iter().map(|r| r.start()).min().unwrap();
-        let x_max = *x_ranges.iter().map(|r| r.end()).max().unwrap();
-        let not_in_any_range_count = (x_min..=x_max)
-            .into_iter()
-            .filter(|x| !x_ranges.iter().any(|range| range.contains(x)))
-            .count();
-        (x_max - x_min) as usize - not_in_any_range_count
+        let covered = self.no_beacon_x_ranges_at_y(y);
+        let beacons_at_y: HashSet<_> = self
+            .signals
+            .iter()
+            .filter(|sig| sig.beacon.y == y)
+            .map(|sig| sig.beacon.x)
+            .collect();
+        covered.len() as usize - beacons_at_y.len()
     }
-    fn no_beacon_x_ranges_at_y(&mut self, y: isize) -> Vec<RangeInclusive<Coord>> {
+    fn no_beacon_x_ranges_at_y(&mut self, y: isize) -> IntervalSet<Coord> {
         self.signals
             .iter()
             .filter_map(|sig| sig.no_beacon_bounds_at_y(y))
             .collect()
     }
     fn tuning_frequency_of_distress_beacon(&mut self, limit: Coord) -> isize {
-        let (x, y) = self.find_point_by_considering_range_boundaries_on_each_line(limit);
+        let (x, y) = self.find_point_not_covered_by_any_range(limit);
         x * 4_000_000 + y
     }
-    /// Looking at all points on a line takes way too long. The result must be just 1 beyond
-    /// the start/end of a range. So let's only consider boundaries while scanning a line.
-    fn find_point_by_considering_range_boundaries_on_each_line(
-        &mut self,
-        limit: Coord,
-    ) -> (Coord, Coord) {
-        'y_loop: for y in 0..=limit {
-            let mut x_ranges = self.no_beacon_x_ranges_at_y(y);
-            x_ranges.sort_unstable_by_key(|r| *r.start());
-            let mut x = 0;
-            let mut right_ranges = x_ranges.as_slice();
-            loop {
-                if let Some(idx) = right_ranges.iter().position(|r| r.start() > &x) {
-                    let left_ranges = &right_ranges[..idx];
-                    right_ranges = &right_ranges[idx..];
-                    if let Some(&max) = left_ranges.iter().map(|r| r.end()).max() {
-                        if max >= limit {
-                            continue 'y_loop;
-                        }
-                        x = max + 1;
-                        if !right_ranges.iter().any(|r| r.contains(&x)) {
-                            return (x, y);
-                        }
-                    }
-                } else {
-                    continue 'y_loop;
-                }
-            }
-        }
-        unreachable!()
+    /// Looking at all points on a line takes way too long. Only the gaps between the merged
+    /// ranges of each line need to be considered.
+    fn find_point_not_covered_by_any_range(&mut self, limit: Coord) -> (Coord, Coord) {
+        (0..=limit)
+            .find_map(|y| {
+                let uncovered = self.no_beacon_x_ranges_at_y(y).complement(0..=limit);
+                uncovered.min().map(|x| (x, y))
+            })
+            .unwrap()
     }
 }
 impl From<&str> for ScanResult {
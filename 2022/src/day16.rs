@@ -1,4 +1,6 @@
-use std::collections::{HashMap, HashSet};
+use dot::{Graph, ToDot};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
 
 const INPUT: &str = include_str!("../input/day16.txt");
 
@@ -16,21 +18,10 @@ type ValveName = &'static str;
 type FlowRate = usize;
 type PressureReleased = usize;
 type Time = u8;
+/// Bit i is set if valve i + 1 of a network is part of the subset
+type Subset = usize;
 
-type OpenValves = HashSet<ValveName>;
-
-#[derive(Eq, PartialEq, Hash)]
-struct Part1State {
-    name: ValveName,
-    total: PressureReleased,
-}
-
-#[derive(Debug, Eq, PartialEq, Hash)]
-struct Part2State {
-    name1: ValveName,
-    name2: ValveName,
-    total: PressureReleased,
-}
+const START: ValveName = "AA";
 
 struct Valve {
     flow_rate: FlowRate,
@@ -42,205 +33,266 @@ struct Volcano {
 }
 impl Volcano {
     fn solve_part_1(self) -> usize {
-        let mut states: HashMap<Part1State, OpenValves> = HashMap::new();
-        states.insert(
-            Part1State {
-                name: "AA",
-                total: 0,
-            },
-            HashSet::new(),
-        );
+        ValveNetwork::from(&self).plan(30, 1).pressure
+    }
+    fn solve_part_2(self) -> usize {
+        ValveNetwork::from(&self).plan(26, 2).pressure
+    }
+    fn flow_rate_for(&self, name: ValveName) -> FlowRate {
+        self.valves_by_name.get(name).unwrap().flow_rate
+    }
+    fn valves_reachable_from(&self, name: ValveName) -> Vec<ValveName> {
+        self.valves_by_name
+            .get(name)
+            .unwrap()
+            .reachable_valves
+            .clone()
+    }
+}
 
-        // Simulate every path
-        const TIME_LIMIT: Time = 30;
-        for time in 0..TIME_LIMIT {
-            let mut next_states = HashMap::new();
-            for (Part1State { name, mut total }, open) in states.drain() {
-                let time = time + 1; // It takes 1 to move or open a valve
+/// The tunnels compressed to the start and the valves worth opening,
+/// with the time it takes to walk from any of them to any other
+struct ValveNetwork {
+    /// The start valve comes first, followed by the valves with a positive flow rate
+    names: Vec<ValveName>,
+    flow_rates: Vec<FlowRate>,
+    travel_times: Vec<Vec<Time>>,
+}
 
-                // Move to a connected valve
-                for name in self.valves_reachable_from(name) {
-                    next_states.insert(Part1State { name, total }, open.clone());
+impl From<&Volcano> for ValveNetwork {
+    fn from(volcano: &Volcano) -> Self {
+        // All pairs shortest paths between all valves, with Floyd-Warshall
+        let mut all: Vec<_> = volcano.valves_by_name.keys().copied().collect();
+        all.sort_unstable();
+        let index: HashMap<_, _> = all.iter().enumerate().map(|(i, name)| (*name, i)).collect();
+        let mut times = vec![vec![Time::MAX; all.len()]; all.len()];
+        for (i, name) in all.iter().enumerate() {
+            times[i][i] = 0;
+            for next in volcano.valves_reachable_from(name) {
+                times[i][index[next]] = 1;
+            }
+        }
+        for k in 0..all.len() {
+            for i in 0..all.len() {
+                for j in 0..all.len() {
+                    let via_k = times[i][k].saturating_add(times[k][j]);
+                    if via_k < times[i][j] {
+                        times[i][j] = via_k;
+                    }
                 }
+            }
+        }
+
+        // The start comes first, and isn't listed again among the valves worth opening
+        assert_eq!(
+            0,
+            volcano.flow_rate_for(START),
+            "The start valve {START} should not be worth opening"
+        );
+        let names: Vec<_> = Some(START)
+            .into_iter()
+            .chain(
+                all.into_iter()
+                    .filter(|name| volcano.flow_rate_for(name) > 0),
+            )
+            .collect();
+        assert!(
+            names.len() <= 21,
+            "Too many valves for a table of all subsets"
+        );
+        let flow_rates = (names.iter())
+            .map(|name| volcano.flow_rate_for(name))
+            .collect();
+        let travel_times = (names.iter())
+            .map(|from| {
+                (names.iter())
+                    .map(|to| times[index[from]][index[to]])
+                    .collect()
+            })
+            .collect();
+        ValveNetwork {
+            names,
+            flow_rates,
+            travel_times,
+        }
+    }
+}
+
+/// A valve opened by an agent, at the end of the given minute
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Opening {
+    valve: ValveName,
+    minute: Time,
+}
+
+type Route = Vec<Opening>;
 
-                // Stay here and open valve
-                let flow_rate = self.flow_rate_for(name);
-                if flow_rate > 0 && !open.contains(name) {
-                    let rem_time = TIME_LIMIT - time;
-                    total += flow_rate * rem_time as usize;
-                    let mut open = open.clone();
-                    open.insert(name);
-                    next_states.insert(Part1State { name, total }, open.clone());
+/// The valves each agent opens, and the pressure they release together
+#[derive(Debug, PartialEq)]
+struct Plan {
+    pressure: PressureReleased,
+    routes: Vec<Route>,
+}
+
+/// The most pressure a single agent can release by opening some of a subset of the valves,
+/// along with the subset it actually opens
+#[derive(Debug, Default, Clone, Copy)]
+struct SubsetBest {
+    pressure: PressureReleased,
+    opened: Subset,
+}
+
+impl ValveNetwork {
+    fn valve_count(&self) -> usize {
+        self.names.len() - 1
+    }
+    /// The best plan for the given number of agents, which all start at the same time.
+    /// Each valve is opened by a single agent, so the agents divide the valves among them.
+    fn plan(&self, time_limit: Time, agent_count: usize) -> Plan {
+        assert!(agent_count > 0, "Someone has to open the valves");
+        let (bests, routes) = self.best_by_subset(time_limit);
+        let subsets = 1 << self.valve_count();
+
+        // The best pressure for each subset with one more agent, and the subset of the last agent
+        let mut pressures: Vec<_> = bests.iter().map(|best| best.pressure).collect();
+        let mut last_agent_subsets = vec![];
+        for _ in 1..agent_count {
+            let mut next = vec![0; subsets];
+            let mut last_agent_subset = vec![0; subsets];
+            for subset in 0..subsets {
+                // All sub-subsets of the subset, including the empty one
+                let mut own = subset;
+                loop {
+                    let pressure = bests[own].pressure + pressures[subset ^ own];
+                    if pressure > next[subset] {
+                        next[subset] = pressure;
+                        last_agent_subset[subset] = own;
+                    }
+                    if own == 0 {
+                        break;
+                    }
+                    own = (own - 1) & subset;
                 }
             }
-            states = next_states;
+            pressures = next;
+            last_agent_subsets.push(last_agent_subset);
+        }
+
+        let mut subset = subsets - 1;
+        let mut agent_routes = vec![];
+        for last_agent_subset in last_agent_subsets.iter().rev() {
+            let own = last_agent_subset[subset];
+            agent_routes.push(routes[&bests[own].opened].clone());
+            subset ^= own;
+        }
+        agent_routes.push(routes[&bests[subset].opened].clone());
+        agent_routes.reverse();
+        Plan {
+            pressure: pressures[subsets - 1],
+            routes: agent_routes,
         }
-        states
-            .keys()
-            .map(|&Part1State { total, .. }| total)
-            .max()
-            .unwrap()
     }
-    fn solve_part_2(self) -> usize {
-        let mut states: HashMap<Part2State, OpenValves> = HashMap::new();
-        states.insert(
-            Part2State {
-                name1: "AA",
-                name2: "AA",
-                total: 0,
-            },
-            HashSet::new(),
-        );
+    /// The best a single agent can do for each subset of valves, along with the best route
+    /// to open exactly the valves of each subset that can be opened in time
+    fn best_by_subset(&self, time_limit: Time) -> (Vec<SubsetBest>, HashMap<Subset, Route>) {
+        let mut search = RouteSearch {
+            network: self,
+            time_limit,
+            bests: vec![SubsetBest::default(); 1 << self.valve_count()],
+            routes: HashMap::from([(0, vec![])]),
+            route: vec![],
+        };
+        search.visit(0, time_limit, 0, 0);
+        let RouteSearch {
+            mut bests, routes, ..
+        } = search;
 
-        let non_zero_valve_count = self
-            .valves_by_name
-            .values()
-            .filter(|Valve { flow_rate, .. }| flow_rate > &0)
-            .count();
-        // println!(
-        //     "{non_zero_valve_count}/{} valves have non-0 flow-rate.",
-        //     self.valves_by_name.len()
-        // );
-
-        // Simulate every path
-        const TIME_LIMIT: Time = 26;
-        for time in 0..TIME_LIMIT {
-            let mut next_states: HashMap<Part2State, OpenValves> = HashMap::new();
-            for (
-                _state @ Part2State {
-                    name1,
-                    name2,
-                    total,
-                },
-                open,
-            ) in states.drain()
-            {
-                // if open.len() == non_zero_valve_count {
-                //     next_states.insert(state, open);
-                //     println!("{time} skipped {name1} and {name2} because all valves open");
-                //     continue;
-                // }
-
-                let time = time + 1; // It takes 1 to move or open a valve
-                let rem_time = TIME_LIMIT - time;
-
-                // println!("{time}: {name1} {name2} {total}");
-
-                let mut options1: Vec<_> = self
-                    // a) Move to a connected valve
-                    .valves_reachable_from(name1)
-                    .into_iter()
-                    .map(|name1| (name1, None /* Opened */))
-                    .chain({
-                        // Or b) Stay here and open valve
-                        let flow_rate = self.flow_rate_for(name1);
-                        if flow_rate > 0 && !open.contains(name1) {
-                            Some((name1, Some(flow_rate)))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                // println!("- Options 1 ({name1}): {:?};\t{:?}", options1, open);
-
-                let options2: Vec<_> = self
-                    // a) Move to a connected valve
-                    .valves_reachable_from(name2)
-                    .into_iter()
-                    .map(|name2| (name2, None /* Opened */))
-                    .chain({
-                        // Or b) Stay here and open valve
-                        let flow_rate = self.flow_rate_for(name2);
-                        if flow_rate > 0 && !open.contains(name2) {
-                            Some((name2, Some(flow_rate)))
-                        } else {
-                            None
-                        }
-                    })
-                    .collect();
-                // println!("- Options 2 ({name2}): {:?};\t{:?}", options2, open);
-
-                while let Some((name1, rate1)) = options1.pop() {
-                    for (name2, rate2) in &options2 {
-                        let mut diff = 0;
-                        let mut currently_open = open.clone();
-                        match (rate1, rate2) {
-                            (Some(rate1), Some(rate2)) => {
-                                if &name1 == name2 {
-                                    // Both opening the same node is invalid.
-                                    // One of them opening it should be handled on another path
-                                    continue;
-                                }
-                                assert_ne!(&name1, name2);
-                                assert_eq!(rate1, self.flow_rate_for(name1));
-                                assert_eq!(rate2, &self.flow_rate_for(name2));
-                                diff = (rate1 + *rate2) * rem_time as usize;
-                                assert!(currently_open.insert(name1));
-                                assert!(currently_open.insert(*name2));
-                            }
-                            (Some(rate1), None) => {
-                                diff = rate1 * rem_time as usize;
-                                assert_eq!(rate1, self.flow_rate_for(name1));
-                                assert!(currently_open.insert(name1));
-                            }
-                            (None, Some(rate2)) => {
-                                assert_eq!(rate2, &self.flow_rate_for(name2));
-                                diff = rate2 * rem_time as usize;
-                                assert!(currently_open.insert(*name2));
-                            }
-                            (None, None) => {}
-                        }
-
-                        // Sort names to reduce problem space
-                        let total = total + diff;
-                        // if diff > 0 {
-                        //     println!(
-                        //         "{time}: added {diff} from {name1} {:?} or {name2} {:?}: {:?}",
-                        //         rate1, rate2, currently_open
-                        //     );
-                        // }
-                        if total > 2106 {
-                            /* example MAX */
-                            panic!(
-                                "{time}: added {diff} for {total} from {name1} {:?} or {name2} {:?}: {:?}",
-                                rate1, rate2, currently_open
-                            );
-                        }
-                        next_states.insert(
-                            Part2State {
-                                name1: if name1 < name2 { name1 } else { name2 },
-                                name2: if name1 >= name2 { name1 } else { name2 },
-                                total,
-                            },
-                            currently_open,
-                        );
-                    }
+        // Opening only some of a subset's valves is an option for that subset as well
+        for valve in 0..self.valve_count() {
+            for subset in 0..bests.len() {
+                let without = subset & !(1 << valve);
+                if bests[without].pressure > bests[subset].pressure {
+                    bests[subset] = bests[without];
                 }
             }
-            states = next_states;
-            if states
-                .iter()
-                .all(|(_, open)| open.len() == non_zero_valve_count)
-            {
-                println!("All valves open for all configurations at time {time}");
-                break;
+        }
+        (bests, routes)
+    }
+}
+
+/// Walks every route a single agent could take, and keeps the best for each subset of valves
+struct RouteSearch<'a> {
+    network: &'a ValveNetwork,
+    time_limit: Time,
+    bests: Vec<SubsetBest>,
+    routes: HashMap<Subset, Route>,
+    route: Route,
+}
+
+impl RouteSearch<'_> {
+    fn visit(&mut self, at: usize, time_left: Time, opened: Subset, pressure: PressureReleased) {
+        if pressure > self.bests[opened].pressure {
+            self.bests[opened] = SubsetBest { pressure, opened };
+            self.routes.insert(opened, self.route.clone());
+        }
+        let network = self.network;
+        for next in 1..network.names.len() {
+            let bit = 1 << (next - 1);
+            if opened & bit != 0 {
+                continue;
             }
+            // Walking there and opening it has to leave at least a minute to release pressure.
+            // Unreachable valves are Time::MAX away, which never leaves any time.
+            let time_to_open = network.travel_times[at][next].saturating_add(1);
+            let time_left = match time_left.checked_sub(time_to_open) {
+                Some(time_left) if time_left > 0 => time_left,
+                _ => continue,
+            };
+            self.route.push(Opening {
+                valve: network.names[next],
+                minute: self.time_limit - time_left,
+            });
+            let pressure = pressure + network.flow_rates[next] * time_left as usize;
+            self.visit(next, time_left, opened | bit, pressure);
+            self.route.pop();
         }
-        states
-            .keys()
-            .map(|&Part2State { total, .. }| total)
-            .max()
-            .unwrap()
     }
-    fn flow_rate_for(&self, name: ValveName) -> FlowRate {
-        self.valves_by_name.get(name).unwrap().flow_rate
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (agent, route) in self.routes.iter().enumerate() {
+            let openings: Vec<_> = (route.iter())
+                .map(|Opening { valve, minute }| format!("{valve} at minute {minute}"))
+                .collect();
+            writeln!(f, "Agent {}: {}", agent + 1, openings.join(", "))?;
+        }
+        write!(f, "Pressure released: {}", self.pressure)
     }
-    fn valves_reachable_from(&self, name: ValveName) -> Vec<ValveName> {
-        self.valves_by_name
-            .get(name)
-            .unwrap()
-            .reachable_valves
-            .clone()
+}
+
+/// Renders the tunnels as undirected edges. Valves with a positive flow rate are boxes,
+/// and the start valve AA is circled twice.
+impl ToDot for Volcano {
+    fn to_dot(&self) -> String {
+        let mut graph = Graph::undirected("volcano");
+        let mut names: Vec<_> = self.valves_by_name.keys().copied().collect();
+        names.sort_unstable();
+        for name in names {
+            let valve = &self.valves_by_name[name];
+            let (label, shape) = match (name, valve.flow_rate) {
+                ("AA", _) => (name.to_string(), "doublecircle"),
+                (_, 0) => (name.to_string(), "ellipse"),
+                (_, rate) => (format!("{name}\nrate {rate}"), "box"),
+            };
+            graph.node(name, &[("label", &label), ("shape", &shape)]);
+            for &neighbor in &valve.reachable_valves {
+                if name < neighbor {
+                    graph.edge(name, neighbor, &[]);
+                }
+            }
+        }
+        graph.to_dot()
     }
 }
 
@@ -278,6 +330,8 @@ impl From<&'static str> for Volcano {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use state_space::{Puzzle, Solver};
+    use std::cmp::Reverse;
 
     const EXAMPLE: &str = "\
 Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
@@ -291,11 +345,277 @@ Valve HH has flow rate=22; tunnel leads to valve GG
 Valve II has flow rate=0; tunnels lead to valves AA, JJ
 Valve JJ has flow rate=21; tunnel leads to valve II";
 
+    /// Opening valves with one or more agents walking around in parallel, searched move by move
+    /// rather than subset by subset, to cross-check the plans
+    struct Eruption<'a> {
+        network: &'a ValveNetwork,
+        time_limit: Time,
+        agent_count: usize,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    struct Agent {
+        time_left: Time,
+        at: usize,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Progress {
+        agents: Vec<Agent>,
+        open: Subset,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Action {
+        Open {
+            agent: usize,
+            valve: usize,
+        },
+        /// Stop opening valves, and leave the rest to the other agents
+        Rest {
+            agent: usize,
+        },
+    }
+
+    impl Eruption<'_> {
+        fn time_left_after_opening(&self, agent: &Agent, valve: usize) -> Option<Time> {
+            let travel_time = self.network.travel_times[agent.at][valve];
+            agent
+                .time_left
+                .checked_sub(travel_time.saturating_add(1))
+                .filter(|time_left| time_left > &0)
+        }
+        fn pressure_released_by(&self, valve: usize, time_left: Time) -> PressureReleased {
+            self.network.flow_rates[valve] * time_left as usize
+        }
+        fn closed_valves(&self, progress: &Progress) -> impl Iterator<Item = usize> + '_ {
+            let open = progress.open;
+            (1..self.network.names.len()).filter(move |valve| open & (1 << (valve - 1)) == 0)
+        }
+    }
+
+    impl Puzzle for Eruption<'_> {
+        type State = Progress;
+        type Move = Action;
+
+        fn start(&self) -> Progress {
+            let agent = Agent {
+                time_left: self.time_limit,
+                at: 0,
+            };
+            Progress {
+                agents: vec![agent; self.agent_count],
+                open: 0,
+            }
+        }
+        fn moves(&self, progress: &Progress) -> Vec<Action> {
+            // The agent with the most time left acts next, so the others never act in the past
+            let Some((agent, current)) = (progress.agents.iter().enumerate())
+                .filter(|(_, agent)| agent.time_left > 0)
+                .max_by_key(|(i, agent)| (agent.time_left, Reverse(*i)))
+            else {
+                return vec![];
+            };
+            let mut actions: Vec<_> = self
+                .closed_valves(progress)
+                .filter(|&valve| self.time_left_after_opening(current, valve).is_some())
+                .map(|valve| Action::Open { agent, valve })
+                .collect();
+            let others_still_busy = (progress.agents.iter().enumerate())
+                .any(|(i, other)| i != agent && other.time_left > 0);
+            if actions.is_empty() || others_still_busy {
+                actions.push(Action::Rest { agent });
+            }
+            actions
+        }
+        fn apply(&self, progress: &Progress, action: &Action) -> Progress {
+            let mut next = progress.clone();
+            match *action {
+                Action::Open { agent, valve } => {
+                    let time_left = self.time_left_after_opening(&progress.agents[agent], valve);
+                    next.agents[agent] = Agent {
+                        time_left: time_left.unwrap(),
+                        at: valve,
+                    };
+                    next.open |= 1 << (valve - 1);
+                }
+                Action::Rest { agent } => next.agents[agent].time_left = 0,
+            }
+            next
+        }
+        fn cost(&self, progress: &Progress, action: &Action) -> PressureReleased {
+            match *action {
+                Action::Open { agent, valve } => {
+                    let time_left = self.time_left_after_opening(&progress.agents[agent], valve);
+                    self.pressure_released_by(valve, time_left.unwrap())
+                }
+                Action::Rest { .. } => 0,
+            }
+        }
+        fn is_goal(&self, _progress: &Progress) -> bool {
+            true
+        }
+        fn heuristic(&self, progress: &Progress) -> Option<PressureReleased> {
+            let pressure = self
+                .closed_valves(progress)
+                .map(|valve| {
+                    (progress.agents.iter())
+                        .filter_map(|agent| self.time_left_after_opening(agent, valve))
+                        .map(|time_left| self.pressure_released_by(valve, time_left))
+                        .max()
+                        .unwrap_or(0)
+                })
+                .sum();
+            Some(pressure)
+        }
+        fn canonical(&self, progress: &Progress) -> Progress {
+            let mut progress = progress.clone();
+            progress.agents.sort_unstable();
+            progress
+        }
+    }
+
+    fn route(openings: &[(ValveName, Time)]) -> Route {
+        (openings.iter())
+            .map(|&(valve, minute)| Opening { valve, minute })
+            .collect()
+    }
+
     #[test]
     fn part1_example() {
         assert_eq!(1_651, Volcano::from(EXAMPLE).solve_part_1());
     }
 
+    #[test]
+    fn example_to_dot() {
+        let dot = Volcano::from(EXAMPLE).to_dot();
+        assert!(dot.starts_with("graph \"volcano\" {\n"));
+        assert!(dot.contains("\"AA\" [label=\"AA\", shape=\"doublecircle\"];"));
+        assert!(dot.contains("\"BB\" [label=\"BB\\nrate 13\", shape=\"box\"];"));
+        assert!(dot.contains("\"FF\" [label=\"FF\", shape=\"ellipse\"];"));
+        assert!(dot.contains("\"AA\" -- \"BB\";"));
+        assert!(!dot.contains("\"BB\" -- \"AA\";"));
+        assert_eq!(10, dot.matches(" -- ").count());
+    }
+
+    #[test]
+    fn example_network_only_keeps_valves_worth_opening() {
+        let network = ValveNetwork::from(&Volcano::from(EXAMPLE));
+        assert_eq!(
+            vec!["AA", "BB", "CC", "DD", "EE", "HH", "JJ"],
+            network.names
+        );
+        assert_eq!(vec![0, 13, 2, 20, 3, 22, 21], network.flow_rates);
+        assert_eq!(vec![0, 1, 2, 1, 2, 5, 2], network.travel_times[0]);
+        assert_eq!(7, network.travel_times[6][5]);
+        assert_eq!(7, network.travel_times[5][6]);
+    }
+
+    #[test]
+    fn unreachable_valves_are_never_opened() {
+        let volcano = Volcano::from(
+            "\
+Valve AA has flow rate=0; tunnel leads to valve BB
+Valve BB has flow rate=5; tunnel leads to valve AA
+Valve CC has flow rate=7; tunnel leads to valve CC",
+        );
+        let network = ValveNetwork::from(&volcano);
+        assert_eq!(Time::MAX, network.travel_times[0][2]);
+        assert_eq!(5 * 28, network.plan(30, 1).pressure);
+        assert_eq!(5 * 28, network.plan(30, 2).pressure);
+    }
+
+    #[test]
+    fn part1_example_plan() {
+        let network = ValveNetwork::from(&Volcano::from(EXAMPLE));
+        let plan = network.plan(30, 1);
+        assert_eq!(1_651, plan.pressure);
+        let expected = [
+            ("DD", 2),
+            ("BB", 5),
+            ("JJ", 9),
+            ("HH", 17),
+            ("EE", 21),
+            ("CC", 24),
+        ];
+        assert_eq!(vec![route(&expected)], plan.routes);
+    }
+
+    #[test]
+    fn part2_example_plan() {
+        let network = ValveNetwork::from(&Volcano::from(EXAMPLE));
+        let plan = network.plan(26, 2);
+        assert_eq!(1_707, plan.pressure);
+        let you = route(&[("JJ", 3), ("BB", 7), ("CC", 9)]);
+        let elephant = route(&[("DD", 2), ("HH", 7), ("EE", 11)]);
+        assert_eq!(2, plan.routes.len());
+        assert!(plan.routes.contains(&you));
+        assert!(plan.routes.contains(&elephant));
+    }
+
+    #[test]
+    fn plan_to_string() {
+        let network = ValveNetwork::from(&Volcano::from(EXAMPLE));
+        assert_eq!(
+            "\
+Agent 1: DD at minute 2, BB at minute 5, JJ at minute 9, HH at minute 17, EE at minute 21, CC at minute 24
+Pressure released: 1651",
+            network.plan(30, 1).to_string()
+        );
+        assert_eq!(
+            "Agent 1: \nAgent 2: \nPressure released: 0",
+            network.plan(2, 2).to_string()
+        );
+    }
+
+    #[test]
+    fn plans_match_a_move_by_move_search() {
+        let network = ValveNetwork::from(&Volcano::from(EXAMPLE));
+        for agent_count in 1..=4 {
+            for time_limit in [1, 2, 5, 10, 15, 20, 26, 30] {
+                let plan = network.plan(time_limit, agent_count);
+                let eruption = Eruption {
+                    network: &network,
+                    time_limit,
+                    agent_count,
+                };
+                let expected = Solver::new(&eruption).maximize().unwrap().value;
+                assert_eq!(
+                    expected, plan.pressure,
+                    "{agent_count} agents, {time_limit}"
+                );
+
+                // The routes release exactly the planned pressure, and never share a valve
+                assert_eq!(agent_count, plan.routes.len());
+                let mut opened: Vec<_> = plan.routes.iter().flatten().collect();
+                let released: usize = (opened.iter())
+                    .map(|Opening { valve, minute }| {
+                        let valve = network.names.iter().position(|name| name == valve);
+                        network.flow_rates[valve.unwrap()] * (time_limit - minute) as usize
+                    })
+                    .sum();
+                assert_eq!(plan.pressure, released);
+                opened.sort_by_key(|opening| opening.valve);
+                opened.dedup_by_key(|opening| opening.valve);
+                assert_eq!(
+                    plan.routes.iter().map(Vec::len).sum::<usize>(),
+                    opened.len()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn more_agents_release_more_pressure() {
+        let network = ValveNetwork::from(&Volcano::from(EXAMPLE));
+        let released: Vec<_> = (1..=4)
+            .map(|agent_count| network.plan(26, agent_count).pressure)
+            .collect();
+        assert_eq!(1_327, released[0]);
+        assert_eq!(1_707, released[1]);
+        assert!(released.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
     #[test]
     fn part1() {
         assert_eq!(1_488, day16_part1());
@@ -1,292 +1,264 @@
-use std::collections::HashSet;
-use std::ops::RangeInclusive;
+use image_export::{Image, Rgb};
+use std::collections::HashMap;
 use Direction::*;
-use Shape::*;
 
 const INPUT: &str = include_str!("../input/day17.txt");
 const P1_ROUNDS: usize = 2022;
 const P2_ROUNDS: usize = 1_000_000_000_000;
 
-pub(crate) fn day17_part1() -> usize {
+/// The rocks in the order they fall, drawn as in the puzzle description
+pub const ROCKS: &str = "\
+####
+
+.#.
+###
+.#.
+
+..#
+..#
+###
+
+#
+#
+#
+#
+
+##
+##";
+
+pub fn day17_part1() -> usize {
     let directions = parse(INPUT);
     tower_height(directions, P1_ROUNDS)
 }
 
-pub(crate) fn day17_part2() -> usize {
+pub fn day17_part2() -> usize {
     let directions = parse(INPUT);
     tower_height(directions, P2_ROUNDS)
 }
 
-struct Floor {
-    y: Y,
-    count: usize,
-    desc: String,
-}
-
-type X = usize;
-type Y = usize;
 fn tower_height(directions: Vec<Direction>, rock_count: usize) -> usize {
-    // Our chamber looks like this:
-    // …       …
-    // |       | 2
-    // |       | 1
-    // +-------+ 0
-    // 012345678
-    let initial_y = 3 + 1;
-    let mut tower_height = 0;
-    let mut wall_height = tower_height + initial_y - 1;
-    let mut shapes = [HBar, Cross, LeftL, VBar, Square].iter().cycle();
-    let mut directions = directions.iter().cycle();
-    let mut extra_height = 0;
-
-    // Init floor
-    let mut floors = vec![Floor {
-        y: 0,
-        count: 0,
-        desc: "+-------+".to_string(),
-    }];
-
-    // Init occupied positions with walls
-    let mut occupied_positions: HashSet<(X, Y)> = (0..=9).into_iter().map(|x| (x, 0)).collect();
-    (1..=wall_height).into_iter().for_each(|y| {
-        occupied_positions.insert((0, y));
-        occupied_positions.insert((8, y));
-    });
-
-    // Simulate all the rocks
-    let mut count = 0;
-    while count < rock_count {
-        count += 1;
-        let mut rock = Rock {
-            shape: shapes.next().unwrap(),
-            left: 2 + 1,
-            bottom: tower_height + initial_y,
-        };
-
-        // Extend walls to cover the rock height for collision tests
-        let old_wall_height = wall_height;
-        wall_height = tower_height + initial_y - 1 + rock.shape.height();
-        (old_wall_height + 1..=wall_height)
-            .into_iter()
-            .for_each(|y| {
-                occupied_positions.insert((0, y));
-                occupied_positions.insert((8, y));
-            });
-
-        // Let the rock do it's thing until it can't drop any more
-        loop {
-            // draw(&rock, &occupied_positions);
-            let direction = directions.next().unwrap();
+    let shapes = parse_shapes(ROCKS);
+    Tower::new(Chamber::default(), &shapes, &directions).height_after(rock_count)
+}
 
-            let can_move = !rock.offset_by(direction).overlaps(&occupied_positions);
-            if can_move {
-                rock.move_in(direction);
-            }
+/// Bit x is set if the cell x units from the left wall is occupied
+type Row = u64;
 
-            let can_fall = !rock.dropped_by_1().overlaps(&occupied_positions);
-            if can_fall {
-                rock.drop_1_unit();
-            } else {
-                break;
-            }
-        }
+pub enum Direction {
+    Left,
+    Right,
+}
 
-        // Store the landed rock in occupied positions
-        for pos in rock.occupied_positions() {
-            occupied_positions.insert(pos);
-        }
+/// A rock's rows from the bottom up
+pub struct Shape {
+    rows: Vec<Row>,
+    width: usize,
+}
 
-        // Recalculate new tower height
-        tower_height = occupied_positions
-            .iter()
-            .filter(|(x, _)| (1..8).contains(x))
-            .map(|&(_, y)| y)
-            .max()
-            .unwrap();
-
-        // Check for new floors, where rocks cover the full width of the chamber
-        let last_floor_y = *floors.last().map(|Floor { y, .. }| y).unwrap();
-        if let Some(y) = (last_floor_y + 1..=tower_height).into_iter().find(|y| {
-            (1..8)
-                .into_iter()
-                .all(|x| occupied_positions.contains(&(x, *y)))
-        }) {
-            let desc = gen_floor_desc(&occupied_positions, last_floor_y..=y);
-            if extra_height == 0 {
-                if let Some(prev) = floors.iter().find(|Floor { desc: d, .. }| desc.eq(d)) {
-                    let period = count - prev.count;
-                    let floor_height = y - prev.y;
-
-                    let mut multi = 0;
-                    let old_count = count;
-                    while count + period < rock_count {
-                        count += period;
-                        multi += 1;
-                    }
-                    extra_height = multi * floor_height;
-                    println!(
-                        "Floor @ {y} with count {old_count} is a repeat of floor @ {} with count {}",
-                        prev.y, prev.count
-                    );
-                    println!(
-                        "Period {period}, floor_height {floor_height}, extra_height {extra_height}"
-                    );
-
-                    // println!("floor:\n{}\n", desc);
-                    // draw(&rock, &occupied_positions);
-                }
-                floors.push(Floor { y, desc, count });
-            }
-        }
+impl From<&str> for Shape {
+    fn from(ascii: &str) -> Self {
+        let rows: Vec<Row> = ascii
+            .lines()
+            .rev()
+            .map(|line| {
+                (line.chars().enumerate())
+                    .filter(|(_, c)| c == &'#')
+                    .fold(0, |row, (x, _)| row | 1 << x)
+            })
+            .collect();
+        let width = rows.iter().fold(0, |all, row| all | row);
+        let width = (Row::BITS - width.leading_zeros()) as usize;
+        Shape { rows, width }
     }
+}
 
-    // dbg!(floors.iter().map(|Floor { y, .. }| y).collect::<Vec<_>>());
-    // if !floors.is_empty() {
-    //     let diffs: Vec<_> = floors.windows(2).map(|a| a[1].y - a[0].y).collect();
-    //     for Floor { y, desc, count } in floors {
-    //         println!("floor at {y} with {count}:\n{desc}\n");
-    //     }
-    // }
-    tower_height + extra_height
+#[derive(Debug, Clone, Copy)]
+pub struct Chamber {
+    width: usize,
+    /// Rocks appear this many units away from the left wall …
+    spawn_left: usize,
+    /// … and this many units above the highest rock, or the floor
+    spawn_gap: usize,
 }
 
-#[allow(unused)]
-fn draw(rock: &Rock, occupied_positions: &HashSet<(X, Y)>) {
-    let max_y = occupied_positions
-        .iter()
-        .map(|(_, y)| *y)
-        .chain(rock.occupied_positions().map(|(_, y)| y))
-        .max()
-        .unwrap_or(0);
-
-    println!(
-        "\n{}",
-        (0..=max_y)
-            .into_iter()
-            .rev()
-            .map(|y| {
-                (0..9usize)
-                    .into_iter()
-                    .map(|x| {
-                        if rock.occupied_positions().any(|pos| pos == (x, y)) {
-                            '@'
-                        } else if occupied_positions.contains(&(x, y)) {
-                            match (x, y) {
-                                (0, 0) | (8, 0) => '+',
-                                (0, _) | (8, _) => '|',
-                                (_, 0) => '-',
-                                _ => '#',
-                            }
-                        } else {
-                            '.'
-                        }
-                    })
-                    .collect::<String>()
-            })
-            .collect::<Vec<_>>()
-            .join("\n")
-    );
+impl Default for Chamber {
+    fn default() -> Self {
+        Chamber::new(7, 2, 3)
+    }
 }
 
-fn gen_floor_desc(occupied_positions: &HashSet<(X, Y)>, y_range: RangeInclusive<Y>) -> String {
-    y_range
-        .into_iter()
-        .rev()
-        .map(|y| {
-            (0..9usize)
-                .into_iter()
-                .map(|x| {
-                    if occupied_positions.contains(&(x, y)) {
-                        match (x, y) {
-                            (0, 0) | (8, 0) => '+',
-                            (0, _) | (8, _) => '|',
-                            (_, 0) => '-',
-                            _ => '#',
-                        }
-                    } else {
-                        '.'
-                    }
-                })
-                .collect::<String>()
-        })
-        .collect::<Vec<_>>()
-        .join("\n")
+impl Chamber {
+    pub fn new(width: usize, spawn_left: usize, spawn_gap: usize) -> Self {
+        assert!(width <= Row::BITS as usize, "Chamber too wide");
+        Chamber {
+            width,
+            spawn_left,
+            spawn_gap,
+        }
+    }
 }
 
-enum Direction {
-    Left,
-    Right,
+/// What the rocks about to fall will run into. If the tower ever looks the same from
+/// the top in this state again, it will grow in the same way from then on.
+#[derive(PartialEq, Eq, Hash)]
+struct CycleKey {
+    shape_index: usize,
+    jet_index: usize,
+    surface_profile: Vec<usize>,
 }
 
-#[derive(Clone)]
-struct Rock<'a> {
-    shape: &'a Shape,
-    // Position
-    left: usize,
-    bottom: usize,
+pub struct Tower<'a> {
+    chamber: Chamber,
+    shapes: &'a [Shape],
+    jets: &'a [Direction],
+    /// The settled rocks from the floor up
+    rows: Vec<Row>,
+    shape_index: usize,
+    jet_index: usize,
 }
-impl<'a> Rock<'a> {
-    fn move_in(&mut self, direction: &Direction) {
-        match direction {
-            Left => self.left -= 1,
-            Right => self.left += 1,
+
+impl<'a> Tower<'a> {
+    pub fn new(chamber: Chamber, shapes: &'a [Shape], jets: &'a [Direction]) -> Self {
+        assert!(!shapes.is_empty() && !jets.is_empty());
+        assert!(
+            (shapes.iter()).all(|shape| chamber.spawn_left + shape.width <= chamber.width),
+            "Shape does not fit into the chamber"
+        );
+        Tower {
+            chamber,
+            shapes,
+            jets,
+            rows: vec![],
+            shape_index: 0,
+            jet_index: 0,
         }
     }
-    fn drop_1_unit(&mut self) {
-        self.bottom -= 1;
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+    pub fn height_after(mut self, rock_count: usize) -> usize {
+        let mut seen = HashMap::new();
+        let mut skipped_height = None;
+        let mut dropped = 0;
+        while dropped < rock_count {
+            if skipped_height.is_none() {
+                let key = self.cycle_key();
+                if let Some((prev_dropped, prev_height)) =
+                    seen.insert(key, (dropped, self.height()))
+                {
+                    let period = dropped - prev_dropped;
+                    let cycles = (rock_count - dropped) / period;
+                    skipped_height = Some(cycles * (self.height() - prev_height));
+                    dropped += cycles * period;
+                    continue;
+                }
+            }
+            self.drop_rock();
+            dropped += 1;
+        }
+        self.height() + skipped_height.unwrap_or(0)
     }
-    fn dropped_by_1(&self) -> Self {
-        let mut clone = self.clone();
-        clone.drop_1_unit();
-        clone
+    fn cycle_key(&self) -> CycleKey {
+        CycleKey {
+            shape_index: self.shape_index,
+            jet_index: self.jet_index,
+            surface_profile: self.surface_profile(),
+        }
     }
-    fn offset_by(&self, direction: &Direction) -> Self {
-        let mut clone = self.clone();
-        clone.move_in(direction);
-        clone
+    /// For each column, how far below the top of the tower its highest rock is
+    fn surface_profile(&self) -> Vec<usize> {
+        (0..self.chamber.width)
+            .map(|x| {
+                (self.rows.iter().rev())
+                    .position(|row| row & 1 << x != 0)
+                    .unwrap_or(self.height())
+            })
+            .collect()
     }
-
-    fn offsets(&self) -> Vec<(X, Y)> {
-        // Offsets of its pixels compared to its bottom left corner (considered (0,0)
-        match self.shape {
-            HBar => vec![(0, 0), (1, 0), (2, 0), (3, 0)],
-            Cross => vec![(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
-            LeftL => vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
-            VBar => vec![(0, 0), (0, 1), (0, 2), (0, 3)],
-            Square => vec![(0, 0), (0, 1), (1, 0), (1, 1)],
+    pub fn drop_rock(&mut self) {
+        let shape = &self.shapes[self.shape_index];
+        self.shape_index = (self.shape_index + 1) % self.shapes.len();
+        let mut left = self.chamber.spawn_left;
+        let mut bottom = self.height() + self.chamber.spawn_gap;
+        loop {
+            let pushed = match self.jets[self.jet_index] {
+                Left => left.checked_sub(1),
+                Right => Some(left + 1),
+            };
+            self.jet_index = (self.jet_index + 1) % self.jets.len();
+            if let Some(pushed) = pushed.filter(|&left| self.fits(shape, left, bottom)) {
+                left = pushed;
+            }
+            match bottom.checked_sub(1) {
+                Some(dropped) if self.fits(shape, left, dropped) => bottom = dropped,
+                _ => break,
+            }
+        }
+        for (y, row) in (bottom..).zip(shape.rows.iter()) {
+            if y >= self.rows.len() {
+                self.rows.resize(y + 1, 0);
+            }
+            self.rows[y] |= row << left;
         }
     }
-    fn occupied_positions(&self) -> impl Iterator<Item = (X, Y)> + '_ {
-        self.offsets()
-            .into_iter()
-            .map(|(x, y)| (self.left + x, self.bottom + y))
+    fn fits(&self, shape: &Shape, left: usize, bottom: usize) -> bool {
+        left + shape.width <= self.chamber.width
+            && (bottom..).zip(shape.rows.iter()).all(|(y, row)| {
+                self.rows
+                    .get(y)
+                    .is_none_or(|occupied| occupied & row << left == 0)
+            })
     }
-    fn overlaps(&self, occupied_positions: &HashSet<(X, Y)>) -> bool {
-        self.occupied_positions()
-            .any(|pos| occupied_positions.contains(&pos))
+    /// The top rows of the tower, including the floor if they reach down that far
+    pub fn frame(&self, row_count: usize) -> String {
+        let mut lines: Vec<_> = (self.rows.iter().rev().take(row_count))
+            .map(|row| {
+                let cells: String = (0..self.chamber.width)
+                    .map(|x| if row & 1 << x != 0 { '#' } else { '.' })
+                    .collect();
+                format!("|{cells}|")
+            })
+            .collect();
+        if row_count > self.height() {
+            lines.push(format!("+{}+", "-".repeat(self.chamber.width)));
+        }
+        lines.join("\n")
     }
-}
-
-enum Shape {
-    HBar,
-    Cross,
-    LeftL,
-    VBar,
-    Square,
-}
-impl Shape {
-    fn height(&self) -> usize {
-        match self {
-            HBar => 1,
-            Cross => 3,
-            LeftL => 3,
-            VBar => 4,
-            Square => 2,
+    /// The top rows of the tower like [`Tower::frame`], with each unit drawn as a
+    /// `scale` × `scale` square. Always `row_count` rows high, so the images of a
+    /// growing tower can make up an animation.
+    pub fn image(&self, row_count: usize, scale: usize) -> Image {
+        const WALL: Rgb = Rgb(128, 128, 128);
+        const ROCK: Rgb = Rgb(205, 133, 63);
+        let width = self.chamber.width + 2;
+        let mut image = Image::new(width * scale, row_count * scale, Rgb::BLACK);
+        let top = self.height().max(row_count.saturating_sub(1));
+        for line in 0..row_count {
+            let Some(y) = (top - line).checked_sub(1) else {
+                for x in 0..width {
+                    image.fill_square(x * scale, line * scale, scale, WALL);
+                }
+                continue;
+            };
+            let row = self.rows.get(y).copied().unwrap_or(0);
+            for x in 0..width {
+                let color = match x {
+                    _ if x == 0 || x == width - 1 => WALL,
+                    x if row & 1 << (x - 1) != 0 => ROCK,
+                    _ => continue,
+                };
+                image.fill_square(x * scale, line * scale, scale, color);
+            }
         }
+        image
     }
 }
 
-fn parse(input: &str) -> Vec<Direction> {
+pub fn parse_shapes(input: &str) -> Vec<Shape> {
+    input.trim().split("\n\n").map(Shape::from).collect()
+}
+
+pub fn parse(input: &str) -> Vec<Direction> {
     input
         .trim()
         .chars()
@@ -320,4 +292,110 @@ mod tests {
     fn part2() {
         assert_eq!(1_523_615_160_362, day17_part2());
     }
+
+    #[test]
+    fn shapes_from_ascii() {
+        let shapes = parse_shapes(ROCKS);
+        let widths: Vec<_> = shapes.iter().map(|shape| shape.width).collect();
+        assert_eq!(vec![4, 3, 3, 1, 2], widths);
+        assert_eq!(vec![0b1111], shapes[0].rows);
+        assert_eq!(vec![0b010, 0b111, 0b010], shapes[1].rows);
+        assert_eq!(vec![0b111, 0b100, 0b100], shapes[2].rows);
+        assert_eq!(vec![0b1; 4], shapes[3].rows);
+        assert_eq!(vec![0b11, 0b11], shapes[4].rows);
+    }
+
+    #[test]
+    fn example_frames() {
+        let (shapes, jets) = (parse_shapes(ROCKS), parse(EXAMPLE));
+        let mut tower = Tower::new(Chamber::default(), &shapes, &jets);
+        assert_eq!("+-------+", tower.frame(5));
+        tower.drop_rock();
+        assert_eq!("|..####.|\n+-------+", tower.frame(5));
+        tower.drop_rock();
+        assert_eq!(
+            "\
+|...#...|
+|..###..|
+|...#...|
+|..####.|
++-------+",
+            tower.frame(5)
+        );
+        for _ in 2..10 {
+            tower.drop_rock();
+        }
+        assert_eq!(
+            "\
+|....#..|
+|....#..|
+|....##.|
+|##..##.|",
+            tower.frame(4)
+        );
+        assert_eq!(17, tower.height());
+    }
+
+    /// One character per unit of an image line drawn with a scale of 2
+    fn units_at(image: &Image, line: usize) -> String {
+        (0..image.width() / 2)
+            .map(|x| match image.pixel_at(x * 2, line * 2) {
+                Some(Rgb::BLACK) => '.',
+                Some(Rgb(128, 128, 128)) => '+',
+                _ => '#',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn example_images() {
+        let (shapes, jets) = (parse_shapes(ROCKS), parse(EXAMPLE));
+        let mut tower = Tower::new(Chamber::default(), &shapes, &jets);
+        tower.drop_rock();
+        let image = tower.image(3, 2);
+        assert_eq!((18, 6), (image.width(), image.height()));
+        assert_eq!("+.......+", units_at(&image, 0));
+        assert_eq!("+..####.+", units_at(&image, 1));
+        assert_eq!("+++++++++", units_at(&image, 2));
+
+        for _ in 1..10 {
+            tower.drop_rock();
+        }
+        let image = tower.image(4, 2);
+        assert_eq!("+....#..+", units_at(&image, 0));
+        assert_eq!("+##..##.+", units_at(&image, 3));
+    }
+
+    #[test]
+    fn cycles_match_simulation_for_other_chambers() {
+        let (shapes, jets) = (parse_shapes(ROCKS), parse(EXAMPLE));
+        for chamber in [Chamber::new(9, 3, 3), Chamber::new(5, 1, 2)] {
+            for rock_count in [1_000, 2_022, 5_000] {
+                let mut simulated = Tower::new(chamber, &shapes, &jets);
+                for _ in 0..rock_count {
+                    simulated.drop_rock();
+                }
+                let tower = Tower::new(chamber, &shapes, &jets);
+                assert_eq!(simulated.height(), tower.height_after(rock_count));
+            }
+        }
+    }
+
+    #[test]
+    fn other_shapes_and_jets() {
+        // Single units in a chamber just as wide only ever stack up
+        let (shapes, jets) = (parse_shapes("#"), parse("<"));
+        let tower = Tower::new(Chamber::new(1, 0, 3), &shapes, &jets);
+        assert_eq!(P2_ROUNDS, tower.height_after(P2_ROUNDS));
+
+        let shapes = parse_shapes("#.#\n###\n\n##");
+        assert_eq!(vec![0b111, 0b101], shapes[0].rows);
+        let jets = parse("<<>><>");
+        let mut simulated = Tower::new(Chamber::new(4, 1, 1), &shapes, &jets);
+        for _ in 0..P1_ROUNDS {
+            simulated.drop_rock();
+        }
+        let tower = Tower::new(Chamber::new(4, 1, 1), &shapes, &jets);
+        assert_eq!(simulated.height(), tower.height_after(P1_ROUNDS));
+    }
 }
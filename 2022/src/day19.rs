@@ -1,79 +1,255 @@
 use rayon::prelude::*;
-use std::collections::HashMap;
+use state_space::{Puzzle, Solver};
 use std::fmt::{Display, Formatter};
 use std::ops::{AddAssign, Deref, DerefMut, SubAssign};
 use ResourceType::*;
 
 const INPUT: &str = include_str!("../input/day19.txt");
 
-pub(crate) fn day19_part1() -> usize {
+pub fn day19_part1() -> usize {
     let input = parse_blueprints(INPUT);
     solve_part1(&input)
 }
 
-pub(crate) fn day19_part2() -> usize {
+pub fn day19_part2() -> usize {
     let input = parse_blueprints(INPUT);
     solve_part2(&input)
 }
 
 fn solve_part1(blueprints: &[Blueprint]) -> usize {
     let max_time = 24;
-    blueprints
-        .par_iter()
+    optimize_all(blueprints, Objective::MostGeodes, max_time)
+        .into_iter()
         .enumerate()
-        .map(|(i, blueprint)| (i + 1, blueprint))
-        .map(|(quality, blueprint)| quality * max_geode_count_for(blueprint, max_time) as usize)
+        .map(|(i, plan)| (i + 1, plan.unwrap()))
+        .map(|(quality, plan)| quality * plan.value)
         .sum()
 }
 
 fn solve_part2(blueprints: &[Blueprint]) -> usize {
     let max_time = 32;
+    let blueprints = &blueprints[..blueprints.len().min(3)];
+    optimize_all(blueprints, Objective::MostGeodes, max_time)
+        .into_iter()
+        .map(|plan| plan.unwrap().value)
+        .product()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Objective {
+    /// Crack as many geodes as possible before the time runs out
+    MostGeodes,
+    /// Collect at least these resources as quickly as possible
+    FastestTo(ResourceCounts),
+}
+
+/// The best plan for each of the blueprints, evaluated in parallel
+pub fn optimize_all(
+    blueprints: &[Blueprint],
+    objective: Objective,
+    max_time: u8,
+) -> Vec<Option<Plan>> {
     blueprints
         .par_iter()
-        .take(3)
-        .map(|blueprint| max_geode_count_for(blueprint, max_time) as usize)
-        .product()
+        .map(|blueprint| optimize(blueprint, objective, max_time))
+        .collect()
 }
 
-fn max_geode_count_for(blueprint: &Blueprint, max_time: u8) -> u8 {
-    let initial_state = State {
-        time_left: max_time,
-        robots: RobotCounts::single_ore_robot(),
-        resources: ResourceCounts::default(),
-    };
-    let mut cache: HashMap<State, u8> = HashMap::new();
-    max_geode_count_starting_in_state(initial_state, blueprint, &mut cache)
+/// The best plan for the objective, or none if it can't be reached before the time runs out
+fn optimize(blueprint: &Blueprint, objective: Objective, max_time: u8) -> Option<Plan> {
+    let factory = Factory::new(blueprint, objective, max_time);
+    let solver = Solver::new(&factory);
+    let solution = match objective {
+        Objective::MostGeodes => solver.par_maximize(),
+        Objective::FastestTo(_) => solver.minimize(),
+    }?;
+
+    let mut state = factory.start();
+    let mut builds = vec![];
+    for step in &solution.moves {
+        state = factory.apply(&state, step);
+        if let Step::Build(robot_type) = step {
+            builds.push((max_time - state.time_left, *robot_type));
+        }
+    }
+    Some(Plan {
+        value: solution.value,
+        builds,
+    })
 }
 
-fn max_geode_count_starting_in_state(
-    state: State,
-    blueprint: &Blueprint,
-    cache: &mut HashMap<State, u8>,
-) -> u8 {
-    if state.time_left == 0 {
-        return state.resources[Geode as usize];
+/// The best build order for a blueprint, and what it achieves for the objective
+#[derive(Debug, PartialEq)]
+pub struct Plan {
+    /// The number of geodes cracked, or the minutes it took to collect the resources
+    pub value: usize,
+    /// The robots built, each along with the minute at the end of which it's ready
+    builds: Vec<(u8, RobotType)>,
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (minute, robot_type) in &self.builds {
+            writeln!(f, "Minute {minute:2}: {robot_type} robot ready")?;
+        }
+        write!(f, "Value: {}", self.value)
     }
+}
+
+/// Building robots according to a blueprint, where each step fast forwards to the
+/// state where the next robot was built
+struct Factory<'a> {
+    blueprint: &'a Blueprint,
+    objective: Objective,
+    max_time: u8,
+    /// More robots than the resources that can be spent per minute won't help
+    max_robots: RobotCounts,
+}
 
-    if let Some(geode_count) = cache.get(&state) {
-        return *geode_count;
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Step {
+    Build(RobotType),
+    /// Wait until the target resources are collected
+    Collect,
+}
+
+impl<'a> Factory<'a> {
+    fn new(blueprint: &'a Blueprint, objective: Objective, max_time: u8) -> Self {
+        let mut max_robots = blueprint.max_cost_by_type;
+        match objective {
+            Objective::MostGeodes => max_robots[Geode as usize] = u8::MAX,
+            Objective::FastestTo(target) => {
+                // Collecting the target takes at least a minute with this many robots
+                for (max, target) in max_robots.iter_mut().zip(target.iter()) {
+                    *max = (*max).max(*target);
+                }
+            }
+        }
+        Factory {
+            blueprint,
+            objective,
+            max_time,
+            max_robots,
+        }
+    }
+    fn target_reached_after_waiting(&self, state: &State) -> Option<State> {
+        let Objective::FastestTo(target) = self.objective else {
+            return None;
+        };
+        let missing = state.resources_missing_to_cover(&target);
+        if !state.producing_materials_needed_to_cover(&missing) {
+            return None;
+        }
+        let time = state.time_to_collect(&missing);
+        let mut resources = state.resources;
+        resources += state.robots.resources_collected_in(time);
+        Some(State {
+            time_left: state.time_left.checked_sub(time)?,
+            robots: state.robots,
+            resources,
+        })
+    }
+    /// The geodes cracked by the robots still to be built, if obsidian was the only
+    /// resource needed for geode robots, and a new obsidian robot was ready every minute
+    fn optimistic_geode_count(&self, state: &State) -> usize {
+        let obsidian_per_robot = self.blueprint.costs_by_robot[Geode as usize][Obsidian as usize];
+        let mut obsidian = state.resources[Obsidian as usize] as usize;
+        let obsidian_robots = state.robots[Obsidian as usize] as usize..;
+        let mut geodes = 0;
+        for (obsidian_robots, time_left) in obsidian_robots.zip((0..state.time_left as usize).rev())
+        {
+            let can_build_geode_robot = obsidian >= obsidian_per_robot as usize;
+            obsidian += obsidian_robots;
+            if can_build_geode_robot {
+                obsidian -= obsidian_per_robot as usize;
+                geodes += time_left;
+            }
+        }
+        geodes
+    }
+    /// The minutes until the target resources could be collected, if a new robot
+    /// of every type was ready every minute and they cost nothing
+    fn optimistic_minutes_to(&self, target: &ResourceCounts, state: &State) -> usize {
+        let missing = state.resources_missing_to_cover(target);
+        (0..4)
+            .map(|resource| {
+                let (missing, mut robots) = (missing[resource], state.robots[resource]);
+                let (mut minutes, mut collected) = (0, 0_usize);
+                while collected < missing as usize {
+                    collected += robots as usize;
+                    robots += 1;
+                    minutes += 1;
+                }
+                minutes
+            })
+            .max()
+            .unwrap()
     }
+}
 
-    let geode_count = state
-        // decide on what types of robot we (still) need
-        .robot_types_still_needed_for(blueprint)
-        // fast forward to the state where one of those robots was built (if possible)
-        .filter_map(|type_id| state.try_building_a_robot_of_type(type_id, blueprint))
-        // calculate the maximum number of geodes cracked starting from that state
-        .map(|next| max_geode_count_starting_in_state(next, blueprint, cache))
-        .max()
-        .unwrap_or(0);
+impl Puzzle for Factory<'_> {
+    type State = State;
+    type Move = Step;
 
-    cache.insert(state, geode_count);
-    geode_count
+    fn start(&self) -> State {
+        State {
+            time_left: self.max_time,
+            robots: RobotCounts::single_ore_robot(),
+            resources: ResourceCounts::default(),
+        }
+    }
+    fn moves(&self, state: &State) -> Vec<Step> {
+        let collected = self.target_reached_after_waiting(state);
+        // Building robots after the target could have been collected is pointless
+        let time_left_after_collecting = collected.as_ref().map_or(0, |state| state.time_left);
+        state
+            // decide on what types of robot we (still) need
+            .robot_types_still_needed_for(&self.max_robots)
+            // and which of those could be built before the time runs out
+            .filter(|&robot_type| {
+                state
+                    .try_building_a_robot_of_type(robot_type, self.blueprint)
+                    .is_some_and(|next| next.time_left > time_left_after_collecting)
+            })
+            .map(Step::Build)
+            .chain(collected.map(|_| Step::Collect))
+            .collect()
+    }
+    fn apply(&self, state: &State, step: &Step) -> State {
+        match step {
+            Step::Build(robot_type) => {
+                state.try_building_a_robot_of_type(*robot_type, self.blueprint)
+            }
+            Step::Collect => self.target_reached_after_waiting(state),
+        }
+        .unwrap()
+    }
+    fn cost(&self, state: &State, step: &Step) -> usize {
+        let next = self.apply(state, step);
+        match self.objective {
+            // Every geode robot cracks one geode per minute it has left
+            Objective::MostGeodes if step == &Step::Build(Geode) => next.time_left as usize,
+            Objective::MostGeodes => 0,
+            Objective::FastestTo(_) => (state.time_left - next.time_left) as usize,
+        }
+    }
+    fn is_goal(&self, state: &State) -> bool {
+        match self.objective {
+            // The robots built so far will keep cracking geodes until the time runs out
+            Objective::MostGeodes => true,
+            Objective::FastestTo(target) => state.resources_missing_to_cover(&target).0 == [0; 4],
+        }
+    }
+    fn heuristic(&self, state: &State) -> Option<usize> {
+        Some(match self.objective {
+            Objective::MostGeodes => self.optimistic_geode_count(state),
+            Objective::FastestTo(target) => self.optimistic_minutes_to(&target, state),
+        })
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
-enum ResourceType {
+pub enum ResourceType {
     Geode,
     Obsidian,
     Clay,
@@ -82,7 +258,7 @@ enum ResourceType {
 
 /// Counts of Geode, Obsidian, Clay and Ore resources (in this order)
 #[derive(Debug, Copy, Clone, Default, Hash, PartialEq, Eq)]
-struct ResourceCounts([u8; 4]);
+pub struct ResourceCounts([u8; 4]);
 
 /// RobotCounts are also equivalent to Resources: counts of Geode, Obsidian, Clay and Ore _robots_
 type RobotCounts = ResourceCounts;
@@ -104,7 +280,7 @@ type RobotCost = ResourceCounts;
 type RobotCosts = [RobotCost; 4];
 
 #[derive(Debug)]
-struct Blueprint {
+pub struct Blueprint {
     costs_by_robot: RobotCosts,
     max_cost_by_type: RobotCost,
 }
@@ -144,16 +320,14 @@ const PRODUCTION_TIME: u8 = 1;
 impl State {
     fn robot_types_still_needed_for<'a>(
         &'a self,
-        blueprint: &'a Blueprint,
-    ) -> impl Iterator<Item = RobotType> + '_ {
-        // Heuristic: Geode robots are always needed, but from the others we won't need more than
-        // the maximum cost of the resource it produces plus the resources we already have
+        max_robots: &'a RobotCounts,
+    ) -> impl Iterator<Item = RobotType> + 'a {
+        // Heuristic: Geode robots are usually always needed, but from the others we won't need
+        // more than the maximum cost of the resource it produces plus the resources we already have
         [Geode, Obsidian, Clay, Ore]
             .into_iter()
             .filter(move |&robot_type| {
-                robot_type == Geode
-                    || self.robots[robot_type as usize]
-                        < blueprint.max_cost_by_type[robot_type as usize]
+                self.robots[robot_type as usize] < max_robots[robot_type as usize]
             })
     }
     fn try_building_a_robot_of_type(
@@ -253,12 +427,8 @@ impl From<&str> for Blueprint {
             // obsidian robot costs 2 ore and 17 clay.
             // geode robot costs 2 ore and 10 obsidian.
             let (robot_type, costs) = part.split_once(" robot costs ").unwrap();
-            let mut costs_by_mat = RobotCost::default();
-            for cost in costs.strip_suffix('.').unwrap().split(" and ") {
-                let (count, material) = cost.split_once(' ').unwrap();
-                costs_by_mat[ResourceType::from(material) as usize] = count.parse().unwrap();
-            }
-            (RobotType::from(robot_type), costs_by_mat)
+            let costs_by_mat = RobotCost::try_from(costs.strip_suffix('.').unwrap()).unwrap();
+            (RobotType::try_from(robot_type).unwrap(), costs_by_mat)
         }) {
             costs_by_robot[robot_type as usize] = costs_by_mat;
         }
@@ -279,7 +449,7 @@ impl From<&str> for Blueprint {
     }
 }
 
-fn parse_blueprints(input: &str) -> Vec<Blueprint> {
+pub fn parse_blueprints(input: &str) -> Vec<Blueprint> {
     input.lines().map(Blueprint::from).collect()
 }
 
@@ -297,15 +467,32 @@ impl Display for ResourceType {
         )
     }
 }
-impl From<&str> for ResourceType {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for ResourceType {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         match s {
-            "ore" => Ore,
-            "clay" => Clay,
-            "obsidian" => Obsidian,
-            "geode" => Geode,
-            _ => unreachable!("Unknown materials {}", s),
+            "ore" => Ok(Ore),
+            "clay" => Ok(Clay),
+            "obsidian" => Ok(Obsidian),
+            "geode" => Ok(Geode),
+            _ => Err(format!("Unknown material '{s}'")),
+        }
+    }
+}
+impl TryFrom<&str> for ResourceCounts {
+    type Error = String;
+
+    /// Resources in the format of the blueprints' costs, such as `2 ore and 17 clay`
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let mut counts = ResourceCounts::default();
+        for part in s.split(" and ") {
+            let (count, material) = (part.trim().split_once(' '))
+                .ok_or_else(|| format!("Expected a count and a material, got '{part}'"))?;
+            let count = (count.parse()).map_err(|_| format!("Expected a count, got '{count}'"))?;
+            counts[ResourceType::try_from(material)? as usize] = count;
         }
+        Ok(counts)
     }
 }
 
@@ -319,40 +506,104 @@ mod tests {
         assert_eq!(9 + 2 * 12, solve_part1(&input));
     }
 
-    #[ignore]
     #[test]
     fn solve_part1_example_blueprint_1() {
         let input = parse_blueprints(EXAMPLE);
         assert_eq!(9, solve_part1(&input[0..=0]));
     }
 
-    #[ignore]
     #[test]
     fn solve_part1_example_blueprint_2() {
         let input = parse_blueprints(EXAMPLE);
         assert_eq!(12, solve_part1(&input[1..=1]));
     }
 
+    #[test]
+    fn part1_example_build_order() {
+        let input = parse_blueprints(EXAMPLE);
+        let plan = optimize(&input[0], Objective::MostGeodes, 24).unwrap();
+        assert_eq!(
+            "\
+Minute  3: Clay robot ready
+Minute  5: Clay robot ready
+Minute  7: Clay robot ready
+Minute 11: Obsidian robot ready
+Minute 12: Clay robot ready
+Minute 15: Obsidian robot ready
+Minute 18: Geode robot ready
+Minute 21: Geode robot ready
+Value: 9",
+            plan.to_string()
+        );
+    }
+
+    #[test]
+    fn parse_resource_counts() {
+        assert_eq!(
+            Ok(ResourceCounts([0, 17, 0, 2])),
+            ResourceCounts::try_from("2 ore and 17 obsidian")
+        );
+        assert_eq!(
+            Err("Unknown material 'diamond'".to_string()),
+            ResourceCounts::try_from("1 diamond")
+        );
+        assert_eq!(
+            Err("Expected a count, got 'many'".to_string()),
+            ResourceCounts::try_from("many geode")
+        );
+    }
+
+    #[test]
+    fn optimize_all_keeps_the_order() {
+        let input = parse_blueprints(EXAMPLE);
+        let values: Vec<_> = optimize_all(&input, Objective::MostGeodes, 24)
+            .into_iter()
+            .map(|plan| plan.unwrap().value)
+            .collect();
+        assert_eq!(vec![9, 12], values);
+    }
+
+    #[test]
+    fn fastest_to_example_targets() {
+        let input = parse_blueprints(EXAMPLE);
+        let fastest_to = |target, max_time| {
+            let objective = Objective::FastestTo(ResourceCounts(target));
+            optimize(&input[0], objective, max_time).map(|plan| plan.value)
+        };
+        // The first geode is cracked in the same minute as in the example
+        assert_eq!(Some(19), fastest_to([1, 0, 0, 0], 24));
+        assert_eq!(None, fastest_to([1, 0, 0, 0], 18));
+        assert_eq!(Some(12), fastest_to([0, 1, 0, 0], 24));
+        // Waiting for 10 ore with a single clay robot
+        assert_eq!(Some(13), fastest_to([0, 0, 10, 10], 24));
+        assert_eq!(Some(0), fastest_to([0, 0, 0, 0], 24));
+
+        let plan = optimize(
+            &input[0],
+            Objective::FastestTo(ResourceCounts([1, 0, 0, 0])),
+            24,
+        );
+        let last_build = plan.unwrap().builds.pop().unwrap();
+        assert_eq!((18, Geode), last_build);
+    }
+
     #[test]
     fn part1() {
         assert_eq!(1_550, day19_part1());
     }
 
-    #[ignore]
     #[test]
     fn solve_part2_example_blueprint_1() {
         let input = parse_blueprints(EXAMPLE);
         assert_eq!(56, solve_part2(&input[0..=0]));
     }
 
-    #[ignore]
     #[test]
     fn solve_part2_example_blueprint_2() {
         let input = parse_blueprints(EXAMPLE);
         assert_eq!(62, solve_part2(&input[1..=1]));
     }
 
-    #[ignore] // Very slow at 105s
     #[test]
     fn part2_example() {
         let input = parse_blueprints(EXAMPLE);
@@ -1,8 +1,9 @@
+use number_theory::Rational;
 use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
+use Expression::*;
 use MonkeyJob::*;
 use MonkeyOp::*;
-use NameOrValue::*;
 
 const INPUT: &str = include_str!("../input/day21.txt");
 
@@ -19,184 +20,248 @@ const HUMN: &str = "humn";
 
 impl<'a> Tree<'a> {
     fn part1(&self) -> isize {
-        self.evaluate(ROOT)
+        let value = self.expression(ROOT, None).unwrap().value().unwrap();
+        value.to_integer().unwrap() as isize
     }
-    fn evaluate(&self, root: MonkeyName) -> isize {
-        match self.tree.get(root).unwrap() {
-            Number(n) => *n,
-            Combine { lhs, rhs, op } => {
-                let left = match lhs {
-                    Name(left) => self.evaluate(left),
-                    Value(left) => *left,
-                };
-                let right = match rhs {
-                    Name(right) => self.evaluate(right),
-                    Value(right) => *right,
-                };
-                op.apply(left, right)
-            }
-        }
+    fn part2(&self) -> isize {
+        let equation = self.equation(ROOT, HUMN).unwrap().simplified().unwrap();
+        let value = equation.solve().unwrap();
+        value.to_integer().unwrap() as isize
     }
-    fn part2(&mut self) -> isize {
-        let values = self.get_non_human_key_value_pairs(ROOT);
-        for (key, value) in values {
-            remove_subtree(&mut self.tree, key);
-            self.tree.insert(key, Number(value));
+    /// The job of the monkey called `name`, with the monkey called `unknown` left as a symbol
+    /// instead of being replaced by its job
+    fn expression(
+        &self,
+        name: MonkeyName<'a>,
+        unknown: Option<MonkeyName<'a>>,
+    ) -> Result<Expression<'a>, SolveError<'a>> {
+        if Some(name) == unknown {
+            return Ok(Unknown(name));
         }
-
-        while self.simplified() {
-            //
+        match self.tree.get(name).ok_or(SolveError::UnknownMonkey(name))? {
+            Number(n) => Ok(Constant(Rational::integer(*n as i128))),
+            Combine { lhs, op, rhs } => Ok(Operation(
+                Box::new(self.expression(lhs, unknown)?),
+                *op,
+                Box::new(self.expression(rhs, unknown)?),
+            )),
         }
-
-        if let Combine { lhs, rhs, .. } = self.tree.get(ROOT).unwrap() {
-            match (lhs, rhs) {
-                (Name(l), Value(r)) => self.calculate_human_value_to_satisfy_equation(l, *r),
-                (Value(l), Name(r)) => self.calculate_human_value_to_satisfy_equation(r, *l),
-                _ => unreachable!(),
-            }
-        } else {
-            unreachable!()
+    }
+    /// The two operands of the monkey called `root` as the sides of an equation
+    fn equation(
+        &self,
+        root: MonkeyName<'a>,
+        unknown: MonkeyName<'a>,
+    ) -> Result<Equation<'a>, SolveError<'a>> {
+        match self.tree.get(root).ok_or(SolveError::UnknownMonkey(root))? {
+            Number(_) => Err(SolveError::NotAnEquation(root)),
+            Combine { lhs, rhs, .. } => Ok(Equation {
+                left: self.expression(lhs, Some(unknown))?,
+                right: self.expression(rhs, Some(unknown))?,
+                unknown,
+            }),
         }
     }
+}
 
-    fn simplified(&mut self) -> bool {
-        let previous_tree_size = self.tree.len();
-        // Find Number and Combine operations with numbers on both sides…
-        let values_by_name: BTreeMap<MonkeyName, isize> = self
-            .tree
-            .iter()
-            .filter(|(name, _)| name != &&HUMN)
-            .filter_map(|(name, job)| match job {
-                Number(value) => Some((*name, *value)),
-                Combine {
-                    lhs: Value(l),
-                    rhs: Value(r),
-                    op,
-                } => Some((*name, op.apply(*l, *r))),
-                _ => None,
-            })
-            .collect();
-
-        // …and put them into other Combine operations…
-        for job in self.tree.values_mut() {
-            if let Combine { lhs, rhs, .. } = job {
-                if let Name(name) = lhs {
-                    if name != &HUMN {
-                        if let Some(value) = values_by_name.get(name) {
-                            *lhs = Value(*value);
-                        }
-                    }
-                }
-                if let Name(name) = rhs {
-                    if name != &HUMN {
-                        if let Some(value) = values_by_name.get(name) {
-                            *rhs = Value(*value);
-                        }
-                    }
-                }
+#[derive(Debug, PartialEq)]
+enum SolveError<'a> {
+    UnknownMonkey(MonkeyName<'a>),
+    NotAnEquation(MonkeyName<'a>),
+    MissingUnknown(MonkeyName<'a>),
+    RepeatedUnknown {
+        unknown: MonkeyName<'a>,
+        count: usize,
+    },
+    NonLinear {
+        unknown: MonkeyName<'a>,
+        expression: String,
+    },
+    DivisionByZero(String),
+    NoUniqueSolution(String),
+    NotAConstant(String),
+}
+impl Display for SolveError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::UnknownMonkey(name) => write!(f, "There's no monkey called {}", name),
+            SolveError::NotAnEquation(name) => write!(f, "{} just yells a number", name),
+            SolveError::MissingUnknown(name) => write!(f, "{} does not occur", name),
+            SolveError::RepeatedUnknown { unknown, count } => {
+                write!(
+                    f,
+                    "{} occurs {} times, but may only occur once",
+                    unknown, count
+                )
             }
+            SolveError::NonLinear {
+                unknown,
+                expression,
+            } => write!(f, "{} occurs non-linearly in {}", unknown, expression),
+            SolveError::DivisionByZero(expression) => {
+                write!(f, "Division by zero in {}", expression)
+            }
+            SolveError::NoUniqueSolution(equation) => {
+                write!(f, "{} has no unique solution", equation)
+            }
+            SolveError::NotAConstant(expression) => write!(f, "{} is not a constant", expression),
         }
-        // …and then remove the now unnecessary nodes
-        for (name, _) in values_by_name {
-            self.tree.remove(name);
-        }
-
-        // Did the tree shrink?
-        self.tree.len() < previous_tree_size
     }
-    fn calculate_human_value_to_satisfy_equation(&self, root: MonkeyName, value: isize) -> isize {
-        if root == HUMN {
-            return value;
-        }
-        // One of the sides contains a human, and should be balanced with the other side
-        match self.tree.get(root).unwrap() {
-            Combine { lhs, rhs, op } => match (lhs, rhs) {
-                (Name(l), Value(r)) => {
-                    let value = match op {
-                        Addition => value - r,
-                        Subtraction => value + r,
-                        Multiplication => value / r,
-                        Division => value * r,
-                    };
-                    self.calculate_human_value_to_satisfy_equation(l, value)
-                }
-                (Value(l), Name(r)) => {
-                    let value = match op {
-                        Addition => value - l,
-                        Subtraction => l - value,
-                        Multiplication => value / l,
-                        Division => l / value,
-                    };
-                    self.calculate_human_value_to_satisfy_equation(r, value)
-                }
-                (Value(_), Value(_)) | (Name(_), Name(_)) => {
-                    unreachable!()
-                }
-            },
-            Number(_) => {
-                unreachable!()
-            }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Expression<'a> {
+    Constant(Rational),
+    Unknown(MonkeyName<'a>),
+    Operation(Box<Expression<'a>>, MonkeyOp, Box<Expression<'a>>),
+}
+impl<'a> Expression<'a> {
+    fn value(&self) -> Result<Rational, SolveError<'a>> {
+        match self.simplified()? {
+            Constant(value) => Ok(value),
+            expression => Err(SolveError::NotAConstant(expression.to_string())),
         }
     }
-    fn get_non_human_key_value_pairs(&self, root: MonkeyName<'a>) -> Vec<(MonkeyName<'a>, isize)> {
-        if root == HUMN {
-            return vec![];
+    fn occurrences(&self, unknown: MonkeyName) -> usize {
+        match self {
+            Constant(_) => 0,
+            Unknown(name) => usize::from(*name == unknown),
+            Operation(lhs, _, rhs) => lhs.occurrences(unknown) + rhs.occurrences(unknown),
         }
-        match self.tree.get(root).unwrap() {
-            Number(value) => vec![(root, *value)],
-            Combine { lhs, rhs, .. } => {
-                let mut pairs: Vec<(MonkeyName, isize)> = vec![];
-                if let Name(left) = rhs {
-                    if let Some(value) = self.evaluate_unless_human(lhs) {
-                        pairs.push((left, value));
-                    }
-                }
-                if let Name(right) = rhs {
-                    if let Some(value) = self.evaluate_unless_human(rhs) {
-                        pairs.push((right, value));
-                    }
-                }
-                pairs
+    }
+    /// Folds constant sub-expressions, and drops operations that don't change their operand
+    fn simplified(&self) -> Result<Self, SolveError<'a>> {
+        let Operation(lhs, op, rhs) = self else {
+            return Ok(self.clone());
+        };
+        let (lhs, rhs) = (lhs.simplified()?, rhs.simplified()?);
+        let (zero, one) = (Rational::ZERO, Rational::ONE);
+        Ok(match (lhs, op, rhs) {
+            (Constant(l), op, Constant(r)) => Constant(
+                op.apply(l, r)
+                    .ok_or_else(|| SolveError::DivisionByZero(self.to_string()))?,
+            ),
+            (Constant(c), Addition, other) | (other, Addition | Subtraction, Constant(c))
+                if c == zero =>
+            {
+                other
             }
-        }
+            (Constant(c), Multiplication, other)
+            | (other, Multiplication | Division, Constant(c))
+                if c == one =>
+            {
+                other
+            }
+            (lhs, op, rhs) => Operation(Box::new(lhs), *op, Box::new(rhs)),
+        })
     }
-    fn evaluate_unless_human(&self, root: &NameOrValue) -> Option<isize> {
-        match root {
-            Name(name) => {
-                if name == &HUMN {
-                    return None;
-                }
-                match self.tree.get(name).unwrap() {
-                    Number(n) => Some(*n),
-                    Combine { lhs, rhs, op } => {
-                        match (
-                            self.evaluate_unless_human(lhs),
-                            self.evaluate_unless_human(rhs),
-                        ) {
-                            (Some(left), Some(right)) => Some(op.apply(left, right)),
-                            _ => None,
+    /// Rewrites the expression as `slope * unknown + intercept`
+    fn linear(&self, unknown: MonkeyName<'a>) -> Result<Linear, SolveError<'a>> {
+        Ok(match self {
+            Constant(value) => Linear::constant(*value),
+            Unknown(name) if *name == unknown => Linear {
+                slope: Rational::ONE,
+                intercept: Rational::ZERO,
+            },
+            Unknown(name) => return Err(SolveError::UnknownMonkey(name)),
+            Operation(lhs, op, rhs) => {
+                let (l, r) = (lhs.linear(unknown)?, rhs.linear(unknown)?);
+                let non_linear = || SolveError::NonLinear {
+                    unknown,
+                    expression: self.to_string(),
+                };
+                match op {
+                    Addition => Linear {
+                        slope: l.slope + r.slope,
+                        intercept: l.intercept + r.intercept,
+                    },
+                    Subtraction => Linear {
+                        slope: l.slope - r.slope,
+                        intercept: l.intercept - r.intercept,
+                    },
+                    Multiplication if !l.slope.is_zero() && !r.slope.is_zero() => {
+                        return Err(non_linear())
+                    }
+                    Multiplication => Linear {
+                        slope: l.slope * r.intercept + r.slope * l.intercept,
+                        intercept: l.intercept * r.intercept,
+                    },
+                    Division if !r.slope.is_zero() => return Err(non_linear()),
+                    Division => {
+                        let divisor = r
+                            .intercept
+                            .recip()
+                            .ok_or_else(|| SolveError::DivisionByZero(self.to_string()))?;
+                        Linear {
+                            slope: l.slope * divisor,
+                            intercept: l.intercept * divisor,
                         }
                     }
                 }
             }
-            Value(value) => Some(*value),
+        })
+    }
+}
+/// Fully parenthesized, so the order of operations is obvious
+impl Display for Expression<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constant(value) if value.to_integer().is_some() => write!(f, "{}", value),
+            Constant(value) => write!(f, "({})", value),
+            Unknown(name) => write!(f, "{}", name),
+            Operation(lhs, op, rhs) => write!(f, "({} {} {})", lhs, op, rhs),
         }
     }
 }
 
-fn remove_subtree(tree: &mut BTreeMap<MonkeyName, MonkeyJob>, root: MonkeyName) {
-    match tree.remove(root).unwrap() {
-        Number(_) => {
-            tree.remove(root);
+/// slope * x + intercept
+struct Linear {
+    slope: Rational,
+    intercept: Rational,
+}
+impl Linear {
+    fn constant(intercept: Rational) -> Self {
+        Linear {
+            slope: Rational::ZERO,
+            intercept,
         }
-        Combine { lhs, rhs, .. } => {
-            if let Name(left) = lhs {
-                remove_subtree(tree, left);
-            }
-            if let Name(right) = rhs {
-                remove_subtree(tree, right);
-            }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Equation<'a> {
+    left: Expression<'a>,
+    right: Expression<'a>,
+    unknown: MonkeyName<'a>,
+}
+impl<'a> Equation<'a> {
+    fn simplified(&self) -> Result<Self, SolveError<'a>> {
+        Ok(Equation {
+            left: self.left.simplified()?,
+            right: self.right.simplified()?,
+            unknown: self.unknown,
+        })
+    }
+    /// The value of the unknown making both sides equal. The unknown must occur exactly once,
+    /// and not as (part of) a divisor, so that the equation is linear.
+    fn solve(&self) -> Result<Rational, SolveError<'a>> {
+        let unknown = self.unknown;
+        match self.left.occurrences(unknown) + self.right.occurrences(unknown) {
+            0 => return Err(SolveError::MissingUnknown(unknown)),
+            1 => {}
+            count => return Err(SolveError::RepeatedUnknown { unknown, count }),
         }
+        let left = self.left.linear(unknown)?;
+        let right = self.right.linear(unknown)?;
+        // left.slope * x + left.intercept = right.slope * x + right.intercept
+        (right.intercept - left.intercept)
+            .checked_div(left.slope - right.slope)
+            .ok_or_else(|| SolveError::NoUniqueSolution(self.to_string()))
+    }
+}
+impl Display for Equation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.left, self.right)
     }
 }
 
@@ -222,18 +287,13 @@ type MonkeyName<'a> = &'a str;
 enum MonkeyJob<'a> {
     Number(isize),
     Combine {
-        lhs: NameOrValue<'a>,
+        lhs: MonkeyName<'a>,
         op: MonkeyOp,
-        rhs: NameOrValue<'a>,
+        rhs: MonkeyName<'a>,
     },
 }
-#[derive(Debug)]
-enum NameOrValue<'a> {
-    Name(MonkeyName<'a>),
-    Value(isize),
-}
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Copy, Clone)]
 enum MonkeyOp {
     Addition,
     Subtraction,
@@ -241,12 +301,13 @@ enum MonkeyOp {
     Division,
 }
 impl MonkeyOp {
-    fn apply(&self, l: isize, r: isize) -> isize {
+    /// None when dividing by zero
+    fn apply(&self, l: Rational, r: Rational) -> Option<Rational> {
         match self {
-            Addition => l + r,
-            Subtraction => l - r,
-            Multiplication => l * r,
-            Division => l / r,
+            Addition => Some(l + r),
+            Subtraction => Some(l - r),
+            Multiplication => Some(l * r),
+            Division => l.checked_div(r),
         }
     }
 }
@@ -270,8 +331,8 @@ impl<'a> From<&'a str> for MonkeyJob<'a> {
         if p.len() == 1 {
             Number(p[0].parse().unwrap())
         } else {
-            let lhs = Name(p[0]);
-            let rhs = Name(p[2]);
+            let lhs = p[0];
+            let rhs = p[2];
             let op = match p[1] {
                 "+" => Addition,
                 "-" => Subtraction,
@@ -324,4 +385,99 @@ hmdt: 32";
     fn part2() {
         assert_eq!(3_721_298_272_959, day21_part2());
     }
+
+    #[test]
+    fn simplified_equation() {
+        let equation = Tree::from(EXAMPLE).equation(ROOT, HUMN).unwrap();
+        assert_eq!(
+            "((4 + (2 * (humn - 3))) / 4) = ((32 - 2) * 5)",
+            equation.to_string()
+        );
+        assert_eq!(
+            "((4 + (2 * (humn - 3))) / 4) = 150",
+            equation.simplified().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn simplification_drops_neutral_operations() {
+        let tree = Tree::from("a: b * c\nb: humn - d\nc: 1\nd: 0");
+        let expression = tree.expression("a", Some(HUMN)).unwrap();
+        assert_eq!("((humn - 0) * 1)", expression.to_string());
+        assert_eq!("humn", expression.simplified().unwrap().to_string());
+    }
+
+    #[test]
+    fn solve_for_any_unknown() {
+        let tree = Tree::from(EXAMPLE);
+        let solve = |unknown| tree.equation(ROOT, unknown).unwrap().solve();
+        assert_eq!(Ok(Rational::integer(301)), solve(HUMN));
+        assert_eq!(Ok(Rational::integer(-293)), solve("dvpt"));
+        assert_eq!(Ok(Rational::integer(298)), solve("ptdq"));
+        assert_eq!(Ok(Rational::integer(2)), solve("sjmn"));
+        assert_eq!(Ok(Rational::new(1, 15)), solve("dbpl"));
+    }
+
+    #[test]
+    fn solve_with_fractions() {
+        let tree = Tree::from("root: a + b\na: humn / c\nb: 2\nc: 3");
+        let equation = tree.equation(ROOT, HUMN).unwrap();
+        assert_eq!(Ok(Rational::integer(6)), equation.solve());
+        let tree = Tree::from("root: a + b\na: c / humn\nb: 2\nc: 3\nhumn: 2");
+        assert_eq!(
+            Ok(Rational::new(3, 2)),
+            tree.equation(ROOT, "b").unwrap().solve()
+        );
+    }
+
+    #[test]
+    fn solve_errors() {
+        let tree = Tree::from("root: a + b\na: humn * humn\nb: 4");
+        assert_eq!(
+            Err(SolveError::RepeatedUnknown {
+                unknown: HUMN,
+                count: 2
+            }),
+            tree.equation(ROOT, HUMN).unwrap().solve()
+        );
+        let tree = Tree::from("root: a + b\na: c / humn\nb: 2\nc: 3");
+        assert_eq!(
+            Err(SolveError::NonLinear {
+                unknown: HUMN,
+                expression: "(c / humn)".replace('c', "3")
+            }),
+            tree.equation(ROOT, HUMN).unwrap().solve()
+        );
+        let tree = Tree::from("root: a + b\na: humn * c\nb: 2\nc: 0");
+        assert_eq!(
+            Err(SolveError::NoUniqueSolution("(humn * 0) = 2".to_string())),
+            tree.equation(ROOT, HUMN).unwrap().solve()
+        );
+        let tree = Tree::from("root: a + b\na: humn / c\nb: 2\nc: 0\nhumn: 1");
+        assert_eq!(
+            Err(SolveError::DivisionByZero("(humn / 0)".to_string())),
+            tree.equation(ROOT, HUMN).unwrap().solve()
+        );
+        assert_eq!(
+            Err(SolveError::MissingUnknown("nobody")),
+            tree.equation(ROOT, "nobody").unwrap().solve()
+        );
+        assert_eq!(
+            Err(SolveError::NotAnEquation("b")),
+            tree.equation("b", HUMN)
+        );
+        let tree = Tree::from("root: a + b\na: 1");
+        assert_eq!(
+            Err(SolveError::UnknownMonkey("b")),
+            tree.equation(ROOT, HUMN)
+        );
+        assert_eq!(
+            "humn occurs 2 times, but may only occur once",
+            SolveError::RepeatedUnknown {
+                unknown: HUMN,
+                count: 2
+            }
+            .to_string()
+        );
+    }
 }
@@ -0,0 +1,3 @@
+pub mod day14;
+pub mod day17;
+pub mod day19;
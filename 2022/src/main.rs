@@ -89,8 +89,7 @@ fn day13() {
     assert_eq!(25_800, day13_part2());
 }
 
-use crate::day14::{day14_part1, day14_part2};
-mod day14;
+use advent_of_code_2022::day14::{day14_part1, day14_part2};
 fn day14() {
     assert_eq!(913, day14_part1());
     assert_eq!(30_762, day14_part2());
@@ -110,8 +109,7 @@ fn day16() {
     assert_eq!(2_111, day16_part2());
 }
 
-use crate::day17::{day17_part1, day17_part2};
-mod day17;
+use advent_of_code_2022::day17::{day17_part1, day17_part2};
 fn day17() {
     assert_eq!(3_071, day17_part1());
     assert_eq!(1_523_615_160_362, day17_part2());
@@ -124,8 +122,7 @@ fn day18() {
     assert_eq!(2_014, day18_part2());
 }
 
-use crate::day19::{day19_part1, day19_part2};
-mod day19;
+use advent_of_code_2022::day19::{day19_part1, day19_part2};
 fn day19() {
     assert_eq!(1_550, day19_part1());
     assert_eq!(18_630, day19_part2());
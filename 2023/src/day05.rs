@@ -1,3 +1,4 @@
+use geometry::IntervalSet;
 use std::ops::RangeInclusive;
 
 const INPUT: &str = include_str!("../input/day05.txt");
@@ -20,7 +21,6 @@ fn minimum_location_reachable_from_seed_ranges(input: &str) -> Number {
     let seed_ranges = convert_seeds_to_seed_ranges(seeds);
     almanac
         .map_ranges_to_location_ranges(seed_ranges)
-        .map(|seed_range| *seed_range.start())
         .min()
         .unwrap()
 }
@@ -47,7 +47,7 @@ type Seed = Number;
 type Seeds = Vec<Seed>;
 type Offset = isize;
 type Range = RangeInclusive<Number>;
-type SeedRanges = Vec<Range>;
+type SeedRanges = IntervalSet<Number>;
 
 #[derive(Debug)]
 struct Almanac {
@@ -103,23 +103,16 @@ impl Almanac {
             .into_iter()
             .map(move |seed| self.map_seed_to_location(seed))
     }
-    fn map_ranges_to_location_ranges(self, ranges: SeedRanges) -> impl Iterator<Item = Range> {
-        ranges
-            .into_iter()
-            .flat_map(move |range| self.map_range(range))
+    fn map_ranges_to_location_ranges(self, ranges: SeedRanges) -> SeedRanges {
+        self.maps
+            .iter()
+            .fold(ranges, |ranges, mapper| mapper.map_ranges(&ranges))
     }
     fn map_seed_to_location(&self, seed: Number) -> Number {
         self.maps
             .iter()
             .fold(seed, |value, mapper| mapper.map_seed(value))
     }
-    fn map_range(&self, range: Range) -> impl Iterator<Item = Range> {
-        let mut input_ranges = vec![range];
-        for map in &self.maps {
-            input_ranges = map.map_ranges(map.split_seed_ranges(input_ranges));
-        }
-        input_ranges.into_iter()
-    }
 }
 
 impl Map {
@@ -129,23 +122,18 @@ impl Map {
             .find(|mapping| mapping.contains(&value))
             .map_or(value, |mapping| mapping.map(value))
     }
-    fn map_ranges(&self, ranges: Vec<Range>) -> Vec<Range> {
-        self.split_seed_ranges(ranges)
-            .into_iter()
-            .map(|range| self.map_range(range))
-            .collect()
-    }
-    fn split_seed_ranges(&self, mut seed_ranges: SeedRanges) -> SeedRanges {
+    fn map_ranges(&self, ranges: &SeedRanges) -> SeedRanges {
+        // Values not covered by any mapping keep their value
+        let mut unmapped = ranges.clone();
+        let mut mapped = SeedRanges::new();
         for mapping in &self.mappings {
-            seed_ranges = seed_ranges
-                .into_iter()
-                .flat_map(|seed_range| mapping.split_seed_ranges(seed_range))
-                .collect();
+            let overlap = ranges.intersection(&SeedRanges::from(mapping.range.clone()));
+            for range in overlap.iter() {
+                mapped.insert(mapping.map(*range.start())..=mapping.map(*range.end()));
+            }
+            unmapped.remove(mapping.range.clone());
         }
-        seed_ranges
-    }
-    fn map_range(&self, range: Range) -> Range {
-        self.map_seed(*range.start())..=self.map_seed(*range.end())
+        mapped.union(&unmapped)
     }
 }
 
@@ -156,44 +144,6 @@ impl Mapping {
     fn map(&self, value: Number) -> Number {
         (value as Offset + self.offset) as Number
     }
-    fn split_seed_ranges(&self, range: Range) -> SeedRanges {
-        self.range.split_on_boundaries(range)
-    }
-}
-
-trait SplitOnBoundary {
-    fn split_on_boundaries(&self, range: Range) -> Vec<Range>;
-}
-impl SplitOnBoundary for Range {
-    fn split_on_boundaries(&self, other: Range) -> Vec<Range> {
-        if other.start() < self.start() && self.end() < other.end() {
-            // other completely encloses this -> split it into 3 parts
-            vec![
-                *other.start()..=*self.start() - 1,
-                self.clone(),
-                *self.end() + 1..=*other.end(),
-            ]
-        } else if self.start() <= other.start() && other.end() <= self.end() {
-            // other is completely inside -> return as is
-            vec![other]
-        } else if other.start() < self.start() && other.contains(self.start()) {
-            // other overlaps on the left -> split it into 2 parts
-            if self.start() == &0 {
-                dbg!(&self);
-                dbg!(&other);
-            }
-            vec![
-                *other.start()..=*self.start() - 1,
-                *self.start()..=*other.end(),
-            ]
-        } else if self.end() < other.end() && other.contains(self.end()) {
-            // other overlaps on the right -> split it into 2 parts
-            vec![*other.start()..=*self.end(), *self.end() + 1..=*other.end()]
-        } else {
-            // other is completely outside  -> return as is
-            vec![other]
-        }
-    }
 }
 
 #[cfg(test)]
@@ -237,18 +187,12 @@ humidity-to-location map:
 ";
 
     #[test]
-    fn test_split_on_boundary() {
-        let range = 4..=7;
-        assert_eq!(range.split_on_boundaries(0..=3), vec![0..=3]);
-        assert_eq!(range.split_on_boundaries(8..=9), vec![8..=9]);
-        assert_eq!(range.split_on_boundaries(5..=6), vec![5..=6]);
-        assert_eq!(range.split_on_boundaries(4..=7), vec![4..=7]);
-        assert_eq!(range.split_on_boundaries(2..=6), vec![2..=3, 4..=6]);
-        assert_eq!(range.split_on_boundaries(6..=9), vec![6..=7, 8..=9]);
-        assert_eq!(range.split_on_boundaries(4..=8), vec![4..=7, 8..=8]);
-        assert_eq!(range.split_on_boundaries(2..=7), vec![2..=3, 4..=7]);
-        assert_eq!(range.split_on_boundaries(2..=9), vec![2..=3, 4..=7, 8..=9]);
-        assert_eq!(range.split_on_boundaries(3..=8), vec![3..=3, 4..=7, 8..=8]);
+    fn test_map_splits_ranges_on_mapping_boundaries() {
+        let map = Map::from("seed-to-soil map:\n50 98 2\n52 50 48");
+        let seeds = SeedRanges::from_iter([45..=55, 97..=100]);
+        let soil = map.map_ranges(&seeds);
+        // 45..=49 and 100 are unmapped, 50..=55 and 97 move up by 2, 98..=99 move down to 50..=51
+        assert_eq!(vec![45..=57, 99..=100], soil.iter().collect::<Vec<_>>());
     }
 
     #[test]
@@ -1,4 +1,5 @@
-use std::collections::{HashMap, HashSet};
+use number_theory::lcm_of;
+use std::collections::HashMap;
 
 const INPUT: &str = include_str!("../input/day08.txt");
 
@@ -44,7 +45,7 @@ fn solve(input: &str, start_suffix: &str, end_suffix: &str) -> usize {
         }
         // when all periods are known, calculate the step count where they meet
         if periods.iter().all(|cycle_count| cycle_count > &0) {
-            return least_common_multiple(periods);
+            return lcm_of(periods);
         }
     }
     unreachable!()
@@ -64,44 +65,10 @@ fn parse(input: &str) -> (Instructions, HashMap<Node, (Node, Node)>) {
     (instructions, node_map)
 }
 
-fn least_common_multiple(nums: Vec<usize>) -> usize {
-    if nums.len() == 1 {
-        return nums[0];
-    }
-    let unique_prime_factors: HashSet<usize> =
-        nums.iter().flat_map(|n| prime_factors(*n)).collect();
-    // the product of the unique prime factors is a reasonable increment
-    // when increasing the number to be tested as least common multiple
-    let upf_product: usize = unique_prime_factors.into_iter().product();
-    let mut lcm = upf_product;
-    while !nums.iter().all(|num| upf_product % num == 0) {
-        lcm += upf_product;
-    }
-    lcm
-}
-
-fn prime_factors(number: usize) -> Vec<usize> {
-    let mut rem = number;
-    let mut div = 2;
-    let mut factors = vec![];
-    while div < rem {
-        while rem % div == 0 {
-            factors.push(div);
-            rem /= div;
-        }
-        if div == 2 {
-            div += 1;
-        } else {
-            div += 2;
-        }
-    }
-    factors.push(rem);
-    factors
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use number_theory::prime_factors;
 
     const EXAMPLE_1_1: &str = "\
 RL
@@ -154,7 +121,7 @@ XXX = (XXX, XXX)
     #[test]
     fn test_least_common_multiple() {
         assert_eq!(
-            least_common_multiple(vec![19241, 16531, 21409, 18157, 14363, 19783]),
+            lcm_of(vec![19241, 16531, 21409, 18157, 14363, 19783]),
             24_035_773_251_517
         );
     }
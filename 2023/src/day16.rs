@@ -1,3 +1,4 @@
+use geometry::Point2;
 use std::collections::{BTreeSet, HashSet};
 use std::fmt::{Display, Formatter};
 
@@ -55,7 +56,7 @@ impl Grid {
             .len()
     }
     fn beams_after_tile_interacted_with(&self, beam: Beam) -> Vec<Beam> {
-        let tile = &self.grid[beam.pos.y as usize][beam.pos.x as usize];
+        let tile = &self.grid[beam.pos.y() as usize][beam.pos.x() as usize];
         match (tile, beam.dir) {
             (Empty, _) | (VerticalSplitter, Up | Down) | (HorizontalSplitter, Left | Right) => {
                 vec![beam]
@@ -72,13 +73,13 @@ impl Grid {
         .collect()
     }
     fn grid_contains(&self, pos: &Position) -> bool {
-        (0..self.width).contains(&pos.x) && (0..self.height).contains(&pos.y)
+        (0..self.width).contains(&pos.x()) && (0..self.height).contains(&pos.y())
     }
 }
 
 impl Beam {
     fn new(x: Coord, y: Coord, dir: Direction) -> Self {
-        let pos = Position { x, y };
+        let pos = Position::new(x, y);
         Beam { pos, dir }
     }
     fn turned(mut self, dir: Direction) -> Self {
@@ -86,12 +87,12 @@ impl Beam {
         self
     }
     fn moved_one_step(mut self) -> Self {
-        match &self.dir {
-            Up => self.pos.y -= 1,
-            Down => self.pos.y += 1,
-            Left => self.pos.x -= 1,
-            Right => self.pos.x += 1,
-        }
+        self.pos += match &self.dir {
+            Up => Position::new(0, -1),
+            Down => Position::new(0, 1),
+            Left => Position::new(-1, 0),
+            Right => Position::new(1, 0),
+        };
         self
     }
 }
@@ -118,11 +119,7 @@ struct Beam {
     dir: Direction,
 }
 
-#[derive(Default, Eq, PartialEq, Hash, Ord, PartialOrd, Copy, Clone)]
-struct Position {
-    x: Coord,
-    y: Coord,
-}
+type Position = Point2<Coord>;
 
 #[derive(Default, Eq, PartialEq, Hash, Clone, Copy)]
 enum Direction {
@@ -195,11 +192,6 @@ impl Display for Beam {
         write!(f, "{} @ {}", self.dir, self.pos)
     }
 }
-impl Display for Position {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({:3}, {:3})", self.x, self.y)
-    }
-}
 impl Display for Direction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1,10 +1,11 @@
-use std::collections::HashMap;
-use std::ops::RangeInclusive;
+use dot::{Graph, ToDot};
+use geometry::{BoundingBox, Point};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 
 use Category::*;
 use Inequality::*;
 use RuleResult::*;
-use RuleType::*;
 
 const INPUT: &str = include_str!("../input/day19.txt");
 
@@ -16,86 +17,205 @@ pub(crate) fn part2() -> usize {
     solve_part2(INPUT)
 }
 
+const START: &str = "in";
+const MIN_RATING: i64 = 1;
+const MAX_RATING: i64 = 4000;
+
 fn solve_part1(input: &'static str) -> usize {
-    let (workflows_by_name, mut parts) = parse(input);
-    let mut accepted = vec![];
-    while let Some(part) = parts.pop() {
-        let mut name = "in";
-        while let Some(workflow) = workflows_by_name.get(&name) {
-            match workflow
-                .rules
-                .iter()
-                .find(|rule| rule.applies_to(&part))
-                .map(|rule| rule.result)
-                .expect("fallback rule to always match")
-            {
-                Accepted => {
-                    accepted.push(part);
-                    break;
-                }
-                Rejected => break,
-                Workflow(id) => {
-                    name = id;
-                }
+    let (workflows, parts) = parse(input);
+    parts
+        .iter()
+        .filter(|part| workflows.accepts(part).unwrap())
+        .map(Part::sum_of_ratings)
+        .sum()
+}
+
+fn solve_part2(input: &'static str) -> usize {
+    let (workflows, _parts) = parse(input);
+    workflows.count_accepted(all_parts()).unwrap()
+}
+
+/// The ratings of a set of parts, one axis per category, indexed by (Category as usize)
+type PartRanges = BoundingBox<4, i64>;
+
+fn all_parts() -> PartRanges {
+    BoundingBox::new(Point::from([MIN_RATING; 4]), Point::from([MAX_RATING; 4]))
+}
+
+/// A rule, identified by the name of its workflow and its index within it
+type RuleId<'a> = (WorkflowName<'a>, usize);
+
+#[derive(Debug, Clone, PartialEq)]
+enum EngineError<'a> {
+    UnknownWorkflow(WorkflowName<'a>),
+    /// The workflows visited, up to and including the first one visited twice
+    Loop(Vec<WorkflowName<'a>>),
+}
+
+/// Where the part ranges ended up after being sent through all workflows
+#[derive(Debug, Default)]
+struct Trace<'a> {
+    accepted: Vec<PartRanges>,
+    matched: HashSet<RuleId<'a>>,
+}
+
+#[derive(Debug)]
+struct Workflows<'a> {
+    by_name: HashMap<WorkflowName<'a>, Workflow<'a>>,
+}
+
+impl<'a> Workflows<'a> {
+    fn get(&self, name: WorkflowName<'a>) -> Result<&Workflow<'a>, EngineError<'a>> {
+        self.by_name
+            .get(name)
+            .ok_or(EngineError::UnknownWorkflow(name))
+    }
+
+    fn accepts(&self, part: &Part) -> Result<bool, EngineError<'a>> {
+        let mut visited = vec![];
+        let mut name = START;
+        loop {
+            let is_loop = visited.contains(&name);
+            visited.push(name);
+            if is_loop {
+                return Err(EngineError::Loop(visited));
+            }
+            match self.get(name)?.result_for(part) {
+                Accepted => return Ok(true),
+                Rejected => return Ok(false),
+                Workflow(next) => name = next,
             }
         }
     }
-    accepted.iter().map(Part::sum_of_ratings).sum()
-}
 
-fn solve_part2(input: &'static str) -> usize {
-    let (mut workflows_by_name, _parts) = parse(input);
-    // Recursively traverse the full workflow tree, and narrow down the maximum ranges as we go
-    count_combinations("in", RangesByCategory::maximum(), &mut workflows_by_name)
-}
-
-fn count_combinations(
-    id: WorkflowName,
-    mut possible_ranges: RangesByCategory,
-    workflows_by_name: &mut HashMap<WorkflowName, Workflow>,
-) -> usize {
-    let mut workflow = workflows_by_name
-        .remove(id)
-        .expect("single target pointing to workflow");
-    let mut combination_count = 0;
-    for rule in workflow.rules.drain(..) {
-        match &rule.rule_type {
-            Conditional {
-                category,
-                condition,
-            } => {
-                // Count matched -> continue at target workflow (with the matched condition applied)
-                let new_ranges = possible_ranges.narrowed_down_by(condition, category);
-                combination_count += match rule.result {
-                    Accepted => new_ranges.convert_to_count_of_acceptable_ratings(),
-                    Workflow(id) => count_combinations(id, new_ranges, workflows_by_name),
-                    Rejected => 0,
-                };
-
-                // Count unmatched -> apply negated condition and continue with next rule
-                let inequality = condition.negated();
-                possible_ranges.ranges[*category as usize].exclude(inequality);
+    /// Disjoint boxes covering exactly the accepted parts within `ranges`
+    fn accepted_ranges(&self, ranges: PartRanges) -> Result<Vec<PartRanges>, EngineError<'a>> {
+        Ok(self.trace(ranges)?.accepted)
+    }
+
+    fn count_accepted(&self, ranges: PartRanges) -> Result<usize, EngineError<'a>> {
+        let accepted = self.accepted_ranges(ranges)?;
+        Ok(accepted.iter().map(BoundingBox::volume).sum::<i64>() as usize)
+    }
+
+    /// Rules that no part within `ranges` is ever handled by, sorted by workflow name and index.
+    /// These are shadowed by earlier rules, or in workflows that are never sent any parts.
+    #[cfg(test)]
+    fn unreachable_rules(&self, ranges: PartRanges) -> Result<Vec<RuleId<'a>>, EngineError<'a>> {
+        let matched = self.trace(ranges)?.matched;
+        let mut unreachable: Vec<_> = self
+            .by_name
+            .iter()
+            .flat_map(|(name, workflow)| (0..=workflow.rules.len()).map(|i| (*name, i)))
+            .filter(|rule| !matched.contains(rule))
+            .collect();
+        unreachable.sort_unstable();
+        Ok(unreachable)
+    }
+
+    /// Sends the ranges through the workflows, starting at "in", and splitting them on each rule
+    fn trace(&self, ranges: PartRanges) -> Result<Trace<'a>, EngineError<'a>> {
+        let mut trace = Trace::default();
+        self.trace_workflow(START, ranges, &mut vec![], &mut trace)?;
+        Ok(trace)
+    }
+
+    fn trace_workflow(
+        &self,
+        name: WorkflowName<'a>,
+        ranges: PartRanges,
+        path: &mut Vec<WorkflowName<'a>>,
+        trace: &mut Trace<'a>,
+    ) -> Result<(), EngineError<'a>> {
+        let is_loop = path.contains(&name);
+        path.push(name);
+        if is_loop {
+            return Err(EngineError::Loop(path.clone()));
+        }
+        let workflow = self.get(name)?;
+        let mut remaining = Some(ranges);
+        for (i, rule) in workflow.rules.iter().enumerate() {
+            let Some(ranges) = remaining else {
+                break;
+            };
+            let (matched, unmatched) = rule.condition.split(&ranges, rule.category);
+            if let Some(matched) = matched {
+                trace.matched.insert((name, i));
+                self.send(matched, rule.result, path, trace)?;
             }
-            UnconditionalFallBack => {
-                // No narrowing down any ranges on this last rule of a workflow
-                return combination_count
-                    + match rule.result {
-                        Accepted => possible_ranges.convert_to_count_of_acceptable_ratings(),
-                        Workflow(id) => count_combinations(id, possible_ranges, workflows_by_name),
-                        Rejected => 0,
-                    };
+            remaining = unmatched;
+        }
+        // The fallback counts as the rule after all conditional ones
+        if let Some(ranges) = remaining {
+            trace.matched.insert((name, workflow.rules.len()));
+            self.send(ranges, workflow.fallback, path, trace)?;
+        }
+        path.pop();
+        Ok(())
+    }
+
+    fn send(
+        &self,
+        ranges: PartRanges,
+        result: RuleResult<'a>,
+        path: &mut Vec<WorkflowName<'a>>,
+        trace: &mut Trace<'a>,
+    ) -> Result<(), EngineError<'a>> {
+        match result {
+            Accepted => trace.accepted.push(ranges),
+            Rejected => {}
+            Workflow(next) => self.trace_workflow(next, ranges, path, trace)?,
+        }
+        Ok(())
+    }
+
+    /// A cycle of workflows sending parts to each other, whether or not any part could
+    /// actually go all the way around it
+    #[cfg(test)]
+    fn find_loop(&self) -> Option<Vec<WorkflowName<'a>>> {
+        let mut names: Vec<_> = self.by_name.keys().copied().collect();
+        names.sort_unstable();
+        let mut done = HashSet::new();
+        names
+            .into_iter()
+            .find_map(|name| self.find_loop_from(name, &mut vec![], &mut done))
+    }
+
+    #[cfg(test)]
+    fn find_loop_from(
+        &self,
+        name: WorkflowName<'a>,
+        path: &mut Vec<WorkflowName<'a>>,
+        done: &mut HashSet<WorkflowName<'a>>,
+    ) -> Option<Vec<WorkflowName<'a>>> {
+        if let Some(start) = path.iter().position(|visited| visited == &name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name);
+            return Some(cycle);
+        }
+        if !done.insert(name) {
+            return None;
+        }
+        // Unknown workflows can't be part of a loop
+        let workflow = self.by_name.get(name)?;
+        path.push(name);
+        let results = workflow.rules.iter().map(|rule| rule.result);
+        for result in results.chain(std::iter::once(workflow.fallback)) {
+            if let Workflow(next) = result {
+                if let Some(cycle) = self.find_loop_from(next, path, done) {
+                    return Some(cycle);
+                }
             }
         }
+        path.pop();
+        None
     }
-    combination_count
 }
 
-fn parse(input: &'static str) -> (HashMap<WorkflowName<'static>, Workflow<'static>>, Vec<Part>) {
+fn parse(input: &'static str) -> (Workflows<'static>, Vec<Part>) {
     let (workflows, parts) = input.trim().split_once("\n\n").unwrap();
-    let workflows: Vec<_> = workflows.lines().map(Workflow::from).collect();
     let parts = parts.lines().map(Part::from).collect();
-    let workflows_by_name = workflows.into_iter().map(|w| (w.name, w)).collect();
-    (workflows_by_name, parts)
+    (Workflows::from(workflows), parts)
 }
 
 type Value = u16;
@@ -105,11 +225,14 @@ type WorkflowName<'a> = &'a str;
 struct Workflow<'a> {
     name: WorkflowName<'a>,
     rules: Vec<Rule<'a>>,
+    /// What happens to parts that none of the rules apply to
+    fallback: RuleResult<'a>,
 }
 
 #[derive(Debug, PartialEq)]
 struct Rule<'a> {
-    rule_type: RuleType,
+    category: Category,
+    condition: Inequality,
     result: RuleResult<'a>,
 }
 
@@ -120,15 +243,6 @@ enum RuleResult<'a> {
     Workflow(WorkflowName<'a>),
 }
 
-#[derive(Debug, PartialEq)]
-enum RuleType {
-    Conditional {
-        category: Category,
-        condition: Inequality,
-    },
-    UnconditionalFallBack,
-}
-
 #[derive(Debug, PartialEq, Copy, Clone)]
 enum Category {
     X,
@@ -151,23 +265,25 @@ struct Part {
     s: Value,
 }
 
+impl<'a> Workflow<'a> {
+    /// The result of the first rule that applies to the part, or the fallback
+    fn result_for(&self, part: &Part) -> RuleResult<'a> {
+        self.rules
+            .iter()
+            .find(|rule| rule.applies_to(part))
+            .map_or(self.fallback, |rule| rule.result)
+    }
+}
+
 impl Rule<'_> {
     fn applies_to(&self, part: &Part) -> bool {
-        match &self.rule_type {
-            Conditional {
-                category,
-                condition,
-            } => {
-                let value = &match category {
-                    X => part.x,
-                    M => part.m,
-                    A => part.a,
-                    S => part.s,
-                };
-                condition.holds_for(value)
-            }
-            UnconditionalFallBack => true,
-        }
+        let value = &match self.category {
+            X => part.x,
+            M => part.m,
+            A => part.a,
+            S => part.s,
+        };
+        self.condition.holds_for(value)
     }
 }
 
@@ -178,11 +294,29 @@ impl Inequality {
             GreaterThan(threshold) => value > threshold,
         }
     }
-    fn negated(&self) -> Self {
-        match self {
-            LessThan(value) => GreaterThan(value - 1),
-            GreaterThan(value) => LessThan(value + 1),
+
+    /// Splits the ranges into those parts for which this holds in `category`,
+    /// and those for which it doesn't. Either may be empty.
+    fn split(
+        &self,
+        ranges: &PartRanges,
+        category: Category,
+    ) -> (Option<PartRanges>, Option<PartRanges>) {
+        let axis = category as usize;
+        let (mut matched, mut unmatched) = (*ranges, *ranges);
+        match *self {
+            LessThan(threshold) => {
+                matched.max[axis] = matched.max[axis].min(threshold as i64 - 1);
+                unmatched.min[axis] = unmatched.min[axis].max(threshold as i64);
+            }
+            GreaterThan(threshold) => {
+                matched.min[axis] = matched.min[axis].max(threshold as i64 + 1);
+                unmatched.max[axis] = unmatched.max[axis].min(threshold as i64);
+            }
         }
+        let non_empty =
+            |ranges: PartRanges| (ranges.min[axis] <= ranges.max[axis]).then_some(ranges);
+        (non_empty(matched), non_empty(unmatched))
     }
 }
 
@@ -192,82 +326,119 @@ impl Part {
     }
 }
 
-#[derive(Clone, Debug)]
-struct RangesByCategory {
-    // indexed by (Category as usize)
-    ranges: [RangeInclusive<Value>; 4],
-}
-
-impl RangesByCategory {
-    fn narrowed_down_by(&self, inequality: &Inequality, category: &Category) -> Self {
-        let mut new_ranges = self.clone();
-        new_ranges.ranges[*category as usize].exclude(*inequality);
-        new_ranges
+impl Display for EngineError<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EngineError::UnknownWorkflow(name) => write!(f, "There's no workflow named {name}"),
+            EngineError::Loop(path) => {
+                write!(f, "Parts go round in circles: {}", path.join(" -> "))
+            }
+        }
     }
-    fn convert_to_count_of_acceptable_ratings(self) -> usize {
-        self.ranges
-            .iter()
-            .map(RangeInclusive::len)
-            .product::<usize>()
+}
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            X => 'x',
+            M => 'm',
+            A => 'a',
+            S => 's',
+        };
+        write!(f, "{c}")
     }
-    fn maximum() -> Self {
-        Self {
-            ranges: [1..=4000, 1..=4000, 1..=4000, 1..=4000],
+}
+impl Display for Inequality {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LessThan(value) => write!(f, "<{value}"),
+            GreaterThan(value) => write!(f, ">{value}"),
         }
     }
 }
-trait ExcludeRange {
-    fn exclude(&mut self, inequality: Inequality);
-}
 
-impl ExcludeRange for RangeInclusive<Value> {
-    fn exclude(&mut self, inequality: Inequality) {
-        match inequality {
-            LessThan(value) => {
-                if self.contains(&value) {
-                    *self = *self.start()..=(value - 1);
-                }
-            }
-            GreaterThan(value) => {
-                if self.contains(&value) {
-                    *self = value + 1..=*self.end();
-                }
+impl ToDot for Workflows<'_> {
+    fn to_dot(&self) -> String {
+        let mut graph = Graph::directed("workflows");
+        graph.attribute("rankdir", "LR");
+        graph.node("A", &[("shape", &"doublecircle"), ("color", &"green")]);
+        graph.node("R", &[("shape", &"doublecircle"), ("color", &"red")]);
+        let mut names: Vec<_> = self.by_name.keys().collect();
+        names.sort_unstable();
+        for name in names {
+            let shape = if *name == START { "house" } else { "box" };
+            graph.node(name, &[("shape", &shape)]);
+            let workflow = &self.by_name[name];
+            for rule in &workflow.rules {
+                let label = format!("{}{}", rule.category, rule.condition);
+                graph.edge(name, rule.result.node(), &[("label", &label)]);
             }
+            graph.edge(name, workflow.fallback.node(), &[("style", &"dashed")]);
         }
+        graph.to_dot()
     }
 }
 
+impl From<&'static str> for Workflows<'_> {
+    fn from(input: &'static str) -> Self {
+        let by_name = input
+            .lines()
+            .map(Workflow::from)
+            .map(|workflow| (workflow.name, workflow))
+            .collect();
+        Workflows { by_name }
+    }
+}
 impl From<&'static str> for Workflow<'_> {
     fn from(line: &'static str) -> Self {
         // px{a<2006:qkq,m>2090:A,rfg}
         let (name, rest) = line.strip_suffix('}').unwrap().split_once('{').unwrap();
-        let parts: Vec<_> = rest.split(',').collect();
-        let rules = parts.into_iter().map(Rule::from).collect();
-        Workflow { name, rules }
+        let (rules, fallback) = match rest.rsplit_once(',') {
+            Some((rules, fallback)) => (rules.split(',').map(Rule::from).collect(), fallback),
+            None => (vec![], rest),
+        };
+        // Every part has to go somewhere, so the last rule must not have a condition
+        assert!(
+            !fallback.contains(':'),
+            "Workflow {name} does not end with a fallback rule"
+        );
+        let fallback = RuleResult::from(fallback);
+        Workflow {
+            name,
+            rules,
+            fallback,
+        }
     }
 }
 impl From<&'static str> for Rule<'_> {
     fn from(line: &'static str) -> Self {
-        // a<2006:qkq or rfg
-        let (rule_type, target) = if let Some((condition, target)) = line.split_once(':') {
-            let category = Category::from(&condition[..1]);
-            let condition = Inequality::from(&condition[1..]);
-            (
-                Conditional {
-                    category,
-                    condition,
-                },
-                target,
-            )
-        } else {
-            (UnconditionalFallBack, line)
+        // a<2006:qkq
+        let Some((condition, target)) = line.split_once(':') else {
+            unreachable!("Rule without condition {line}");
         };
-        let result = match target {
+        Rule {
+            category: Category::from(&condition[..1]),
+            condition: Inequality::from(&condition[1..]),
+            result: RuleResult::from(target),
+        }
+    }
+}
+impl<'a> RuleResult<'a> {
+    /// The name of the DOT node this result leads to
+    fn node(&self) -> &'a str {
+        match self {
+            Accepted => "A",
+            Rejected => "R",
+            Workflow(next) => next,
+        }
+    }
+}
+impl From<&'static str> for RuleResult<'_> {
+    fn from(target: &'static str) -> Self {
+        match target {
             "A" => Accepted,
             "R" => Rejected,
             id => Workflow(id),
-        };
-        Rule { rule_type, result }
+        }
     }
 }
 impl From<&str> for Category {
@@ -340,26 +511,33 @@ hdj{m>838:A,pv}
                 name: "px",
                 rules: vec![
                     Rule {
-                        rule_type: Conditional {
-                            category: A,
-                            condition: LessThan(2006)
-                        },
+                        category: A,
+                        condition: LessThan(2006),
                         result: Workflow("qkq")
                     },
                     Rule {
-                        rule_type: Conditional {
-                            category: M,
-                            condition: GreaterThan(2090)
-                        },
+                        category: M,
+                        condition: GreaterThan(2090),
                         result: Accepted
                     },
-                    Rule {
-                        rule_type: UnconditionalFallBack,
-                        result: Workflow("rfg")
-                    }
                 ],
+                fallback: Workflow("rfg"),
             }
         );
+        assert_eq!(
+            Workflow::from("never{A}"),
+            Workflow {
+                name: "never",
+                rules: vec![],
+                fallback: Accepted,
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Workflow in does not end with a fallback rule")]
+    fn test_parsing_a_workflow_without_fallback() {
+        let _ = Workflow::from("in{x<10:A,s>5:R}");
     }
 
     #[test]
@@ -375,6 +553,105 @@ hdj{m>838:A,pv}
         );
     }
 
+    #[test]
+    fn test_evaluating_single_parts() {
+        let (workflows, parts) = parse(EXAMPLE);
+        let accepted: Vec<_> = parts
+            .iter()
+            .map(|part| workflows.accepts(part).unwrap())
+            .collect();
+        assert_eq!(vec![true, false, true, false, true], accepted);
+    }
+
+    #[test]
+    fn test_accepted_ranges_are_disjoint_boxes() {
+        let (workflows, _parts) = parse(EXAMPLE);
+        let accepted = workflows.accepted_ranges(all_parts()).unwrap();
+        for (i, a) in accepted.iter().enumerate() {
+            for b in &accepted[i + 1..] {
+                assert!(a.intersection(b).is_none());
+            }
+        }
+        let total: i64 = accepted.iter().map(BoundingBox::volume).sum();
+        assert_eq!(167_409_079_868_000, total);
+    }
+
+    #[test]
+    fn test_counting_within_narrower_ranges() {
+        let workflows = Workflows::from("in{x<10:A,m>5:R,A}");
+        let ranges = BoundingBox::new(Point::from([1, 1, 1, 1]), Point::from([20, 10, 1, 1]));
+        // x in 1..=9 for any m, plus x in 10..=20 with m in 1..=5
+        assert_eq!(9 * 10 + 11 * 5, workflows.count_accepted(ranges).unwrap());
+    }
+
+    #[test]
+    fn test_unreachable_rules() {
+        let workflows = Workflows::from("in{x<10:A,x<5:R,s>9:ok,R}\nok{s<5:R,A}\nnever{A}");
+        assert_eq!(
+            vec![("in", 1), ("never", 0), ("ok", 0)],
+            workflows.unreachable_rules(all_parts()).unwrap()
+        );
+        let (workflows, _parts) = parse(EXAMPLE);
+        assert!(workflows.unreachable_rules(all_parts()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_loops_and_unknown_workflows() {
+        let part = Part::from("{x=1,m=2,a=3,s=4}");
+        let workflows = Workflows::from("in{x<10:a,R}\na{b}\nb{m>1:a,A}");
+        assert_eq!(Some(vec!["a", "b", "a"]), workflows.find_loop());
+        let expected = EngineError::Loop(vec!["in", "a", "b", "a"]);
+        assert_eq!(Err(expected.clone()), workflows.accepts(&part));
+        assert_eq!(Err(expected), workflows.count_accepted(all_parts()));
+
+        // Structurally a loop, but no part can go round it
+        let workflows = Workflows::from("in{x<10:a,R}\na{x>20:in,A}");
+        assert_eq!(Some(vec!["a", "in", "a"]), workflows.find_loop());
+        assert_eq!(
+            Ok(9 * 4000 * 4000 * 4000),
+            workflows.count_accepted(all_parts())
+        );
+
+        let workflows = Workflows::from("in{x<10:nope,A}");
+        assert_eq!(
+            Err(EngineError::UnknownWorkflow("nope")),
+            workflows.accepts(&part)
+        );
+        assert_eq!(
+            "There's no workflow named nope",
+            workflows.accepts(&part).unwrap_err().to_string()
+        );
+        assert_eq!(None, workflows.find_loop());
+
+        // Referring to the same unknown workflow twice is not a loop
+        let workflows = Workflows::from("in{x<10:nope,s<5:nope,A}");
+        assert_eq!(None, workflows.find_loop());
+        assert_eq!(
+            Err(EngineError::UnknownWorkflow("nope")),
+            workflows.count_accepted(all_parts())
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let workflows = Workflows::from("in{s<1351:px,A}\npx{a>2006:R,A}");
+        assert_eq!(
+            r#"digraph "workflows" {
+    rankdir="LR";
+    "A" [shape="doublecircle", color="green"];
+    "R" [shape="doublecircle", color="red"];
+    "in" [shape="house"];
+    "px" [shape="box"];
+    "in" -> "px" [label="s<1351"];
+    "in" -> "A" [style="dashed"];
+    "px" -> "R" [label="a>2006"];
+    "px" -> "A" [style="dashed"];
+}
+"#,
+            workflows.to_dot()
+        );
+    }
+
     #[test]
     fn test_part1_example() {
         assert_eq!(19_114, solve_part1(EXAMPLE));
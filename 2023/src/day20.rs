@@ -1,3 +1,5 @@
+use dot::{Graph, ToDot};
+use number_theory::combine;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{Display, Formatter};
 use std::ops::{Add, AddAssign};
@@ -36,50 +38,265 @@ fn solve_part1(input: &'static str) -> usize {
 }
 
 fn solve_part2(input: &'static str) -> usize {
-    // The "rx" module did not produce a low output within a reasonable time frame,
-    // so I analyzed the modules. There's a single Conjunction module leading to "rx":
-    // &vf -> rx
-
-    // Got get "rx" low, all "vf"'s inputs must be high, at the same time.
-    // "vf"'s inputs are:
-    // &pm -> vf
-    // &mk -> vf
-    // &pk -> vf
-    // &hf -> vf
-
-    // Each of them produces a "high" pulse periodically (around ~4k button presses),
-    // but each of their periods is different and all are prime numbers,
-    // so all of them are high only after lcm(3881,3889,4013,4021) button presses,
-    // which in this case (periods are prime numbers) is equal to the product of their periods
-
-    let signals: Vec<_> = ["pm", "mk", "pk", "hf"]
-        .into_iter()
-        .map(|name| InMessage::new(name, High))
+    // The "rx" module does not receive a low pulse within a reasonable number of button presses,
+    // but the network is made up of independent counters that can be analyzed separately
+    match first_low_pulse_press(input, "rx") {
+        Ok(prediction) => prediction.press,
+        Err(e) => panic!("{e}"),
+    }
+}
+
+/// Give up looking for a counter's period after this many button presses
+const MAX_PERIOD: usize = 1 << 16;
+
+/// A group of modules driven by one of the broadcaster's outputs, independent of all other
+/// groups, whose single output feeds the conjunction in front of the target module
+#[derive(Debug, PartialEq)]
+struct Counter {
+    entry: ModuleName,
+    output: ModuleName,
+    /// The number of button presses after which the counter's state starts repeating
+    cycle_start: usize,
+    period: usize,
+    /// The button presses, up to `cycle_start + period`, that make the output send a high pulse
+    firing_presses: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Prediction {
+    press: usize,
+    counters: Vec<Counter>,
+}
+
+/// Explains why the network does not fit the assumptions of [`first_low_pulse_press`]
+#[derive(Debug, PartialEq)]
+enum AnalysisError {
+    UnknownModule(ModuleName),
+    NotASingleFeeder(Vec<ModuleName>),
+    FeederNotAConjunction(ModuleName),
+    SharedModule {
+        module: ModuleName,
+        entries: [ModuleName; 2],
+    },
+    NotASingleOutput {
+        entry: ModuleName,
+        outputs: Vec<ModuleName>,
+    },
+    UncountedInput(ModuleName),
+    NoPeriod(ModuleName),
+    NeverFires(ModuleName),
+    OutputStaysHigh {
+        output: ModuleName,
+        press: usize,
+    },
+    NoCommonPress,
+}
+
+/// Predicts the first button press that delivers a low pulse to the `target` module,
+/// without simulating the whole network for that many presses.
+///
+/// This relies on the structure of the puzzle input:
+/// - the target is fed by a single conjunction, the collector, which sends a low pulse
+///   once all of its inputs have most recently sent it a high pulse,
+/// - each of the broadcaster's outputs starts an independent group of modules (a counter)
+///   with a single output into the collector,
+/// - each counter's output sends a high pulse, followed by a low pulse within the same
+///   button press, in a periodic pattern.
+///
+/// Each counter is simulated on its own until its state repeats, and the presses on which
+/// all counters fire together are found by combining their periods with the CRT.
+fn first_low_pulse_press(
+    input: &'static str,
+    target: ModuleName,
+) -> Result<Prediction, AnalysisError> {
+    let modules = parse_module_types(input);
+    let module_by_name: HashMap<ModuleName, &ModuleType> = modules
+        .iter()
+        .map(|module| (module.name(), module))
+        .collect();
+    for name in [target, BROADCASTER] {
+        if !module_by_name.contains_key(name) {
+            return Err(AnalysisError::UnknownModule(name));
+        }
+    }
+    let feeders: Vec<_> = modules
+        .iter()
+        .filter(|module| module.outputs().contains(&target))
+        .map(ModuleType::name)
         .collect();
-    let mut periods = vec![None; 4];
+    let [collector] = feeders[..] else {
+        return Err(AnalysisError::NotASingleFeeder(feeders));
+    };
+    let Conjunction(_, collector_inputs, _) = module_by_name[collector] else {
+        return Err(AnalysisError::FeederNotAConjunction(collector));
+    };
+
+    // Check the structure before simulating anything
+    let mut entry_by_module: HashMap<ModuleName, ModuleName> = HashMap::new();
+    let mut groups = vec![];
+    for entry in module_by_name[BROADCASTER].outputs() {
+        let group = modules_reachable_from(entry, collector, &module_by_name);
+        let mut members: Vec<_> = group.iter().copied().collect();
+        members.sort_unstable();
+        for module in members {
+            if let Some(other) = entry_by_module.insert(module, entry) {
+                let entries = [other, entry];
+                return Err(AnalysisError::SharedModule { module, entries });
+            }
+        }
+        let mut outputs: Vec<_> = group
+            .iter()
+            .filter(|name| module_by_name[*name].outputs().contains(&collector))
+            .copied()
+            .collect();
+        outputs.sort_unstable();
+        let [output] = outputs[..] else {
+            return Err(AnalysisError::NotASingleOutput { entry, outputs });
+        };
+        groups.push((entry, output, group));
+    }
+    let mut inputs: Vec<_> = collector_inputs.keys().copied().collect();
+    inputs.sort_unstable();
+    if let Some(input) = inputs
+        .into_iter()
+        .find(|input| !groups.iter().any(|(_, output, _)| output == input))
+    {
+        return Err(AnalysisError::UncountedInput(input));
+    }
 
-    let mut modules_by_name = parse_modules_by_name(input);
-    for button_press_count in 1usize..5000 {
-        let mut inputs = vec![OutMessage::from_button_press()];
-        while !inputs.is_empty() {
-            inputs = process(inputs, &mut modules_by_name);
-            inputs.iter().for_each(|out| {
-                for (i, wanted) in signals.iter().enumerate() {
-                    if wanted == &out.signal && periods[i].is_none() {
-                        periods[i] = Some(button_press_count);
-                    }
+    let counters = groups
+        .into_iter()
+        .map(|(entry, output, group)| simulate_counter(input, &group, entry, output, collector))
+        .collect::<Result<Vec<_>, _>>()?;
+    let press = first_common_firing_press(&counters).ok_or(AnalysisError::NoCommonPress)?;
+    Ok(Prediction { press, counters })
+}
+
+/// All modules reachable from `entry` without passing through `collector`
+fn modules_reachable_from(
+    entry: ModuleName,
+    collector: ModuleName,
+    module_by_name: &HashMap<ModuleName, &ModuleType>,
+) -> HashSet<ModuleName> {
+    let mut reachable = HashSet::from([entry]);
+    let mut pending = vec![entry];
+    while let Some(name) = pending.pop() {
+        for output in module_by_name[name].outputs() {
+            if output != collector && reachable.insert(output) {
+                pending.push(output);
+            }
+        }
+    }
+    reachable
+}
+
+fn simulate_counter(
+    input: &'static str,
+    group: &HashSet<ModuleName>,
+    entry: ModuleName,
+    output: ModuleName,
+    collector: ModuleName,
+) -> Result<Counter, AnalysisError> {
+    let mut modules_by_name: HashMap<_, _> = parse_modules_by_name(input)
+        .into_iter()
+        .filter(|(name, _)| group.contains(name))
+        .collect();
+    let mut press_by_state = HashMap::from([(state_of(&modules_by_name), 0)]);
+    let mut firing_presses = vec![];
+    let mut remembered = Low;
+    for press in 1..=MAX_PERIOD {
+        let mut messages = vec![OutMessage::new(BROADCASTER, Low, entry)];
+        while !messages.is_empty() {
+            messages = process(messages, &mut modules_by_name);
+            for message in messages.iter().filter(|m| m.destination == collector) {
+                remembered = message.signal.pulse;
+                if remembered == High && firing_presses.last() != Some(&press) {
+                    firing_presses.push(press);
                 }
+            }
+        }
+        if remembered == High {
+            return Err(AnalysisError::OutputStaysHigh { output, press });
+        }
+        if let Some(cycle_start) = press_by_state.insert(state_of(&modules_by_name), press) {
+            if firing_presses.is_empty() {
+                return Err(AnalysisError::NeverFires(entry));
+            }
+            return Ok(Counter {
+                entry,
+                output,
+                cycle_start,
+                period: press - cycle_start,
+                firing_presses,
             });
-            if periods.iter().all(|p| p.is_some()) {
-                return periods.into_iter().flatten().product::<usize>();
+        }
+    }
+    Err(AnalysisError::NoPeriod(entry))
+}
+
+/// The on-states of the flip-flops and the remembered pulses of the conjunctions
+fn state_of(modules_by_name: &HashMap<ModuleName, Module>) -> Vec<bool> {
+    let mut names: Vec<_> = modules_by_name.keys().collect();
+    names.sort_unstable();
+    let mut state = vec![];
+    for name in names {
+        match &modules_by_name[name].mod_type {
+            FlipFlop(_, on_off, _) => state.push(on_off == &State::On),
+            Conjunction(_, inputs, _) => {
+                let mut inputs: Vec<_> = inputs.iter().collect();
+                inputs.sort_unstable_by_key(|(input, _)| **input);
+                state.extend(inputs.into_iter().map(|(_, pulse)| pulse == &High));
             }
+            Broadcaster(_) | Untyped(_) => {}
         }
     }
-    unreachable!()
+    state
+}
+
+impl Counter {
+    fn fires_at(&self, press: usize) -> bool {
+        let press = if press > self.cycle_start + self.period {
+            self.cycle_start + 1 + (press - self.cycle_start - 1) % self.period
+        } else {
+            press
+        };
+        self.firing_presses.contains(&press)
+    }
+}
+
+fn first_common_firing_press(counters: &[Counter]) -> Option<usize> {
+    // Before all counters are cycling, try every press
+    let all_cycling = counters.iter().map(|c| c.cycle_start).max()?;
+    if let Some(press) = (1..=all_cycling).find(|&press| counters.iter().all(|c| c.fires_at(press)))
+    {
+        return Some(press);
+    }
+    // Afterwards, each counter fires on presses congruent to one of its firing presses
+    let mut congruences = vec![(0, 1)];
+    for counter in counters {
+        let residues: Vec<_> = counter
+            .firing_presses
+            .iter()
+            .filter(|&&press| press > counter.cycle_start)
+            .map(|&press| (press % counter.period, counter.period))
+            .collect();
+        congruences = congruences
+            .into_iter()
+            .flat_map(|congruence| residues.iter().filter_map(move |&r| combine(congruence, r)))
+            .collect();
+    }
+    congruences
+        .into_iter()
+        .map(|(residue, modulus)| {
+            // The smallest press after all_cycling that satisfies the congruence
+            residue + (all_cycling + modulus - residue) / modulus * modulus
+        })
+        .min()
 }
 
 fn parse_modules_by_name(input: &'static str) -> HashMap<ModuleName, Module> {
-    parse_module_types(input)
+    Configuration::from(input)
+        .0
         .into_iter()
         .map(|mod_type| (mod_type.name(), Module::from(mod_type)))
         .collect()
@@ -299,6 +516,35 @@ fn parse_module_types(input: &'static str) -> Vec<ModuleType> {
     modules
 }
 
+/// The parsed module configuration, in input order
+struct Configuration(Vec<ModuleType>);
+
+impl From<&'static str> for Configuration {
+    fn from(input: &'static str) -> Self {
+        Configuration(parse_module_types(input))
+    }
+}
+
+impl ToDot for Configuration {
+    fn to_dot(&self) -> String {
+        let mut graph = Graph::directed("modules");
+        graph.edge(BUTTON, BROADCASTER, &[]);
+        for module in &self.0 {
+            let (label, shape) = match module {
+                Broadcaster(_) => (BROADCASTER.to_string(), "doubleoctagon"),
+                FlipFlop(name, ..) => (format!("%{name}"), "box"),
+                Conjunction(name, ..) => (format!("&{name}"), "invhouse"),
+                Untyped(name) => (name.to_string(), "doublecircle"),
+            };
+            graph.node(module.name(), &[("label", &label), ("shape", &shape)]);
+            for output in module.outputs() {
+                graph.edge(module.name(), output, &[]);
+            }
+        }
+        graph.to_dot()
+    }
+}
+
 impl From<ModuleType> for Module {
     fn from(mod_type: ModuleType) -> Self {
         Module {
@@ -320,6 +566,50 @@ impl From<&'static str> for ModuleType {
     }
 }
 
+impl Display for AnalysisError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalysisError::UnknownModule(name) => write!(f, "there is no module {name}"),
+            AnalysisError::NotASingleFeeder(feeders) => write!(
+                f,
+                "the target should be fed by a single conjunction, but is fed by [{}]",
+                feeders.join(", ")
+            ),
+            AnalysisError::FeederNotAConjunction(name) => {
+                write!(f, "the target's only input {name} is not a conjunction")
+            }
+            AnalysisError::SharedModule { module, entries } => write!(
+                f,
+                "the counters starting at {} and {} are not independent, both reach {module}",
+                entries[0], entries[1]
+            ),
+            AnalysisError::NotASingleOutput { entry, outputs } => write!(
+                f,
+                "the counter starting at {entry} should have a single output into the collector, but has [{}]",
+                outputs.join(", ")
+            ),
+            AnalysisError::UncountedInput(name) => {
+                write!(f, "the collector input {name} is not driven by any counter")
+            }
+            AnalysisError::NoPeriod(entry) => write!(
+                f,
+                "the counter starting at {entry} did not repeat within {MAX_PERIOD} presses"
+            ),
+            AnalysisError::NeverFires(entry) => write!(
+                f,
+                "the counter starting at {entry} never sends a high pulse to the collector"
+            ),
+            AnalysisError::OutputStaysHigh { output, press } => write!(
+                f,
+                "the counter output {output} is still high at the end of button press {press}"
+            ),
+            AnalysisError::NoCommonPress => {
+                write!(f, "the counters never fire during the same button press")
+            }
+        }
+    }
+}
+
 impl Display for OutMessage {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}-> {}", self.signal, self.destination)
@@ -409,6 +699,161 @@ broadcaster -> a
         );
     }
 
+    #[test]
+    fn test_example2_to_dot() {
+        let dot = Configuration::from(EXAMPLE_2).to_dot();
+        assert_eq!(
+            "digraph \"modules\" {
+    \"broadcaster\" [label=\"broadcaster\", shape=\"doubleoctagon\"];
+    \"a\" [label=\"%a\", shape=\"box\"];
+    \"inv\" [label=\"&inv\", shape=\"invhouse\"];
+    \"b\" [label=\"%b\", shape=\"box\"];
+    \"con\" [label=\"&con\", shape=\"invhouse\"];
+    \"output\" [label=\"output\", shape=\"doublecircle\"];
+    \"button\" -> \"broadcaster\";
+    \"broadcaster\" -> \"a\";
+    \"a\" -> \"inv\";
+    \"a\" -> \"con\";
+    \"inv\" -> \"b\";
+    \"b\" -> \"con\";
+    \"con\" -> \"output\";
+}
+",
+            dot
+        );
+    }
+
+    /// Three counters with periods 3, 5 and 7 feeding the collector c
+    const COUNTERS: &str = "\
+broadcaster -> a0, b0, d0
+%a0 -> a1, ha
+%a1 -> ha
+&ha -> ia, a0
+&ia -> c
+%b0 -> b1, hb
+%b1 -> b2
+%b2 -> hb
+&hb -> ib, b0, b1
+&ib -> c
+%d0 -> d1, hd
+%d1 -> d2, hd
+%d2 -> hd
+&hd -> id, d0
+&id -> c
+&c -> rx
+";
+
+    /// Simulates the whole network until the target receives the pulse
+    fn brute_force_first_low_pulse_press(input: &'static str, target: ModuleName) -> usize {
+        let mut modules_by_name = parse_modules_by_name(input);
+        for press in 1.. {
+            let mut messages = vec![OutMessage::from_button_press()];
+            while !messages.is_empty() {
+                messages = process(messages, &mut modules_by_name);
+                if messages
+                    .iter()
+                    .any(|m| m.destination == target && m.signal.pulse == Low)
+                {
+                    return press;
+                }
+            }
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn test_first_low_pulse_press_to_counters_with_prime_periods() {
+        let prediction = first_low_pulse_press(COUNTERS, "rx").unwrap();
+        assert_eq!(3 * 5 * 7, prediction.press);
+        assert_eq!(
+            brute_force_first_low_pulse_press(COUNTERS, "rx"),
+            prediction.press
+        );
+        let periods: Vec<_> = prediction.counters.iter().map(|c| c.period).collect();
+        assert_eq!(vec![3, 5, 7], periods);
+        assert_eq!(
+            Counter {
+                entry: "a0",
+                output: "ia",
+                cycle_start: 1,
+                period: 3,
+                firing_presses: vec![3],
+            },
+            prediction.counters[0]
+        );
+    }
+
+    #[test]
+    fn test_first_low_pulse_press_to_counters_with_common_factors() {
+        let input = "\
+broadcaster -> a0, b0
+%a0 -> a1, ha
+%a1 -> a2
+%a2 -> a3
+%a3 -> ha
+&ha -> ia, a0, a1, a2
+&ia -> c
+%b0 -> b1, hb
+%b1 -> b2, hb
+%b2 -> b3, hb
+%b3 -> hb
+&hb -> ib, b0
+&ib -> c
+&c -> rx
+";
+        let prediction = first_low_pulse_press(input, "rx").unwrap();
+        assert_eq!(45, prediction.press);
+        assert_eq!(
+            brute_force_first_low_pulse_press(input, "rx"),
+            prediction.press
+        );
+    }
+
+    #[test]
+    fn test_first_low_pulse_press_explains_unsupported_structures() {
+        assert_eq!(
+            Err(AnalysisError::UnknownModule("nope")),
+            first_low_pulse_press(COUNTERS, "nope")
+        );
+        assert_eq!(
+            Err(AnalysisError::NotASingleFeeder(vec!["a0", "a1"])),
+            first_low_pulse_press(COUNTERS, "ha")
+        );
+        assert_eq!(
+            Err(AnalysisError::FeederNotAConjunction("a0")),
+            first_low_pulse_press(COUNTERS, "a1")
+        );
+        assert_eq!(
+            Err(AnalysisError::FeederNotAConjunction("c")),
+            first_low_pulse_press(EXAMPLE_1, "inv")
+        );
+        assert_eq!(
+            Err(AnalysisError::NotASingleOutput {
+                entry: "a",
+                outputs: vec!["a", "b"]
+            }),
+            first_low_pulse_press(EXAMPLE_2, "output")
+        );
+        assert_eq!(
+            Err(AnalysisError::SharedModule {
+                module: "i",
+                entries: ["a", "b"]
+            }),
+            first_low_pulse_press(
+                "broadcaster -> a, b\n%a -> m\n%b -> m\n&m -> i\n&i -> c\n&c -> rx",
+                "rx"
+            )
+        );
+        // The collector remembers the flip-flop's high pulse until the next press
+        assert_eq!(
+            Err(AnalysisError::OutputStaysHigh {
+                output: "a",
+                press: 1
+            }),
+            first_low_pulse_press("broadcaster -> a\n%a -> c\n&c -> rx", "rx")
+        );
+    }
+
     #[test]
     fn test_part1_example1() {
         assert_eq!(8000 * 4000, solve_part1(EXAMPLE_1));
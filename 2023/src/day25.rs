@@ -1,3 +1,4 @@
+use dot::ToDot;
 #[allow(unused_imports)]
 use petgraph::{algo, Graph};
 use std::collections::{HashMap, HashSet};
@@ -14,7 +15,7 @@ type NodeIdx = usize;
 type Edge = (EdgeIdx, EdgeIdx);
 
 fn solve_part1_with_graphviz(input: &str) -> usize {
-    let (nodes, edges) = parse(input);
+    let Wiring { nodes, edges } = Wiring::from(input);
     let node_by_index: Vec<Node> = nodes.into_iter().collect();
     let index_by_node: HashMap<Node, NodeIdx> = node_by_index
         .iter()
@@ -193,6 +194,41 @@ fn parse(input: &str) -> (HashSet<&str>, HashMap<&str, HashSet<&str>>) {
     (nodes, edges)
 }
 
+/// The components and their wires, as listed in the input
+struct Wiring<'a> {
+    nodes: HashSet<Node<'a>>,
+    edges: HashMap<Node<'a>, HashSet<Node<'a>>>,
+}
+
+impl<'a> From<&'a str> for Wiring<'a> {
+    fn from(input: &'a str) -> Self {
+        let (nodes, edges) = parse(input);
+        Wiring { nodes, edges }
+    }
+}
+
+/// Renders the wires as undirected edges, laid out so that the three wires connecting
+/// the two groups of components stand out
+impl ToDot for Wiring<'_> {
+    fn to_dot(&self) -> String {
+        let mut graph = dot::Graph::undirected("wiring");
+        graph.attribute("layout", "neato");
+        let mut nodes: Vec<_> = self.nodes.iter().collect();
+        nodes.sort_unstable();
+        for from in nodes {
+            let Some(tos) = self.edges.get(from) else {
+                continue;
+            };
+            let mut tos: Vec<_> = tos.iter().collect();
+            tos.sort_unstable();
+            for to in tos {
+                graph.edge(from, to, &[]);
+            }
+        }
+        graph.to_dot()
+    }
+}
+
 #[cfg(test)]
 fn solve_part1_brute_force_with_petgraph(input: &str) -> usize {
     let (nodes, edges) = parse(input);
@@ -314,6 +350,14 @@ rzs: qnr cmg lsr rsh
 frs: qnr lhk lsr
 ";
 
+    #[test]
+    fn test_example_to_dot() {
+        let dot = Wiring::from(EXAMPLE).to_dot();
+        assert!(dot.starts_with("graph \"wiring\" {\n    layout=\"neato\";\n"));
+        assert!(dot.contains("    \"bvb\" -- \"hfx\";\n    \"bvb\" -- \"xhk\";\n"));
+        assert_eq!(33, dot.matches(" -- ").count());
+    }
+
     #[test]
     fn test_part1_example_brute_force_home_brew() {
         assert_eq!(6 * 9, solve_part1_brute_force_home_brew(EXAMPLE));
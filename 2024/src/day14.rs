@@ -1,4 +1,5 @@
 use crate::vec_2d::Vec2D;
+use crate::vec_tile_grid::VecTileGrid;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 
@@ -91,15 +92,15 @@ fn solve_part2(input: &str, grid_size: &Vec2D) -> usize {
 
 #[expect(unused)]
 fn to_string(robots: &[Robot], grid_size: &Vec2D) -> String {
+    to_grid(robots, grid_size).to_string()
+}
+
+fn to_grid(robots: &[Robot], grid_size: &Vec2D) -> VecTileGrid<char> {
     let mut chars = vec![vec![' '; grid_size.x as usize]; grid_size.y as usize];
     for robot in robots.iter() {
         chars[robot.p.y as usize][robot.p.x as usize] = '#';
     }
-    chars
-        .iter()
-        .map(|line| line.iter().collect::<String>())
-        .collect::<Vec<String>>()
-        .join("\n")
+    VecTileGrid { chars }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -175,6 +176,7 @@ impl ConstrainedGridOps for Vec2D {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use image_export::{Image, Rgb};
 
     const EXAMPLE_GRID_SIZE: Vec2D = Vec2D { x: 11, y: 7 };
 
@@ -198,6 +200,27 @@ p=9,5 v=-3,-3
         assert_eq!(12, solve_part1(EXAMPLE, &EXAMPLE_GRID_SIZE));
     }
 
+    fn to_image(robots: &[Robot], grid_size: &Vec2D) -> Image {
+        let palette = |c: &char| {
+            if c == &'#' {
+                Rgb(0, 160, 0)
+            } else {
+                Rgb::BLACK
+            }
+        };
+        Image::from_grid(&to_grid(robots, grid_size), &palette, 4)
+    }
+
+    #[test]
+    fn test_to_image() {
+        let robots: Vec<_> = parse(EXAMPLE).collect();
+        let image = to_image(&robots, &EXAMPLE_GRID_SIZE);
+        assert_eq!((44, 28), (image.width(), image.height()));
+        // Robot at p=0,0 is drawn as a 4x4 square, the tile to its right is empty
+        assert_eq!(Some(Rgb(0, 160, 0)), image.pixel_at(3, 3));
+        assert_eq!(Some(Rgb::BLACK), image.pixel_at(4, 0));
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(228_410_028, solve_part1(INPUT, &GRID_SIZE));
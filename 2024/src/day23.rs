@@ -163,6 +163,41 @@ fn parse_pairs_into_connections(input: &str) -> HashMap<Name, Tuple> {
     connections
 }
 
+/// The LAN, for rendering with [`dot::ToDot`]
+#[cfg(test)]
+struct Network<'a> {
+    connections: HashMap<Name<'a>, Tuple<'a>>,
+}
+
+#[cfg(test)]
+impl<'a> From<&'a str> for Network<'a> {
+    fn from(input: &'a str) -> Self {
+        Network {
+            connections: parse_pairs_into_connections(input),
+        }
+    }
+}
+
+/// Renders the connections as undirected edges, with the computers starting with a `t` filled
+#[cfg(test)]
+impl dot::ToDot for Network<'_> {
+    fn to_dot(&self) -> String {
+        let mut graph = dot::Graph::undirected("network");
+        graph.attribute("layout", "neato");
+        let mut names: Vec<_> = self.connections.keys().copied().collect();
+        names.sort_unstable();
+        for name in names {
+            if name.starts_with('t') {
+                graph.node(name, &[("style", &"filled")]);
+            }
+            for &other in self.connections[name].iter().filter(|&&o| name < o) {
+                graph.edge(name, other, &[]);
+            }
+        }
+        graph.to_dot()
+    }
+}
+
 fn parse_pairs_into_connections2(input: &str) -> (Vec<&str>, Vec<Vec<usize>>) {
     let pairs: Vec<_> = input
         .trim()
@@ -205,6 +240,7 @@ fn parse_pairs_into_tuple_set(input: &str) -> TupleSet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use dot::ToDot;
 
     const EXAMPLE: &str = "\
 kh-tc
@@ -241,6 +277,16 @@ tb-vc
 td-yn
 ";
 
+    #[test]
+    fn test_example_to_dot() {
+        let dot = Network::from(EXAMPLE).to_dot();
+        assert!(dot.starts_with("graph \"network\" {\n    layout=\"neato\";\n"));
+        assert!(dot.contains("\"ta\" [style=\"filled\"];"));
+        assert!(!dot.contains("\"kh\" [style"));
+        assert!(dot.contains("\"kh\" -- \"tc\";"));
+        assert_eq!(EXAMPLE.lines().count(), dot.matches(" -- ").count());
+    }
+
     #[test]
     fn test_part1_example() {
         assert_eq!(7, solve_part1(EXAMPLE));
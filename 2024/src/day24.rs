@@ -1,301 +1,42 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt::{Debug, Formatter};
+use circuit::{check_ripple_carry_adder, read_number, Circuit};
 
 const INPUT: &str = include_str!("../../2024/input/day24.txt");
-const EXPECTATION_FUNC: BinaryPredicate = |x, y| x + y;
-type BinaryPredicate = fn(usize, usize) -> usize;
 
 pub fn part1() -> usize {
     solve_part1(INPUT)
 }
 
 pub fn part2() -> String {
-    solve_part2(INPUT, EXPECTATION_FUNC)
+    solve_part2(INPUT)
 }
 
 fn solve_part1(input: &str) -> usize {
-    let (gate_by_name, inputs_by_name) = parse(input);
-    gate_by_name
-        .calculate_outputs(&inputs_by_name)
-        .sum_up_z_bits()
+    let circuit = parse(input);
+    let values = circuit.evaluate().expect("a circuit without cycles");
+    read_number(&values, 'z') as usize
 }
 
-fn solve_part2(input: &str, expectation_func: BinaryPredicate) -> String {
-    let (gate_by_name, inputs_by_name) = parse(input);
-    gate_by_name.list_outputs_to_swap(inputs_by_name, expectation_func)
+fn solve_part2(input: &str) -> String {
+    let circuit = parse(input);
+    // The circuit is meant to be a ripple-carry adder, so a structural check
+    // reveals the wires that were swapped
+    let miswirings = check_ripple_carry_adder(&circuit);
+    miswirings
+        .into_iter()
+        .map(|miswiring| miswiring.wire)
+        .collect::<Vec<_>>()
+        .join(",")
 }
 
-type WireName<'n> = &'n str;
-struct GateByName<'n>(HashMap<WireName<'n>, Gate<'n>>);
-type ValueByName<'n> = HashMap<WireName<'n>, bool>;
-struct OutputByName<'n>(ValueByName<'n>);
-
-#[derive(Debug)]
-struct Gate<'n> {
-    input1: WireName<'n>,
-    op: Operation,
-    input2: WireName<'n>,
-}
-
-#[derive(Ord, PartialOrd, Eq, PartialEq, Clone)]
-enum Operation {
-    And,
-    Or,
-    Xor,
-}
-
-fn parse(input: &str) -> (GateByName, ValueByName) {
-    let (wires, gates) = input.split_once("\n\n").unwrap();
-
-    let inputs_by_name: HashMap<WireName, bool> = wires
-        .lines()
-        .filter_map(|line| line.split_once(": "))
-        .map(|(name, value)| (name, value == "1"))
-        .collect();
-
-    let gate_by_name = gates
-        .lines()
-        .map(|line| {
-            // Example: x00 AND y00 -> z00
-            let parts: Vec<_> = line.split_whitespace().collect();
-            let input1 = parts[0];
-            let op = Operation::from(parts[1]);
-            let input2 = parts[2];
-            let output = parts[4];
-            (output, Gate { input1, op, input2 })
-        })
-        .collect();
-    (GateByName(gate_by_name), inputs_by_name)
-}
-
-impl From<&str> for Operation {
-    fn from(op: &str) -> Self {
-        match op {
-            "AND" => Operation::And,
-            "OR" => Operation::Or,
-            "XOR" => Operation::Xor,
-            _ => unreachable!("Illegal op {op}"),
-        }
-    }
-}
-impl Operation {
-    fn apply(&self, lhs: bool, rhs: bool) -> bool {
-        match self {
-            Operation::And => lhs & rhs,
-            Operation::Or => lhs | rhs,
-            Operation::Xor => lhs ^ rhs,
-        }
-    }
-}
-impl Debug for Operation {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Operation::And => "AND",
-                Operation::Or => "OR ",
-                Operation::Xor => "XOR",
-            }
-        )
-    }
-}
-
-impl<'n> GateByName<'n> {
-    fn calculate_outputs(&'n self, inputs: &'n ValueByName<'n>) -> OutputByName<'n> {
-        let mut outputs = ValueByName::new();
-        for &name in self.0.keys().filter(|name| name.starts_with('z')) {
-            self.value_of_wire(name, inputs, &mut outputs);
-        }
-        OutputByName(outputs)
-    }
-
-    fn value_of_wire(
-        &'n self,
-        name: WireName<'n>,
-        inputs: &ValueByName<'n>,
-        outputs: &mut ValueByName<'n>,
-    ) -> bool {
-        if name.starts_with(['x', 'y']) {
-            inputs[&name]
-        } else if let Some(value) = outputs.get(name) {
-            *value
-        } else {
-            let Gate { input1, op, input2 } = &self.0[name];
-            let value1 = self.value_of_wire(input1, inputs, outputs);
-            let value2 = self.value_of_wire(input2, inputs, outputs);
-            let value = op.apply(value1, value2);
-            outputs.insert(name, value);
-            value
-        }
-    }
-
-    // Includes all ancestors of the given gate (not including the gate itself)
-    fn ancestors_of(&self, name: WireName<'n>) -> HashSet<WireName<'n>> {
-        let mut ancestors = HashSet::new();
-        if let Some(Gate { input1, input2, .. }) = self.0.get(name) {
-            if !input1.starts_with(['x', 'y']) && !ancestors.contains(input1) {
-                ancestors.insert(*input1);
-                ancestors.extend(self.ancestors_of(input1));
-            }
-            if !input2.starts_with(['x', 'y']) && !ancestors.contains(input2) {
-                ancestors.insert(*input2);
-                ancestors.extend(self.ancestors_of(input2));
-            }
-        }
-        ancestors
-    }
-
-    fn list_outputs_to_swap(
-        mut self,
-        mut inputs: ValueByName<'n>,
-        expectation_func: BinaryPredicate,
-    ) -> String {
-        let mut swaps: Vec<String> = vec![];
-
-        while let Some(idx) = self.index_of_first_error(&mut inputs, expectation_func) {
-            // There's an error at index `idx`, there must be some misconfigured gates nearby
-
-            let z_at = |idx| {
-                self.0
-                    .keys()
-                    .find(|&name| *name == format!("z{idx:02}").as_str())
-                    .unwrap()
-            };
-
-            // The misconfiguration must be after the previous and before the next index
-            let curr_z = z_at(idx);
-            let next_z = z_at(idx + 1);
-            let prev_z = z_at(idx - 1);
-            let mut next_ancestors = self.ancestors_of(next_z);
-            // Add the current output gate, because it could be missing
-            // from the next output gate's ancestors due to a misconfiguration
-            next_ancestors.insert(curr_z);
-            let prev_ancestors = self.ancestors_of(prev_z);
-            let candidates = next_ancestors
-                .difference(&prev_ancestors)
-                .cloned()
-                .collect();
-
-            // Find the correct pair to swap by trial and error
-            swaps.extend(self.try_swapping_to_find_correct_pair(
-                &mut inputs,
-                candidates,
-                expectation_func,
-                idx,
-            ));
-        }
-        swaps.sort_unstable();
-        swaps.join(",")
-    }
-
-    fn index_of_first_error(
-        &self,
-        inputs: &mut ValueByName,
-        expectation_func: BinaryPredicate,
-    ) -> Option<usize> {
-        let input_len = inputs.len() / 2; // inputs contains both x and y
-        let set_input_bit = |prefix: char, val: usize, inputs: &mut ValueByName| {
-            for (name, bit) in inputs
-                .iter_mut()
-                .filter(|(name, _)| name.starts_with(prefix))
-            {
-                let idx: usize = name.strip_prefix(['x', 'y']).unwrap().parse().unwrap();
-                *bit = (val & (1 << idx)) != 0;
-            }
-        };
-
-        for idx in 0..input_len - 1 {
-            for (x, y) in [(0, 1), (1, 0), (1, 1)] {
-                let x = x << idx;
-                let y = y << idx;
-                set_input_bit('x', x, inputs);
-                set_input_bit('y', y, inputs);
-                let actual = self.calculate_outputs(inputs).sum_up_z_bits();
-                let expected = expectation_func(x, y);
-                let diff = actual ^ expected;
-                if actual != expected {
-                    return (0usize..).find(|idx| diff & (1 << *idx) != 0);
-                }
-            }
-        }
-        None
-    }
-
-    fn try_swapping_to_find_correct_pair<'a>(
-        &mut self,
-        inputs_by_name: &'a mut ValueByName<'n>,
-        candidates: Vec<WireName<'n>>,
-        expectation_func: BinaryPredicate,
-        prev_idx: usize,
-    ) -> Vec<String> {
-        let mut swaps: Vec<_> = vec![];
-        for (i, gate1) in candidates.iter().enumerate().take(candidates.len() - 1) {
-            for gate2 in candidates.iter().skip(i + 1) {
-                self.swap(gate1, gate2);
-
-                if !self.contains_cycle(gate1)
-                    && !self.contains_cycle(gate2)
-                    && self
-                        .index_of_first_error(inputs_by_name, expectation_func)
-                        .is_none_or(|curr_idx| curr_idx > prev_idx)
-                {
-                    // Fails later or not at all
-                    swaps.push(gate1.to_string());
-                    swaps.push(gate2.to_string());
-                    return swaps;
-                }
-                // Revert
-                self.swap(gate1, gate2);
-            }
-        }
-        unreachable!();
-    }
-
-    fn swap(&mut self, name1: WireName<'n>, name2: WireName<'n>) {
-        let gate1 = self.0.remove(name1).unwrap();
-        let gate2 = self.0.remove(name2).unwrap();
-        self.0.insert(name2, gate1);
-        self.0.insert(name1, gate2);
-    }
-
-    fn contains_cycle(&self, name: WireName) -> bool {
-        self.is_node_its_own_child(name, &mut HashSet::new())
-    }
-
-    fn is_node_its_own_child(
-        &self,
-        name: WireName<'n>,
-        children: &mut HashSet<WireName<'n>>,
-    ) -> bool {
-        if name.starts_with(['x', 'y']) {
-            return false;
-        }
-        if !children.insert(name) {
-            return true;
-        }
-        if let Some(Gate { input1, input2, .. }) = &self.0.get(name) {
-            self.is_node_its_own_child(input1, children)
-                || self.is_node_its_own_child(input2, children)
-        } else {
-            false
-        }
-    }
-}
-
-impl OutputByName<'_> {
-    fn sum_up_z_bits(self) -> usize {
-        self.0
-            .into_iter()
-            .filter(|(name, bit)| name.starts_with('z') && *bit)
-            .map(|(name, _)| 2usize.pow(name.strip_prefix("z").unwrap().parse().unwrap()))
-            .sum()
-    }
+fn parse(input: &str) -> Circuit {
+    Circuit::parse(input, 1).expect("valid gates")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use circuit::CircuitError;
+    use std::collections::HashSet;
 
     const SMALL_EXAMPLE_1: &str = "\
 x00: 1
@@ -420,34 +161,34 @@ z10: 1
 z11: 0
 z12: 0";
 
-        let (gate_by_name, inputs_by_name) = parse(EXAMPLE_1);
-
-        let expected_values: HashMap<WireName, bool> = expected_gate_values
+        let values = parse(EXAMPLE_1).evaluate().unwrap();
+        for (name, value) in expected_gate_values
             .lines()
             .map(|line| line.split_once(": ").unwrap())
-            .map(|(name, value)| (name, value == "1"))
-            .collect();
-
-        let actual_values = gate_by_name.calculate_outputs(&inputs_by_name);
-        for name in gate_by_name.0.keys() {
-            let expected = &expected_values[name];
-            let actual = actual_values.0[name];
-            assert_eq!(expected, &actual);
+        {
+            assert_eq!(value == "1", values[name] == 1, "wire {name}");
         }
     }
 
     #[test]
     fn test_ancestors_of() {
-        let (gate_by_name, _) = parse(INPUT);
-        let z01_deps = gate_by_name.ancestors_of("z01");
+        let circuit = parse(INPUT);
+        let z01_deps: HashSet<_> = circuit
+            .ancestors_of("z01")
+            .into_iter()
+            .filter(|name| !name.starts_with(['x', 'y']))
+            .collect();
         assert_eq!(z01_deps, HashSet::from(["mkf", "msh"]));
     }
 
     #[test]
     fn test_cycle_detection() {
-        let (mut gate_by_name, _) = parse(INPUT);
-        gate_by_name.swap("z11", "cgn"); // this produces a cycle
-        assert!(!gate_by_name.contains_cycle("z11"));
-        assert!(gate_by_name.contains_cycle("z12"));
+        let mut circuit = parse(INPUT);
+        circuit.swap_outputs("z11", "cgn"); // this produces a cycle
+        let Err(CircuitError::Cycle(wires)) = circuit.topological_order() else {
+            panic!("expected a cycle");
+        };
+        assert!(wires.contains(&"z12".to_string()));
+        assert!(!wires.contains(&"z11".to_string()));
     }
 }
@@ -34,6 +34,18 @@ impl<T> TileGrid<T> for HashTileGrid<T> {
     }
 }
 
+impl<T> image_export::Tiles<T> for HashTileGrid<T> {
+    fn width(&self) -> usize {
+        TileGrid::width(self)
+    }
+    fn height(&self) -> usize {
+        TileGrid::height(self)
+    }
+    fn tile_at(&self, x: usize, y: usize) -> Option<&T> {
+        self.chars.get(&Vec2D::new(x, y))
+    }
+}
+
 impl<T> From<&str> for HashTileGrid<T>
 where
     T: From<char>,
@@ -39,6 +39,18 @@ impl<T> TileGrid<T> for VecTileGrid<T> {
     }
 }
 
+impl<T> image_export::Tiles<T> for VecTileGrid<T> {
+    fn width(&self) -> usize {
+        TileGrid::width(self)
+    }
+    fn height(&self) -> usize {
+        TileGrid::height(self)
+    }
+    fn tile_at(&self, x: usize, y: usize) -> Option<&T> {
+        self.chars.get(y).and_then(|line| line.get(x))
+    }
+}
+
 impl<T> From<&str> for VecTileGrid<T>
 where
     T: From<char>,
@@ -1,3 +1,4 @@
+use geometry::IntervalSet;
 use std::ops::RangeInclusive;
 
 const INPUT: &str = include_str!("../../2025/input/day05.txt");
@@ -13,30 +14,16 @@ pub fn part2() -> usize {
 
 fn solve_part1(input: &str) -> usize {
     let (id_ranges, available_ids) = parse(input);
+    let fresh_ids = IntervalSet::from_iter(id_ranges);
     available_ids
         .iter()
-        .filter(|&&id| id_ranges.iter().any(|range| range.contains(&id)))
+        .filter(|id| fresh_ids.contains(id))
         .count()
 }
 
 fn solve_part2(input: &str) -> usize {
-    let (mut id_ranges, _) = parse(input);
-    id_ranges.sort_unstable_by_key(|range| *range.start());
-
-    let mut total = 0;
-    let mut curr = id_ranges.remove(0);
-    for next in id_ranges.drain(..) {
-        if curr.contains(next.start()) {
-            if curr.end() < next.end() {
-                curr = *curr.start()..=*next.end();
-            }
-        } else {
-            total += curr.end() - curr.start() + 1;
-            curr = next;
-        }
-    }
-    total += curr.end() - curr.start() + 1;
-    total
+    let (id_ranges, _) = parse(input);
+    IntervalSet::from_iter(id_ranges).len()
 }
 
 fn parse(input: &str) -> (Vec<RangeInclusive<Id>>, Vec<Id>) {
@@ -1,4 +1,4 @@
-use crate::vec_3d::Vec3D;
+use geometry::Point3;
 use std::collections::HashSet;
 
 const INPUT: &str = include_str!("../../2025/input/day08.txt");
@@ -30,21 +30,21 @@ fn solve_part2(input: &str) -> usize {
 
     let (_, final_connection) = solve(&positions, pair_count);
     let (a, b) = final_connection.unwrap();
-    (positions[a].x * positions[b].x) as usize
+    (positions[a].x() * positions[b].x()) as usize
 }
 
-fn parse_junction_box_positions(input: &str) -> Vec<Vec3D> {
+fn parse_junction_box_positions(input: &str) -> Vec<Point3> {
     input
         .trim()
         .lines()
         .map(|line| {
             let n: Vec<_> = line.split(',').map(|n| n.parse().unwrap()).collect();
-            Vec3D::new(n[0], n[1], n[2])
+            Point3::new(n[0], n[1], n[2])
         })
         .collect()
 }
 
-fn solve(positions: &[Vec3D], pair_count: usize) -> (Vec<HashSet<usize>>, Option<(usize, usize)>) {
+fn solve(positions: &[Point3], pair_count: usize) -> (Vec<HashSet<usize>>, Option<(usize, usize)>) {
     let ordered_position_index_pairs = order_into_pairs_by_ascending_distance(positions);
 
     // The first index is the circuit index, starting from 0.
@@ -80,14 +80,13 @@ fn solve(positions: &[Vec3D], pair_count: usize) -> (Vec<HashSet<usize>>, Option
     (circuits, final_connection)
 }
 
-fn order_into_pairs_by_ascending_distance(positions: &[Vec3D]) -> Vec<(usize, usize)> {
+fn order_into_pairs_by_ascending_distance(positions: &[Point3]) -> Vec<(usize, usize)> {
     let len = positions.len();
     let mut distances: Vec<((usize, usize), usize)> = Vec::with_capacity(len * (len - 1));
     for a in 0..len - 1 {
         for b in a + 1..len {
             let pair = if a < b { (a, b) } else { (b, a) };
-            let diff = positions[a] - positions[b];
-            let distance_squared = (diff.x * diff.x + diff.y * diff.y + diff.z * diff.z) as usize;
+            let distance_squared = positions[a].squared_euclidean_distance(&positions[b]) as usize;
             distances.push((pair, distance_squared));
         }
     }
@@ -12,5 +12,4 @@ pub mod day11;
 pub mod day12;
 pub mod tile_grid;
 pub mod vec_2d;
-pub mod vec_3d;
 pub mod vec_tile_grid;
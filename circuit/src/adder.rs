@@ -0,0 +1,151 @@
+use crate::{Circuit, Gate, Operand};
+use std::fmt::{Display, Formatter};
+
+/// A wire whose gate doesn't fit its place in a ripple-carry adder
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Miswiring {
+    pub wire: String,
+    pub reason: &'static str,
+}
+
+/// Checks that the circuit has the structure of a ripple-carry adder adding the numbers on the
+/// `xNN` and `yNN` wires into the `zNN` wires. Each bit `i > 0` of such an adder consists of
+///
+/// - `x_i XOR y_i -> s_i` and `x_i AND y_i -> a_i` (half adder on the inputs)
+/// - `s_i XOR c_i -> z_i` and `s_i AND c_i -> b_i` (half adder with the incoming carry)
+/// - `a_i OR b_i -> c_i+1` (outgoing carry)
+///
+/// Bit 0 has no incoming carry, so `x00 XOR y00 -> z00` and `x00 AND y00 -> c_1`,
+/// and the final carry is the highest output bit.
+///
+/// Returns the wires whose gate breaks one of these rules, sorted by name. Swapping the outputs
+/// of misplaced gates shows up here as both wires being reported.
+pub fn check_ripple_carry_adder(circuit: &Circuit) -> Vec<Miswiring> {
+    let last_output = circuit
+        .wires()
+        .filter(|wire| is_output(wire))
+        .max()
+        .map(str::to_string);
+    let mut miswirings = vec![];
+    let mut report = |wire: &str, reason| {
+        miswirings.push(Miswiring {
+            wire: wire.to_string(),
+            reason,
+        })
+    };
+    for wire in circuit.wires() {
+        let gate = circuit.gate(wire).unwrap();
+        let is_last_output = Some(wire) == last_output.as_deref();
+        let has_input_operands = gate.inputs().iter().any(|op| is_input(op));
+        let is_first_bit = gate
+            .inputs()
+            .iter()
+            .any(|op| matches!(op, Operand::Wire(w) if w == "x00" || w == "y00"));
+        let feeds = |predicate: fn(&Gate) -> bool| {
+            circuit
+                .consumers_of(wire)
+                .any(|(_, consumer)| predicate(consumer))
+        };
+        match gate {
+            Gate::Buffer(Operand::Constant(_)) if is_input_wire(wire) => {}
+            _ if is_last_output && !matches!(gate, Gate::Or(..)) => {
+                report(wire, "the final carry must be the output of an OR gate")
+            }
+            _ if is_output(wire) && !is_last_output && !matches!(gate, Gate::Xor(..)) => {
+                report(wire, "output bits must be the output of an XOR gate")
+            }
+            Gate::Xor(..) if !has_input_operands && !is_output(wire) => {
+                report(wire, "an XOR of the carry must drive an output bit")
+            }
+            Gate::Xor(..) if has_input_operands && !is_first_bit && !feeds(is_xor) => {
+                report(wire, "an XOR of the inputs must feed an XOR with the carry")
+            }
+            Gate::And(..) if !is_first_bit && !feeds(is_or) => {
+                report(wire, "an AND must feed an OR computing the next carry")
+            }
+            Gate::Xor(..) | Gate::And(..) | Gate::Or(..) => {}
+            _ => report(wire, "only AND, OR and XOR gates belong in an adder"),
+        }
+    }
+    miswirings.sort_unstable();
+    miswirings
+}
+
+fn is_output(wire: &str) -> bool {
+    wire.starts_with('z')
+}
+
+fn is_input_wire(wire: &str) -> bool {
+    wire.starts_with(['x', 'y'])
+}
+
+fn is_input(operand: &Operand) -> bool {
+    operand.wire().is_some_and(is_input_wire)
+}
+
+fn is_xor(gate: &Gate) -> bool {
+    matches!(gate, Gate::Xor(..))
+}
+
+fn is_or(gate: &Gate) -> bool {
+    matches!(gate, Gate::Or(..))
+}
+
+impl Display for Miswiring {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.wire, self.reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_number;
+
+    /// Builds an adder of two `bits`-bit numbers with the given inputs
+    fn adder(bits: usize, x: u64, y: u64) -> Circuit {
+        let mut lines = vec![];
+        for i in 0..bits {
+            lines.push(format!("x{i:02}: {}", (x >> i) & 1));
+            lines.push(format!("y{i:02}: {}", (y >> i) & 1));
+        }
+        lines.push("x00 XOR y00 -> z00".to_string());
+        lines.push("x00 AND y00 -> c01".to_string());
+        for i in 1..bits {
+            let carry_out = if i + 1 == bits {
+                format!("z{bits:02}")
+            } else {
+                format!("c{:02}", i + 1)
+            };
+            lines.push(format!("x{i:02} XOR y{i:02} -> s{i:02}"));
+            lines.push(format!("x{i:02} AND y{i:02} -> a{i:02}"));
+            lines.push(format!("s{i:02} XOR c{i:02} -> z{i:02}"));
+            lines.push(format!("s{i:02} AND c{i:02} -> b{i:02}"));
+            lines.push(format!("a{i:02} OR b{i:02} -> {carry_out}"));
+        }
+        Circuit::parse(&lines.join("\n"), 1).unwrap()
+    }
+
+    #[test]
+    fn test_correct_adder() {
+        let circuit = adder(6, 45, 29);
+        assert!(check_ripple_carry_adder(&circuit).is_empty());
+        let values = circuit.evaluate().unwrap();
+        assert_eq!(45 + 29, read_number(&values, 'z'));
+    }
+
+    #[test]
+    fn test_swapped_outputs_are_named() {
+        for (wire1, wire2) in [("z03", "b03"), ("s02", "a02"), ("z04", "c04")] {
+            let mut circuit = adder(6, 0, 0);
+            circuit.swap_outputs(wire1, wire2);
+            let wires: Vec<_> = check_ripple_carry_adder(&circuit)
+                .into_iter()
+                .map(|m| m.wire)
+                .collect();
+            let mut expected = vec![wire1, wire2];
+            expected.sort_unstable();
+            assert_eq!(expected, wires);
+        }
+    }
+}
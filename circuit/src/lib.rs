@@ -0,0 +1,436 @@
+//! Simulation of circuits made of named wires, each driven by a single gate.
+//!
+//! Values are words of a configurable width: 16 bits for the 2015 day 7 instruction booklet,
+//! a single bit for the 2024 day 24 gate network.
+
+mod adder;
+
+pub use adder::{check_ripple_carry_adder, Miswiring};
+
+use dot::{Graph, ToDot};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+
+pub type Value = u64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Wire(String),
+    Constant(Value),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Gate {
+    Buffer(Operand),
+    Not(Operand),
+    And(Operand, Operand),
+    Or(Operand, Operand),
+    Xor(Operand, Operand),
+    LShift(Operand, u32),
+    RShift(Operand, u32),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CircuitError {
+    Parse(String),
+    UndefinedWire(String),
+    /// The wires forming the cycle, each driving the next and the last one driving the first
+    Cycle(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Circuit {
+    gates: BTreeMap<String, Gate>,
+    word_width: u32,
+}
+
+impl Operand {
+    fn parse(s: &str) -> Operand {
+        s.parse()
+            .map(Operand::Constant)
+            .unwrap_or_else(|_| Operand::Wire(s.to_string()))
+    }
+    pub fn wire(&self) -> Option<&str> {
+        match self {
+            Operand::Wire(name) => Some(name),
+            Operand::Constant(_) => None,
+        }
+    }
+}
+
+impl Gate {
+    /// The operands feeding this gate. Shift amounts are not operands.
+    pub fn inputs(&self) -> Vec<&Operand> {
+        match self {
+            Gate::Buffer(a) | Gate::Not(a) | Gate::LShift(a, _) | Gate::RShift(a, _) => vec![a],
+            Gate::And(a, b) | Gate::Or(a, b) | Gate::Xor(a, b) => vec![a, b],
+        }
+    }
+    pub fn input_wires(&self) -> impl Iterator<Item = &str> {
+        self.inputs().into_iter().filter_map(Operand::wire)
+    }
+    fn apply(&self, value_of: impl Fn(&Operand) -> Value, mask: Value) -> Value {
+        let value = match self {
+            Gate::Buffer(a) => value_of(a),
+            Gate::Not(a) => !value_of(a),
+            Gate::And(a, b) => value_of(a) & value_of(b),
+            Gate::Or(a, b) => value_of(a) | value_of(b),
+            Gate::Xor(a, b) => value_of(a) ^ value_of(b),
+            Gate::LShift(a, n) => value_of(a).checked_shl(*n).unwrap_or(0),
+            Gate::RShift(a, n) => value_of(a).checked_shr(*n).unwrap_or(0),
+        };
+        value & mask
+    }
+}
+
+impl Circuit {
+    pub fn new(word_width: u32) -> Self {
+        assert!(
+            (1..=Value::BITS).contains(&word_width),
+            "Word width must be between 1 and {}",
+            Value::BITS
+        );
+        Circuit {
+            gates: BTreeMap::new(),
+            word_width,
+        }
+    }
+
+    /// Parses lines such as `x AND y -> z`, `NOT x -> y`, `x LSHIFT 2 -> y`, `123 -> x`
+    /// or `x00: 1`. Empty lines are skipped.
+    pub fn parse(input: &str, word_width: u32) -> Result<Circuit, CircuitError> {
+        let mut circuit = Circuit::new(word_width);
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (output, gate) = Circuit::parse_line(line)?;
+            if circuit.gates.contains_key(output) {
+                return Err(CircuitError::Parse(format!(
+                    "Wire {output} is driven twice"
+                )));
+            }
+            circuit.set(output, gate);
+        }
+        Ok(circuit)
+    }
+
+    fn parse_line(line: &str) -> Result<(&str, Gate), CircuitError> {
+        let error = || CircuitError::Parse(format!("Invalid line '{line}'"));
+        if let Some((wire, value)) = line.split_once(": ") {
+            let value = value.parse().map_err(|_| error())?;
+            return Ok((wire, Gate::Buffer(Operand::Constant(value))));
+        }
+        let (gate, output) = line.split_once(" -> ").ok_or_else(error)?;
+        let parts: Vec<_> = gate.split_ascii_whitespace().collect();
+        let gate = match parts[..] {
+            [a] => Gate::Buffer(Operand::parse(a)),
+            ["NOT", a] => Gate::Not(Operand::parse(a)),
+            [a, "AND", b] => Gate::And(Operand::parse(a), Operand::parse(b)),
+            [a, "OR", b] => Gate::Or(Operand::parse(a), Operand::parse(b)),
+            [a, "XOR", b] => Gate::Xor(Operand::parse(a), Operand::parse(b)),
+            [a, "LSHIFT", n] => Gate::LShift(Operand::parse(a), n.parse().map_err(|_| error())?),
+            [a, "RSHIFT", n] => Gate::RShift(Operand::parse(a), n.parse().map_err(|_| error())?),
+            _ => return Err(error()),
+        };
+        Ok((output.trim(), gate))
+    }
+
+    pub fn word_width(&self) -> u32 {
+        self.word_width
+    }
+
+    /// Drives `wire` with `gate`, replacing any gate already driving it
+    pub fn set(&mut self, wire: impl Into<String>, gate: Gate) {
+        self.gates.insert(wire.into(), gate);
+    }
+
+    pub fn gate(&self, wire: &str) -> Option<&Gate> {
+        self.gates.get(wire)
+    }
+
+    /// All wires driven by a gate, in alphabetical order
+    pub fn wires(&self) -> impl Iterator<Item = &str> {
+        self.gates.keys().map(String::as_str)
+    }
+
+    /// The wires whose gates take `wire` as input, along with those gates
+    pub fn consumers_of<'a>(&'a self, wire: &'a str) -> impl Iterator<Item = (&'a str, &'a Gate)> {
+        self.gates
+            .iter()
+            .filter(move |(_, gate)| gate.input_wires().any(|input| input == wire))
+            .map(|(output, gate)| (output.as_str(), gate))
+    }
+
+    /// All wires that `wire` depends on, directly or indirectly
+    pub fn ancestors_of(&self, wire: &str) -> HashSet<&str> {
+        let mut ancestors = HashSet::new();
+        let mut queue: Vec<_> = self.gate(wire).into_iter().collect();
+        while let Some(gate) = queue.pop() {
+            for input in gate.input_wires() {
+                if let Some((input, gate)) = self.gates.get_key_value(input) {
+                    if ancestors.insert(input.as_str()) {
+                        queue.push(gate);
+                    }
+                }
+            }
+        }
+        ancestors
+    }
+
+    /// Exchanges the gates driving the two wires
+    pub fn swap_outputs(&mut self, wire1: &str, wire2: &str) {
+        let gate1 = self.gates.remove(wire1).expect("wire 1 to exist");
+        let gate2 = self.gates.remove(wire2).expect("wire 2 to exist");
+        self.gates.insert(wire1.to_string(), gate2);
+        self.gates.insert(wire2.to_string(), gate1);
+    }
+
+    /// Orders the wires such that each one comes after all the wires its gate depends on
+    pub fn topological_order(&self) -> Result<Vec<&str>, CircuitError> {
+        let mut pending_inputs: HashMap<&str, usize> = HashMap::new();
+        let mut consumers: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (wire, gate) in &self.gates {
+            for input in gate.input_wires() {
+                if !self.gates.contains_key(input) {
+                    return Err(CircuitError::UndefinedWire(input.to_string()));
+                }
+                *pending_inputs.entry(wire).or_default() += 1;
+                consumers.entry(input).or_default().push(wire);
+            }
+        }
+        let mut ready: VecDeque<&str> = self
+            .wires()
+            .filter(|wire| !pending_inputs.contains_key(wire))
+            .collect();
+        let mut order = Vec::with_capacity(self.gates.len());
+        while let Some(wire) = ready.pop_front() {
+            order.push(wire);
+            for &consumer in consumers.get(wire).into_iter().flatten() {
+                let pending = pending_inputs.get_mut(consumer).unwrap();
+                *pending -= 1;
+                if *pending == 0 {
+                    pending_inputs.remove(consumer);
+                    ready.push_back(consumer);
+                }
+            }
+        }
+        if pending_inputs.is_empty() {
+            Ok(order)
+        } else {
+            Err(CircuitError::Cycle(self.find_cycle(&pending_inputs)))
+        }
+    }
+
+    /// Walks backwards from any of the `unresolved` wires until a wire repeats
+    fn find_cycle(&self, unresolved: &HashMap<&str, usize>) -> Vec<String> {
+        let mut wire = *unresolved.keys().min().unwrap();
+        let mut path: Vec<&str> = vec![];
+        while !path.contains(&wire) {
+            path.push(wire);
+            wire = self.gates[wire]
+                .input_wires()
+                .find(|input| unresolved.contains_key(input))
+                .unwrap();
+        }
+        let start = path.iter().position(|&w| w == wire).unwrap();
+        // The path follows inputs, reverse it to follow the signal instead
+        let mut cycle: Vec<_> = path[start..].iter().rev().map(|w| w.to_string()).collect();
+        let first = (0..cycle.len()).min_by_key(|&i| &cycle[i]).unwrap();
+        cycle.rotate_left(first);
+        cycle
+    }
+
+    /// Calculates the value of every wire
+    pub fn evaluate(&self) -> Result<HashMap<String, Value>, CircuitError> {
+        let mask = Value::MAX >> (Value::BITS - self.word_width);
+        let mut values: HashMap<String, Value> = HashMap::with_capacity(self.gates.len());
+        for wire in self.topological_order()? {
+            let value = self.gates[wire].apply(
+                |operand| match operand {
+                    Operand::Wire(input) => values[input],
+                    Operand::Constant(value) => *value,
+                },
+                mask,
+            );
+            values.insert(wire.to_string(), value);
+        }
+        Ok(values)
+    }
+}
+
+/// Renders the circuit with an edge from every input to its consumer
+impl ToDot for Circuit {
+    fn to_dot(&self) -> String {
+        let mut graph = Graph::directed("circuit");
+        graph.attribute("rankdir", "LR");
+        for (wire, gate) in &self.gates {
+            let shape = match gate {
+                Gate::Buffer(Operand::Constant(_)) => "box",
+                _ => "ellipse",
+            };
+            let label = format!("{wire}\n{gate}");
+            graph.node(wire, &[("label", &label), ("shape", &shape)]);
+            for input in gate.input_wires() {
+                graph.edge(input, wire, &[]);
+            }
+        }
+        graph.to_dot()
+    }
+}
+
+/// Combines the values of wires named `<prefix><bit index>`, such as `z00`, `z01`, …, into a number
+pub fn read_number(values: &HashMap<String, Value>, prefix: char) -> u64 {
+    values
+        .iter()
+        .filter_map(|(wire, value)| {
+            let bit: u32 = wire.strip_prefix(prefix)?.parse().ok()?;
+            Some((value & 1) << bit)
+        })
+        .sum()
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Wire(name) => write!(f, "{name}"),
+            Operand::Constant(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Display for Gate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Gate::Buffer(a) => write!(f, "{a}"),
+            Gate::Not(a) => write!(f, "NOT {a}"),
+            Gate::And(a, b) => write!(f, "{a} AND {b}"),
+            Gate::Or(a, b) => write!(f, "{a} OR {b}"),
+            Gate::Xor(a, b) => write!(f, "{a} XOR {b}"),
+            Gate::LShift(a, n) => write!(f, "{a} LSHIFT {n}"),
+            Gate::RShift(a, n) => write!(f, "{a} RSHIFT {n}"),
+        }
+    }
+}
+
+impl Display for CircuitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitError::Parse(msg) => write!(f, "{msg}"),
+            CircuitError::UndefinedWire(wire) => write!(f, "Wire {wire} is not driven by any gate"),
+            CircuitError::Cycle(wires) => write!(f, "Cycle through wires {}", wires.join(" -> ")),
+        }
+    }
+}
+
+impl std::error::Error for CircuitError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOKLET: &str = "\
+123 -> x
+456 -> y
+x AND y -> d
+x OR y -> e
+x LSHIFT 2 -> f
+y RSHIFT 2 -> g
+NOT x -> h
+NOT y -> i";
+
+    #[test]
+    fn test_evaluate_16_bit_booklet() {
+        let values = Circuit::parse(BOOKLET, 16).unwrap().evaluate().unwrap();
+        let expected = [
+            ("d", 72),
+            ("e", 507),
+            ("f", 492),
+            ("g", 114),
+            ("h", 65412),
+            ("i", 65079),
+            ("x", 123),
+            ("y", 456),
+        ];
+        for (wire, value) in expected {
+            assert_eq!(value, values[wire], "wire {wire}");
+        }
+    }
+
+    #[test]
+    fn test_evaluate_single_bit_gates() {
+        let input = "\
+x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+";
+        let values = Circuit::parse(input, 1).unwrap().evaluate().unwrap();
+        assert_eq!(4, read_number(&values, 'z'));
+        assert_eq!(7, read_number(&values, 'x'));
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let circuit = Circuit::parse("b -> c\n1 -> a\na AND c -> d\na -> b", 16).unwrap();
+        assert_eq!(
+            vec!["a", "b", "c", "d"],
+            circuit.topological_order().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            Err(CircuitError::UndefinedWire("q".to_string())),
+            Circuit::parse("q -> a", 16).unwrap().evaluate()
+        );
+        assert!(matches!(
+            Circuit::parse("a FOO b -> c", 16),
+            Err(CircuitError::Parse(_))
+        ));
+        assert!(matches!(
+            Circuit::parse("1 -> a\n2 -> a", 16),
+            Err(CircuitError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let circuit = Circuit::parse("1 -> a\na AND d -> b\nb -> c\nNOT c -> d", 16).unwrap();
+        assert_eq!(
+            Err(CircuitError::Cycle(vec![
+                "b".to_string(),
+                "c".to_string(),
+                "d".to_string()
+            ])),
+            circuit.topological_order()
+        );
+    }
+
+    #[test]
+    fn test_ancestors_and_consumers() {
+        let circuit = Circuit::parse(BOOKLET, 16).unwrap();
+        assert_eq!(HashSet::from(["x", "y"]), circuit.ancestors_of("d"));
+        let consumers: Vec<_> = circuit.consumers_of("x").map(|(wire, _)| wire).collect();
+        assert_eq!(vec!["d", "e", "f", "h"], consumers);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = Circuit::parse("1 -> x\nNOT x -> y", 16).unwrap().to_dot();
+        assert_eq!(
+            "digraph \"circuit\" {
+    rankdir=\"LR\";
+    \"x\" [label=\"x\\n1\", shape=\"box\"];
+    \"y\" [label=\"y\\nNOT x\", shape=\"ellipse\"];
+    \"x\" -> \"y\";
+}
+",
+            dot
+        );
+    }
+}
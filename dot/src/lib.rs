@@ -0,0 +1,176 @@
+//! Rendering of graph-shaped puzzle models as Graphviz DOT text,
+//! to be viewed with standard tools such as `dot -Tsvg graph.dot > graph.svg`.
+
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::Path;
+
+/// A model that can be rendered as a Graphviz graph
+pub trait ToDot {
+    fn to_dot(&self) -> String;
+
+    fn write_dot(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_dot())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Directed,
+    Undirected,
+}
+
+type Attributes = Vec<(String, String)>;
+
+/// A DOT graph, built up node by node and edge by edge.
+/// Nodes and edges are rendered in the order they were added.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    kind: Kind,
+    name: String,
+    attributes: Attributes,
+    nodes: Vec<(String, Attributes)>,
+    edges: Vec<(String, String, Attributes)>,
+}
+
+impl Graph {
+    pub fn directed(name: &str) -> Self {
+        Graph::new(Kind::Directed, name)
+    }
+
+    pub fn undirected(name: &str) -> Self {
+        Graph::new(Kind::Undirected, name)
+    }
+
+    fn new(kind: Kind, name: &str) -> Self {
+        Graph {
+            kind,
+            name: name.to_string(),
+            attributes: vec![],
+            nodes: vec![],
+            edges: vec![],
+        }
+    }
+
+    /// Sets a graph-wide attribute, such as `rankdir` or `layout`
+    pub fn attribute(&mut self, key: &str, value: impl Display) -> &mut Self {
+        self.attributes.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a node with attributes such as `label` or `shape`. Nodes only referenced by
+    /// edges don't need to be added, unless they should have attributes.
+    pub fn node(&mut self, id: impl Display, attributes: &[(&str, &dyn Display)]) -> &mut Self {
+        self.nodes.push((id.to_string(), to_owned(attributes)));
+        self
+    }
+
+    pub fn edge(
+        &mut self,
+        from: impl Display,
+        to: impl Display,
+        attributes: &[(&str, &dyn Display)],
+    ) -> &mut Self {
+        let edge = (from.to_string(), to.to_string(), to_owned(attributes));
+        self.edges.push(edge);
+        self
+    }
+}
+
+fn to_owned(attributes: &[(&str, &dyn Display)]) -> Attributes {
+    attributes
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Quotes an ID or attribute value, so any name or label is valid DOT
+fn quoted(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+fn attribute_list(attributes: &Attributes) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let list: Vec<_> = attributes
+        .iter()
+        .map(|(key, value)| format!("{key}={}", quoted(value)))
+        .collect();
+    format!(" [{}]", list.join(", "))
+}
+
+impl Display for Graph {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (keyword, edge_op) = match self.kind {
+            Kind::Directed => ("digraph", "->"),
+            Kind::Undirected => ("graph", "--"),
+        };
+        writeln!(f, "{keyword} {} {{", quoted(&self.name))?;
+        for (key, value) in &self.attributes {
+            writeln!(f, "    {key}={};", quoted(value))?;
+        }
+        for (id, attributes) in &self.nodes {
+            writeln!(f, "    {}{};", quoted(id), attribute_list(attributes))?;
+        }
+        for (from, to, attributes) in &self.edges {
+            let (from, to) = (quoted(from), quoted(to));
+            writeln!(
+                f,
+                "    {from} {edge_op} {to}{};",
+                attribute_list(attributes)
+            )?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl ToDot for Graph {
+    fn to_dot(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directed_graph() {
+        let mut graph = Graph::directed("example");
+        graph
+            .attribute("rankdir", "LR")
+            .node("a", &[("label", &"a\n42"), ("shape", &"box")])
+            .node("b", &[])
+            .edge("a", "b", &[("label", &7)])
+            .edge("b", "c", &[]);
+        assert_eq!(
+            "digraph \"example\" {
+    rankdir=\"LR\";
+    \"a\" [label=\"a\\n42\", shape=\"box\"];
+    \"b\";
+    \"a\" -> \"b\" [label=\"7\"];
+    \"b\" -> \"c\";
+}
+",
+            graph.to_dot()
+        );
+    }
+
+    #[test]
+    fn test_undirected_graph_quotes_names() {
+        let mut graph = Graph::undirected("say \"hi\"");
+        graph.edge("back\\slash", "x", &[]);
+        assert_eq!(
+            "graph \"say \\\"hi\\\"\" {
+    \"back\\\\slash\" -- \"x\";
+}
+",
+            graph.to_dot()
+        );
+    }
+}
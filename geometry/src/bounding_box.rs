@@ -0,0 +1,193 @@
+use crate::{Coordinate, Point};
+
+/// The smallest axis-aligned box containing a set of points. Both corners are inclusive.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct BoundingBox<const N: usize, T> {
+    pub min: Point<N, T>,
+    pub max: Point<N, T>,
+}
+
+impl<const N: usize, T: Coordinate> BoundingBox<N, T> {
+    pub fn new(min: Point<N, T>, max: Point<N, T>) -> Self {
+        BoundingBox { min, max }
+    }
+    /// Returns `None` if there are no points
+    pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point<N, T>>) -> Option<Self>
+    where
+        T: 'a,
+    {
+        let mut points = points.into_iter();
+        let first = *points.next()?;
+        let mut bbox = BoundingBox::new(first, first);
+        points.for_each(|p| bbox.include(p));
+        Some(bbox)
+    }
+    /// Grows the box just enough to contain `point`
+    pub fn include(&mut self, point: &Point<N, T>) {
+        self.min = self.min.zip_with(point, Ord::min);
+        self.max = self.max.zip_with(point, Ord::max);
+    }
+    pub fn contains(&self, point: &Point<N, T>) -> bool {
+        (0..N).all(|i| self.min[i] <= point[i] && point[i] <= self.max[i])
+    }
+    /// The number of integer points along each axis
+    pub fn size(&self) -> Point<N, T> {
+        (self.max - self.min).map(|c| c + T::ONE)
+    }
+    pub fn volume(&self) -> T {
+        self.size().coords.into_iter().product()
+    }
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = self.min.zip_with(&other.min, Ord::max);
+        let max = self.max.zip_with(&other.max, Ord::min);
+        (0..N)
+            .all(|i| min[i] <= max[i])
+            .then_some(BoundingBox { min, max })
+    }
+    /// Splits the part of this box outside of `other` into at most 2 * N disjoint boxes
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(overlap) = self.intersection(other) else {
+            return vec![*self];
+        };
+        let mut pieces = vec![];
+        let mut rest = *self;
+        for axis in 0..N {
+            if rest.min[axis] < overlap.min[axis] {
+                let mut below = rest;
+                below.max[axis] = overlap.min[axis] - T::ONE;
+                pieces.push(below);
+                rest.min[axis] = overlap.min[axis];
+            }
+            if overlap.max[axis] < rest.max[axis] {
+                let mut above = rest;
+                above.min[axis] = overlap.max[axis] + T::ONE;
+                pieces.push(above);
+                rest.max[axis] = overlap.max[axis];
+            }
+        }
+        pieces
+    }
+    /// A box larger by `margin` on every side
+    pub fn expand(&self, margin: T) -> Self {
+        BoundingBox::new(self.min.map(|c| c - margin), self.max.map(|c| c + margin))
+    }
+    /// Iterates all contained points with the first axis changing fastest,
+    /// which is reading order for 2D grids
+    pub fn points(&self) -> Points<N, T> {
+        Points {
+            bbox: *self,
+            next: Some(self.min),
+        }
+    }
+}
+
+pub struct Points<const N: usize, T> {
+    bbox: BoundingBox<N, T>,
+    next: Option<Point<N, T>>,
+}
+
+impl<const N: usize, T: Coordinate> Iterator for Points<N, T> {
+    type Item = Point<N, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        let mut next = current;
+        self.next = None;
+        for axis in 0..N {
+            if next[axis] < self.bbox.max[axis] {
+                next[axis] += T::ONE;
+                self.next = Some(next);
+                break;
+            }
+            next[axis] = self.bbox.min[axis];
+        }
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Point2, Point3};
+
+    #[test]
+    fn test_bounding_box() {
+        let points = [Point2::new(1, 5), Point2::new(-2, 3), Point2::new(4, 4)];
+        let bbox = BoundingBox::from_points(&points).unwrap();
+        assert_eq!(Point2::new(-2, 3), bbox.min);
+        assert_eq!(Point2::new(4, 5), bbox.max);
+        assert_eq!(Point2::new(7, 3), bbox.size());
+        assert_eq!(21, bbox.volume());
+        assert!(bbox.contains(&Point2::new(0, 4)));
+        assert!(!bbox.contains(&Point2::new(0, 6)));
+        assert_eq!(45, bbox.expand(1).volume());
+        assert!(BoundingBox::<2, i32>::from_points(&[]).is_none());
+    }
+
+    #[test]
+    fn test_intersection_and_subtraction() {
+        let a = BoundingBox::new(Point3::new(0, 0, 0), Point3::new(3, 3, 3));
+        let b = BoundingBox::new(Point3::new(2, 1, -5), Point3::new(5, 2, 5));
+        let overlap = a.intersection(&b).unwrap();
+        assert_eq!(
+            BoundingBox::new(Point3::new(2, 1, 0), Point3::new(3, 2, 3)),
+            overlap
+        );
+        let pieces = a.subtract(&b);
+        assert_eq!(3, pieces.len());
+        let volume: i32 = pieces.iter().map(BoundingBox::volume).sum();
+        assert_eq!(a.volume() - overlap.volume(), volume);
+        assert!(pieces.iter().all(|p| p.intersection(&b).is_none()));
+
+        let far = BoundingBox::new(Point3::new(9, 9, 9), Point3::new(9, 9, 9));
+        assert!(a.intersection(&far).is_none());
+        assert_eq!(vec![a], a.subtract(&far));
+        assert!(a.subtract(&a.expand(1)).is_empty());
+    }
+
+    #[test]
+    fn test_points_in_reading_order() {
+        let bbox = BoundingBox::new(Point2::new(0, 0), Point2::new(1, 1));
+        let points: Vec<_> = bbox.points().collect();
+        let expected = [(0, 0), (1, 0), (0, 1), (1, 1)].map(Point2::from);
+        assert_eq!(expected.to_vec(), points);
+
+        let cube = BoundingBox::new(Point3::origin(), Point3::new(2, 2, 2));
+        assert_eq!(27, cube.points().count());
+        assert!(cube.points().all(|p| cube.contains(&p)));
+    }
+
+    fn range(start: i32, end: i32) -> BoundingBox<1, i32> {
+        BoundingBox::new(Point::from([start]), Point::from([end]))
+    }
+
+    #[test]
+    fn test_range_no_overlap() {
+        assert_eq!(None, range(1, 3).intersection(&range(4, 7)));
+    }
+
+    #[test]
+    fn test_range_overlaps_end() {
+        assert_eq!(Some(range(3, 4)), range(1, 4).intersection(&range(3, 7)));
+    }
+
+    #[test]
+    fn test_range_overlaps_start() {
+        assert_eq!(Some(range(3, 4)), range(3, 7).intersection(&range(1, 4)));
+    }
+
+    #[test]
+    fn test_range_overlap_enclosing() {
+        assert_eq!(Some(range(3, 4)), range(1, 7).intersection(&range(3, 4)));
+    }
+
+    #[test]
+    fn test_range_overlap_enclosed() {
+        assert_eq!(Some(range(3, 4)), range(3, 4).intersection(&range(1, 7)));
+    }
+
+    #[test]
+    fn test_range_overlap_identical() {
+        assert_eq!(Some(range(0, 0)), range(0, 0).intersection(&range(0, 0)));
+    }
+}
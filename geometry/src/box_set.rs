@@ -0,0 +1,107 @@
+use crate::{BoundingBox, Coordinate, Point};
+
+/// A set of integer points in N dimensions, stored as disjoint axis-aligned boxes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoxSet<const N: usize, T> {
+    boxes: Vec<BoundingBox<N, T>>,
+}
+
+impl<const N: usize, T: Coordinate> Default for BoxSet<N, T> {
+    fn default() -> Self {
+        BoxSet::new()
+    }
+}
+
+impl<const N: usize, T: Coordinate> FromIterator<BoundingBox<N, T>> for BoxSet<N, T> {
+    fn from_iter<I: IntoIterator<Item = BoundingBox<N, T>>>(boxes: I) -> Self {
+        let mut set = BoxSet::new();
+        boxes.into_iter().for_each(|b| set.insert(b));
+        set
+    }
+}
+
+impl<const N: usize, T: Coordinate> BoxSet<N, T> {
+    pub fn new() -> Self {
+        BoxSet { boxes: vec![] }
+    }
+
+    /// Adds all points within `new`
+    pub fn insert(&mut self, new: BoundingBox<N, T>) {
+        // Removing first keeps the boxes disjoint without having to split the new one
+        self.remove(&new);
+        self.boxes.push(new);
+    }
+
+    /// Removes all points within `old`, splitting the boxes it overlaps
+    pub fn remove(&mut self, old: &BoundingBox<N, T>) {
+        self.boxes = self.boxes.iter().flat_map(|b| b.subtract(old)).collect();
+    }
+
+    /// The points of this set that are also within `bounds`
+    pub fn intersection(&self, bounds: &BoundingBox<N, T>) -> Self {
+        BoxSet {
+            boxes: self
+                .boxes
+                .iter()
+                .filter_map(|b| b.intersection(bounds))
+                .collect(),
+        }
+    }
+
+    pub fn contains(&self, point: &Point<N, T>) -> bool {
+        self.boxes.iter().any(|b| b.contains(point))
+    }
+
+    /// The number of points in this set
+    pub fn volume(&self) -> T {
+        self.boxes.iter().map(BoundingBox::volume).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// The disjoint boxes making up this set, in no particular order
+    pub fn boxes(&self) -> &[BoundingBox<N, T>] {
+        &self.boxes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point3;
+
+    fn cube(min: isize, max: isize) -> BoundingBox<3, isize> {
+        BoundingBox::new(Point3::new(min, min, min), Point3::new(max, max, max))
+    }
+
+    #[test]
+    fn test_reactor_reboot_small_example() {
+        let mut set = BoxSet::new();
+        set.insert(cube(10, 12));
+        assert_eq!(27, set.volume());
+        set.insert(cube(11, 13));
+        assert_eq!(27 + 19, set.volume());
+        set.remove(&cube(9, 11));
+        assert_eq!(27 + 19 - 8, set.volume());
+        set.insert(cube(10, 10));
+        assert_eq!(39, set.volume());
+        assert!(set.contains(&Point3::new(10, 10, 10)));
+        assert!(!set.contains(&Point3::new(11, 11, 11)));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let set: BoxSet<2, i32> = [
+            BoundingBox::new(Point::from([0, 0]), Point::from([9, 0])),
+            BoundingBox::new(Point::from([0, 5]), Point::from([9, 5])),
+        ]
+        .into_iter()
+        .collect();
+        let clipped =
+            set.intersection(&BoundingBox::new(Point::from([5, 0]), Point::from([20, 3])));
+        assert_eq!(5, clipped.volume());
+        assert_eq!(20, set.volume());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{Coordinate, Point2};
+
+/// One of the four orthogonal directions on a grid with y pointing down
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn turn_right(self) -> Self {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+    pub fn turn_left(self) -> Self {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+    pub fn reverse(self) -> Self {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+    pub fn offset<T: Coordinate>(self) -> Point2<T> {
+        match self {
+            Direction::North => Point2::new(T::ZERO, -T::ONE),
+            Direction::East => Point2::new(T::ONE, T::ZERO),
+            Direction::South => Point2::new(T::ZERO, T::ONE),
+            Direction::West => Point2::new(-T::ONE, T::ZERO),
+        }
+    }
+}
+
+/// Accepts the usual puzzle notations: `^>v<`, `URDL` and `NESW`
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'U' | 'N' => Ok(Direction::North),
+            '>' | 'R' | 'E' => Ok(Direction::East),
+            'v' | 'D' | 'S' => Ok(Direction::South),
+            '<' | 'L' | 'W' => Ok(Direction::West),
+            _ => Err(c),
+        }
+    }
+}
+
+/// One of the four orthogonal or four diagonal directions on a grid with y pointing down
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    /// Turns 45° clockwise
+    pub fn turn_right(self) -> Self {
+        Direction8::ALL[(self as usize + 1) % 8]
+    }
+    /// Turns 45° counter-clockwise
+    pub fn turn_left(self) -> Self {
+        Direction8::ALL[(self as usize + 7) % 8]
+    }
+    pub fn reverse(self) -> Self {
+        Direction8::ALL[(self as usize + 4) % 8]
+    }
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+    pub fn offset<T: Coordinate>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Direction8::North => (T::ZERO, -T::ONE),
+            Direction8::NorthEast => (T::ONE, -T::ONE),
+            Direction8::East => (T::ONE, T::ZERO),
+            Direction8::SouthEast => (T::ONE, T::ONE),
+            Direction8::South => (T::ZERO, T::ONE),
+            Direction8::SouthWest => (-T::ONE, T::ONE),
+            Direction8::West => (-T::ONE, T::ZERO),
+            Direction8::NorthWest => (-T::ONE, -T::ONE),
+        };
+        Point2::new(x, y)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(dir: Direction) -> Self {
+        Direction8::ALL[dir as usize * 2]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::East, Direction::North.turn_right());
+        assert_eq!(Direction::West, Direction::North.turn_left());
+        assert_eq!(Direction::South, Direction::North.reverse());
+        for dir in Direction::ALL {
+            assert_eq!(dir, dir.turn_left().turn_right());
+            assert_eq!(dir.reverse(), dir.turn_right().turn_right());
+            assert_eq!(dir.offset::<i32>().rotate_cw(), dir.turn_right().offset());
+        }
+        assert_eq!(Direction8::NorthWest, Direction8::North.turn_left());
+        assert_eq!(Direction8::SouthWest, Direction8::NorthEast.reverse());
+        assert!(Direction8::SouthEast.is_diagonal());
+        assert_eq!(Direction8::West, Direction::West.into());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Direction::North), Direction::try_from('^'));
+        assert_eq!(Ok(Direction::North), Direction::try_from('U'));
+        assert_eq!(Ok(Direction::West), Direction::try_from('W'));
+        assert_eq!(Ok(Direction::South), Direction::try_from('v'));
+        assert_eq!(Err('x'), Direction::try_from('x'));
+    }
+}
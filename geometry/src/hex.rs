@@ -0,0 +1,406 @@
+//! Hexagonal grids, following the conventions of <https://www.redblobgames.com/grids/hexagons/>.
+//!
+//! [`Axial`] is the primary coordinate system. [`Cube`] and [`Offset`] coordinates convert
+//! to and from it. Directions come in two flavors depending on how the hexagons are laid out:
+//! [`PointyTop`] hexagons have east and west neighbors, [`FlatTop`] ones have north and south.
+
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// The axial offsets of the six neighbors, in counter-clockwise order starting east (pointy-top)
+/// or south-east (flat-top)
+const NEIGHBOR_OFFSETS: [Axial; 6] = [
+    Axial::new(1, 0),
+    Axial::new(1, -1),
+    Axial::new(0, -1),
+    Axial::new(-1, 0),
+    Axial::new(-1, 1),
+    Axial::new(0, 1),
+];
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Axial {
+    pub q: isize,
+    pub r: isize,
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cube {
+    pub q: isize,
+    pub r: isize,
+    pub s: isize,
+}
+
+/// Column and row in a rectangular layout, where every other row or column is shifted
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Offset {
+    pub col: isize,
+    pub row: isize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OffsetLayout {
+    /// Pointy-top, odd rows shifted right
+    OddR,
+    /// Pointy-top, even rows shifted right
+    EvenR,
+    /// Flat-top, odd columns shifted down
+    OddQ,
+    /// Flat-top, even columns shifted down
+    EvenQ,
+}
+
+impl Axial {
+    pub const ORIGIN: Axial = Axial::new(0, 0);
+
+    pub const fn new(q: isize, r: isize) -> Self {
+        Axial { q, r }
+    }
+    pub fn step(self, dir: impl HexDirection) -> Self {
+        self + dir.offset()
+    }
+    pub fn distance_to(&self, other: &Axial) -> usize {
+        let d = *self - *other;
+        ((d.q.abs() + d.r.abs() + (d.q + d.r).abs()) / 2) as usize
+    }
+    pub fn distance_to_origin(&self) -> usize {
+        self.distance_to(&Axial::ORIGIN)
+    }
+    pub fn neighbors(&self) -> impl Iterator<Item = Axial> + '_ {
+        NEIGHBOR_OFFSETS.iter().map(move |offset| *self + *offset)
+    }
+    /// The 6 * radius hexes at exactly `radius` steps away, or just this one if `radius` is 0
+    pub fn ring(&self, radius: usize) -> Vec<Axial> {
+        if radius == 0 {
+            return vec![*self];
+        }
+        let mut hex = *self + NEIGHBOR_OFFSETS[4] * radius as isize;
+        let mut ring = Vec::with_capacity(6 * radius);
+        for offset in NEIGHBOR_OFFSETS {
+            for _ in 0..radius {
+                ring.push(hex);
+                hex += offset;
+            }
+        }
+        ring
+    }
+    /// All hexes within `radius` steps, ordered ring by ring from the center outwards
+    pub fn spiral(&self, radius: usize) -> Vec<Axial> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+    /// The hexes on the straight line from this one to `other`, including both ends
+    pub fn line_to(&self, other: &Axial) -> Vec<Axial> {
+        let n = self.distance_to(other);
+        let (a, b) = (Cube::from(*self), Cube::from(*other));
+        // Nudge the start slightly so points exactly on an edge are rounded consistently
+        let (aq, ar, as_) = (a.q as f64 + 1e-6, a.r as f64 + 1e-6, a.s as f64 - 2e-6);
+        (0..=n)
+            .map(|i| {
+                let t = if n == 0 { 0.0 } else { i as f64 / n as f64 };
+                let lerp = |from: f64, to: isize| from + (to as f64 - from) * t;
+                Cube::round(lerp(aq, b.q), lerp(ar, b.r), lerp(as_, b.s)).into()
+            })
+            .collect()
+    }
+
+    pub fn to_offset(self, layout: OffsetLayout) -> Offset {
+        let Axial { q, r } = self;
+        match layout {
+            OffsetLayout::OddR => Offset::new(q + (r - (r & 1)) / 2, r),
+            OffsetLayout::EvenR => Offset::new(q + (r + (r & 1)) / 2, r),
+            OffsetLayout::OddQ => Offset::new(q, r + (q - (q & 1)) / 2),
+            OffsetLayout::EvenQ => Offset::new(q, r + (q + (q & 1)) / 2),
+        }
+    }
+    pub fn from_offset(offset: Offset, layout: OffsetLayout) -> Self {
+        let Offset { col, row } = offset;
+        match layout {
+            OffsetLayout::OddR => Axial::new(col - (row - (row & 1)) / 2, row),
+            OffsetLayout::EvenR => Axial::new(col - (row + (row & 1)) / 2, row),
+            OffsetLayout::OddQ => Axial::new(col, row - (col - (col & 1)) / 2),
+            OffsetLayout::EvenQ => Axial::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+}
+
+impl Cube {
+    pub fn new(q: isize, r: isize, s: isize) -> Self {
+        assert_eq!(0, q + r + s, "Cube coordinates must sum to 0");
+        Cube { q, r, s }
+    }
+    /// Rounds fractional cube coordinates to the nearest hex
+    fn round(q: f64, r: f64, s: f64) -> Cube {
+        let (mut rq, mut rr, mut rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        } else {
+            rs = -rq - rr;
+        }
+        Cube::new(rq as isize, rr as isize, rs as isize)
+    }
+}
+
+impl Offset {
+    pub fn new(col: isize, row: isize) -> Self {
+        Offset { col, row }
+    }
+}
+
+impl From<Axial> for Cube {
+    fn from(Axial { q, r }: Axial) -> Self {
+        Cube { q, r, s: -q - r }
+    }
+}
+
+impl From<Cube> for Axial {
+    fn from(Cube { q, r, .. }: Cube) -> Self {
+        Axial { q, r }
+    }
+}
+
+impl Add for Axial {
+    type Output = Axial;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Axial::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Axial {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Axial {
+    type Output = Axial;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Axial::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<isize> for Axial {
+    type Output = Axial;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Axial::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+/// One of the six directions of a hex layout
+pub trait HexDirection: Copy {
+    const ALL: [Self; 6];
+
+    /// The position of this direction within [`HexDirection::ALL`]
+    fn index(self) -> usize;
+
+    fn offset(self) -> Axial {
+        NEIGHBOR_OFFSETS[self.index()]
+    }
+    /// Turns 60° clockwise
+    fn turn_right(self) -> Self {
+        Self::ALL[(self.index() + 5) % 6]
+    }
+    /// Turns 60° counter-clockwise
+    fn turn_left(self) -> Self {
+        Self::ALL[(self.index() + 1) % 6]
+    }
+    fn reverse(self) -> Self {
+        Self::ALL[(self.index() + 3) % 6]
+    }
+}
+
+/// Directions between pointy-top hexes: `e`, `ne`, `nw`, `w`, `sw` and `se`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PointyTop {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+/// Directions between flat-top hexes: `se`, `ne`, `n`, `nw`, `sw` and `s`
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FlatTop {
+    SouthEast,
+    NorthEast,
+    North,
+    NorthWest,
+    SouthWest,
+    South,
+}
+
+impl HexDirection for PointyTop {
+    const ALL: [Self; 6] = [
+        PointyTop::East,
+        PointyTop::NorthEast,
+        PointyTop::NorthWest,
+        PointyTop::West,
+        PointyTop::SouthWest,
+        PointyTop::SouthEast,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl HexDirection for FlatTop {
+    const ALL: [Self; 6] = [
+        FlatTop::SouthEast,
+        FlatTop::NorthEast,
+        FlatTop::North,
+        FlatTop::NorthWest,
+        FlatTop::SouthWest,
+        FlatTop::South,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl TryFrom<&str> for PointyTop {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "e" => Ok(PointyTop::East),
+            "ne" => Ok(PointyTop::NorthEast),
+            "nw" => Ok(PointyTop::NorthWest),
+            "w" => Ok(PointyTop::West),
+            "sw" => Ok(PointyTop::SouthWest),
+            "se" => Ok(PointyTop::SouthEast),
+            _ => Err(format!("Invalid pointy-top direction '{s}'")),
+        }
+    }
+}
+
+impl TryFrom<&str> for FlatTop {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "se" => Ok(FlatTop::SouthEast),
+            "ne" => Ok(FlatTop::NorthEast),
+            "n" => Ok(FlatTop::North),
+            "nw" => Ok(FlatTop::NorthWest),
+            "sw" => Ok(FlatTop::SouthWest),
+            "s" => Ok(FlatTop::South),
+            _ => Err(format!("Invalid flat-top direction '{s}'")),
+        }
+    }
+}
+
+impl PointyTop {
+    /// Parses directions written without separators, such as `esenee`
+    pub fn parse_path(path: &str) -> Result<Vec<PointyTop>, String> {
+        path.split_inclusive(['e', 'w'])
+            .map(PointyTop::try_from)
+            .collect()
+    }
+}
+
+impl FlatTop {
+    /// Parses comma-separated directions, such as `ne,ne,s,s`
+    pub fn parse_path(path: &str) -> Result<Vec<FlatTop>, String> {
+        path.trim().split(',').map(FlatTop::try_from).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn walk<D: HexDirection>(path: Vec<D>) -> Axial {
+        path.into_iter().fold(Axial::ORIGIN, Axial::step)
+    }
+
+    #[test]
+    fn test_flat_top_distances() {
+        let distance = |path| walk(FlatTop::parse_path(path).unwrap()).distance_to_origin();
+        assert_eq!(3, distance("ne,ne,ne"));
+        assert_eq!(0, distance("ne,ne,sw,sw"));
+        assert_eq!(2, distance("ne,ne,s,s"));
+        assert_eq!(3, distance("se,sw,se,sw,sw"));
+    }
+
+    #[test]
+    fn test_pointy_top_paths() {
+        assert_eq!(
+            Axial::ORIGIN,
+            walk(PointyTop::parse_path("nwwswee").unwrap())
+        );
+        assert_eq!(
+            Axial::new(0, 1),
+            walk(PointyTop::parse_path("esew").unwrap())
+        );
+        assert!(PointyTop::parse_path("en").is_err());
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(PointyTop::SouthEast, PointyTop::East.turn_right());
+        assert_eq!(PointyTop::NorthEast, PointyTop::East.turn_left());
+        assert_eq!(FlatTop::South, FlatTop::North.reverse());
+        for dir in FlatTop::ALL {
+            assert_eq!(Axial::ORIGIN, dir.offset() + dir.reverse().offset());
+        }
+    }
+
+    #[test]
+    fn test_rings_and_spirals() {
+        let center = Axial::new(2, -1);
+        for radius in 0..4 {
+            let ring = center.ring(radius);
+            assert_eq!(if radius == 0 { 1 } else { 6 * radius }, ring.len());
+            assert!(ring.iter().all(|hex| hex.distance_to(&center) == radius));
+        }
+        let spiral = center.spiral(3);
+        assert_eq!(37, spiral.len());
+        assert_eq!(37, spiral.iter().collect::<HashSet<_>>().len());
+        assert_eq!(center, spiral[0]);
+    }
+
+    #[test]
+    fn test_line() {
+        let from = Axial::new(0, 0);
+        let to = Axial::new(3, -1);
+        let line = from.line_to(&to);
+        assert_eq!(4, line.len());
+        assert_eq!(from, line[0]);
+        assert_eq!(to, line[3]);
+        assert!(line.windows(2).all(|w| w[0].distance_to(&w[1]) == 1));
+        assert_eq!(vec![from], from.line_to(&from));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let layouts = [
+            OffsetLayout::OddR,
+            OffsetLayout::EvenR,
+            OffsetLayout::OddQ,
+            OffsetLayout::EvenQ,
+        ];
+        for hex in Axial::new(1, -2).spiral(3) {
+            assert_eq!(hex, Axial::from(Cube::from(hex)));
+            for layout in layouts {
+                assert_eq!(hex, Axial::from_offset(hex.to_offset(layout), layout));
+            }
+        }
+        assert_eq!(
+            Offset::new(1, 1),
+            Axial::new(1, 1).to_offset(OffsetLayout::OddR)
+        );
+        assert_eq!(
+            Offset::new(2, 1),
+            Axial::new(1, 1).to_offset(OffsetLayout::EvenR)
+        );
+    }
+}
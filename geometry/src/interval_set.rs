@@ -0,0 +1,245 @@
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, RangeInclusive, Sub};
+
+/// An integer type, signed or unsigned, usable as bound of an [`IntervalSet`].
+pub trait Integer: Copy + Debug + Ord + Add<Output = Self> + Sub<Output = Self> + Sum {
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent inclusive intervals.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    // Each entry is (start, end), with start <= end.
+    // There's at least one integer not in the set between consecutive entries.
+    intervals: Vec<(T, T)>,
+}
+
+impl<T: Integer> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet::new()
+    }
+}
+
+impl<T: Integer> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = IntervalSet::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Integer> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(ranges: I) -> Self {
+        let mut set = IntervalSet::new();
+        ranges.into_iter().for_each(|range| set.insert(range));
+        set
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+
+    /// Adds all integers of `range`, merging it with any overlapping or adjacent intervals
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let mut merged = Vec::with_capacity(self.intervals.len() + 1);
+        let mut inserted = false;
+        for &(s, e) in &self.intervals {
+            if is_before(e, start) {
+                merged.push((s, e));
+            } else if is_before(end, s) {
+                if !inserted {
+                    merged.push((start, end));
+                    inserted = true;
+                }
+                merged.push((s, e));
+            } else {
+                start = start.min(s);
+                end = end.max(e);
+            }
+        }
+        if !inserted {
+            merged.push((start, end));
+        }
+        self.intervals = merged;
+    }
+
+    /// Removes all integers of `range`, splitting intervals where necessary
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+        let mut remaining = Vec::with_capacity(self.intervals.len() + 1);
+        for &(s, e) in &self.intervals {
+            if e < start || end < s {
+                remaining.push((s, e));
+                continue;
+            }
+            if s < start {
+                remaining.push((s, start - T::ONE));
+            }
+            if end < e {
+                remaining.push((end + T::ONE, e));
+            }
+        }
+        self.intervals = remaining;
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let idx = self.intervals.partition_point(|(_, e)| e < value);
+        self.intervals.get(idx).is_some_and(|(s, _)| s <= value)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.iter().for_each(|range| union.insert(range));
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (s1, e1) = self.intervals[i];
+            let (s2, e2) = other.intervals[j];
+            let (start, end) = (s1.max(s2), e1.min(e2));
+            if start <= end {
+                intervals.push((start, end));
+            }
+            if e1 < e2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        other.iter().for_each(|range| difference.remove(range));
+        difference
+    }
+
+    /// All integers within `bounds` that are not in this set
+    pub fn complement(&self, bounds: RangeInclusive<T>) -> Self {
+        IntervalSet::from(bounds).difference(self)
+    }
+
+    /// The number of integers in this set. The count must fit in `T`, so this overflows
+    /// for sets such as all of `0..=255` as `u8` or `-100..=100` as `i8`.
+    pub fn len(&self) -> T {
+        self.intervals.iter().map(|&(s, e)| e - s + T::ONE).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|&(s, _)| s)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|&(_, e)| e)
+    }
+
+    /// The disjoint intervals in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.intervals.iter().map(|&(s, e)| s..=e)
+    }
+
+    /// The number of disjoint intervals
+    pub fn interval_count(&self) -> usize {
+        self.intervals.len()
+    }
+}
+
+/// Whether `end` lies before `start` with at least one integer in between
+fn is_before<T: Integer>(end: T, start: T) -> bool {
+    // Written so it can't overflow, unlike `start - end` for signed types far apart
+    end < start && end + T::ONE < start
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges<T: Integer>(set: &IntervalSet<T>) -> Vec<RangeInclusive<T>> {
+        set.iter().collect()
+    }
+
+    #[test]
+    fn test_insert_merges_overlapping_and_adjacent() {
+        let set: IntervalSet<u32> = [5..=8, 0..=2, 4..=7, 12..=14, 3..=3].into_iter().collect();
+        assert_eq!(vec![0..=8, 12..=14], ranges(&set));
+        assert_eq!(12, set.len());
+        assert!(set.contains(&13));
+        assert!(!set.contains(&10));
+        assert_eq!(Some(0), set.min());
+        assert_eq!(Some(14), set.max());
+    }
+
+    #[test]
+    fn test_insert_at_type_bounds() {
+        let mut set = IntervalSet::from(250u8..=255);
+        set.insert(0..=3);
+        set.insert(4..=249);
+        assert_eq!(vec![0..=255], ranges(&set));
+    }
+
+    #[test]
+    fn test_insert_far_apart_signed() {
+        let mut set = IntervalSet::from(-100i8..=-90);
+        set.insert(50..=60);
+        set.insert(-89..=-89);
+        assert_eq!(vec![-100..=-89, 50..=60], ranges(&set));
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set = IntervalSet::from(-10i64..=10);
+        set.remove(-2..=3);
+        assert_eq!(vec![-10..=-3, 4..=10], ranges(&set));
+        set.remove(-20..=-10);
+        set.remove(10..=10);
+        assert_eq!(vec![-9..=-3, 4..=9], ranges(&set));
+        assert_eq!(13, set.len());
+    }
+
+    #[test]
+    fn test_set_algebra() {
+        let a: IntervalSet<i32> = [0..=10, 20..=30].into_iter().collect();
+        let b: IntervalSet<i32> = [5..=25].into_iter().collect();
+        assert_eq!(vec![0..=30], ranges(&a.union(&b)));
+        assert_eq!(vec![5..=10, 20..=25], ranges(&a.intersection(&b)));
+        assert_eq!(vec![0..=4, 26..=30], ranges(&a.difference(&b)));
+        assert_eq!(vec![11..=19], ranges(&b.difference(&a)));
+        assert_eq!(
+            vec![-5..=-1, 11..=19, 31..=35],
+            ranges(&a.complement(-5..=35))
+        );
+        assert!(a.intersection(&IntervalSet::from(11..=19)).is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+//! Points, directions, rotations, bounding boxes, hex grids and interval sets shared
+//! by the yearly solutions.
+//!
+//! 2D grids use the screen convention: x grows to the right (east) and y grows downwards (south).
+//!
+//! The 2024 and 2025 grid puzzles keep their own `Vec2D`, as it is tied to the `TileGrid`
+//! indexing shared by most of their days.
+
+mod bounding_box;
+mod box_set;
+mod coordinate;
+mod direction;
+pub mod hex;
+mod interval_set;
+mod point;
+mod rotation;
+
+pub use bounding_box::{BoundingBox, Points};
+pub use box_set::BoxSet;
+pub use coordinate::Coordinate;
+pub use direction::{Direction, Direction8};
+pub use interval_set::{Integer, IntervalSet};
+pub use point::{Point, Point2, Point3};
+pub use rotation::Rotation;
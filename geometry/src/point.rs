@@ -0,0 +1,282 @@
+use crate::Coordinate;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A point (or vector) with `N` coordinates of type `T`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize, T> {
+    pub coords: [T; N],
+}
+
+pub type Point2<T = isize> = Point<2, T>;
+pub type Point3<T = isize> = Point<3, T>;
+
+impl<const N: usize, T: Coordinate> Default for Point<N, T> {
+    fn default() -> Self {
+        Point::origin()
+    }
+}
+
+impl<const N: usize, T: Coordinate> From<[T; N]> for Point<N, T> {
+    fn from(coords: [T; N]) -> Self {
+        Point { coords }
+    }
+}
+
+impl<T: Coordinate> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2::new(x, y)
+    }
+}
+
+impl<T: Coordinate> From<(T, T, T)> for Point3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3::new(x, y, z)
+    }
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { coords: [x, y] }
+    }
+}
+
+impl<T: Coordinate> Point2<T> {
+    /// Rotates 90° clockwise around the origin (with y pointing down)
+    pub fn rotate_cw(self) -> Self {
+        Point2::new(-self.y(), self.x())
+    }
+    /// Rotates 90° counter-clockwise around the origin (with y pointing down)
+    pub fn rotate_ccw(self) -> Self {
+        Point2::new(self.y(), -self.x())
+    }
+    pub fn rotate_180(self) -> Self {
+        -self
+    }
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point { coords: [x, y, z] }
+    }
+}
+
+impl<T: Coordinate> Point3<T> {
+    pub fn z(&self) -> T {
+        self.coords[2]
+    }
+}
+
+impl<const N: usize, T: Coordinate> Point<N, T> {
+    pub fn origin() -> Self {
+        Point {
+            coords: [T::ZERO; N],
+        }
+    }
+    pub fn x(&self) -> T {
+        const { assert!(N >= 1, "Point has no x coordinate") };
+        self.coords[0]
+    }
+    /// Using this on a `Point<1, _>` fails to compile
+    pub fn y(&self) -> T {
+        const { assert!(N >= 2, "Point has no y coordinate") };
+        self.coords[1]
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        (*self - *other).coords.into_iter().map(T::abs).sum()
+    }
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        (*self - *other)
+            .coords
+            .into_iter()
+            .map(T::abs)
+            .max()
+            .unwrap_or(T::ZERO)
+    }
+    pub fn squared_euclidean_distance(&self, other: &Self) -> T {
+        (*self - *other).coords.into_iter().map(|c| c * c).sum()
+    }
+    pub fn euclidean_distance(&self, other: &Self) -> f64 {
+        self.squared_euclidean_distance(other).to_f64().sqrt()
+    }
+    /// The length of this vector measured as Manhattan distance to the origin
+    pub fn manhattan_length(&self) -> T {
+        self.manhattan_distance(&Point::origin())
+    }
+
+    /// The sign of each coordinate, e.g. to take a single step along this vector
+    pub fn signum(&self) -> Self {
+        self.map(T::signum)
+    }
+    pub fn is_adjacent_to(&self, other: &Self) -> bool {
+        self.chebyshev_distance(other) <= T::ONE
+    }
+
+    /// The 2 * N neighbors differing by one along a single axis
+    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..N).flat_map(move |axis| {
+            [-T::ONE, T::ONE].into_iter().map(move |delta| {
+                let mut neighbor = *self;
+                neighbor.coords[axis] += delta;
+                neighbor
+            })
+        })
+    }
+    /// The 3^N - 1 neighbors including diagonals
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Self> + '_ {
+        let count = 3usize.pow(N as u32);
+        (0..count)
+            .filter(move |&i| i != count / 2)
+            .map(move |mut i| {
+                let mut neighbor = *self;
+                for coord in neighbor.coords.iter_mut() {
+                    match i % 3 {
+                        0 => *coord -= T::ONE,
+                        2 => *coord += T::ONE,
+                        _ => {}
+                    }
+                    i /= 3;
+                }
+                neighbor
+            })
+    }
+
+    pub fn map(&self, f: impl Fn(T) -> T) -> Self {
+        Point {
+            coords: self.coords.map(f),
+        }
+    }
+    pub fn zip_with(&self, other: &Self, f: impl Fn(T, T) -> T) -> Self {
+        let mut coords = self.coords;
+        for (c, o) in coords.iter_mut().zip(other.coords) {
+            *c = f(*c, o);
+        }
+        Point { coords }
+    }
+}
+
+impl<const N: usize, T: Coordinate> Add for Point<N, T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a + b)
+    }
+}
+
+impl<const N: usize, T: Coordinate> AddAssign for Point<N, T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const N: usize, T: Coordinate> Sub for Point<N, T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(&rhs, |a, b| a - b)
+    }
+}
+
+impl<const N: usize, T: Coordinate> SubAssign for Point<N, T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const N: usize, T: Coordinate> Neg for Point<N, T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        self.map(|c| -c)
+    }
+}
+
+impl<const N: usize, T: Coordinate> Mul<T> for Point<N, T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.map(|c| c * rhs)
+    }
+}
+
+impl<const N: usize, T> Index<usize> for Point<N, T> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.coords[axis]
+    }
+}
+
+impl<const N: usize, T> IndexMut<usize> for Point<N, T> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.coords[axis]
+    }
+}
+
+impl<const N: usize, T: Display> Display for Point<N, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for (i, c) in self.coords.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{c}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point2::new(1, 2);
+        let b = Point2::new(3, -4);
+        assert_eq!(Point2::new(4, -2), a + b);
+        assert_eq!(Point2::new(-2, 6), a - b);
+        assert_eq!(Point2::new(3, 6), a * 3);
+        assert_eq!(Point2::new(-1, -2), -a);
+
+        let mut c = Point3::new(1, 2, 3);
+        c += Point3::new(1, 1, 1);
+        c -= Point3::new(0, 0, 5);
+        assert_eq!(Point3::new(2, 3, -1), c);
+        assert_eq!(-1, c[2]);
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point3::new(1, 2, 3);
+        let b = Point3::new(4, -2, 3);
+        assert_eq!(7, a.manhattan_distance(&b));
+        assert_eq!(4, a.chebyshev_distance(&b));
+        assert_eq!(25, a.squared_euclidean_distance(&b));
+        assert_eq!(5.0, a.euclidean_distance(&b));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p: Point2<i32> = Point2::new(5, 5);
+        assert_eq!(4, p.orthogonal_neighbors().count());
+        assert_eq!(8, p.all_neighbors().count());
+        assert!(p.all_neighbors().all(|n| n.is_adjacent_to(&p) && n != p));
+        assert_eq!(26, Point3::<i8>::origin().all_neighbors().count());
+    }
+
+    #[test]
+    fn test_rotate_2d() {
+        let east = Point2::new(1, 0);
+        let south = Point2::new(0, 1);
+        assert_eq!(south, east.rotate_cw());
+        assert_eq!(east, south.rotate_ccw());
+        assert_eq!(Point2::new(-1, 0), east.rotate_180());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("(1, -2, 3)", Point3::new(1, -2, 3).to_string());
+    }
+}
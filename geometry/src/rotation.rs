@@ -0,0 +1,143 @@
+use crate::{Coordinate, Point3};
+
+/// One of the 24 proper rotations of 3D space that map axes onto axes.
+///
+/// Applying it yields a point whose i-th coordinate is the `axes[i]`-th coordinate
+/// of the original, negated if `negate[i]` is set.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    axes: [usize; 3],
+    negate: [bool; 3],
+}
+
+const PERMUTATIONS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        axes: [0, 1, 2],
+        negate: [false; 3],
+    };
+
+    /// All 24 orientations, starting with the identity
+    pub fn all() -> Vec<Rotation> {
+        PERMUTATIONS
+            .into_iter()
+            .flat_map(|axes| {
+                (0..8).map(move |signs| Rotation {
+                    axes,
+                    negate: [signs & 1 != 0, signs & 2 != 0, signs & 4 != 0],
+                })
+            })
+            .filter(|rotation| rotation.determinant() == 1)
+            .collect()
+    }
+
+    /// A quarter turn counter-clockwise around the x, y or z axis (right-handed)
+    pub fn quarter_turn(axis: usize) -> Rotation {
+        let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+        let mut axes = [0, 1, 2];
+        let mut negate = [false; 3];
+        // new[a] = -old[b], new[b] = old[a]
+        axes[a] = b;
+        negate[a] = true;
+        axes[b] = a;
+        Rotation { axes, negate }
+    }
+
+    pub fn apply<T: Coordinate>(&self, point: Point3<T>) -> Point3<T> {
+        let mut coords = point.coords;
+        for (i, coord) in coords.iter_mut().enumerate() {
+            *coord = point.coords[self.axes[i]];
+            if self.negate[i] {
+                *coord = -*coord;
+            }
+        }
+        Point3::from(coords)
+    }
+
+    /// The rotation equivalent to first applying `first`, then `self`
+    pub fn compose(&self, first: &Rotation) -> Rotation {
+        let mut axes = [0; 3];
+        let mut negate = [false; 3];
+        for i in 0..3 {
+            axes[i] = first.axes[self.axes[i]];
+            negate[i] = self.negate[i] ^ first.negate[self.axes[i]];
+        }
+        Rotation { axes, negate }
+    }
+
+    pub fn inverse(&self) -> Rotation {
+        let mut axes = [0; 3];
+        let mut negate = [false; 3];
+        for i in 0..3 {
+            axes[self.axes[i]] = i;
+            negate[self.axes[i]] = self.negate[i];
+        }
+        Rotation { axes, negate }
+    }
+
+    fn determinant(&self) -> i8 {
+        let inversions = (0..3)
+            .flat_map(|i| (i + 1..3).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.axes[i] > self.axes[j])
+            .count();
+        let negations = self.negate.iter().filter(|&&n| n).count();
+        if (inversions + negations) % 2 == 0 {
+            1
+        } else {
+            -1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_all_orientations_are_distinct() {
+        let all = Rotation::all();
+        assert_eq!(24, all.len());
+        assert_eq!(Rotation::IDENTITY, all[0]);
+        let p = Point3::new(5, 6, -4);
+        let images: HashSet<_> = all.iter().map(|r| r.apply(p)).collect();
+        assert_eq!(24, images.len());
+        assert!(images.contains(&Point3::new(-5, 4, -6)));
+        assert!(!images.contains(&Point3::new(-5, 6, -4)));
+    }
+
+    #[test]
+    fn test_group_properties() {
+        let all = Rotation::all();
+        let p = Point3::new(1, 2, 3);
+        for a in &all {
+            assert_eq!(Rotation::IDENTITY, a.compose(&a.inverse()));
+            for b in &all {
+                let ab = a.compose(b);
+                assert!(all.contains(&ab));
+                assert_eq!(a.apply(b.apply(p)), ab.apply(p));
+            }
+        }
+    }
+
+    #[test]
+    fn test_quarter_turns() {
+        let x = Point3::new(1, 0, 0);
+        let y = Point3::new(0, 1, 0);
+        let z = Point3::new(0, 0, 1);
+        assert_eq!(y, Rotation::quarter_turn(2).apply(x));
+        assert_eq!(z, Rotation::quarter_turn(0).apply(y));
+        assert_eq!(x, Rotation::quarter_turn(1).apply(z));
+        let turn = Rotation::quarter_turn(1);
+        let full_turn = turn.compose(&turn).compose(&turn).compose(&turn);
+        assert_eq!(Rotation::IDENTITY, full_turn);
+    }
+}
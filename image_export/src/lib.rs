@@ -0,0 +1,555 @@
+//! Headless export of grids and frame sequences to PPM, PNG and animated GIF,
+//! so visual debug output can be produced without a window (e.g. on CI).
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+/// A rectangular grid of tiles, where some positions may be empty
+pub trait Tiles<T> {
+    fn width(&self) -> usize;
+    fn height(&self) -> usize;
+    fn tile_at(&self, x: usize, y: usize) -> Option<&T>;
+}
+
+/// Maps a tile to the color it should be drawn with
+pub trait Palette<T> {
+    fn color_of(&self, tile: &T) -> Rgb;
+}
+
+impl<T, F> Palette<T> for F
+where
+    F: Fn(&T) -> Rgb,
+{
+    fn color_of(&self, tile: &T) -> Rgb {
+        self(tile)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Image {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// Draws every tile of the grid as a `scale` × `scale` square.
+    /// Positions without a tile are drawn black.
+    pub fn from_grid<T>(grid: &impl Tiles<T>, palette: &impl Palette<T>, scale: usize) -> Self {
+        let mut image = Image::new(grid.width() * scale, grid.height() * scale, Rgb::BLACK);
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                if let Some(tile) = grid.tile_at(x, y) {
+                    image.fill_square(x * scale, y * scale, scale, palette.color_of(tile));
+                }
+            }
+        }
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn pixel_at(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+    pub fn set_pixel(&mut self, x: usize, y: usize, color: Rgb) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+    pub fn fill_square(&mut self, left: usize, top: usize, size: usize, color: Rgb) {
+        for y in top..top + size {
+            for x in left..left + size {
+                self.set_pixel(x, y, color);
+            }
+        }
+    }
+
+    /// Binary (P6) portable pixmap
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        for Rgb(r, g, b) in &self.pixels {
+            bytes.extend([*r, *g, *b]);
+        }
+        bytes
+    }
+
+    /// 8-bit RGB PNG. The image data is stored without compression,
+    /// which keeps the encoder simple at the cost of larger files.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (1 + 3 * self.width));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0); // filter type none
+            for Rgb(r, g, b) in row {
+                raw.extend([*r, *g, *b]);
+            }
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // bit depth 8, color type 2 (RGB), default compression, filter and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut bytes = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+        write_png_chunk(&mut bytes, b"IHDR", &header);
+        write_png_chunk(&mut bytes, b"IDAT", &zlib_stored(&raw));
+        write_png_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+
+    pub fn save_ppm(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        File::create(path)?.write_all(&self.to_ppm())
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        File::create(path)?.write_all(&self.to_png())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportError {
+    /// GIF stores width and height as 16-bit numbers
+    TooLargeForGif { width: usize, height: usize },
+    /// GIF color tables hold at most 256 colors
+    TooManyColors,
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::TooLargeForGif { width, height } => write!(
+                f,
+                "Frames of {width}×{height} pixels exceed the GIF maximum of {0}×{0}",
+                u16::MAX
+            ),
+            ExportError::TooManyColors => {
+                write!(f, "Frames use more than the GIF maximum of 256 colors")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+/// A sequence of equally sized frames, exported as a looping animated GIF
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    frames: Vec<Image>,
+    /// Delay between frames in hundredths of a second
+    delay: u16,
+}
+
+impl Animation {
+    pub fn new(delay_in_centiseconds: u16) -> Self {
+        Animation {
+            frames: vec![],
+            delay: delay_in_centiseconds,
+        }
+    }
+
+    pub fn push(&mut self, frame: Image) {
+        if let Some(first) = self.frames.first() {
+            assert_eq!(
+                (first.width, first.height),
+                (frame.width, frame.height),
+                "All frames must have the same size"
+            );
+        }
+        self.frames.push(frame);
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// GIF89a with a single global color table shared by all frames
+    pub fn to_gif(&self) -> Result<Vec<u8>, ExportError> {
+        let (width, height) = match self.frames.first() {
+            Some(frame) => match (u16::try_from(frame.width), u16::try_from(frame.height)) {
+                (Ok(width), Ok(height)) => (width, height),
+                _ => {
+                    return Err(ExportError::TooLargeForGif {
+                        width: frame.width,
+                        height: frame.height,
+                    })
+                }
+            },
+            None => (0, 0),
+        };
+
+        let mut colors: Vec<Rgb> = vec![];
+        let mut index_by_color: HashMap<Rgb, u8> = HashMap::new();
+        let indexed_frames: Vec<Vec<u8>> = self
+            .frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .pixels
+                    .iter()
+                    .map(|color| match index_by_color.entry(*color) {
+                        Entry::Occupied(entry) => Ok(*entry.get()),
+                        Entry::Vacant(entry) => {
+                            let index = u8::try_from(colors.len())
+                                .map_err(|_| ExportError::TooManyColors)?;
+                            colors.push(*color);
+                            Ok(*entry.insert(index))
+                        }
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+
+        // The color table holds 2^(table_bits) entries, with 1 <= table_bits <= 8
+        let table_bits = (1..=8).find(|bits| colors.len() <= 1 << bits).unwrap();
+        colors.resize(1 << table_bits, Rgb::BLACK);
+
+        let mut bytes = b"GIF89a".to_vec();
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.push(0b1000_0000 | ((table_bits - 1) << 4) | (table_bits - 1));
+        bytes.extend([0, 0]); // background color index, pixel aspect ratio
+        for Rgb(r, g, b) in &colors {
+            bytes.extend([*r, *g, *b]);
+        }
+        // Loop forever
+        bytes.extend([0x21, 0xFF, 0x0B]);
+        bytes.extend(b"NETSCAPE2.0");
+        bytes.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        let min_code_size = table_bits.max(2);
+        for indices in indexed_frames {
+            // Graphic control extension with the frame delay
+            bytes.extend([0x21, 0xF9, 0x04, 0x00]);
+            bytes.extend(self.delay.to_le_bytes());
+            bytes.extend([0x00, 0x00]);
+            // Image descriptor covering the whole canvas, no local color table
+            bytes.push(0x2C);
+            bytes.extend([0, 0, 0, 0]);
+            bytes.extend(width.to_le_bytes());
+            bytes.extend(height.to_le_bytes());
+            bytes.push(0);
+
+            bytes.push(min_code_size);
+            for block in lzw_encode(&indices, min_code_size).chunks(255) {
+                bytes.push(block.len() as u8);
+                bytes.extend(block);
+            }
+            bytes.push(0); // block terminator
+        }
+        bytes.push(0x3B); // trailer
+        Ok(bytes)
+    }
+
+    pub fn save_gif(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let gif = self
+            .to_gif()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+        File::create(path)?.write_all(&gif)
+    }
+}
+
+fn write_png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65_521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
+
+/// Wraps the data in a zlib stream made of uncompressed ("stored") deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK_LEN: usize = 65_535;
+    let mut bytes = vec![0x78, 0x01];
+    let block_count = data.len().div_ceil(MAX_BLOCK_LEN).max(1);
+    for i in 0..block_count {
+        let block =
+            &data[(i * MAX_BLOCK_LEN).min(data.len())..((i + 1) * MAX_BLOCK_LEN).min(data.len())];
+        let is_final = i + 1 == block_count;
+        bytes.push(is_final as u8);
+        let len = block.len() as u16;
+        bytes.extend(len.to_le_bytes());
+        bytes.extend((!len).to_le_bytes());
+        bytes.extend(block);
+    }
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+/// Variable-length-code LZW as used by GIF, returning the packed bytes
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    const MAX_CODE: u16 = 4095;
+    let clear_code: u16 = 1 << min_code_size;
+    let end_code = clear_code + 1;
+
+    let mut writer = BitWriter::default();
+    let mut code_size = min_code_size + 1;
+    let mut next_code = end_code + 1;
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    writer.write(clear_code, code_size);
+
+    let Some((first, rest)) = indices.split_first() else {
+        writer.write(end_code, code_size);
+        return writer.finish();
+    };
+    let mut prefix = *first as u16;
+    for index in rest {
+        if let Some(code) = codes.get(&(prefix, *index)) {
+            prefix = *code;
+            continue;
+        }
+        writer.write(prefix, code_size);
+        if next_code > MAX_CODE {
+            writer.write(clear_code, code_size);
+            codes.clear();
+            code_size = min_code_size + 1;
+            next_code = end_code + 1;
+        } else {
+            if next_code == 1 << code_size {
+                code_size += 1;
+            }
+            codes.insert((prefix, *index), next_code);
+            next_code += 1;
+        }
+        prefix = *index as u16;
+    }
+    writer.write(prefix, code_size);
+    writer.write(end_code, code_size);
+    writer.finish()
+}
+
+/// Packs codes least significant bit first
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bit_count: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bit_count;
+        self.bit_count += size;
+        while self.bit_count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bit_count -= 8;
+        }
+    }
+    fn finish(mut self) -> Vec<u8> {
+        if self.bit_count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CharGrid(Vec<Vec<char>>);
+
+    impl From<&str> for CharGrid {
+        fn from(s: &str) -> Self {
+            CharGrid(s.lines().map(|line| line.chars().collect()).collect())
+        }
+    }
+
+    impl Tiles<char> for CharGrid {
+        fn width(&self) -> usize {
+            self.0[0].len()
+        }
+        fn height(&self) -> usize {
+            self.0.len()
+        }
+        fn tile_at(&self, x: usize, y: usize) -> Option<&char> {
+            self.0.get(y).and_then(|line| line.get(x))
+        }
+    }
+
+    fn palette(c: &char) -> Rgb {
+        match c {
+            '#' => Rgb::WHITE,
+            'O' => Rgb(255, 0, 0),
+            _ => Rgb::BLACK,
+        }
+    }
+
+    #[test]
+    fn test_from_grid_with_scale() {
+        let grid = CharGrid::from("#.\n.O");
+        let image = Image::from_grid(&grid, &palette, 2);
+        assert_eq!((4, 4), (image.width(), image.height()));
+        assert_eq!(Some(Rgb::WHITE), image.pixel_at(1, 1));
+        assert_eq!(Some(Rgb::BLACK), image.pixel_at(2, 1));
+        assert_eq!(Some(Rgb(255, 0, 0)), image.pixel_at(3, 3));
+        assert_eq!(None, image.pixel_at(4, 0));
+    }
+
+    #[test]
+    fn test_to_ppm() {
+        let grid = CharGrid::from("#.O");
+        let image = Image::from_grid(&grid, &palette, 1);
+        let mut expected = b"P6\n3 1\n255\n".to_vec();
+        expected.extend([255, 255, 255, 0, 0, 0, 255, 0, 0]);
+        assert_eq!(expected, image.to_ppm());
+    }
+
+    #[test]
+    fn test_crc32_and_adler32() {
+        assert_eq!(0xCBF4_3926, crc32(b"123456789"));
+        assert_eq!(0x11E6_0398, adler32(b"Wikipedia"));
+    }
+
+    #[test]
+    fn test_to_png() {
+        let image = Image::new(2, 1, Rgb(1, 2, 3));
+        let png = image.to_png();
+        assert_eq!(
+            &[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'],
+            &png[..8]
+        );
+        // IHDR chunk: length, type, width, height, bit depth, color type
+        assert_eq!(&[0, 0, 0, 13], &png[8..12]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(&[0, 0, 0, 2, 0, 0, 0, 1, 8, 2], &png[16..26]);
+        // IDAT chunk holds: zlib header, one final stored block of 7 bytes, adler32
+        let raw = [0, 1, 2, 3, 1, 2, 3];
+        let mut idat = vec![0x78, 0x01, 0x01, 7, 0, !7, 0xFF];
+        idat.extend(raw);
+        idat.extend(adler32(&raw).to_be_bytes());
+        assert_eq!(&(idat.len() as u32).to_be_bytes(), &png[33..37]);
+        assert_eq!(b"IDAT", &png[37..41]);
+        assert_eq!(&idat[..], &png[41..41 + idat.len()]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xAE, 0x42, 0x60, 0x82]));
+    }
+
+    #[test]
+    fn test_lzw_encode_sample_image() {
+        // The 10×10 sample image from "What's In A GIF" by Matthew Flickinger
+        let indices: Vec<u8> = "\
+1111122222
+1111122222
+1111122222
+1110000222
+1110000222
+2220000111
+2220000111
+2222211111
+2222211111
+2222211111"
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| d as u8)
+            .collect();
+        assert_eq!(
+            vec![
+                0x8C, 0x2D, 0x99, 0x87, 0x2A, 0x1C, 0xDC, 0x33, 0xA0, 0x02, 0x75, 0xEC, 0x95, 0xFA,
+                0xA8, 0xDE, 0x60, 0x8C, 0x04, 0x91, 0x4C, 0x01
+            ],
+            lzw_encode(&indices, 2)
+        );
+    }
+
+    #[test]
+    fn test_to_gif() {
+        let mut animation = Animation::new(10);
+        let grid = CharGrid::from("#.\n.O");
+        animation.push(Image::from_grid(&grid, &palette, 1));
+        let grid = CharGrid::from(".#\nO.");
+        animation.push(Image::from_grid(&grid, &palette, 1));
+        assert_eq!(2, animation.frame_count());
+
+        let gif = animation.to_gif().unwrap();
+        assert_eq!(b"GIF89a", &gif[..6]);
+        assert_eq!(&[2, 0, 2, 0], &gif[6..10]);
+        // Global color table with 2^2 entries: white, black, red and padding
+        assert_eq!(0b1001_0001, gif[10]);
+        assert_eq!(&[255, 255, 255, 0, 0, 0, 255, 0, 0, 0, 0, 0], &gif[13..25]);
+        assert_eq!(2, gif.windows(2).filter(|w| w == &[0x21, 0xF9]).count());
+        assert_eq!(Some(&0x3B), gif.last());
+    }
+
+    #[test]
+    fn test_to_gif_rejects_frames_too_large() {
+        let mut animation = Animation::new(10);
+        animation.push(Image::new(70_000, 1, Rgb::BLACK));
+        assert_eq!(
+            Err(ExportError::TooLargeForGif {
+                width: 70_000,
+                height: 1
+            }),
+            animation.to_gif()
+        );
+    }
+
+    #[test]
+    fn test_to_gif_rejects_more_than_256_colors() {
+        let mut animation = Animation::new(10);
+        let mut image = Image::new(16, 16, Rgb::BLACK);
+        for i in 0..256 {
+            image.set_pixel(i % 16, i / 16, Rgb(i as u8, 0, 0));
+        }
+        animation.push(image.clone());
+        assert!(animation.to_gif().is_ok());
+        image.set_pixel(0, 0, Rgb::WHITE);
+        animation.push(image);
+        assert_eq!(Err(ExportError::TooManyColors), animation.to_gif());
+    }
+
+    #[test]
+    #[should_panic(expected = "All frames must have the same size")]
+    fn test_frames_of_different_size_are_rejected() {
+        let mut animation = Animation::new(10);
+        animation.push(Image::new(1, 1, Rgb::BLACK));
+        animation.push(Image::new(2, 1, Rgb::BLACK));
+    }
+}
@@ -0,0 +1,229 @@
+//! Nested lists of integers, as written in puzzles like `[1,[2,[3,4]],5]`:
+//! the distress signal packets of 2022 day 13 and the snailfish numbers of 2021 day 18.
+//!
+//! Puzzle specific operations, such as the snailfish arithmetic, are built on top of [`Value`].
+
+mod parse;
+
+pub use parse::{ParseError, ParseErrorKind};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::slice;
+use std::str::FromStr;
+use Value::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    Integer(usize),
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Parses a complete value, such as `[1,[2,3],[]]`, without any surrounding whitespace
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        parse::parse(input)
+    }
+
+    pub fn pair(left: Value, right: Value) -> Self {
+        List(vec![left, right])
+    }
+
+    pub fn as_integer(&self) -> Option<usize> {
+        match self {
+            Integer(value) => Some(*value),
+            List(_) => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Integer(_) => None,
+            List(values) => Some(values),
+        }
+    }
+
+    /// The number of lists enclosing the innermost value, 0 for integers
+    pub fn depth(&self) -> usize {
+        match self {
+            Integer(_) => 0,
+            List(values) => 1 + values.iter().map(Value::depth).max().unwrap_or(0),
+        }
+    }
+
+    /// Compares by the distress signal rules of 2022 day 13: integers by value, lists
+    /// element by element with the shorter list first if one is a prefix of the other,
+    /// and an integer compared to a list as if it were a list containing just that integer.
+    ///
+    /// These rules make `1`, `[1]` and `[[1]]` equal, so this is only a total preorder.
+    pub fn cmp_as_packets(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Integer(left), Integer(right)) => left.cmp(right),
+            _ => {
+                let (left, right) = (self.as_slice(), other.as_slice());
+                for (l, r) in left.iter().zip(right) {
+                    match l.cmp_as_packets(r) {
+                        Ordering::Equal => {}
+                        unequal => return unequal,
+                    }
+                }
+                left.len().cmp(&right.len())
+            }
+        }
+    }
+
+    /// An integer as a list containing just that integer, without allocating
+    fn as_slice(&self) -> &[Value] {
+        match self {
+            Integer(_) => slice::from_ref(self),
+            List(values) => values,
+        }
+    }
+
+    /// Structural order, used to break ties between values that are equal as packets:
+    /// integers before lists, integers by value, and lists lexicographically
+    fn cmp_structurally(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Integer(left), Integer(right)) => left.cmp(right),
+            (Integer(_), List(_)) => Ordering::Less,
+            (List(_), Integer(_)) => Ordering::Greater,
+            (List(left), List(right)) => {
+                for (l, r) in left.iter().zip(right) {
+                    match l.cmp_structurally(r) {
+                        Ordering::Equal => {}
+                        unequal => return unequal,
+                    }
+                }
+                left.len().cmp(&right.len())
+            }
+        }
+    }
+}
+
+/// The order of [`Value::cmp_as_packets`], with ties between structurally different values
+/// such as `1` and `[1]` broken by putting integers before lists. This keeps the order total
+/// and consistent with `==`, so sorting packets works as the puzzle expects.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cmp_as_packets(other)
+            .then_with(|| self.cmp_structurally(other))
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Integer(value)
+    }
+}
+
+impl From<Vec<Value>> for Value {
+    fn from(values: Vec<Value>) -> Self {
+        List(values)
+    }
+}
+
+impl FromStr for Value {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Value::parse(s)
+    }
+}
+
+/// Writes the value the way it's parsed, without any whitespace
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Integer(value) => write!(f, "{value}"),
+            List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(input: &str) -> Value {
+        Value::parse(input).unwrap()
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for input in ["[]", "7", "[1,[2,[3,[4,[5,6,0]]]],8,9]", "[[[]],[10,200]]"] {
+            assert_eq!(input, value(input).to_string());
+        }
+    }
+
+    #[test]
+    fn test_accessors() {
+        let list = value("[1,[2,[]]]");
+        assert_eq!(None, list.as_integer());
+        assert_eq!(Some(1), list.as_list().unwrap()[0].as_integer());
+        assert_eq!(3, list.depth());
+        assert_eq!(0, value("42").depth());
+        assert_eq!(
+            list,
+            List(vec![1.into(), Value::pair(2.into(), vec![].into())])
+        );
+    }
+
+    #[test]
+    fn test_cmp_as_packets() {
+        let pairs = [
+            ("[1,1,3,1,1]", "[1,1,5,1,1]", Ordering::Less),
+            ("[[1],[2,3,4]]", "[[1],4]", Ordering::Less),
+            ("[9]", "[[8,7,6]]", Ordering::Greater),
+            ("[[4,4],4,4]", "[[4,4],4,4,4]", Ordering::Less),
+            ("[7,7,7,7]", "[7,7,7]", Ordering::Greater),
+            ("[]", "[3]", Ordering::Less),
+            ("[[[]]]", "[[]]", Ordering::Greater),
+            (
+                "[1,[2,[3,[4,[5,6,7]]]],8,9]",
+                "[1,[2,[3,[4,[5,6,0]]]],8,9]",
+                Ordering::Greater,
+            ),
+            ("[[1]]", "1", Ordering::Equal),
+        ];
+        for (left, right, expected) in pairs {
+            assert_eq!(expected, value(left).cmp_as_packets(&value(right)));
+            assert_eq!(
+                expected.reverse(),
+                value(right).cmp_as_packets(&value(left))
+            );
+        }
+    }
+
+    #[test]
+    fn test_ord_is_total_and_consistent_with_eq() {
+        let mut values: Vec<_> = ["[[1]]", "[1]", "1", "[1,0]", "0", "[]", "[[]]", "[1]"]
+            .into_iter()
+            .map(value)
+            .collect();
+        values.sort();
+        let sorted: Vec<_> = values.iter().map(Value::to_string).collect();
+        assert_eq!(
+            vec!["[]", "[[]]", "0", "1", "[1]", "[1]", "[[1]]", "[1,0]"],
+            sorted
+        );
+        for a in &values {
+            for b in &values {
+                assert_eq!(a == b, a.cmp(b) == Ordering::Equal);
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::Value;
+use std::fmt::{Display, Formatter};
+
+/// Why and where, as byte offset into the input, parsing failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub pos: usize,
+    pub kind: ParseErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedEnd,
+    /// Found a character where an integer or a list should start
+    ExpectedValue(char),
+    /// Found a character where a list should continue with a comma or end
+    ExpectedCommaOrClose(char),
+    IntegerTooLarge,
+    TrailingInput,
+}
+
+/// Parses directly from the input bytes, without allocating anything but the values
+pub(crate) fn parse(input: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser {
+        input: input.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.value()?;
+    if parser.pos < parser.input.len() {
+        return Err(parser.error(ParseErrorKind::TrailingInput));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Result<u8, ParseError> {
+        self.input
+            .get(self.pos)
+            .copied()
+            .ok_or(self.error(ParseErrorKind::UnexpectedEnd))
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        match self.peek()? {
+            b'[' => self.list(),
+            b'0'..=b'9' => self.integer(),
+            c => Err(self.error(ParseErrorKind::ExpectedValue(c as char))),
+        }
+    }
+
+    fn list(&mut self) -> Result<Value, ParseError> {
+        self.pos += 1; // [
+        let mut values = vec![];
+        if self.peek()? == b']' {
+            self.pos += 1;
+            return Ok(Value::List(values));
+        }
+        loop {
+            values.push(self.value()?);
+            match self.peek()? {
+                b',' => self.pos += 1,
+                b']' => {
+                    self.pos += 1;
+                    return Ok(Value::List(values));
+                }
+                c => return Err(self.error(ParseErrorKind::ExpectedCommaOrClose(c as char))),
+            }
+        }
+    }
+
+    fn integer(&mut self) -> Result<Value, ParseError> {
+        let start = self.pos;
+        let mut value = 0_usize;
+        while let Some(digit @ b'0'..=b'9') = self.input.get(self.pos) {
+            value = value
+                .checked_mul(10)
+                .and_then(|value| value.checked_add((digit - b'0') as usize))
+                .ok_or(ParseError {
+                    pos: start,
+                    kind: ParseErrorKind::IntegerTooLarge,
+                })?;
+            self.pos += 1;
+        }
+        Ok(Value::Integer(value))
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            pos: self.pos,
+            kind,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::ExpectedValue(c) => write!(f, "expected '[' or a digit, found '{c}'"),
+            ParseErrorKind::ExpectedCommaOrClose(c) => {
+                write!(f, "expected ',' or ']', found '{c}'")
+            }
+            ParseErrorKind::IntegerTooLarge => write!(f, "integer too large"),
+            ParseErrorKind::TrailingInput => write!(f, "unexpected input after the value"),
+        }?;
+        write!(f, " at position {}", self.pos)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ParseErrorKind::*;
+
+    fn error(input: &str) -> (usize, ParseErrorKind) {
+        let error = Value::parse(input).unwrap_err();
+        (error.pos, error.kind)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Value::List(vec![])), Value::parse("[]"));
+        assert_eq!(
+            Ok(Value::List(vec![
+                Value::Integer(10),
+                Value::List(vec![Value::Integer(0)])
+            ])),
+            Value::parse("[10,[0]]")
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!((0, UnexpectedEnd), error(""));
+        assert_eq!((4, UnexpectedEnd), error("[1,2"));
+        assert_eq!((3, ExpectedValue(']')), error("[1,]"));
+        assert_eq!((1, ExpectedValue(' ')), error("[ 1]"));
+        assert_eq!((4, ExpectedCommaOrClose('[')), error("[[1][2]]"));
+        assert_eq!((3, TrailingInput), error("[1]]"));
+        assert_eq!((3, IntegerTooLarge), error("[1,99999999999999999999999]"));
+        assert_eq!(
+            "expected ',' or ']', found '[' at position 4",
+            Value::parse("[[1][2]]").unwrap_err().to_string()
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{add_mod, inv_mod, mul_mod, sub_mod};
+
+/// The function x ↦ (a * x + b) % modulus.
+///
+/// Composition of affine maps is again an affine map, which makes it possible to apply
+/// one a huge number of times with [`AffineMap::pow`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AffineMap {
+    pub a: usize,
+    pub b: usize,
+    pub modulus: usize,
+}
+
+impl AffineMap {
+    pub fn new(a: usize, b: usize, modulus: usize) -> Self {
+        AffineMap {
+            a: a % modulus,
+            b: b % modulus,
+            modulus,
+        }
+    }
+
+    pub fn identity(modulus: usize) -> Self {
+        AffineMap::new(1, 0, modulus)
+    }
+
+    pub fn apply(&self, x: usize) -> usize {
+        add_mod(mul_mod(self.a, x, self.modulus), self.b, self.modulus)
+    }
+
+    /// The map applying `self` first and `next` afterwards
+    pub fn then(&self, next: &AffineMap) -> AffineMap {
+        assert_eq!(self.modulus, next.modulus, "moduli must match");
+        let m = self.modulus;
+        // next(self(x)) = next.a * (self.a * x + self.b) + next.b
+        AffineMap {
+            a: mul_mod(next.a, self.a, m),
+            b: add_mod(mul_mod(next.a, self.b, m), next.b, m),
+            modulus: m,
+        }
+    }
+
+    /// The map applying `self` `n` times, by repeated squaring
+    pub fn pow(&self, mut n: usize) -> AffineMap {
+        let mut result = AffineMap::identity(self.modulus);
+        let mut square = *self;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.then(&square);
+            }
+            square = square.then(&square);
+            n >>= 1;
+        }
+        result
+    }
+
+    /// The map undoing `self`, if `a` is invertible modulo the modulus
+    pub fn inverse(&self) -> Option<AffineMap> {
+        // x = (y - b) / a = a⁻¹ * y - a⁻¹ * b
+        let a_inv = inv_mod(self.a, self.modulus)?;
+        let b = sub_mod(0, mul_mod(a_inv, self.b, self.modulus), self.modulus);
+        Some(AffineMap::new(a_inv, b, self.modulus))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_then_applies_in_order() {
+        let double = AffineMap::new(2, 0, 101);
+        let increment = AffineMap::new(1, 1, 101);
+        assert_eq!(21, double.then(&increment).apply(10)); // 2 * 10 + 1
+        assert_eq!(22, increment.then(&double).apply(10)); // 2 * (10 + 1)
+    }
+
+    #[test]
+    fn test_pow_matches_repeated_application() {
+        let map = AffineMap::new(7, 3, 10_007);
+        let mut x = 2019;
+        for n in 0..50 {
+            assert_eq!(x, map.pow(n).apply(2019));
+            x = map.apply(x);
+        }
+    }
+
+    #[test]
+    fn test_inverse() {
+        let map = AffineMap::new(12854400258724, 75, 119315717514047);
+        let inverse = map.inverse().unwrap();
+        assert_eq!(AffineMap::identity(map.modulus), map.then(&inverse));
+        assert_eq!(2020, inverse.apply(map.apply(2020)));
+        assert_eq!(None, AffineMap::new(4, 1, 10).inverse());
+    }
+}
@@ -0,0 +1,252 @@
+//! Modular arithmetic and number theory helpers: the gcd, lcm and Chinese remainder theorem
+//! calculations behind the many "when do all these cycles line up" puzzles,
+//! the modular affine maps behind the card shuffling ones,
+//! and exact fractions for solving equations without rounding.
+//!
+//! Products are calculated with `u128` intermediates, so any modulus up to `usize::MAX` is safe.
+
+mod affine;
+mod rational;
+
+pub use affine::AffineMap;
+pub use rational::Rational;
+
+/// (a + b) % m
+pub fn add_mod(a: usize, b: usize, m: usize) -> usize {
+    ((a as u128 + b as u128) % m as u128) as usize
+}
+
+/// (a - b) % m, without going negative
+pub fn sub_mod(a: usize, b: usize, m: usize) -> usize {
+    let (a, b) = (a % m, b % m);
+    if a >= b {
+        a - b
+    } else {
+        a + (m - b)
+    }
+}
+
+/// (a * b) % m
+pub fn mul_mod(a: usize, b: usize, m: usize) -> usize {
+    (a as u128 * b as u128 % m as u128) as usize
+}
+
+/// base ^ exp % m, by repeated squaring
+pub fn pow_mod(base: usize, mut exp: usize, m: usize) -> usize {
+    let mut result = 1 % m;
+    let mut square = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, square, m);
+        }
+        square = mul_mod(square, square, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The x such that (a * x) % m == 1, if a and m are coprime
+pub fn inv_mod(a: usize, m: usize) -> Option<usize> {
+    let (g, x, _) = extended_gcd(a as i128, m as i128);
+    (g == 1).then(|| x.rem_euclid(m as i128) as usize)
+}
+
+/// Returns (g, x, y) such that a * x + b * y = g = gcd(a, b)
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// greatest common divisor
+pub fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// least common multiple
+///
+/// # Panics
+///
+/// If the result does not fit in a `usize`
+pub fn lcm(a: usize, b: usize) -> usize {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        (a / gcd(a, b))
+            .checked_mul(b)
+            .unwrap_or_else(|| panic!("lcm({a}, {b}) does not fit in a usize"))
+    }
+}
+
+/// The least common multiple of all numbers, or 1 if there are none
+pub fn lcm_of(numbers: impl IntoIterator<Item = usize>) -> usize {
+    numbers.into_iter().fold(1, lcm)
+}
+
+/// The prime factors of a number, in ascending order and repeated as often as they divide it
+pub fn prime_factors(mut number: usize) -> Vec<usize> {
+    let mut factors = vec![];
+    let mut divisor = 2;
+    while divisor * divisor <= number {
+        while number.is_multiple_of(divisor) {
+            factors.push(divisor);
+            number /= divisor;
+        }
+        divisor += if divisor == 2 { 1 } else { 2 };
+    }
+    if number > 1 {
+        factors.push(number);
+    }
+    factors
+}
+
+/// A congruence x ≡ residue (mod modulus), as a (residue, modulus) pair
+pub type Congruence = (usize, usize);
+
+/// Combines two congruences into a single one modulo the lcm of both moduli,
+/// or `None` if no number satisfies both. The moduli don't need to be coprime.
+///
+/// # Panics
+///
+/// If the lcm of both moduli does not fit in a `usize`
+pub fn combine((a1, m1): Congruence, (a2, m2): Congruence) -> Option<Congruence> {
+    let (g, p, _) = extended_gcd(m1 as i128, m2 as i128);
+    let diff = a2 as i128 - a1 as i128;
+    if diff % g != 0 {
+        return None;
+    }
+    let step = m2 / g as usize;
+    let Some(lcm) = m1.checked_mul(step) else {
+        panic!("the lcm of the moduli {m1} and {m2} does not fit in a usize");
+    };
+    let k = mul_mod(
+        (diff / g).rem_euclid(step as i128) as usize,
+        p.rem_euclid(step as i128) as usize,
+        step,
+    );
+    // k < step, so m1 * k < lcm doesn't overflow either
+    Some((add_mod(a1, m1 * k, lcm), lcm))
+}
+
+/// Solves a system of congruences with the Chinese remainder theorem, generalized to moduli
+/// that aren't pairwise coprime. The smallest non-negative solution is the returned residue.
+///
+/// # Panics
+///
+/// If the lcm of all moduli does not fit in a `usize`
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Option<Congruence> {
+    congruences.into_iter().try_fold((0, 1), combine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mul_mod_does_not_overflow() {
+        assert_eq!(
+            46400556811471,
+            mul_mod(53029207790359, 93748063761037, 119315717514047)
+        );
+        assert_eq!(0, mul_mod(usize::MAX / 6700417, 6700417, usize::MAX));
+        assert_eq!(1, mul_mod(usize::MAX - 1, usize::MAX - 1, usize::MAX));
+    }
+
+    #[test]
+    fn test_add_and_sub_mod() {
+        assert_eq!(
+            usize::MAX - 2,
+            add_mod(usize::MAX - 1, usize::MAX - 1, usize::MAX)
+        );
+        assert_eq!(7, sub_mod(2, 5, 10));
+        assert_eq!(3, sub_mod(5, 12, 10));
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(1, pow_mod(12, 0, 13));
+        assert_eq!(0, pow_mod(12, 0, 1));
+        assert_eq!(445, pow_mod(4, 13, 497));
+        // Fermat's little theorem
+        let p = 119315717514047;
+        assert_eq!(1, pow_mod(12854400258724, p - 1, p));
+    }
+
+    #[test]
+    fn test_inv_mod() {
+        assert_eq!(Some(9), inv_mod(9, 10));
+        assert_eq!(Some(3), inv_mod(7, 10));
+        assert_eq!(None, inv_mod(4, 10));
+        let p = 119315717514047;
+        let inverse = inv_mod(12854400258724, p).unwrap();
+        assert_eq!(1, mul_mod(inverse, 12854400258724, p));
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!((2, -9, 47), extended_gcd(240, 46));
+        assert_eq!((6, -1, 0), extended_gcd(-6, 0));
+    }
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(6, gcd(12, 18));
+        assert_eq!(36, lcm(12, 18));
+        assert_eq!(1, lcm_of([]));
+        assert_eq!(2772, lcm_of([18, 28, 44]));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in a usize")]
+    fn test_lcm_overflow() {
+        lcm(usize::MAX, usize::MAX - 1);
+    }
+
+    #[test]
+    fn test_prime_factors() {
+        assert_eq!(vec![2, 3, 5, 7], prime_factors(2 * 3 * 5 * 7));
+        assert_eq!(vec![2, 2, 2, 3, 3], prime_factors(72));
+        assert_eq!(vec![71, 271], prime_factors(71 * 271));
+        assert!(prime_factors(1).is_empty());
+    }
+
+    #[test]
+    fn test_crt_with_coprime_moduli() {
+        // The bus schedule 7,13,x,x,59,x,31,19
+        let congruences = [
+            (0, 7),
+            (13 - 1, 13),
+            (59 - 4, 59),
+            (31 - 6, 31),
+            (19 - 7, 19),
+        ];
+        assert_eq!(Some((1068781, 7 * 13 * 59 * 31 * 19)), crt(congruences));
+    }
+
+    #[test]
+    fn test_crt_with_common_factors() {
+        assert_eq!(Some((10, 12)), crt([(4, 6), (2, 4)]));
+        assert_eq!(None, crt([(1, 6), (2, 4)]));
+        assert_eq!(Some((0, 1)), crt([]));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit in a usize")]
+    fn test_crt_overflow() {
+        crt([(0, 1 << 40), (0, (1 << 40) - 1)]);
+    }
+}
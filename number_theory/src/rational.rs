@@ -0,0 +1,179 @@
+use crate::extended_gcd;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An exact fraction, always kept in lowest terms with a positive denominator.
+///
+/// The arithmetic operators panic on division by zero, like the integer ones,
+/// see [`Rational::checked_div`] for the alternative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational::integer(0);
+    pub const ONE: Rational = Rational::integer(1);
+
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert_ne!(0, denominator, "denominator must not be zero");
+        let (g, _, _) = extended_gcd(numerator, denominator);
+        let sign = denominator.signum();
+        Rational {
+            numerator: sign * numerator / g,
+            denominator: sign * denominator / g,
+        }
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Rational {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator == 0
+    }
+
+    /// The value as integer, if it is one
+    pub fn to_integer(&self) -> Option<i128> {
+        (self.denominator == 1).then_some(self.numerator)
+    }
+
+    pub fn recip(self) -> Option<Self> {
+        (!self.is_zero()).then(|| Rational::new(self.denominator, self.numerator))
+    }
+
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        rhs.recip().map(|recip| self * recip)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational::integer(value)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Rational::new(
+            self.numerator * rhs.denominator + rhs.numerator * self.denominator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Rational::new(
+            self.numerator * rhs.numerator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.checked_div(rhs).expect("division by zero")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross-multiplying keeps the order
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.denominator == 1 {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lowest_terms() {
+        assert_eq!(Rational::new(1, 2), Rational::new(-3, -6));
+        assert_eq!(-2, Rational::new(4, -6).numerator());
+        assert_eq!(3, Rational::new(4, -6).denominator());
+        assert_eq!(Rational::ZERO, Rational::new(0, -5));
+        assert_eq!(Some(7), Rational::new(21, 3).to_integer());
+        assert_eq!(None, Rational::new(1, 3).to_integer());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let (half, third) = (Rational::new(1, 2), Rational::new(1, 3));
+        assert_eq!(Rational::new(5, 6), half + third);
+        assert_eq!(Rational::new(1, 6), half - third);
+        assert_eq!(Rational::new(-1, 6), third - half);
+        assert_eq!(Rational::new(1, 6), half * third);
+        assert_eq!(Rational::new(3, 2), half / third);
+        assert_eq!(None, half.checked_div(Rational::ZERO));
+        assert_eq!(None, Rational::ZERO.recip());
+        assert_eq!(Rational::ONE, (third * 3.into()).recip().unwrap());
+    }
+
+    #[test]
+    fn test_order_and_display() {
+        let mut values = [
+            Rational::new(1, 2),
+            Rational::new(-1, 3),
+            Rational::integer(0),
+        ];
+        values.sort();
+        let values: Vec<_> = values.iter().map(Rational::to_string).collect();
+        assert_eq!(vec!["-1/3", "0", "1/2"], values);
+    }
+}
@@ -0,0 +1,186 @@
+use std::cmp::{max, min};
+
+pub type Coord = usize;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Pos {
+    pub x: Coord,
+    pub y: Coord,
+}
+
+impl Pos {
+    pub const fn new(x: Coord, y: Coord) -> Self {
+        Pos { x, y }
+    }
+    pub fn left(&self) -> Option<Pos> {
+        self.x.checked_sub(1).map(|x| Pos::new(x, self.y))
+    }
+    pub fn right(&self) -> Pos {
+        Pos::new(self.x + 1, self.y)
+    }
+    pub fn above(&self) -> Option<Pos> {
+        self.y.checked_sub(1).map(|y| Pos::new(self.x, y))
+    }
+    pub fn below(&self) -> Pos {
+        Pos::new(self.x, self.y + 1)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Cell {
+    /// Particles can move through it
+    Empty,
+    /// Rock or clay, particles rest on it
+    Solid,
+    /// Where the particles come from
+    Source,
+    /// Particles passed through it, and may still do so
+    Flowing,
+    /// A particle came to rest in it
+    Settled,
+}
+
+impl Cell {
+    /// How the cell is drawn, with settled particles drawn as given
+    pub fn to_char(self, settled: char) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::Solid => '#',
+            Cell::Source => '+',
+            Cell::Flowing => '|',
+            Cell::Settled => settled,
+        }
+    }
+}
+
+/// A rectangle of cells from the source's row down to the lowest solid cell, one column
+/// wider than the solid cells on each side, so particles can fall past them on either side.
+/// Anything outside of it is bottomless.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    source: Pos,
+    x_min: Coord,
+    width: usize,
+    /// Indexed by [y * width + x - x_min]
+    cells: Vec<Cell>,
+}
+
+impl Grid {
+    pub fn new(source: Pos, solids: impl IntoIterator<Item = Pos>) -> Self {
+        let solids: Vec<_> = solids.into_iter().collect();
+        let (mut x_min, mut x_max, mut y_max) = (source.x, source.x, source.y);
+        for pos in &solids {
+            x_min = min(x_min, pos.x);
+            x_max = max(x_max, pos.x);
+            y_max = max(y_max, pos.y);
+        }
+        let x_min = x_min.saturating_sub(1);
+        let width = x_max + 1 - x_min + 1;
+        let mut grid = Grid {
+            source,
+            x_min,
+            width,
+            cells: vec![Cell::Empty; width * (y_max + 1)],
+        };
+        for pos in solids {
+            grid.set(pos, Cell::Solid);
+        }
+        grid.set(source, Cell::Source);
+        grid
+    }
+
+    /// Adds an endless solid floor the given number of rows below the lowest solid cell.
+    /// The grid is widened far enough for anything falling diagonally from the source
+    /// to land on it.
+    pub fn with_floor(self, gap: usize) -> Self {
+        let floor_y = self.height() - 1 + gap;
+        let widening = floor_y - self.source.y;
+        let x_min = self.x_min.saturating_sub(widening);
+        let x_max = self.x_max() + widening;
+        let mut grid = Grid {
+            source: self.source,
+            x_min,
+            width: x_max + 1 - x_min,
+            cells: vec![Cell::Empty; (x_max + 1 - x_min) * (floor_y + 1)],
+        };
+        for (pos, cell) in self.cells() {
+            grid.set(pos, cell);
+        }
+        for x in x_min..=x_max {
+            grid.set(Pos::new(x, floor_y), Cell::Solid);
+        }
+        grid
+    }
+
+    pub fn source(&self) -> Pos {
+        self.source
+    }
+    pub fn x_min(&self) -> Coord {
+        self.x_min
+    }
+    pub fn x_max(&self) -> Coord {
+        self.x_min + self.width - 1
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    fn index_of(&self, pos: Pos) -> Option<usize> {
+        let x = pos.x.checked_sub(self.x_min).filter(|x| x < &self.width)?;
+        (pos.y < self.height()).then_some(pos.y * self.width + x)
+    }
+    /// The cell at the given position, if it's within the grid
+    pub fn get(&self, pos: Pos) -> Option<Cell> {
+        self.index_of(pos).map(|i| self.cells[i])
+    }
+    pub fn set(&mut self, pos: Pos, cell: Cell) {
+        let i = self.index_of(pos).expect("position within the grid");
+        self.cells[i] = cell;
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (Pos, Cell)> + '_ {
+        (self.cells.iter().enumerate()).map(|(i, cell)| {
+            let pos = Pos::new(self.x_min + i % self.width, i / self.width);
+            (pos, *cell)
+        })
+    }
+    pub fn count(&self, cell: Cell) -> usize {
+        self.cells.iter().filter(|c| c == &&cell).count()
+    }
+
+    /// Draws each row, with settled particles drawn as given
+    pub fn draw(&self, settled: char) -> Vec<String> {
+        (self.cells.chunks(self.width))
+            .map(|row| row.iter().map(|cell| cell.to_char(settled)).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_covers_the_solids_and_source() {
+        let grid = Grid::new(Pos::new(5, 0), [Pos::new(3, 2), Pos::new(6, 3)]);
+        assert_eq!((2, 7), (grid.x_min(), grid.x_max()));
+        assert_eq!((6, 4), (grid.width(), grid.height()));
+        assert_eq!(vec!["...+..", "......", ".#....", "....#."], grid.draw('o'));
+        assert_eq!(Some(Cell::Solid), grid.get(Pos::new(6, 3)));
+        assert_eq!(None, grid.get(Pos::new(6, 4)));
+        assert_eq!(None, grid.get(Pos::new(1, 0)));
+        assert_eq!(2, grid.count(Cell::Solid));
+    }
+
+    #[test]
+    fn floor_is_wide_enough() {
+        let grid = Grid::new(Pos::new(5, 0), [Pos::new(5, 1)]).with_floor(2);
+        assert_eq!(
+            vec!["....+....", "....#....", ".........", "#########"],
+            grid.draw('o')
+        );
+    }
+}
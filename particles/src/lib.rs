@@ -0,0 +1,69 @@
+//! Particles pouring from a source onto a grid of solid cells, such as the water of
+//! 2018 day 17 or the sand of 2022 day 14.
+//!
+//! The [`Grid`] only knows where things are, while a [`Behaviour`] decides how its particles
+//! move. A [`Simulation`] runs them step by step, and can draw a frame after each step.
+//! The grid uses the screen convention: y grows downwards.
+
+mod grid;
+mod sand;
+mod water;
+
+pub use grid::{Cell, Coord, Grid, Pos};
+pub use sand::Sand;
+pub use water::Water;
+
+use std::fmt::{Display, Formatter};
+
+pub trait Behaviour {
+    /// How the particles that came to rest are drawn
+    const SETTLED: char;
+
+    /// Moves the particles on until something changes, and tells whether anything did.
+    /// Once nothing changes any more, the grid is stable.
+    fn step(&mut self, grid: &mut Grid) -> bool;
+}
+
+#[derive(Debug)]
+pub struct Simulation<B> {
+    grid: Grid,
+    behaviour: B,
+}
+
+impl<B: Behaviour> Simulation<B> {
+    pub fn new(grid: Grid, behaviour: B) -> Self {
+        Simulation { grid, behaviour }
+    }
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+    pub fn into_grid(self) -> Grid {
+        self.grid
+    }
+
+    pub fn step(&mut self) -> bool {
+        self.behaviour.step(&mut self.grid)
+    }
+    /// Runs until the grid is stable, and returns the number of steps it took
+    pub fn run(&mut self) -> usize {
+        self.run_for(usize::MAX)
+    }
+    /// Runs for at most the given number of steps, and returns the number of steps taken
+    pub fn run_for(&mut self, max_steps: usize) -> usize {
+        let mut steps = 0;
+        while steps < max_steps && self.step() {
+            steps += 1;
+        }
+        steps
+    }
+    /// Runs until the grid is stable, with a drawing of the grid after each step
+    pub fn frames(&mut self) -> impl Iterator<Item = String> + '_ {
+        std::iter::from_fn(move || self.step().then(|| self.to_string()))
+    }
+}
+
+impl<B: Behaviour> Display for Simulation<B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid.draw(B::SETTLED).join("\n"))
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{Behaviour, Cell, Grid, Pos};
+
+/// Grains that fall straight down if they can, or else diagonally down to the left
+/// or right, and come to rest where none of these are empty.
+///
+/// Each grain would follow the path of the previous one up to where that one came to rest,
+/// so the path is kept, and the next grain starts falling from just above there.
+#[derive(Debug, Default)]
+pub struct Sand {
+    path: Vec<Pos>,
+}
+
+impl Behaviour for Sand {
+    const SETTLED: char = 'o';
+
+    /// Lets a single grain come to rest. Nothing changes any more once the grains
+    /// fall out of the grid, or come to rest on the source.
+    fn step(&mut self, grid: &mut Grid) -> bool {
+        if self.path.is_empty() {
+            if grid.get(grid.source()) != Some(Cell::Source) {
+                return false;
+            }
+            self.path.push(grid.source());
+        }
+        loop {
+            let pos = *self.path.last().unwrap();
+            let below = pos.below();
+            let mut next = None;
+            for candidate in [Some(below), below.left(), Some(below.right())] {
+                match candidate.and_then(|candidate| grid.get(candidate)) {
+                    None => return false,
+                    Some(Cell::Empty) => {
+                        next = candidate;
+                        break;
+                    }
+                    Some(_) => {}
+                }
+            }
+            match next {
+                Some(next) => self.path.push(next),
+                None => {
+                    grid.set(pos, Cell::Settled);
+                    self.path.pop();
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Simulation;
+
+    /// The rocks of 2022 day 14's example
+    fn example() -> Grid {
+        let rocks = (496..=498)
+            .map(|x| Pos::new(x, 6))
+            .chain((4..=5).map(|y| Pos::new(498, y)))
+            .chain((494..=502).map(|x| Pos::new(x, 9)))
+            .chain((4..=8).map(|y| Pos::new(502, y)))
+            .chain(Some(Pos::new(503, 4)));
+        Grid::new(Pos::new(500, 0), rocks)
+    }
+
+    /// Drops each grain from the source, without remembering any paths
+    fn settled_without_memo(mut grid: Grid) -> usize {
+        'grains: loop {
+            let mut pos = grid.source();
+            'falling: loop {
+                for next in [
+                    pos.below(),
+                    Pos::new(pos.x - 1, pos.y + 1),
+                    pos.below().right(),
+                ] {
+                    match grid.get(next) {
+                        None => break 'grains,
+                        Some(Cell::Empty) => {
+                            pos = next;
+                            continue 'falling;
+                        }
+                        Some(_) => {}
+                    }
+                }
+                grid.set(pos, Cell::Settled);
+                if pos == grid.source() {
+                    break 'grains;
+                }
+                continue 'grains;
+            }
+        }
+        grid.count(Cell::Settled)
+    }
+
+    #[test]
+    fn example_until_grains_fall_out() {
+        let mut simulation = Simulation::new(example(), Sand::default());
+        assert_eq!(24, simulation.run());
+        assert_eq!(
+            "\
+.......+....
+............
+.......o....
+......ooo...
+.....#ooo##.
+....o#ooo#..
+...###ooo#..
+.....oooo#..
+..o.ooooo#..
+.#########..",
+            simulation.to_string()
+        );
+        assert!(!simulation.step());
+    }
+
+    #[test]
+    fn example_frames() {
+        let mut simulation = Simulation::new(example(), Sand::default());
+        let frames: Vec<_> = simulation.frames().collect();
+        assert_eq!(24, frames.len());
+        let rows = |frame: &str, y: usize| -> Vec<String> {
+            frame.lines().skip(y).take(2).map(String::from).collect()
+        };
+        assert_eq!(vec![".........#..", ".......o.#.."], rows(&frames[0], 7));
+        assert_eq!(vec![".......o.#..", ".....oooo#.."], rows(&frames[4], 7));
+        assert_eq!(simulation.to_string(), frames[23]);
+    }
+
+    #[test]
+    fn example_with_floor() {
+        let mut simulation = Simulation::new(example().with_floor(2), Sand::default());
+        assert_eq!(93, simulation.run());
+        assert_eq!(Some(Cell::Settled), simulation.grid().get(Pos::new(500, 0)));
+    }
+
+    #[test]
+    fn memo_settles_the_same_grains() {
+        for floor in [None, Some(2), Some(5)] {
+            let grid = match floor {
+                Some(gap) => example().with_floor(gap),
+                None => example(),
+            };
+            let mut simulation = Simulation::new(grid.clone(), Sand::default());
+            simulation.run();
+            let expected = settled_without_memo(grid);
+            assert_eq!(expected, simulation.grid().count(Cell::Settled));
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{Behaviour, Cell, Grid, Pos};
+use std::collections::VecDeque;
+
+/// Water that flows down if it can, or else spreads sideways. Where it's held in on both
+/// sides by solid cells it settles, and the water flowing in from above spreads on top of it.
+#[derive(Debug, Default)]
+pub struct Water {
+    /// The ends of the streams, which have yet to flow on
+    flows: Option<VecDeque<Pos>>,
+}
+
+impl Behaviour for Water {
+    const SETTLED: char = '~';
+
+    /// Lets a single stream flow down by one cell, or spread sideways
+    fn step(&mut self, grid: &mut Grid) -> bool {
+        let flows = (self.flows).get_or_insert_with(|| VecDeque::from([grid.source()]));
+        while let Some(flow) = flows.pop_front() {
+            let below = flow.below();
+            match grid.get(below) {
+                Some(Cell::Empty) => {
+                    grid.set(below, Cell::Flowing);
+                    flows.push_back(below);
+                    return true;
+                }
+                Some(Cell::Solid | Cell::Settled) => {
+                    flows.extend(flow_sideways(grid, flow));
+                    return true;
+                }
+                // Another stream already flows there, or this one flows out of the grid
+                Some(Cell::Flowing | Cell::Source) | None => {}
+            }
+        }
+        false
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    fn of(&self, pos: Pos) -> Option<Pos> {
+        match self {
+            Side::Left => pos.left(),
+            Side::Right => Some(pos.right()),
+        }
+    }
+    fn other(&self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+fn flow_sideways(grid: &mut Grid, pos: Pos) -> Vec<Pos> {
+    let mut next_flows = vec![];
+    for side in [Side::Left, Side::Right] {
+        let next = flow_to_one_side(grid, pos, side);
+        // Settling raised the water level, so the streams flowing in from above go on instead
+        if next.first().is_some_and(|first| first.y < pos.y) {
+            return next;
+        }
+        next_flows.extend(next);
+    }
+    next_flows
+}
+
+fn flow_to_one_side(grid: &mut Grid, pos: Pos, side: Side) -> Vec<Pos> {
+    let Some(next) = side.of(pos) else {
+        return vec![];
+    };
+    match grid.get(next) {
+        Some(Cell::Empty) => {
+            // Flow into it, and flow on from there later
+            grid.set(next, Cell::Flowing);
+            vec![next]
+        }
+        Some(Cell::Solid) => {
+            // There's a wall on this side. If there's a wall on the other side too,
+            // the whole puddle settles, and the streams flowing into it go on
+            match find_wall(grid, pos, side.other()) {
+                Some(wall) => {
+                    settle(grid, pos, wall, side.other());
+                    inflows(grid, pos, wall, side.other())
+                }
+                None => vec![],
+            }
+        }
+        Some(Cell::Flowing) => {
+            // This could be another stream, both growing inward.
+            // #|||->  <-|||#
+            // In this case neither side would check for walls any more, so let's do it here
+            let this_wall = find_wall(grid, pos, side);
+            let other_wall = find_wall(grid, pos, side.other());
+            match (this_wall, other_wall) {
+                (Some(this_wall), Some(other_wall)) => {
+                    let from = side.other().of(this_wall).unwrap();
+                    settle(grid, from, other_wall, side.other());
+                    inflows(grid, this_wall, other_wall, side.other())
+                }
+                _ => vec![],
+            }
+        }
+        Some(Cell::Settled | Cell::Source) | None => vec![],
+    }
+}
+
+/// The solid cell the flowing cells next to the given position lead to on the given side
+fn find_wall(grid: &Grid, pos: Pos, side: Side) -> Option<Pos> {
+    let mut edge = side.of(pos)?;
+    while grid.get(edge) == Some(Cell::Flowing) {
+        edge = side.of(edge)?;
+    }
+    (grid.get(edge) == Some(Cell::Solid)).then_some(edge)
+}
+
+fn settle(grid: &mut Grid, from: Pos, wall: Pos, side: Side) {
+    let mut pos = from;
+    while pos != wall {
+        grid.set(pos, Cell::Settled);
+        pos = side.of(pos).unwrap();
+    }
+}
+
+/// The streams flowing down onto the cells from the given position up to the wall
+fn inflows(grid: &Grid, from: Pos, wall: Pos, side: Side) -> Vec<Pos> {
+    let mut inflows = vec![];
+    let mut pos = from;
+    while pos != wall {
+        if let Some(above) = pos
+            .above()
+            .filter(|&above| grid.get(above) == Some(Cell::Flowing))
+        {
+            inflows.push(above);
+        }
+        pos = side.of(pos).unwrap();
+    }
+    inflows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Simulation;
+
+    /// The clay of 2018 day 17's example
+    fn example() -> Grid {
+        let clay = [
+            (495..=495, 2..=7),
+            (495..=501, 7..=7),
+            (501..=501, 3..=7),
+            (498..=498, 2..=4),
+            (506..=506, 1..=2),
+            (498..=498, 10..=13),
+            (504..=504, 10..=13),
+            (498..=504, 13..=13),
+        ]
+        .into_iter()
+        .flat_map(|(xs, ys)| xs.flat_map(move |x| ys.clone().map(move |y| Pos::new(x, y))));
+        Grid::new(Pos::new(500, 0), clay)
+    }
+
+    #[test]
+    fn example_until_stable() {
+        let mut simulation = Simulation::new(example(), Water::default());
+        simulation.run();
+        assert_eq!(
+            "\
+......+.......
+......|.....#.
+.#..#||||...#.
+.#..#~~#|.....
+.#..#~~#|.....
+.#~~~~~#|.....
+.#~~~~~#|.....
+.#######|.....
+........|.....
+...|||||||||..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#~~~~~#|..
+...|#######|..",
+            simulation.to_string()
+        );
+        let grid = simulation.grid();
+        assert_eq!(29, grid.count(Cell::Settled));
+        assert_eq!(57, grid.count(Cell::Settled) + grid.count(Cell::Flowing));
+    }
+
+    #[test]
+    fn example_frames() {
+        let mut simulation = Simulation::new(example(), Water::default());
+        let frames: Vec<_> = simulation.frames().collect();
+        assert_eq!(".#~~~~~#......", frames[9].lines().nth(6).unwrap());
+        assert_eq!(".#....|#......", frames[9].lines().nth(5).unwrap());
+        assert_eq!(simulation.to_string(), *frames.last().unwrap());
+    }
+
+    #[test]
+    fn floor_without_walls_lets_water_flow_off_the_sides() {
+        let grid = Grid::new(Pos::new(10, 0), [Pos::new(10, 2)]).with_floor(1);
+        let mut simulation = Simulation::new(grid, Water::default());
+        simulation.run();
+        assert_eq!(
+            "\
+....+....
+...|||...
+||||#||||
+#########",
+            simulation.to_string()
+        );
+    }
+}
@@ -0,0 +1,582 @@
+//! Searching the states of puzzles that are solved by a sequence of moves, such as the
+//! elevator of 2016 day 11, the amphipods of 2021 day 23, the valves of 2022 day 16
+//! or the robot factory of 2022 day 19.
+//!
+//! A [`Puzzle`] only describes its states and moves. The [`Solver`] either finds the cheapest
+//! way to a goal with A*, or the most valuable one with a depth-first branch-and-bound search,
+//! and keeps [`Stats`] on how much of the state space it had to visit.
+
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::ops::{Add, AddAssign};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub trait Puzzle {
+    type State: Clone + Eq + Hash;
+    type Move: Clone;
+
+    fn start(&self) -> Self::State;
+    /// The moves that are allowed from the given state
+    fn moves(&self, state: &Self::State) -> Vec<Self::Move>;
+    fn apply(&self, state: &Self::State, mv: &Self::Move) -> Self::State;
+    /// What a move costs when minimizing, or gains when maximizing
+    fn cost(&self, state: &Self::State, mv: &Self::Move) -> usize;
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// An optimistic estimate of what is still to come after the given state: never more than
+    /// the remaining cost when minimizing, and never less than the remaining gain when
+    /// maximizing. Without one, minimizing is Dijkstra's algorithm, and nothing is pruned
+    /// when maximizing.
+    fn heuristic(&self, _state: &Self::State) -> Option<usize> {
+        None
+    }
+
+    /// Maps states that are equivalent, for example because they only differ by
+    /// interchangeable parts, onto the same one, so that only one of them is explored
+    fn canonical(&self, state: &Self::State) -> Self::State {
+        state.clone()
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// States whose moves were generated
+    pub expanded: usize,
+    /// States skipped because an equivalent one was already reached as cheaply or profitably
+    pub dedup_hits: usize,
+    /// States skipped because the heuristic showed that they can't beat the bound
+    pub pruned: usize,
+}
+
+impl Add for Stats {
+    type Output = Stats;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Stats {
+            expanded: self.expanded + rhs.expanded,
+            dedup_hits: self.dedup_hits + rhs.dedup_hits,
+            pruned: self.pruned + rhs.pruned,
+        }
+    }
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<M> {
+    /// The total cost when minimizing, or the total gain when maximizing
+    pub value: usize,
+    pub moves: Vec<M>,
+    pub stats: Stats,
+}
+
+/// A state, along with the index of the state and the move it was reached from
+type Node<S, M> = (S, Option<(usize, M)>);
+
+pub struct Solver<'a, P> {
+    puzzle: &'a P,
+    upper_bound: Option<usize>,
+}
+
+impl<'a, P: Puzzle> Solver<'a, P> {
+    pub fn new(puzzle: &'a P) -> Self {
+        Solver {
+            puzzle,
+            upper_bound: None,
+        }
+    }
+
+    /// Don't look for solutions that cost more than this when minimizing,
+    /// for example because a solution with this cost is already known
+    pub fn with_upper_bound(mut self, upper_bound: usize) -> Self {
+        self.upper_bound = Some(upper_bound);
+        self
+    }
+
+    /// The cheapest sequence of moves from the start to a goal, if there is one within the bound
+    pub fn minimize(&self) -> Option<Solution<P::Move>> {
+        let puzzle = self.puzzle;
+        let mut stats = Stats::default();
+
+        // Every state pushed so far
+        let mut nodes: Vec<Node<P::State, P::Move>> = vec![(puzzle.start(), None)];
+        let mut lowest_costs = HashMap::from([(puzzle.canonical(&nodes[0].0), 0)]);
+        let mut candidates = BinaryHeap::from([Reverse((0, 0, 0))]);
+
+        while let Some(Reverse((_, cost, index))) = candidates.pop() {
+            let state = nodes[index].0.clone();
+            if lowest_costs[&puzzle.canonical(&state)] < cost {
+                // Reached again more cheaply after this was pushed
+                stats.dedup_hits += 1;
+                continue;
+            }
+            if puzzle.is_goal(&state) {
+                return Some(Solution {
+                    value: cost,
+                    moves: Self::moves_leading_to(index, nodes),
+                    stats,
+                });
+            }
+            stats.expanded += 1;
+            for mv in puzzle.moves(&state) {
+                let next = puzzle.apply(&state, &mv);
+                let next_cost = cost + puzzle.cost(&state, &mv);
+                let estimate = next_cost + puzzle.heuristic(&next).unwrap_or(0);
+                if self.upper_bound.is_some_and(|bound| estimate > bound) {
+                    stats.pruned += 1;
+                    continue;
+                }
+                match lowest_costs.entry(puzzle.canonical(&next)) {
+                    Entry::Occupied(lowest) if *lowest.get() <= next_cost => {
+                        stats.dedup_hits += 1;
+                        continue;
+                    }
+                    Entry::Occupied(mut lowest) => *lowest.get_mut() = next_cost,
+                    Entry::Vacant(lowest) => {
+                        lowest.insert(next_cost);
+                    }
+                }
+                candidates.push(Reverse((estimate, next_cost, nodes.len())));
+                nodes.push((next, Some((index, mv))));
+            }
+        }
+        None
+    }
+
+    fn moves_leading_to(mut index: usize, mut nodes: Vec<Node<P::State, P::Move>>) -> Vec<P::Move> {
+        let mut moves = vec![];
+        while let Some((previous, mv)) = nodes[index].1.take() {
+            moves.push(mv);
+            index = previous;
+        }
+        moves.reverse();
+        moves
+    }
+
+    /// The most valuable sequence of moves from the start to a goal, if any goal is reachable.
+    /// The search is depth-first, so no state may be reachable from itself, as is the case
+    /// when the state includes a time limit or the resources used up so far.
+    pub fn maximize(&self) -> Option<Solution<P::Move>> {
+        let start = self.puzzle.start();
+        let mut branch = Branch::new(self.puzzle, None);
+        branch.visit_start(&start);
+        branch.explore(&start, 0);
+        branch.into_solution()
+    }
+
+    /// Like [`Solver::maximize`], but explores the moves from the start in parallel.
+    /// The branches share the best value found so far for pruning, but not their
+    /// deduplication, so the stats differ from those of a sequential search.
+    pub fn par_maximize(&self) -> Option<Solution<P::Move>>
+    where
+        P: Sync,
+        P::State: Send + Sync,
+        P::Move: Send + Sync,
+    {
+        let puzzle = self.puzzle;
+        let start = puzzle.start();
+        let best_so_far = AtomicUsize::new(0);
+
+        let mut root = Branch::new(puzzle, Some(&best_so_far));
+        root.visit_start(&start);
+        root.stats.expanded += 1;
+        let root = (root.best, root.stats);
+
+        let (best, stats) = puzzle
+            .moves(&start)
+            .into_par_iter()
+            .map(|mv| {
+                let mut branch = Branch::new(puzzle, Some(&best_so_far));
+                branch.visit(&start, 0, mv);
+                (branch.best, branch.stats)
+            })
+            .reduce(
+                || (None, Stats::default()),
+                |(a, mut stats), (b, other_stats)| {
+                    stats += other_stats;
+                    let best = match (a, b) {
+                        (Some(a), Some(b)) if b.0 > a.0 => Some(b),
+                        (a, b) => a.or(b),
+                    };
+                    (best, stats)
+                },
+            );
+        let best = match (root.0, best) {
+            (Some(root), Some(best)) if root.0 >= best.0 => Some(root),
+            (root, best) => best.or(root),
+        };
+        best.map(|(value, moves)| Solution {
+            value,
+            moves,
+            stats: root.1 + stats,
+        })
+    }
+}
+
+/// The depth-first state of a (part of a) maximizing search
+struct Branch<'a, P: Puzzle> {
+    puzzle: &'a P,
+    highest_gains: HashMap<P::State, usize>,
+    path: Vec<P::Move>,
+    best: Option<(usize, Vec<P::Move>)>,
+    /// The best value found by any branch, plus one so that zero means none yet
+    shared_best: Option<&'a AtomicUsize>,
+    stats: Stats,
+}
+
+impl<'a, P: Puzzle> Branch<'a, P> {
+    fn new(puzzle: &'a P, shared_best: Option<&'a AtomicUsize>) -> Self {
+        Branch {
+            puzzle,
+            highest_gains: HashMap::new(),
+            path: vec![],
+            best: None,
+            shared_best,
+            stats: Stats::default(),
+        }
+    }
+
+    fn best_value(&self) -> Option<usize> {
+        let shared = self
+            .shared_best
+            .and_then(|best| best.load(Ordering::Relaxed).checked_sub(1));
+        self.best.as_ref().map(|(value, _)| *value).max(shared)
+    }
+
+    fn record_if_goal(&mut self, state: &P::State, gained: usize) {
+        if self.puzzle.is_goal(state) && self.best.as_ref().is_none_or(|(v, _)| gained > *v) {
+            self.best = Some((gained, self.path.clone()));
+            if let Some(best) = self.shared_best {
+                best.fetch_max(gained + 1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn visit_start(&mut self, start: &P::State) {
+        self.highest_gains.insert(self.puzzle.canonical(start), 0);
+        self.record_if_goal(start, 0);
+    }
+
+    fn explore(&mut self, state: &P::State, gained: usize) {
+        self.stats.expanded += 1;
+        for mv in self.puzzle.moves(state) {
+            self.visit(state, gained, mv);
+        }
+    }
+
+    fn visit(&mut self, state: &P::State, gained: usize, mv: P::Move) {
+        let puzzle = self.puzzle;
+        let next = puzzle.apply(state, &mv);
+        let gained = gained + puzzle.cost(state, &mv);
+        if let (Some(remaining), Some(best)) = (puzzle.heuristic(&next), self.best_value()) {
+            if gained + remaining <= best {
+                self.stats.pruned += 1;
+                return;
+            }
+        }
+        match self.highest_gains.entry(puzzle.canonical(&next)) {
+            Entry::Occupied(highest) if *highest.get() >= gained => {
+                self.stats.dedup_hits += 1;
+                return;
+            }
+            Entry::Occupied(mut highest) => *highest.get_mut() = gained,
+            Entry::Vacant(highest) => {
+                highest.insert(gained);
+            }
+        }
+        self.path.push(mv);
+        self.record_if_goal(&next, gained);
+        self.explore(&next, gained);
+        self.path.pop();
+    }
+
+    fn into_solution(self) -> Option<Solution<P::Move>> {
+        let stats = self.stats;
+        self.best.map(|(value, moves)| Solution {
+            value,
+            moves,
+            stats,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Walking on a line of positions `0..len`, where stepping right costs 1 and left costs 3
+    struct Line {
+        len: usize,
+        start: usize,
+        goal: usize,
+    }
+
+    impl Puzzle for Line {
+        type State = usize;
+        type Move = isize;
+
+        fn start(&self) -> usize {
+            self.start
+        }
+        fn moves(&self, &pos: &usize) -> Vec<isize> {
+            [-1, 1]
+                .into_iter()
+                .filter(|step| (0..self.len).contains(&pos.wrapping_add_signed(*step)))
+                .collect()
+        }
+        fn apply(&self, &pos: &usize, step: &isize) -> usize {
+            pos.wrapping_add_signed(*step)
+        }
+        fn cost(&self, _pos: &usize, step: &isize) -> usize {
+            if *step < 0 {
+                3
+            } else {
+                1
+            }
+        }
+        fn is_goal(&self, pos: &usize) -> bool {
+            *pos == self.goal
+        }
+    }
+
+    /// The same line, with an admissible heuristic
+    struct GuidedLine(Line);
+
+    impl Puzzle for GuidedLine {
+        type State = usize;
+        type Move = isize;
+
+        fn start(&self) -> usize {
+            self.0.start()
+        }
+        fn moves(&self, pos: &usize) -> Vec<isize> {
+            self.0.moves(pos)
+        }
+        fn apply(&self, pos: &usize, step: &isize) -> usize {
+            self.0.apply(pos, step)
+        }
+        fn cost(&self, pos: &usize, step: &isize) -> usize {
+            self.0.cost(pos, step)
+        }
+        fn is_goal(&self, pos: &usize) -> bool {
+            self.0.is_goal(pos)
+        }
+        fn heuristic(&self, pos: &usize) -> Option<usize> {
+            Some(pos.abs_diff(self.0.goal))
+        }
+    }
+
+    /// Picking items with a value and a weight without exceeding the capacity.
+    /// Items are picked in ascending order, so that every set of items is reached only once.
+    struct Knapsack {
+        items: Vec<(usize, usize)>,
+        capacity: usize,
+        bounded: bool,
+    }
+
+    impl Puzzle for Knapsack {
+        /// The items picked so far
+        type State = Vec<usize>;
+        type Move = usize;
+
+        fn start(&self) -> Vec<usize> {
+            vec![]
+        }
+        fn moves(&self, picked: &Vec<usize>) -> Vec<usize> {
+            let weight: usize = picked.iter().map(|&i| self.items[i].1).sum();
+            let first = picked.last().map_or(0, |last| last + 1);
+            (first..self.items.len())
+                .filter(|&i| weight + self.items[i].1 <= self.capacity)
+                .collect()
+        }
+        fn apply(&self, picked: &Vec<usize>, item: &usize) -> Vec<usize> {
+            let mut picked = picked.clone();
+            picked.push(*item);
+            picked
+        }
+        fn cost(&self, _picked: &Vec<usize>, item: &usize) -> usize {
+            self.items[*item].0
+        }
+        fn is_goal(&self, _picked: &Vec<usize>) -> bool {
+            true
+        }
+        fn heuristic(&self, picked: &Vec<usize>) -> Option<usize> {
+            let first = picked.last().map_or(0, |last| last + 1);
+            self.bounded
+                .then(|| self.items[first..].iter().map(|(value, _)| value).sum())
+        }
+    }
+
+    fn knapsack(bounded: bool) -> Knapsack {
+        Knapsack {
+            items: vec![(10, 5), (40, 4), (30, 6), (50, 3), (5, 1), (25, 4)],
+            capacity: 10,
+            bounded,
+        }
+    }
+
+    /// Two tokens on a ring of positions, where only the set of occupied positions matters
+    struct Tokens;
+
+    impl Puzzle for Tokens {
+        type State = [u8; 2];
+        type Move = (usize, u8);
+
+        fn start(&self) -> [u8; 2] {
+            [0, 0]
+        }
+        fn moves(&self, _tokens: &[u8; 2]) -> Vec<(usize, u8)> {
+            vec![(0, 1), (0, 7), (1, 1), (1, 7)]
+        }
+        fn apply(&self, tokens: &[u8; 2], &(token, step): &(usize, u8)) -> [u8; 2] {
+            let mut tokens = *tokens;
+            tokens[token] = (tokens[token] + step) % 8;
+            tokens
+        }
+        fn cost(&self, _tokens: &[u8; 2], _mv: &(usize, u8)) -> usize {
+            1
+        }
+        fn is_goal(&self, tokens: &[u8; 2]) -> bool {
+            tokens == &[3, 6] || tokens == &[6, 3]
+        }
+    }
+
+    struct SortedTokens;
+
+    impl Puzzle for SortedTokens {
+        type State = [u8; 2];
+        type Move = (usize, u8);
+
+        fn start(&self) -> [u8; 2] {
+            Tokens.start()
+        }
+        fn moves(&self, tokens: &[u8; 2]) -> Vec<(usize, u8)> {
+            Tokens.moves(tokens)
+        }
+        fn apply(&self, tokens: &[u8; 2], mv: &(usize, u8)) -> [u8; 2] {
+            Tokens.apply(tokens, mv)
+        }
+        fn cost(&self, tokens: &[u8; 2], mv: &(usize, u8)) -> usize {
+            Tokens.cost(tokens, mv)
+        }
+        fn is_goal(&self, tokens: &[u8; 2]) -> bool {
+            Tokens.is_goal(tokens)
+        }
+        fn canonical(&self, tokens: &[u8; 2]) -> [u8; 2] {
+            let mut tokens = *tokens;
+            tokens.sort_unstable();
+            tokens
+        }
+    }
+
+    #[test]
+    fn minimize_finds_cheapest_moves() {
+        let line = Line {
+            len: 10,
+            start: 4,
+            goal: 1,
+        };
+        let solution = Solver::new(&line).minimize().unwrap();
+        assert_eq!(9, solution.value);
+        assert_eq!(vec![-1, -1, -1], solution.moves);
+
+        let line = Line { goal: 4, ..line };
+        let solution = Solver::new(&line).minimize().unwrap();
+        assert_eq!(0, solution.value);
+        assert!(solution.moves.is_empty());
+    }
+
+    #[test]
+    fn heuristic_expands_fewer_states() {
+        let line = Line {
+            len: 100,
+            start: 50,
+            goal: 60,
+        };
+        let dijkstra = Solver::new(&line).minimize().unwrap();
+        let guided = GuidedLine(line);
+        let a_star = Solver::new(&guided).minimize().unwrap();
+        assert_eq!(10, dijkstra.value);
+        assert_eq!(dijkstra.value, a_star.value);
+        assert_eq!(dijkstra.moves, a_star.moves);
+        assert_eq!(10, a_star.stats.expanded);
+        assert!(dijkstra.stats.expanded > a_star.stats.expanded);
+    }
+
+    #[test]
+    fn upper_bound_prunes_and_may_exclude_all_solutions() {
+        let line = GuidedLine(Line {
+            len: 100,
+            start: 50,
+            goal: 20,
+        });
+        let unbounded = Solver::new(&line).minimize().unwrap();
+        assert_eq!(90, unbounded.value);
+        assert_eq!(0, unbounded.stats.pruned);
+
+        let bounded = Solver::new(&line).with_upper_bound(90).minimize().unwrap();
+        assert_eq!(90, bounded.value);
+        assert!(bounded.stats.pruned > 0);
+
+        assert_eq!(None, Solver::new(&line).with_upper_bound(89).minimize());
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        let line = Line {
+            len: 10,
+            start: 4,
+            goal: 10,
+        };
+        assert_eq!(None, Solver::new(&line).minimize());
+    }
+
+    #[test]
+    fn canonicalization_dedups_symmetric_states() {
+        let plain = Solver::new(&Tokens).minimize().unwrap();
+        let sorted = Solver::new(&SortedTokens).minimize().unwrap();
+        // 3 steps forward and 2 backward
+        assert_eq!(5, plain.value);
+        assert_eq!(5, sorted.value);
+        assert!(sorted.stats.dedup_hits > 0);
+        assert!(sorted.stats.expanded < plain.stats.expanded);
+
+        // The moves still apply to the actual states, not the canonical ones
+        let end = sorted
+            .moves
+            .iter()
+            .fold(SortedTokens.start(), |tokens, mv| Tokens.apply(&tokens, mv));
+        assert!(Tokens.is_goal(&end));
+    }
+
+    #[test]
+    fn maximize_with_and_without_pruning() {
+        let exhaustive = Solver::new(&knapsack(false)).maximize().unwrap();
+        let bounded = Solver::new(&knapsack(true)).maximize().unwrap();
+        // Items of values 40, 50 and 5 with weights 4, 3 and 1
+        assert_eq!(95, exhaustive.value);
+        assert_eq!(vec![1, 3, 4], exhaustive.moves);
+        assert_eq!(exhaustive.value, bounded.value);
+        assert_eq!(exhaustive.moves, bounded.moves);
+        assert_eq!(0, exhaustive.stats.pruned);
+        assert!(bounded.stats.pruned > 0);
+        assert!(bounded.stats.expanded < exhaustive.stats.expanded);
+    }
+
+    #[test]
+    fn par_maximize_finds_the_same_value() {
+        for bounded in [false, true] {
+            let sequential = Solver::new(&knapsack(bounded)).maximize().unwrap();
+            let parallel = Solver::new(&knapsack(bounded)).par_maximize().unwrap();
+            assert_eq!(sequential.value, parallel.value);
+            assert_eq!(sequential.moves, parallel.moves);
+        }
+    }
+}